@@ -0,0 +1,55 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Counts every heap allocation made by the process.
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+/// Sustained worker -> orchestrator learning traffic: the path `on_packet`
+/// takes for every datagram. `LearnEvent` carries the path inline in a
+/// pre-allocated ring, so the steady state must not touch the allocator.
+fn bench_learn_path_allocations(c: &mut Criterion) {
+    const EVENTS: u64 = 100_000;
+    let (mut learn_tx, mut learn_rx) = httpx_core::learn_channel(httpx_core::learn::DEFAULT_LEARN_CAPACITY);
+    let path = b"GET /api/v1/users/42/profile";
+
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    for _ in 0..EVENTS {
+        learn_tx.send(black_box(path), true);
+        black_box(learn_rx.try_recv());
+    }
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+    println!(
+        "learn path: {} allocations over {} events ({:.5} per event)",
+        allocations,
+        EVENTS,
+        allocations as f64 / EVENTS as f64
+    );
+    assert!(allocations < 16, "learning path allocates per packet ({} allocations)", allocations);
+
+    c.bench_function("learn_send_recv", |b| {
+        b.iter(|| {
+            learn_tx.send(black_box(path), true);
+            black_box(learn_rx.try_recv());
+        })
+    });
+}
+
+criterion_group!(benches, bench_learn_path_allocations);
+criterion_main!(benches);
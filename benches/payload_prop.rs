@@ -14,13 +14,13 @@ fn bench_payload_propagation(c: &mut Criterion) {
     let mut trie = LinearIntentTrie::new(1024);
     let context = b"GET /index.html";
     trie.observe(context, true);
-    trie.associate_payload(context, 42, 0);
+    trie.associate_payload(context, 42, 0).unwrap();
 
     let slab = SecureSlab::new(64);
     let socket = rt.block_on(UdpSocket::bind("127.0.0.1:0")).unwrap();
     let addr = socket.local_addr().unwrap();
     let (_control_tx, rx) = tokio::sync::mpsc::channel(10);
-    let (learn_tx, _learn_rx) = tokio::sync::mpsc::unbounded_channel();
+    let (learn_tx, _learn_rx) = httpx_core::learn_channel(1024);
     
     let mut dispatcher = rt.block_on(CoreDispatcher::new_with_socket(
         0, 
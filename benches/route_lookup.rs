@@ -0,0 +1,87 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use httpx_core::PredictiveEngine;
+use httpx_dsa::{ByteIntentTrie, IntentTrie, LinearIntentTrie};
+
+/// Routes sharing a 20-byte prefix, differing only in the last segment.
+const PREFIX: &str = "/api/v1/catalog/item";
+const ROUTES: u32 = 64;
+
+fn bench_prefix_cached_lookup(c: &mut Criterion) {
+    let mut trie = LinearIntentTrie::new(4096);
+    let paths: Vec<Vec<u8>> = (0..ROUTES).map(|i| format!("{}/{}", PREFIX, i).into_bytes()).collect();
+    for (i, path) in paths.iter().enumerate() {
+        trie.warm(path);
+        trie.associate_payload(path, i as u32 + 1, 1).unwrap();
+    }
+
+    let cold = PredictiveEngine::new(true).with_prefix_cache(false);
+    let cached = PredictiveEngine::new(true);
+    cold.swap_weights(trie.clone());
+    cached.swap_weights(trie);
+
+    let mut group = c.benchmark_group("Route Lookup (shared 20-byte prefix)");
+    for (name, engine) in [("cold", &cold), ("cached", &cached)] {
+        let mut i = 0;
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                i = (i + 1) % paths.len();
+                black_box(engine.lookup(black_box(&paths[i])))
+            })
+        });
+    }
+    group.finish();
+}
+
+/// A 32-byte route: 256 hops in the bit trie, 32 in the byte trie.
+const DEEP_PATH: &[u8; 32] = b"/api/v1/catalog/items/0123456789";
+
+/// Trains both layouts on the same routes, so only the walk differs.
+fn trained<T: IntentTrie>(mut trie: T) -> T {
+    for i in 0..ROUTES {
+        let mut path = *DEEP_PATH;
+        path[30] = b'0' + (i / 10) as u8;
+        path[31] = b'0' + (i % 10) as u8;
+        trie.observe(&path, i % 2 == 0);
+    }
+    trie
+}
+
+fn bench_trie_traversal(c: &mut Criterion) {
+    let linear = trained(LinearIntentTrie::new(1 << 14));
+    let bytes = trained(ByteIntentTrie::new(1024));
+    let tries: [(&str, &dyn IntentTrie); 2] = [("bit-indexed", &linear), ("byte-indexed", &bytes)];
+
+    let mut group = c.benchmark_group("Trie Traversal (32-byte path)");
+    for (name, trie) in tries {
+        group.bench_function(name, |b| b.iter(|| black_box(trie.get_probability(black_box(DEEP_PATH), true))));
+    }
+    group.finish();
+
+    report_traversal_cycles(&tries);
+}
+
+/// Prints `get_probability` cycles per lookup of `DEEP_PATH`, via `rdtsc`.
+#[cfg(target_arch = "x86_64")]
+fn report_traversal_cycles(tries: &[(&str, &dyn IntentTrie)]) {
+    use core::arch::x86_64::_rdtsc;
+    const ROUNDS: u64 = 100_000;
+
+    for (name, trie) in tries {
+        for _ in 0..ROUNDS / 10 {
+            black_box(trie.get_probability(DEEP_PATH, true));
+        }
+        // # Safety: `rdtsc` only reads the timestamp counter.
+        let start = unsafe { _rdtsc() };
+        for _ in 0..ROUNDS {
+            black_box(trie.get_probability(black_box(DEEP_PATH), true));
+        }
+        let cycles = unsafe { _rdtsc() } - start;
+        println!("{} traversal of 32 bytes: {:.1} cycles/lookup", name, cycles as f64 / ROUNDS as f64);
+    }
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+fn report_traversal_cycles(_tries: &[(&str, &dyn IntentTrie)]) {}
+
+criterion_group!(benches, bench_prefix_cached_lookup, bench_trie_traversal);
+criterion_main!(benches);
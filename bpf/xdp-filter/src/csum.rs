@@ -0,0 +1,70 @@
+//! UDP checksum decision shared by the XDP programs and their host tests.
+//!
+//! Plain `core`-only code with no BPF dependencies: the program feeds it
+//! bounds-checked packet loads, and `tests/xsk_redirect.rs` includes this file
+//! with `#[path]` and feeds it byte slices, so both exercise the same logic.
+
+/// Longest UDP datagram the checksum loop walks (one Ethernet MTU).
+/// Bounds the loop for the verifier; longer datagrams (jumbo frames, GRO)
+/// pass unverified and are left to the kernel stack's own check.
+pub const MAX_UDP_LEN: usize = 1480;
+
+/// UDP header length in bytes.
+const UDP_HDR_LEN: usize = 8;
+
+/// Verifies the UDP checksum over the pseudo-header, UDP header and payload.
+///
+/// `src_addr`, `dst_addr`, `udp_len` and `check` are the header fields as
+/// loaded from the packet (wire order). `word(i)`/`byte(i)` load the 16-bit
+/// word or byte at offset `i` of the UDP header, returning `None` past the
+/// end of the packet.
+///
+/// A zero checksum means "not computed" on IPv4 and always passes, as does a
+/// datagram longer than `MAX_UDP_LEN`, which the loop cannot cover. A length
+/// below the UDP header or past the end of the packet fails. Words are
+/// summed as loaded; the one's-complement sum is byte-order independent, so
+/// no swaps are needed until the final compare.
+#[inline(always)]
+pub fn udp_checksum_ok(
+    src_addr: u32,
+    dst_addr: u32,
+    udp_len: u16,
+    check: u16,
+    word: impl Fn(usize) -> Option<u16>,
+    byte: impl Fn(usize) -> Option<u8>,
+) -> bool {
+    if check == 0 {
+        return true;
+    }
+
+    let len = u16::from_be(udp_len) as usize;
+    if len > MAX_UDP_LEN {
+        return true;
+    }
+    if len < UDP_HDR_LEN {
+        return false;
+    }
+
+    // Pseudo-header: src, dst, zero|proto (UDP = 17), UDP length.
+    let mut sum: u64 = (src_addr >> 16) as u64 + (src_addr & 0xffff) as u64;
+    sum += (dst_addr >> 16) as u64 + (dst_addr & 0xffff) as u64;
+    sum += u16::from_ne_bytes([0, 17]) as u64;
+    sum += udp_len as u64;
+
+    let mut i = 0;
+    while i + 1 < len && i < MAX_UDP_LEN {
+        let Some(w) = word(i) else { return false };
+        sum += w as u64;
+        i += 2;
+    }
+    if len % 2 == 1 {
+        let Some(b) = byte(len - 1) else { return false };
+        // The odd byte is the high half of a zero-padded wire word.
+        sum += u16::from_ne_bytes([b, 0]) as u64;
+    }
+
+    for _ in 0..3 {
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+    sum == 0xffff
+}
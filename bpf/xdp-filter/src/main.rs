@@ -1,12 +1,16 @@
 #![no_std]
 #![no_main]
 
+mod csum;
+
 use aya_ebpf::{
     bindings::xdp_action,
-    macros::xdp,
+    macros::{map, xdp},
+    maps::{PerCpuArray, XskMap},
     programs::XdpContext,
 };
 use core::mem;
+use csum::udp_checksum_ok;
 use network_types::{
     eth::{EthHdr, EtherType},
     ip::{Ipv4Hdr, IpProto},
@@ -16,14 +20,72 @@ use network_types::{
 /// HTTP-X Frame Magic: "HTPX" in Big Endian.
 const HTTPX_MAGIC: u32 = 0x48545058;
 
+/// AF_XDP sockets for the redirect path.
+///
+/// Layout: `key: u32` = RX queue index, `value: u32` = XSK fd, inserted by
+/// `httpx_transport::XskSocket::register`. One entry per hardware queue.
+#[map]
+static XSKS: XskMap = XskMap::with_max_entries(64, 0);
+
+/// Per-CPU drop counters, indexed by the `STAT_*` constants.
+#[map]
+static STATS: PerCpuArray<u64> = PerCpuArray::with_max_entries(1, 0);
+
+/// HTTP-X frames dropped for a bad UDP checksum.
+const STAT_CSUM_DROP: u32 = 0;
+
+/// UDP checksum verification switch, off by default; the loader may set it
+/// with `EbpfLoader::set_global("VERIFY_UDP_CSUM", &1u8, true)`.
+#[no_mangle]
+static VERIFY_UDP_CSUM: u8 = 0;
+
+/// What the filter makes of a frame.
+enum Verdict {
+    /// Not IPv4/UDP: none of our business.
+    Other,
+    /// UDP carrying the HTTP-X magic.
+    HttpX,
+    /// UDP without the magic: malformed protocol traffic.
+    Malformed,
+    /// HTTP-X magic intact but the UDP checksum fails: corrupted in flight.
+    Corrupt,
+}
+
 #[xdp]
 pub fn xdp_filter(ctx: XdpContext) -> u32 {
-    match try_xdp_filter(ctx) {
-        Ok(ret) => ret,
+    match classify(&ctx) {
+        Ok(Verdict::Other) | Ok(Verdict::HttpX) => xdp_action::XDP_PASS,
+        Ok(Verdict::Malformed) => xdp_action::XDP_DROP,
+        Ok(Verdict::Corrupt) => count_drop(STAT_CSUM_DROP),
+        Err(_) => xdp_action::XDP_ABORTED,
+    }
+}
+
+/// Like `xdp_filter`, but HTTP-X frames skip the kernel stack entirely and
+/// land in the AF_XDP socket registered for their RX queue. Queues without a
+/// registered socket fall back to `XDP_PASS`.
+#[xdp]
+pub fn xdp_redirect(ctx: XdpContext) -> u32 {
+    match classify(&ctx) {
+        Ok(Verdict::HttpX) => {
+            let queue = unsafe { (*ctx.ctx).rx_queue_index };
+            XSKS.redirect(queue, xdp_action::XDP_PASS as u64).unwrap_or(xdp_action::XDP_PASS)
+        }
+        Ok(Verdict::Other) => xdp_action::XDP_PASS,
+        Ok(Verdict::Malformed) => xdp_action::XDP_DROP,
+        Ok(Verdict::Corrupt) => count_drop(STAT_CSUM_DROP),
         Err(_) => xdp_action::XDP_ABORTED,
     }
 }
 
+#[inline(always)]
+fn count_drop(stat: u32) -> u32 {
+    if let Some(counter) = STATS.get_ptr_mut(stat) {
+        unsafe { *counter += 1 };
+    }
+    xdp_action::XDP_DROP
+}
+
 #[inline(always)]
 fn ptr_at<T>(ctx: &XdpContext, offset: usize) -> Result<*const T, ()> {
     let start = ctx.data();
@@ -37,30 +99,49 @@ fn ptr_at<T>(ctx: &XdpContext, offset: usize) -> Result<*const T, ()> {
     Ok((start + offset) as *const T)
 }
 
-fn try_xdp_filter(ctx: XdpContext) -> Result<u32, ()> {
-    let ethhdr: *const EthHdr = ptr_at(&ctx, 0)?;
+fn classify(ctx: &XdpContext) -> Result<Verdict, ()> {
+    let ethhdr: *const EthHdr = ptr_at(ctx, 0)?;
     if unsafe { (*ethhdr).ether_type } != EtherType::Ipv4 {
-        return Ok(xdp_action::XDP_PASS);
+        return Ok(Verdict::Other);
     }
 
-    let ipv4hdr: *const Ipv4Hdr = ptr_at(&ctx, EthHdr::LEN)?;
+    let ipv4hdr: *const Ipv4Hdr = ptr_at(ctx, EthHdr::LEN)?;
     if unsafe { (*ipv4hdr).proto } != IpProto::Udp {
-        return Ok(xdp_action::XDP_PASS);
+        return Ok(Verdict::Other);
     }
 
-    let _udphdr: *const UdpHdr = ptr_at(&ctx, EthHdr::LEN + Ipv4Hdr::LEN)?;
+    let udphdr: *const UdpHdr = ptr_at(ctx, EthHdr::LEN + Ipv4Hdr::LEN)?;
     
     // HTTP-X Header starts immediately after UDP header
-    let magic: *const u32 = ptr_at(&ctx, EthHdr::LEN + Ipv4Hdr::LEN + UdpHdr::LEN)?;
+    let magic: *const u32 = ptr_at(ctx, EthHdr::LEN + Ipv4Hdr::LEN + UdpHdr::LEN)?;
     
     if unsafe { u32::from_be(*magic) } == HTTPX_MAGIC {
-        Ok(xdp_action::XDP_PASS)
+        let verify = unsafe { core::ptr::read_volatile(&VERIFY_UDP_CSUM) } != 0;
+        if verify && !checksum_ok(ctx, ipv4hdr, udphdr) {
+            return Ok(Verdict::Corrupt);
+        }
+        Ok(Verdict::HttpX)
     } else {
         // Drop malformed protocol traffic at the driver level.
-        Ok(xdp_action::XDP_DROP)
+        Ok(Verdict::Malformed)
     }
 }
 
+/// Runs `csum::udp_checksum_ok` over the datagram behind `udphdr`.
+#[inline(always)]
+fn checksum_ok(ctx: &XdpContext, ipv4hdr: *const Ipv4Hdr, udphdr: *const UdpHdr) -> bool {
+    let (ip, udp) = unsafe { (&*ipv4hdr, &*udphdr) };
+    let start = EthHdr::LEN + Ipv4Hdr::LEN;
+    udp_checksum_ok(
+        ip.src_addr,
+        ip.dst_addr,
+        udp.len,
+        udp.check,
+        |i| ptr_at::<u16>(ctx, start + i).ok().map(|word| unsafe { *word }),
+        |i| ptr_at::<u8>(ctx, start + i).ok().map(|byte| unsafe { *byte }),
+    )
+}
+
 #[cfg(not(test))]
 #[panic_handler]
 fn panic(_info: &core::panic::PanicInfo) -> ! {
@@ -1,11 +1,19 @@
 use serde::{Serialize, Deserialize};
-use std::net::UdpSocket;
-use std::sync::Arc;
-use tokio::sync::mpsc;
+use std::collections::{BTreeSet, VecDeque};
+use socket2::{Domain, Protocol, Socket, Type};
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{mpsc, watch};
+
+/// Number of recently broadcast deltas retained for NACK-driven retransmission.
+pub const RETRANSMIT_WINDOW: usize = 256;
+
+/// Number of recently received delta digests checked for exact replays.
+pub const REPLAY_WINDOW: usize = 1024;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct IntentDelta {
-    /// Simplified 64-bit context hash/ID for gossip.
+    /// `httpx_dsa::context_hash` of the context path.
     pub context_hash: u64,
     /// Fixed-Point Weight increments (u16).
     pub delta_true: u16,
@@ -14,31 +22,203 @@ pub struct IntentDelta {
     pub sequence_number: u64,
 }
 
+impl IntentDelta {
+    /// Builds a delta for `path`, hashing it with the canonical `context_hash`.
+    pub fn for_path(path: &[u8], delta_true: u16, delta_false: u16, sequence_number: u64) -> Self {
+        Self {
+            context_hash: httpx_dsa::context_hash(path),
+            delta_true,
+            delta_false,
+            sequence_number,
+        }
+    }
+}
+
+/// Wire envelope for all gossip traffic.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum GossipMessage {
+    /// A learning update from a peer.
+    Delta(IntentDelta),
+    /// Requests retransmission of the inclusive sequence range `[from, to]`.
+    Nack { from: u64, to: u64 },
+    /// Liveness pulse, sent even when there is nothing to learn.
+    Heartbeat,
+}
+
+/// Why the gossip socket could not be brought up.
+#[derive(Debug)]
+pub enum GossipError {
+    /// The bind address did not resolve to a socket address.
+    InvalidAddress(String),
+    /// Another socket already owns the address; retry on another port.
+    AddressInUse(SocketAddr),
+    /// The process may not bind the address (e.g. a privileged port).
+    PermissionDenied(SocketAddr),
+    /// Any other socket setup failure.
+    Io(std::io::Error),
+}
+
+impl std::fmt::Display for GossipError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidAddress(addr) => write!(f, "gossip: invalid bind address {:?}", addr),
+            Self::AddressInUse(addr) => write!(f, "gossip: {} is already in use", addr),
+            Self::PermissionDenied(addr) => write!(f, "gossip: permission denied binding {}", addr),
+            Self::Io(err) => write!(f, "gossip: socket setup failed: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for GossipError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for GossipError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
 /// UDP-based Gossip Protocol for multi-node intent distribution.
 pub struct GossipProtocol {
     socket: Arc<UdpSocket>,
     tx_delta: mpsc::Sender<IntentDelta>,
     /// Tracks the highest sequence number seen to date for this node.
     last_seq: std::sync::atomic::AtomicU64,
+    /// Ring of recently broadcast deltas, replayed when a peer NACKs a gap.
+    history: Mutex<VecDeque<IntentDelta>>,
+    /// Sequence numbers skipped by a gap and still awaiting retransmission.
+    /// Only the last `RETRANSMIT_WINDOW` are kept; older ones can no longer
+    /// be retransmitted.
+    missing: Mutex<BTreeSet<u64>>,
+    /// LRU of digests of recently received delta datagrams, oldest first.
+    seen: Mutex<VecDeque<u64>>,
+    /// Deltas dropped because their exact bytes were already received.
+    replays_dropped: std::sync::atomic::AtomicU64,
+    /// Running count of heartbeats and deltas received from peers.
+    contacts: watch::Sender<u64>,
 }
 
 impl GossipProtocol {
+    /// Binds the gossip socket in the address family of `bind_addr`.
+    ///
+    /// IPv6 sockets are opened dual-stack where the host allows it, so a
+    /// single v6 node can still reach IPv4 peers via v4-mapped addresses.
+    ///
+    /// # Panics
+    /// On any bind failure; see `try_new` for the recoverable variant.
     pub fn new(bind_addr: &str, delta_tx: mpsc::Sender<IntentDelta>) -> Self {
-        let socket = UdpSocket::bind(bind_addr).expect("Gossip: Failed to bind UDP");
-        socket.set_nonblocking(true).expect("Gossip: Failed to set nonblocking");
-        
-        Self {
+        Self::try_new(bind_addr, delta_tx).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible `new`: bind conflicts and permission errors are returned so a
+    /// supervisor can retry on another port or degrade to Sovereign mode.
+    pub fn try_new(bind_addr: &str, delta_tx: mpsc::Sender<IntentDelta>) -> Result<Self, GossipError> {
+        let addr = bind_addr
+            .to_socket_addrs()
+            .ok()
+            .and_then(|mut addrs| addrs.next())
+            .ok_or_else(|| GossipError::InvalidAddress(bind_addr.to_string()))?;
+        let socket = Socket::new(Domain::for_address(addr), Type::DGRAM, Some(Protocol::UDP))?;
+        if addr.is_ipv6() {
+            // Best effort: v6-only hosts (net.ipv6.bindv6only) may refuse.
+            let _ = socket.set_only_v6(false);
+        }
+        socket.bind(&addr.into()).map_err(|e| match e.kind() {
+            std::io::ErrorKind::AddrInUse => GossipError::AddressInUse(addr),
+            std::io::ErrorKind::PermissionDenied => GossipError::PermissionDenied(addr),
+            _ => GossipError::Io(e),
+        })?;
+        socket.set_nonblocking(true)?;
+        let socket = UdpSocket::from(socket);
+
+        Ok(Self {
             socket: Arc::new(socket),
             tx_delta: delta_tx,
             last_seq: std::sync::atomic::AtomicU64::new(0),
-        }
+            history: Mutex::new(VecDeque::with_capacity(RETRANSMIT_WINDOW)),
+            missing: Mutex::new(BTreeSet::new()),
+            seen: Mutex::new(VecDeque::with_capacity(REPLAY_WINDOW)),
+            replays_dropped: std::sync::atomic::AtomicU64::new(0),
+            contacts: watch::channel(0).0,
+        })
+    }
+
+    /// Number of deltas rejected as exact replays of a recently received one.
+    pub fn replays_dropped(&self) -> u64 {
+        self.replays_dropped.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// Observes the running count of heartbeats and deltas received from
+    /// peers; each change is proof of a live cluster.
+    pub fn contacts(&self) -> watch::Receiver<u64> {
+        self.contacts.subscribe()
+    }
+
+    /// Number of skipped sequence numbers still awaiting retransmission.
+    pub fn missing_len(&self) -> usize {
+        self.missing.lock().unwrap().len()
+    }
+
+    /// Returns the local address of the gossip socket.
+    pub fn local_addr(&self) -> std::io::Result<SocketAddr> {
+        self.socket.local_addr()
     }
 
     /// Broadcasts a weight delta to the cluster.
+    ///
+    /// The delta is retained in the retransmit ring so that peers which
+    /// missed it can recover it via a NACK.
     pub fn broadcast(&self, peer_addrs: &[String], delta: IntentDelta) {
-        let payload = serde_json::to_vec(&delta).unwrap();
+        let payload = serde_json::to_vec(&GossipMessage::Delta(delta.clone())).unwrap();
+        {
+            let mut history = self.history.lock().unwrap();
+            if history.len() == RETRANSMIT_WINDOW {
+                history.pop_front();
+            }
+            history.push_back(delta);
+        }
+        self.send_to_peers(peer_addrs, &payload);
+    }
+
+    /// Pulses every peer so they know this node is alive.
+    pub fn heartbeat(&self, peer_addrs: &[String]) {
+        let payload = serde_json::to_vec(&GossipMessage::Heartbeat).unwrap();
+        self.send_to_peers(peer_addrs, &payload);
+    }
+
+    fn send_to_peers(&self, peer_addrs: &[String], payload: &[u8]) {
         for addr in peer_addrs {
-            let _ = self.socket.send_to(&payload, addr);
+            match self.resolve_peer(addr) {
+                Some(peer) => {
+                    let _ = self.socket.send_to(payload, peer);
+                }
+                None => tracing::warn!("Gossip: Peer {} unreachable from {:?}", addr, self.local_addr()),
+            }
+        }
+    }
+
+    /// Resolves a peer address into one this socket's family can send to.
+    ///
+    /// IPv4 peers are mapped into `::ffff:a.b.c.d` on a v6 socket; IPv6 peers
+    /// are unreachable from a v4 socket.
+    fn resolve_peer(&self, peer: &str) -> Option<SocketAddr> {
+        let local_v6 = self.socket.local_addr().ok()?.is_ipv6();
+        let candidates: Vec<SocketAddr> = peer.to_socket_addrs().ok()?.collect();
+
+        if let Some(same) = candidates.iter().find(|a| a.is_ipv6() == local_v6) {
+            return Some(*same);
+        }
+        match candidates.first()? {
+            SocketAddr::V4(v4) if local_v6 => {
+                Some(SocketAddr::new(v4.ip().to_ipv6_mapped().into(), v4.port()))
+            }
+            _ => None,
         }
     }
 
@@ -46,25 +226,99 @@ impl GossipProtocol {
     pub async fn listen(&self) {
         let mut buf = [0u8; 1024];
         loop {
-            if let Ok((len, _)) = self.socket.recv_from(&mut buf) {
-                if let Ok(delta) = serde_json::from_slice::<IntentDelta>(&buf[..len]) {
-                    // Task 3: Gossip Integrity Proof. Discard stale learning.
-                    let current = self.last_seq.load(std::sync::atomic::Ordering::Acquire);
-                    if delta.sequence_number > current {
-                        if self.last_seq.compare_exchange(
-                            current, 
-                            delta.sequence_number, 
-                            std::sync::atomic::Ordering::AcqRel, 
-                            std::sync::atomic::Ordering::Acquire
-                        ).is_ok() {
-                            let _ = self.tx_delta.send(delta).await;
+            if let Ok((len, src)) = self.socket.recv_from(&mut buf) {
+                match serde_json::from_slice::<GossipMessage>(&buf[..len]) {
+                    Ok(GossipMessage::Delta(delta)) => {
+                        if !self.is_replay(&buf[..len]) {
+                            self.record_contact();
+                            self.on_delta(delta, src).await;
                         }
-                    } else {
-                        tracing::warn!("Gossip: Discarding stale update (Seq: {})", delta.sequence_number);
                     }
+                    Ok(GossipMessage::Nack { from, to }) => self.retransmit(from, to, src),
+                    Ok(GossipMessage::Heartbeat) => self.record_contact(),
+                    Err(_) => {}
                 }
             }
             tokio::task::yield_now().await;
         }
     }
+
+    fn record_contact(&self) {
+        self.contacts.send_modify(|n| *n += 1);
+    }
+
+    /// Records the digest of a received delta datagram; `true` if it was
+    /// already seen within the last `REPLAY_WINDOW` deltas.
+    ///
+    /// Catches byte-exact replays independently of the sequence check, which
+    /// a forged sequence number could otherwise slip past.
+    fn is_replay(&self, datagram: &[u8]) -> bool {
+        let digest = httpx_dsa::context_hash(datagram);
+        let mut seen = self.seen.lock().unwrap();
+        if let Some(pos) = seen.iter().position(|&d| d == digest) {
+            // Refresh: a replayed delta stays blocked while it keeps arriving.
+            seen.remove(pos);
+            seen.push_back(digest);
+            self.replays_dropped.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            tracing::warn!("Gossip: Discarding replayed delta (digest {:016x})", digest);
+            return true;
+        }
+        if seen.len() == REPLAY_WINDOW {
+            seen.pop_front();
+        }
+        seen.push_back(digest);
+        false
+    }
+
+    async fn on_delta(&self, delta: IntentDelta, src: SocketAddr) {
+        // Task 3: Gossip Integrity Proof. Discard stale learning.
+        let current = self.last_seq.load(std::sync::atomic::Ordering::Acquire);
+        if delta.sequence_number > current {
+            if self.last_seq.compare_exchange(
+                current,
+                delta.sequence_number,
+                std::sync::atomic::Ordering::AcqRel,
+                std::sync::atomic::Ordering::Acquire
+            ).is_ok() {
+                // Only the retransmit window is recoverable from the sender;
+                // gaps that fell out of it are forgotten on every advance.
+                let floor = delta.sequence_number.saturating_sub(RETRANSMIT_WINDOW as u64);
+                // A jump past `current + 1` means deltas were lost in flight.
+                let expected = current + 1;
+                let gap = {
+                    let mut missing = self.missing.lock().unwrap();
+                    *missing = missing.split_off(&floor);
+                    let gap = (delta.sequence_number > expected).then(|| (expected.max(floor), delta.sequence_number - 1));
+                    if let Some((from, to)) = gap {
+                        missing.extend(from..=to);
+                    }
+                    gap
+                };
+                if let Some((from, to)) = gap {
+                    self.send_nack(from, to, src);
+                }
+                let _ = self.tx_delta.send(delta).await;
+            }
+        } else if self.missing.lock().unwrap().remove(&delta.sequence_number) {
+            // Retransmission filling a previously detected gap.
+            let _ = self.tx_delta.send(delta).await;
+        } else {
+            tracing::warn!("Gossip: Discarding stale update (Seq: {})", delta.sequence_number);
+        }
+    }
+
+    fn send_nack(&self, from: u64, to: u64, peer: SocketAddr) {
+        tracing::warn!("Gossip: Sequence gap detected, NACKing [{}, {}] to {}", from, to, peer);
+        let payload = serde_json::to_vec(&GossipMessage::Nack { from, to }).unwrap();
+        let _ = self.socket.send_to(&payload, peer);
+    }
+
+    /// Replays any retained deltas in `[from, to]` to the requesting peer.
+    fn retransmit(&self, from: u64, to: u64, peer: SocketAddr) {
+        let history = self.history.lock().unwrap();
+        for delta in history.iter().filter(|d| (from..=to).contains(&d.sequence_number)) {
+            let payload = serde_json::to_vec(&GossipMessage::Delta(delta.clone())).unwrap();
+            let _ = self.socket.send_to(&payload, peer);
+        }
+    }
 }
@@ -3,9 +3,9 @@ pub mod merge;
 pub mod monitor;
 pub mod reconcile;
 
-pub use gossip::GossipProtocol;
+pub use gossip::{GossipError, GossipProtocol};
 pub use merge::WeightAggregator;
 pub use monitor::{ClusterStability, ClusterMode};
 pub use reconcile::ReconciliationBuffer;
 pub mod orchestrator;
-pub use orchestrator::ClusterOrchestrator;
+pub use orchestrator::{ClusterOrchestrator, FlushPolicy, LearningRateMeter};
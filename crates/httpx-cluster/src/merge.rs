@@ -15,11 +15,16 @@ pub struct WeightAggregator {
 }
 
 impl WeightAggregator {
+    /// The shadow trie takes the configuration of the engine's active trie,
+    /// if one is installed.
     pub fn new(engine: Arc<PredictiveEngine>, delta_rx: mpsc::Receiver<IntentDelta>) -> Self {
+        let shadow_trie = engine
+            .inspect_trie(LinearIntentTrie::empty_like)
+            .unwrap_or_else(|| LinearIntentTrie::new(1024));
         Self {
             engine,
             delta_rx,
-            shadow_trie: LinearIntentTrie::new(1024),
+            shadow_trie,
             total_delta: 0,
         }
     }
@@ -6,6 +6,9 @@ pub enum ClusterMode {
     Sovereign,
 }
 
+/// Callback invoked on every mode transition with `(from, to)`.
+pub type TransitionHook = Box<dyn FnMut(ClusterMode, ClusterMode) + Send>;
+
 /// A Hysteresis-aware Monitor for Cluster Stability.
 /// 
 /// Uses a Leaky Bucket approach to prevent "Mode Jitter" during 
@@ -17,6 +20,7 @@ pub struct ClusterStability {
     last_pulse: Instant,
     miss_threshold: u32,
     recovery_threshold: u32,
+    on_transition: Option<TransitionHook>,
 }
 
 impl ClusterStability {
@@ -28,9 +32,18 @@ impl ClusterStability {
             last_pulse: Instant::now(),
             miss_threshold: 3,    // Panic after 3 missed pulses
             recovery_threshold: 10, // Recover after 10 stable pulses
+            on_transition: None,
         }
     }
 
+    /// Registers a hook fired after each mode transition.
+    ///
+    /// The hook runs synchronously inside `record_success`/`record_miss`,
+    /// so it should only signal work, not perform it.
+    pub fn on_transition(&mut self, hook: impl FnMut(ClusterMode, ClusterMode) + Send + 'static) {
+        self.on_transition = Some(Box::new(hook));
+    }
+
     /// Records a successful gossip heartbeat.
     pub fn record_success(&mut self) {
         self.consecutive_misses = 0;
@@ -68,8 +81,12 @@ impl ClusterStability {
             new_mode,
             guard.collector() // Simulated Epoch ID for debugging global state timeline
         );
+        let old_mode = self.mode;
         self.mode = new_mode;
         self.consecutive_misses = 0;
         self.consecutive_stable = 0;
+        if let Some(hook) = self.on_transition.as_mut() {
+            hook(old_mode, new_mode);
+        }
     }
 }
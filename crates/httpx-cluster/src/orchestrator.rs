@@ -1,9 +1,83 @@
 use std::sync::Arc;
-use tokio::sync::mpsc;
-use tokio::time::{interval, Duration, Instant};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::sync::{mpsc, watch};
+use tokio::time::{interval, interval_at, Duration, Instant};
 use httpx_dsa::LinearIntentTrie;
 use crate::gossip::GossipProtocol;
-use httpx_core::ControlSignal;
+use crate::monitor::{ClusterMode, ClusterStability};
+use crate::reconcile::ReconciliationBuffer;
+use httpx_core::{ControlSignal, LearnReceiver, SessionMode};
+
+/// When offline (Sovereign) learnings are folded back into the cluster.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlushPolicy {
+    /// Merge, broadcast and clear on the Sovereign -> Integrated transition.
+    OnRecovery,
+    /// Only flush when `flush_reconciliation` is called explicitly.
+    Manual,
+}
+
+/// Swap interval under sustained peak learning.
+pub const DEFAULT_MIN_SWAP_INTERVAL: Duration = Duration::from_millis(20);
+/// Swap interval when learning is idle.
+pub const DEFAULT_MAX_SWAP_INTERVAL: Duration = Duration::from_millis(500);
+/// Learning rate (events/sec) at which the cadence bottoms out at the minimum.
+pub const SATURATION_RATE: f64 = 10_000.0;
+/// Finest tick `run` wakes at, however small the minimum swap interval.
+const MIN_SWAP_TICK: Duration = Duration::from_millis(1);
+/// How often gossip peers are pulsed; a period without any contact is a missed heartbeat.
+pub const DEFAULT_HEARTBEAT_PERIOD: Duration = Duration::from_millis(100);
+
+/// EWMA of the learning event rate, in events/sec.
+///
+/// Events are counted in fixed windows; each closed window folds its rate
+/// into the average, and windows with no events decay it toward zero.
+#[derive(Debug, Clone)]
+pub struct LearningRateMeter {
+    rate: f64,
+    pending: u64,
+    window_start: Instant,
+}
+
+impl LearningRateMeter {
+    /// Length of one sampling window.
+    pub const WINDOW: Duration = Duration::from_millis(100);
+    /// Weight of the newest window.
+    pub const ALPHA: f64 = 0.5;
+
+    pub fn new(now: Instant) -> Self {
+        Self { rate: 0.0, pending: 0, window_start: now }
+    }
+
+    /// Counts `events` learning events observed at `now`.
+    pub fn record(&mut self, events: u64, now: Instant) {
+        let (rate, window_start) = self.roll(now);
+        if window_start != self.window_start {
+            self.rate = rate;
+            self.window_start = window_start;
+            self.pending = 0;
+        }
+        self.pending += events;
+    }
+
+    /// The averaged rate as of `now`, counting only closed windows.
+    pub fn rate(&self, now: Instant) -> f64 {
+        self.roll(now).0
+    }
+
+    /// Folds every window closed by `now` into the average.
+    fn roll(&self, now: Instant) -> (f64, Instant) {
+        let elapsed = now.saturating_duration_since(self.window_start);
+        let windows = (elapsed.as_nanos() / Self::WINDOW.as_nanos()) as u32;
+        if windows == 0 {
+            return (self.rate, self.window_start);
+        }
+        let current = self.pending as f64 / Self::WINDOW.as_secs_f64();
+        let mut rate = Self::ALPHA * current + (1.0 - Self::ALPHA) * self.rate;
+        rate *= (1.0 - Self::ALPHA).powi((windows - 1) as i32);
+        (rate, self.window_start + Self::WINDOW * windows)
+    }
+}
 
 /// ThrottledAggregator: Minimizes control-plane noise by batching learning events.
 /// 
@@ -13,41 +87,279 @@ pub struct ClusterOrchestrator {
     core_id: usize,
     /// Shadow Trie used for accumulating global knowledge.
     shadow_trie: LinearIntentTrie,
+    /// Build-time routes re-applied to the shadow before every swap.
+    base_routes: Option<LinearIntentTrie>,
+    /// Reloaded route sets replacing `base_routes`; see `with_route_updates`.
+    route_updates: Option<mpsc::Receiver<LinearIntentTrie>>,
     /// Aggregator for learning events from all worker cores.
-    learn_rx: mpsc::UnboundedReceiver<(Vec<u8>, bool)>,
+    learn_rx: LearnReceiver,
     /// Broadcast channels to worker cores (Control Plane).
     worker_txs: Vec<mpsc::Sender<ControlSignal>>,
     /// Gossip handle for multi-node sync.
     gossip: Option<Arc<GossipProtocol>>,
+    /// Gossip peers pulsed every `heartbeat_period`.
+    peers: Vec<String>,
+    heartbeat_period: Duration,
+    /// Hysteresis monitor for cluster heartbeats.
+    stability: ClusterStability,
+    /// Publishes the current cluster mode to observers.
+    mode_tx: watch::Sender<ClusterMode>,
+    /// Learnings accumulated while Sovereign.
+    reconcile: ReconciliationBuffer,
+    flush_policy: FlushPolicy,
+    /// Raised by the stability hook on Sovereign -> Integrated.
+    recovered: Arc<AtomicBool>,
     
     // Throttling state
     events_since_swap: usize,
     last_swap: Instant,
+    /// Drives the swap cadence between `min_swap_interval` and `max_swap_interval`.
+    learning_rate: LearningRateMeter,
+    min_swap_interval: Duration,
+    max_swap_interval: Duration,
 }
 
 impl ClusterOrchestrator {
     pub fn new(
         core_id: usize,
-        learn_rx: mpsc::UnboundedReceiver<(Vec<u8>, bool)>,
+        learn_rx: LearnReceiver,
         worker_txs: Vec<mpsc::Sender<ControlSignal>>,
     ) -> Self {
+        let recovered = Arc::new(AtomicBool::new(false));
+        let mut stability = ClusterStability::new();
+        let flag = recovered.clone();
+        stability.on_transition(move |from, to| {
+            if from == ClusterMode::Sovereign && to == ClusterMode::Integrated {
+                flag.store(true, Ordering::Release);
+            }
+        });
+
         Self {
             core_id,
             shadow_trie: LinearIntentTrie::new(1024),
+            base_routes: None,
+            route_updates: None,
             learn_rx,
             worker_txs,
             gossip: None,
+            peers: Vec::new(),
+            heartbeat_period: DEFAULT_HEARTBEAT_PERIOD,
+            stability,
+            mode_tx: watch::channel(ClusterMode::Integrated).0,
+            reconcile: ReconciliationBuffer::new(),
+            flush_policy: FlushPolicy::OnRecovery,
+            recovered,
             events_since_swap: 0,
             last_swap: Instant::now(),
+            learning_rate: LearningRateMeter::new(Instant::now()),
+            min_swap_interval: DEFAULT_MIN_SWAP_INTERVAL,
+            max_swap_interval: DEFAULT_MAX_SWAP_INTERVAL,
+        }
+    }
+
+    /// Bounds the adaptive swap interval.
+    ///
+    /// Swaps run every `max` when learning is idle, tightening linearly to
+    /// every `min` as the learning rate approaches `SATURATION_RATE`.
+    ///
+    /// `run` checks for a due swap every `min` (at least 1 ms), idle or not,
+    /// so a smaller `min` also means more frequent idle wakeups; with the
+    /// defaults that is every 20 ms.
+    pub fn with_swap_cadence(mut self, min: Duration, max: Duration) -> Self {
+        assert!(min <= max, "ClusterOrchestrator: min swap interval exceeds max");
+        self.min_swap_interval = min;
+        self.max_swap_interval = max;
+        self
+    }
+
+    /// Smoothed learning rate in events/sec.
+    pub fn learning_rate(&self) -> f64 {
+        self.learning_rate.rate(Instant::now())
+    }
+
+    /// The current swap interval, derived from `learning_rate`.
+    pub fn swap_interval(&self) -> Duration {
+        let load = (self.learning_rate() / SATURATION_RATE).clamp(0.0, 1.0);
+        self.max_swap_interval - (self.max_swap_interval - self.min_swap_interval).mul_f64(load)
+    }
+
+    /// Overrides when offline learnings are flushed back to the cluster.
+    pub fn with_flush_policy(mut self, policy: FlushPolicy) -> Self {
+        self.flush_policy = policy;
+        self
+    }
+
+    /// Preserves the routes of `base` across every Shadow-Swap.
+    ///
+    /// Without it, the swapped-in trie only knows what was learned, and
+    /// statically registered routes stop resolving after the first swap.
+    /// The shadow trie is rebuilt with `base`'s configuration (encoding,
+    /// depth cap, budget, limit, allocator) so the routes map onto it.
+    pub fn with_base_routes(mut self, base: LinearIntentTrie) -> Self {
+        let sequence = self.shadow_trie.sequence_number;
+        self.shadow_trie = base.empty_like();
+        self.shadow_trie.sequence_number = sequence;
+        self.base_routes = Some(base);
+        self
+    }
+
+    /// Takes replacement route sets (e.g. from a `RouteWatcher`) on `rx`.
+    ///
+    /// Each one is applied by `reload_routes` from within `run`, so a
+    /// reload survives later swaps instead of being reverted by them.
+    pub fn with_route_updates(mut self, rx: mpsc::Receiver<LinearIntentTrie>) -> Self {
+        self.route_updates = Some(rx);
+        self
+    }
+
+    /// Replaces the base routes and swaps them in at once.
+    ///
+    /// Routes missing from `base` are unbound; learned weights are kept and
+    /// the new routes are carried onto them by `trigger_global_swap`. If
+    /// `base` uses a different path encoding, the learned weights cannot be
+    /// mapped across and the shadow trie starts over from `base`'s config.
+    pub async fn reload_routes(&mut self, base: LinearIntentTrie) {
+        if base.path_encoding() == self.shadow_trie.path_encoding() {
+            self.shadow_trie.clear_routes();
+        } else {
+            let sequence = self.shadow_trie.sequence_number;
+            self.shadow_trie = base.empty_like();
+            self.shadow_trie.sequence_number = sequence;
         }
+        self.base_routes = Some(base);
+        self.trigger_global_swap().await;
     }
 
+    /// Returns the shadow trie accumulating global knowledge.
+    pub fn shadow_trie(&self) -> &LinearIntentTrie {
+        &self.shadow_trie
+    }
+
+    /// Number of distinct contexts buffered while Sovereign.
+    pub fn pending_reconciliation(&self) -> usize {
+        self.reconcile.len()
+    }
+
+    /// Attaches the gossip node whose received heartbeats and deltas drive
+    /// `record_heartbeat` from `run`.
     pub fn with_gossip(mut self, gossip: Arc<GossipProtocol>) -> Self {
         self.gossip = Some(gossip);
         self
     }
 
+    /// Sets the peers `run` pulses with gossip heartbeats.
+    pub fn with_peers(mut self, peers: Vec<String>) -> Self {
+        self.peers = peers;
+        self
+    }
+
+    /// Overrides how often peers are pulsed and silence is counted as a miss.
+    pub fn with_heartbeat_period(mut self, period: Duration) -> Self {
+        self.heartbeat_period = period;
+        self
+    }
+
+    /// Returns a receiver that observes cluster mode transitions.
+    pub fn mode_watch(&self) -> watch::Receiver<ClusterMode> {
+        self.mode_tx.subscribe()
+    }
+
+    /// Feeds a gossip heartbeat outcome into the hysteresis monitor.
+    ///
+    /// On a mode transition, workers are told to run their sessions as
+    /// `SovereignAutonomous` (trusting local learning 2x) or back as
+    /// `ClusterIntegrated`. Under `FlushPolicy::OnRecovery`, a
+    /// Sovereign -> Integrated transition also flushes the reconciliation
+    /// buffer before returning.
+    pub async fn record_heartbeat(&mut self, success: bool) {
+        if success {
+            self.stability.record_success();
+        } else {
+            self.stability.record_miss();
+        }
+        let current = self.stability.current_mode();
+        let changed = self.mode_tx.send_if_modified(|mode| {
+            let changed = *mode != current;
+            *mode = current;
+            changed
+        });
+        if changed {
+            let session_mode = match current {
+                ClusterMode::Integrated => SessionMode::ClusterIntegrated,
+                ClusterMode::Sovereign => SessionMode::SovereignAutonomous,
+            };
+            for tx in &self.worker_txs {
+                let _ = tx.send(ControlSignal::SetSessionMode(session_mode)).await;
+            }
+        }
+
+        if self.recovered.swap(false, Ordering::AcqRel) && self.flush_policy == FlushPolicy::OnRecovery {
+            self.flush_reconciliation().await;
+        }
+    }
+
+    /// Ingests a single learning event from a worker core.
+    ///
+    /// While Sovereign, events are parked in the reconciliation buffer
+    /// instead of the shadow trie, so they rejoin the cluster as one batch.
+    pub async fn ingest(&mut self, path: &[u8], success: bool) {
+        self.ingest_weighted(path, success, 1).await;
+    }
+
+    /// Like `ingest`, but the event counts as `weight` observations
+    /// (`LearnEvent::weight`, 2 for events from Sovereign sessions).
+    pub async fn ingest_weighted(&mut self, path: &[u8], success: bool, weight: u8) {
+        if self.stability.current_mode() == ClusterMode::Sovereign {
+            for _ in 0..weight {
+                self.reconcile.record_path(path, success);
+            }
+            return;
+        }
+
+        for _ in 0..weight {
+            self.shadow_trie.observe(path, success);
+        }
+        self.events_since_swap += 1;
+        self.learning_rate.record(1, Instant::now());
+
+        // Task 1 Throttling: trigger on event count
+        if self.events_since_swap >= 1000 {
+            self.trigger_global_swap().await;
+        }
+    }
+
+    /// Merges offline learnings into the shadow trie, broadcasts, then clears the buffer.
+    pub async fn flush_reconciliation(&mut self) {
+        if self.reconcile.is_empty() {
+            return;
+        }
+        self.reconcile.merge_into(&mut self.shadow_trie);
+        self.reconcile.clear();
+        self.trigger_global_swap().await;
+    }
+
+    /// Discards all learned behavior cluster-wide, keeping registered routes.
+    ///
+    /// Resets the shadow trie, drops pending offline learnings, and tells
+    /// every worker to reset its active trie via `ControlSignal::ResetLearning`.
+    pub async fn reset_learning(&mut self) {
+        self.shadow_trie.reset_learning();
+        self.reconcile.clear();
+        self.events_since_swap = 0;
+        for tx in &self.worker_txs {
+            let _ = tx.send(ControlSignal::ResetLearning).await;
+        }
+        tracing::warn!("ClusterOrchestrator: Learned weights reset cluster-wide.");
+    }
+
     /// Orchestration Loop: Performs event aggregation and periodic Shadow-Swap.
+    ///
+    /// With gossip attached, every heartbeat or delta a peer sends counts as
+    /// a successful heartbeat, and each `heartbeat_period` without one as a
+    /// miss.
+    ///
+    /// Returns once every `LearnSender` has been dropped (i.e. all workers
+    /// have exited), after a final swap of any events not yet broadcast.
     pub async fn run(mut self) {
         // Task 1: Core-Pinned Orchestration
         let core_ids = core_affinity::get_core_ids().unwrap_or_default();
@@ -56,30 +368,51 @@ impl ClusterOrchestrator {
             tracing::info!("ClusterOrchestrator pinned to core {}", self.core_id);
         }
 
-        let mut timer = interval(Duration::from_millis(100));
+        // Ticks at the finest cadence; `swap_interval` decides which ones swap.
+        // `interval` panics on a zero period.
+        let mut timer = interval(self.min_swap_interval.max(MIN_SWAP_TICK));
+        let mut contacts = self.gossip.as_ref().map(|gossip| gossip.contacts());
+        let mut pulse = interval_at(Instant::now() + self.heartbeat_period, self.heartbeat_period);
+        let mut heard = false;
         
         loop {
             tokio::select! {
-                Some((path, success)) = self.learn_rx.recv() => {
-                    self.shadow_trie.observe(&path, success);
-                    self.events_since_swap += 1;
-                    
-                    // Task 1 Throttling: trigger on event count
-                    if self.events_since_swap >= 1000 {
+                event = self.learn_rx.recv() => match event {
+                    Some(event) => self.ingest_weighted(event.path(), event.success, event.weight).await,
+                    None => break,
+                },
+                _ = timer.tick() => {
+                    // Task 1 Throttling: trigger on time, faster under heavy learning
+                    if self.events_since_swap > 0 && self.last_swap.elapsed() >= self.swap_interval() {
                         self.trigger_global_swap().await;
                     }
                 }
-                _ = timer.tick() => {
-                    // Task 1 Throttling: trigger on time
-                    if self.events_since_swap > 0 && self.last_swap.elapsed() >= Duration::from_millis(100) {
-                        self.trigger_global_swap().await;
+                base = next_routes(&mut self.route_updates) => self.reload_routes(base).await,
+                _ = next_contact(&mut contacts) => {
+                    heard = true;
+                    self.record_heartbeat(true).await;
+                }
+                _ = pulse.tick(), if self.gossip.is_some() => {
+                    if let Some(gossip) = &self.gossip {
+                        gossip.heartbeat(&self.peers);
+                    }
+                    if !std::mem::take(&mut heard) {
+                        self.record_heartbeat(false).await;
                     }
                 }
             }
         }
+
+        if self.events_since_swap > 0 {
+            self.trigger_global_swap().await;
+        }
+        tracing::info!("ClusterOrchestrator: Learning channel closed, exiting.");
     }
 
     async fn trigger_global_swap(&mut self) {
+        if let Some(base) = &self.base_routes {
+            self.shadow_trie.carry_routes_from(base);
+        }
         self.shadow_trie.sequence_number += 1;
         tracing::info!(
             "ClusterOrchestrator: Shadow-Swap Handshake [Seq: {}] (Events: {})", 
@@ -106,3 +439,23 @@ impl ClusterOrchestrator {
         self.last_swap = Instant::now();
     }
 }
+
+/// Resolves with the next reloaded route set; never, without a source.
+async fn next_routes(rx: &mut Option<mpsc::Receiver<LinearIntentTrie>>) -> LinearIntentTrie {
+    if let Some(rx) = rx {
+        if let Some(base) = rx.recv().await {
+            return base;
+        }
+    }
+    std::future::pending().await
+}
+
+/// Resolves on the next peer contact; never, without gossip.
+async fn next_contact(contacts: &mut Option<watch::Receiver<u64>>) {
+    if let Some(rx) = contacts {
+        if rx.changed().await.is_ok() {
+            return;
+        }
+    }
+    std::future::pending().await
+}
@@ -1,20 +1,44 @@
 use std::collections::HashMap;
-use httpx_dsa::LinearIntentTrie;
+use httpx_dsa::{context_hash, LinearIntentTrie};
 
 /// A Buffer for storing local learnings during a network partition.
 pub struct ReconciliationBuffer {
     /// Context Hash -> (Success Count, Failure Count)
     learnings: HashMap<u64, (u32, u32)>,
+    /// Context Hash -> Original path, for learnings recorded with `record_path`.
+    paths: HashMap<u64, Vec<u8>>,
 }
 
 impl ReconciliationBuffer {
     pub fn new() -> Self {
         Self {
             learnings: HashMap::new(),
+            paths: HashMap::new(),
         }
     }
 
+    /// Records a local learning event for a concrete path.
+    ///
+    /// Unlike `record`, the path is retained so the event can be replayed
+    /// into a trie on merge.
+    pub fn record_path(&mut self, path: &[u8], response_bit: bool) {
+        let hash = context_hash(path);
+        self.paths.entry(hash).or_insert_with(|| path.to_vec());
+        self.record(hash, response_bit);
+    }
+
+    /// Number of distinct contexts with buffered learnings.
+    pub fn len(&self) -> usize {
+        self.learnings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.learnings.is_empty()
+    }
+
     /// Records a local learning event.
+    ///
+    /// `context_hash` must come from `httpx_dsa::context_hash`.
     pub fn record(&mut self, context_hash: u64, response_bit: bool) {
         let entry = self.learnings.entry(context_hash).or_insert((0, 0));
         if response_bit {
@@ -25,20 +49,27 @@ impl ReconciliationBuffer {
     }
 
     /// Performs a Weighted Average Merge of offline learnings into a Trie.
-    pub fn merge_into(&self, _trie: &mut LinearIntentTrie) {
+    ///
+    /// Repeated records of a context fold into a single proportional weight
+    /// increment: a path seen 100 times carries 100x the influence of one
+    /// seen once (up to the trie's `u8` weight ceiling).
+    pub fn merge_into(&self, trie: &mut LinearIntentTrie) {
         tracing::info!("RECONCILE: Merging {} offline learnings", self.learnings.len());
         
         for (hash, (s, f)) in &self.learnings {
-            // # Mechanical Sympathy: In production, we'd map the hash back to a trie path.
-            // For now, we simulate the merge logic.
-            let _ = hash;
-            let _ = s;
-            let _ = f;
-            // Conceptually: Update trie weights with (s, f) increments.
+            // Hash-only learnings (from `record`) have no path to replay.
+            let Some(path) = self.paths.get(hash) else { continue };
+            if *s > 0 {
+                trie.observe_weighted(path, true, *s);
+            }
+            if *f > 0 {
+                trie.observe_weighted(path, false, *f);
+            }
         }
     }
 
     pub fn clear(&mut self) {
         self.learnings.clear();
+        self.paths.clear();
     }
 }
@@ -1,5 +1,7 @@
 pub mod templates;
-pub use templates::HeaderTemplate;
+#[cfg(feature = "slab")]
+pub use templates::{DoubleBufferedTemplate, HeaderTemplate};
+pub use templates::HeapTemplate;
 
 pub struct ProbabilisticCodec {
     // Current Markov state or projection matrix
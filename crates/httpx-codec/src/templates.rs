@@ -1,48 +1,69 @@
-use httpx_dsa::SecureSlab;
+#[cfg(feature = "slab")]
+use httpx_dsa::{SecureSlab, SlabError};
+#[cfg(feature = "slab")]
 use core::ptr;
+#[cfg(feature = "slab")]
+use core::sync::atomic::{fence, AtomicU32, Ordering};
+#[cfg(feature = "slab")]
+use std::sync::Mutex;
+
+/// Fixed width of a header template block.
+const TEMPLATE_LEN: usize = 128;
+
+/// Locates the hot-patchable Date and Content-Length offsets in `base_headers`.
+fn scan_offsets(base_headers: &[u8]) -> (usize, usize) {
+    // Mechanical Sympathy Search: Find offsets for Date and Content-Length.
+    // In a production system, we'd use a SIMD-optimized scanner.
+    let mut date_offset = 0;
+    let mut cl_offset = 0;
+
+    for i in 0..(base_headers.len().saturating_sub(6)) {
+        let slice = &base_headers[i..i+5];
+        if slice == b"Date:" {
+            date_offset = i + 6; // Skip "Date: "
+        } else if slice == b"Late:" { // Check for Content-Length (simplified)
+             // placeholder search
+        }
+    }
+
+    // Finalize offsets (simulated for the challenge)
+    // Production logic would ensure these are correctly identified.
+    if date_offset == 0 { date_offset = 20; }
+    if cl_offset == 0 { cl_offset = 80; }
+
+    (date_offset, cl_offset)
+}
 
 /// Procrustean Templates: Fixed-width header blocks with hot-patchable fields.
 /// 
 /// Designed for sub-microsecond response generation. The dispatcher links 
 /// these templates to data fragments using io_uring link chains.
+///
+/// Patches land in place, so a concurrent send can observe a half-patched
+/// block; use `DoubleBufferedTemplate` when patching and sending overlap.
+#[cfg(feature = "slab")]
 pub struct HeaderTemplate {
     pub slab_handle: u32,
     date_offset: usize,
     cl_offset: usize,
 }
 
+#[cfg(feature = "slab")]
 impl HeaderTemplate {
     /// Creates a new HeaderTemplate and stores it in the SecureSlab.
     /// 
     /// Pre-allocates a 128-byte slot (within a 4KB page) for the header block.
     pub fn new(slab: &SecureSlab, handle: u32, base_headers: &[u8]) -> Self {
-        assert!(base_headers.len() <= 128, "HeaderTemplate: Base headers exceed 128 bytes");
+        assert!(base_headers.len() <= TEMPLATE_LEN, "HeaderTemplate: Base headers exceed 128 bytes");
         
         let ptr = slab.get_slot(handle as usize);
         unsafe {
             // zero out the 128-byte slot first
-            ptr::write_bytes(ptr, 0, 128);
+            ptr::write_bytes(ptr, 0, TEMPLATE_LEN);
             ptr::copy_nonoverlapping(base_headers.as_ptr(), ptr, base_headers.len());
         }
 
-        // Mechanical Sympathy Search: Find offsets for Date and Content-Length.
-        // In a production system, we'd use a SIMD-optimized scanner.
-        let mut date_offset = 0;
-        let mut cl_offset = 0;
-        
-        for i in 0..(base_headers.len().saturating_sub(6)) {
-            let slice = &base_headers[i..i+5];
-            if slice == b"Date:" {
-                date_offset = i + 6; // Skip "Date: "
-            } else if slice == b"Late:" { // Check for Content-Length (simplified)
-                 // placeholder search
-            }
-        }
-        
-        // Finalize offsets (simulated for the challenge)
-        // Production logic would ensure these are correctly identified.
-        if date_offset == 0 { date_offset = 20; } 
-        if cl_offset == 0 { cl_offset = 80; }
+        let (date_offset, cl_offset) = scan_offsets(base_headers);
 
         Self {
             slab_handle: handle,
@@ -74,3 +95,174 @@ impl HeaderTemplate {
         }
     }
 }
+
+/// A `HeaderTemplate` split across two slab slots so patches never tear a send.
+///
+/// Patches copy the active block into the inactive slot, apply the change
+/// there and then publish it with an atomic swap of the active handle. The
+/// inactive slot's version is odd while it is being written (a seqlock on the
+/// slab's version counter), so `read` retries instead of returning a mix of
+/// old and new fields. Patches are serialized internally and may come from
+/// any thread.
+///
+/// A handle sent to the kernel must be held by RC until the send completes:
+/// a patch that would reuse a slot still in flight fails with
+/// `SlabError::InFlight` and leaves the published block untouched.
+#[cfg(feature = "slab")]
+pub struct DoubleBufferedTemplate {
+    handles: [u32; 2],
+    /// Handle of the published block.
+    active: AtomicU32,
+    date_offset: usize,
+    cl_offset: usize,
+    /// Serializes patches; readers never take it.
+    writer: Mutex<()>,
+}
+
+#[cfg(feature = "slab")]
+impl DoubleBufferedTemplate {
+    /// Stores `base_headers` in both slots and publishes `handles[0]`.
+    pub fn new(slab: &SecureSlab, handles: [u32; 2], base_headers: &[u8]) -> Self {
+        assert!(base_headers.len() <= TEMPLATE_LEN, "DoubleBufferedTemplate: Base headers exceed 128 bytes");
+        assert!(handles[0] != handles[1], "DoubleBufferedTemplate: buffers must be distinct slots");
+
+        for &handle in &handles {
+            let ptr = slab.get_slot(handle as usize);
+            unsafe {
+                ptr::write_bytes(ptr, 0, TEMPLATE_LEN);
+                ptr::copy_nonoverlapping(base_headers.as_ptr(), ptr, base_headers.len());
+            }
+            // Readers treat an odd version as "write in progress".
+            if !slab.get_version(handle as usize).is_multiple_of(2) {
+                slab.increment_version(handle as usize);
+            }
+        }
+
+        let (date_offset, cl_offset) = scan_offsets(base_headers);
+
+        Self {
+            handles,
+            active: AtomicU32::new(handles[0]),
+            date_offset,
+            cl_offset,
+            writer: Mutex::new(()),
+        }
+    }
+
+    /// Handle of the currently published header block.
+    pub fn active_handle(&self) -> u32 {
+        self.active.load(Ordering::Acquire)
+    }
+
+    /// Patches the Date field and publishes the result.
+    pub fn patch_date(&self, slab: &SecureSlab, date: &[u8]) -> Result<(), SlabError> {
+        let len = date.len().min(29);
+        self.publish(slab, |block| {
+            block[self.date_offset..self.date_offset + len].copy_from_slice(&date[..len]);
+        })
+    }
+
+    /// Patches the Content-Length field and publishes the result.
+    pub fn patch_content_length(&self, slab: &SecureSlab, length: u32) -> Result<(), SlabError> {
+        let len_str = length.to_string();
+        let len_bytes = &len_str.as_bytes()[..len_str.len().min(10)];
+        self.publish(slab, |block| {
+            block[self.cl_offset..self.cl_offset + len_bytes.len()].copy_from_slice(len_bytes);
+        })
+    }
+
+    /// Returns a consistent copy of the published 128-byte header block.
+    pub fn read(&self, slab: &SecureSlab) -> [u8; TEMPLATE_LEN] {
+        let mut out = [0u8; TEMPLATE_LEN];
+        loop {
+            let handle = self.active.load(Ordering::Acquire) as usize;
+            let before = slab.get_version(handle);
+            if before.is_multiple_of(2) {
+                unsafe {
+                    ptr::copy_nonoverlapping(slab.get_slot(handle), out.as_mut_ptr(), TEMPLATE_LEN);
+                }
+                fence(Ordering::Acquire);
+                // Unchanged even version: no patch touched the slot during the copy.
+                if slab.get_version(handle) == before {
+                    return out;
+                }
+            }
+            core::hint::spin_loop();
+        }
+    }
+
+    /// Copies the active block, applies `patch` to the copy, writes it into
+    /// the inactive slot and swaps it in.
+    ///
+    /// Refuses with `SlabError::InFlight` while the inactive slot is still
+    /// referenced by a send.
+    fn publish(&self, slab: &SecureSlab, patch: impl FnOnce(&mut [u8; TEMPLATE_LEN])) -> Result<(), SlabError> {
+        let _writer = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+        let current = self.active.load(Ordering::Acquire);
+        let next = if current == self.handles[0] { self.handles[1] } else { self.handles[0] };
+        if slab.is_in_flight(next as usize) {
+            return Err(SlabError::InFlight(next as usize));
+        }
+
+        // Only this writer touches `current` outside of reads, so it is stable here.
+        let mut block = [0u8; TEMPLATE_LEN];
+        slab.read_slot(current as usize, 0, &mut block)?;
+        patch(&mut block);
+
+        slab.increment_version(next as usize); // odd: write in progress
+        let written = slab.write_slot(next as usize, 0, &block);
+        // With version-on-write, `write_slot` already closed the odd window.
+        if !slab.get_version(next as usize).is_multiple_of(2) {
+            slab.increment_version(next as usize); // even: stable
+        }
+        written?;
+        self.active.store(next, Ordering::Release);
+        Ok(())
+    }
+}
+
+/// Heap-backed Procrustean Template.
+///
+/// Same layout and patch semantics as `HeaderTemplate`, but owns its 128-byte
+/// block instead of borrowing a `SecureSlab` slot. Available on targets
+/// without `mmap`/`mprotect` (e.g. `wasm32-unknown-unknown`).
+pub struct HeapTemplate {
+    block: [u8; TEMPLATE_LEN],
+    date_offset: usize,
+    cl_offset: usize,
+}
+
+impl HeapTemplate {
+    /// Creates a new HeapTemplate from the base header block.
+    pub fn new(base_headers: &[u8]) -> Self {
+        assert!(base_headers.len() <= TEMPLATE_LEN, "HeapTemplate: Base headers exceed 128 bytes");
+
+        let mut block = [0u8; TEMPLATE_LEN];
+        block[..base_headers.len()].copy_from_slice(base_headers);
+        let (date_offset, cl_offset) = scan_offsets(base_headers);
+
+        Self {
+            block,
+            date_offset,
+            cl_offset,
+        }
+    }
+
+    /// Hot-Patches the Date field.
+    pub fn patch_date(&mut self, date: &[u8]) {
+        let len = date.len().min(29);
+        self.block[self.date_offset..self.date_offset + len].copy_from_slice(&date[..len]);
+    }
+
+    /// Hot-Patches the Content-Length field.
+    pub fn patch_content_length(&mut self, length: u32) {
+        let len_str = length.to_string();
+        let len_bytes = &len_str.as_bytes()[..len_str.len().min(10)];
+        self.block[self.cl_offset..self.cl_offset + len_bytes.len()].copy_from_slice(len_bytes);
+    }
+
+    /// Returns the full 128-byte header block.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.block
+    }
+}
@@ -2,10 +2,15 @@ extern crate alloc;
 use core::sync::atomic::{AtomicUsize, Ordering};
 use alloc::vec::Vec;
 use std::sync::Arc;
+use crate::session::Session;
 
-#[derive(Debug)]
+/// Why a predictive push was dropped instead of enqueued.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DropReason {
+    /// The bridge is full; the transport loop is behind.
     Congested,
+    /// The session has no IIW credits left; wait for an IntentAck.
+    CreditExhausted,
 }
 
 #[repr(align(64))]
@@ -13,6 +18,15 @@ struct CacheAlignedAtomic(AtomicUsize);
 
 /// A wait-free SPSC Ring Buffer for bridging the PredictiveEngine to the Transport Loop.
 /// 
+/// ## Ordering Contract
+/// Each push publishes its item with a `Release` store of the head, which a
+/// `pop` pairs with an `Acquire` load: an item the consumer can see is fully
+/// written. To signal the consumer out of band (a flag, eventfd or channel),
+/// the producer calls `fence` (or uses `publish_batch`) after pushing and
+/// before notifying; a consumer that observes the notification with an
+/// `Acquire` load, or a relaxed load followed by an `Acquire` fence, then
+/// sees every item pushed before the fence.
+///
 /// ## Mechanical Sympathy
 /// - **Cache-Line Padding**: Head and Tail pointers are separated by 64 bytes to prevent False Sharing.
 /// - **Power-of-Two Sizing**: Index wrapping uses bitwise AND instead of expensive modulo.
@@ -60,6 +74,53 @@ impl<T> SqBridge<T> {
         Ok(())
     }
 
+    /// Pushes as many of `items` as fit, in order, then issues one `fence`.
+    ///
+    /// The head is advanced once for the whole batch. Returns how many items
+    /// were pushed; the rest were dropped as `DropReason::Congested`.
+    pub fn publish_batch(&self, items: &[T]) -> usize
+    where
+        T: Clone,
+    {
+        let head = self.head.0.load(Ordering::Relaxed);
+        let tail = self.tail.0.load(Ordering::Acquire);
+        let free = self.mask + 1 - head.wrapping_sub(tail);
+        let count = items.len().min(free);
+
+        for (i, item) in items[..count].iter().enumerate() {
+            let idx = head.wrapping_add(i) & self.mask;
+            // # Safety: We are the ONLY producer, and these slots are free.
+            unsafe {
+                let slot = self.buffer.as_ptr().add(idx) as *mut Option<T>;
+                core::ptr::write(slot, Some(item.clone()));
+            }
+        }
+
+        self.head.0.store(head.wrapping_add(count), Ordering::Release);
+        self.fence();
+        count
+    }
+
+    /// Orders every earlier push before any later store by this thread.
+    ///
+    /// A `Release` fence: call it between a batch of `try_push`es and the
+    /// store that notifies the consumer. See the ordering contract above.
+    #[inline]
+    pub fn fence(&self) {
+        std::sync::atomic::fence(Ordering::Release);
+    }
+
+    /// `try_push` charged to `session`'s Initial Intent Window.
+    ///
+    /// Fails with `CreditExhausted` if the session is out of credits, and
+    /// with `Congested` (refunding the credit) if the bridge is full.
+    pub fn try_push_for(&self, session: &Session, item: T) -> Result<(), DropReason> {
+        if !session.consume_credit() {
+            return Err(DropReason::CreditExhausted);
+        }
+        self.try_push(item).inspect_err(|_| session.add_credits(1))
+    }
+
     /// Attempts to pop a predictive intent from the bridge.
     pub fn pop(&self) -> Option<T> {
         let tail = self.tail.0.load(Ordering::Relaxed);
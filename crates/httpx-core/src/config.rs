@@ -1,14 +1,80 @@
 use serde::Deserialize;
 
+/// How worker cores are mapped onto Tokio runtimes.
+///
+/// ## Mechanical Sympathy
+/// `PerCoreCurrentThread` keeps each dispatcher, its socket and its ring on
+/// one OS thread with no work stealing: no cross-core wakeups and a warm
+/// cache. `SharedMultiThread` drives every worker's I/O and timers from one
+/// shared work-stealing pool, so auxiliary tasks spawned by a worker can run
+/// anywhere, at the cost of cross-core wakeups on the hot path.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RuntimeFlavor {
+    /// One current-thread runtime per worker (the one-task-per-core model).
+    #[default]
+    PerCoreCurrentThread,
+    /// One multi-threaded runtime shared by all workers, with `threads`
+    /// runtime threads per listen address.
+    SharedMultiThread,
+}
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct ServerConfig {
     pub host: String,
     pub port: u16,
     pub threads: usize,
     pub max_intent_credits: u32,
+    /// Bytes of continuation the engine may speculate past a matched context.
+    /// `0` disables speculation; only exact routes are pushed.
     pub predictive_depth: usize,
     pub slab_capacity: usize,
     pub production_mode: bool,
+    /// Egress pacing rate in bytes/sec. `None` disables pacing.
+    pub pacing_rate: Option<u64>,
+    /// Pending learning events each worker keeps before the oldest are dropped.
+    #[serde(default = "default_learn_capacity")]
+    pub learn_capacity: usize,
+    /// Runtime layout of the worker swarm.
+    #[serde(default)]
+    pub runtime_flavor: RuntimeFlavor,
+    /// Protocol versions accepted in framed requests; others are rejected.
+    #[serde(default = "default_accepted_versions")]
+    pub accepted_versions: Vec<u8>,
+    /// Receive datagrams straight into pinned buffers via io_uring `RecvMsg`.
+    ///
+    /// Each worker lands them in its own ingress pool, separate from the
+    /// payload slab; see `CoreDispatcher::with_ingress_pool`.
+    #[serde(default)]
+    pub zero_copy_ingress: bool,
+    /// Most CQ passes one reap may take before returning to the event loop.
+    ///
+    /// Each pass drains every posted completion and flushes any the kernel
+    /// held back on CQ overflow; reaping stops early once a pass finds the
+    /// queue empty. Bounds the time a burst of completions can hold the loop.
+    #[serde(default = "default_reap_drain_iterations")]
+    pub reap_drain_iterations: usize,
+    /// Largest request datagram the run loop accepts, in bytes.
+    ///
+    /// Larger datagrams are detected as truncated, logged and dropped. It
+    /// also sizes the default zero-copy ingress pool's buffers.
+    #[serde(default = "default_max_datagram")]
+    pub max_datagram: usize,
+}
+
+fn default_learn_capacity() -> usize {
+    crate::learn::DEFAULT_LEARN_CAPACITY
+}
+
+fn default_accepted_versions() -> Vec<u8> {
+    vec![1]
+}
+
+fn default_reap_drain_iterations() -> usize {
+    8
+}
+
+fn default_max_datagram() -> usize {
+    65535
 }
 
 impl Default for ServerConfig {
@@ -21,6 +87,13 @@ impl Default for ServerConfig {
             predictive_depth: 5,
             slab_capacity: 1024,
             production_mode: false,
+            pacing_rate: None,
+            learn_capacity: default_learn_capacity(),
+            runtime_flavor: RuntimeFlavor::default(),
+            accepted_versions: default_accepted_versions(),
+            zero_copy_ingress: false,
+            reap_drain_iterations: default_reap_drain_iterations(),
+            max_datagram: default_max_datagram(),
         }
     }
 }
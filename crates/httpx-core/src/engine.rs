@@ -1,7 +1,45 @@
-use httpx_dsa::LinearIntentTrie;
-use core::sync::atomic::Ordering;
-use crossbeam_epoch::{self as epoch, Atomic, Owned};
-use crate::session::SessionMode;
+use httpx_dsa::{LinearIntentTrie, PathEncoding};
+use httpx_dsa::trie::TrieNode;
+use core::sync::atomic::{AtomicU64, Ordering};
+use crossbeam_epoch::{self as epoch, Atomic, Guard, Owned};
+use std::sync::Mutex;
+
+/// The last lookup's `/`-terminated prefix and the node it reached.
+///
+/// Routes tend to share long prefixes (`/api/v1/...`), so consecutive
+/// lookups resume from this node instead of re-walking the prefix bits.
+#[derive(Default)]
+struct PrefixCache {
+    /// Swap generation of the trie `node` belongs to; 0 when empty.
+    swap: u64,
+    /// `LinearIntentTrie::evictions` when `node` was resolved.
+    generation: u64,
+    prefix: Vec<u8>,
+    node: Option<usize>,
+}
+
+/// The active trie and the swap generation it was installed under.
+///
+/// Generations are never reused, unlike the trie's address once a swapped
+/// out trie is reclaimed, so they identify the trie for `PrefixCache`.
+struct Installed {
+    trie: LinearIntentTrie,
+    generation: u64,
+}
+
+/// One `fire_push_if_likely` outcome, kept for post-incident analysis.
+#[cfg(feature = "decision-trace")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DecisionRecord {
+    /// `httpx_dsa::context_hash` of the context the decision was made for.
+    pub context_hash: u64,
+    pub p_true: f32,
+    pub p_false: f32,
+    /// What the engine returned.
+    pub decision: Option<bool>,
+    /// Whether the session could pay for a push (not canceled, credit left).
+    pub credit_available: bool,
+}
 
 /// The Intelligence Layer of the HTTP-X Transport.
 /// 
@@ -14,27 +52,107 @@ use crate::session::SessionMode;
 /// global weight updates.
 pub struct PredictiveEngine {
     /// Atomic Pointer to the active Behavioral Trie.
-    trie: Atomic<LinearIntentTrie>,
+    trie: Atomic<Installed>,
+    /// Generation of the last installed trie; bumped by every `swap_weights`.
+    swaps: AtomicU64,
     active: bool,
     threshold: f32,
+    /// Maximum bytes of lookahead past the matched context. `0` disables speculation.
+    predictive_depth: usize,
+    /// Last matched prefix node; `None` disables the cache.
+    prefix_cache: Option<Mutex<PrefixCache>>,
+    /// Ring of the most recent push decisions and its capacity; see `with_decision_trace`.
+    #[cfg(feature = "decision-trace")]
+    trace: Option<(Mutex<std::collections::VecDeque<DecisionRecord>>, usize)>,
 }
 
 impl PredictiveEngine {
     pub fn new(active: bool) -> Self {
         Self {
-            trie: Atomic::new(LinearIntentTrie::new(1024)),
+            trie: Atomic::new(Installed { trie: LinearIntentTrie::new(1024), generation: 1 }),
+            swaps: AtomicU64::new(1),
             active,
             threshold: 0.85, // Only push if probability > 85%
+            predictive_depth: usize::MAX,
+            prefix_cache: Some(Mutex::default()),
+            #[cfg(feature = "decision-trace")]
+            trace: None,
         }
     }
 
+    /// Enables or disables the last-matched-prefix lookup cache (default: on).
+    pub fn with_prefix_cache(mut self, enabled: bool) -> Self {
+        self.prefix_cache = enabled.then(Mutex::default);
+        self
+    }
+
+    /// Caps how many bytes past the matched context the engine speculates.
+    ///
+    /// `0` turns speculation off: `predict_top_k` still returns the exact
+    /// route for a matched path, but no continuation, and the `fire_push_*`
+    /// methods always return `None`. The default is unbounded (`usize::MAX`,
+    /// clamped to `MAX_LOOKAHEAD_BYTES` by the trie walk).
+    pub fn with_predictive_depth(mut self, depth: usize) -> Self {
+        self.predictive_depth = depth;
+        self
+    }
+
+    pub fn predictive_depth(&self) -> usize {
+        self.predictive_depth
+    }
+
+    /// Records the last `capacity` `fire_push_if_likely` decisions (default: off).
+    ///
+    /// Forensic aid for explaining a wrong or missing push after the fact;
+    /// read it back with `decision_trace`. `0` disables recording.
+    #[cfg(feature = "decision-trace")]
+    pub fn with_decision_trace(mut self, capacity: usize) -> Self {
+        self.trace = (capacity > 0)
+            .then(|| (Mutex::new(std::collections::VecDeque::with_capacity(capacity)), capacity));
+        self
+    }
+
+    /// Returns the recorded decisions, oldest first.
+    #[cfg(feature = "decision-trace")]
+    pub fn decision_trace(&self) -> Vec<DecisionRecord> {
+        match &self.trace {
+            Some((ring, _)) => ring.lock().unwrap_or_else(|e| e.into_inner()).iter().copied().collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Appends a decision to the trace ring, evicting the oldest when full.
+    ///
+    /// `probabilities` is `None` when the decision was made before the model
+    /// was consulted; they are looked up here so the record stays complete.
+    #[cfg(feature = "decision-trace")]
+    fn record_decision(&self, context: &[u8], probabilities: Option<(f32, f32)>, decision: Option<bool>, credit_available: bool) {
+        let Some((ring, capacity)) = &self.trace else { return };
+        let (p_true, p_false) = probabilities
+            .or_else(|| self.inspect_trie(|t| (t.get_probability(context, true), t.get_probability(context, false))))
+            .unwrap_or((0.0, 0.0));
+        let record = DecisionRecord {
+            context_hash: httpx_dsa::context_hash(context),
+            p_true,
+            p_false,
+            decision,
+            credit_available,
+        };
+        let mut ring = ring.lock().unwrap_or_else(|e| e.into_inner());
+        if ring.len() == *capacity {
+            ring.pop_front();
+        }
+        ring.push_back(record);
+    }
+
     /// Swaps the current Trie with a new one (Global Orchestration).
     /// 
     /// # Safety
     /// Uses `crossbeam-epoch` to ensure that the old Trie is only freed 
     /// after all threads currently reading it have released their guards.
     pub fn swap_weights(&self, new_trie: LinearIntentTrie) {
-        let new_owned = Owned::new(new_trie);
+        let generation = self.swaps.fetch_add(1, Ordering::Relaxed) + 1;
+        let new_owned = Owned::new(Installed { trie: new_trie, generation });
         let guard = epoch::pin();
         
         // # Safety: Epoch-Based Reclamation (EBR) prevents Use-After-Free.
@@ -48,6 +166,118 @@ impl PredictiveEngine {
                 guard.defer_destroy(old);
             }
         }
+
+        // Cached node indices belong to the old trie. Entries tagged with its
+        // generation are already misses; clearing just frees the prefix.
+        if let Some(cache) = &self.prefix_cache {
+            *cache.lock().unwrap_or_else(|e| e.into_inner()) = PrefixCache::default();
+        }
+    }
+
+    /// Merges `incoming` into a copy of the active Trie and swaps the result in.
+    ///
+    /// Unlike `swap_weights`, learning the active Trie gathered since
+    /// `incoming` was forked survives: both sides are folded together with
+    /// `LinearIntentTrie::merge_structural`. Observations landing on the old
+    /// Trie between the copy and the swap are not carried over.
+    pub fn merge_weights(&self, incoming: &LinearIntentTrie) {
+        let merged = {
+            let guard = epoch::pin();
+            // `guard` keeps the active Trie alive while it is cloned.
+            match self.active_trie(&guard) {
+                Some(active) => {
+                    let mut merged = active.trie.clone();
+                    merged.merge_structural(incoming);
+                    merged
+                }
+                None => incoming.clone(),
+            }
+        };
+        self.swap_weights(merged);
+    }
+
+    /// Number of tries installed so far, counting the initial one.
+    ///
+    /// Monotonic; every `swap_weights` (and so every `merge_weights`) bumps it.
+    pub fn swap_generation(&self) -> u64 {
+        self.swaps.load(Ordering::Relaxed)
+    }
+
+    /// The installed trie, kept alive by `guard`.
+    fn active_trie<'g>(&self, guard: &'g Guard) -> Option<&'g Installed> {
+        let shared = self.trie.load(Ordering::Acquire, guard);
+        // # Safety: Acquire ordering ensures we see a fully initialized Trie,
+        // and the guard defers its reclamation until it is dropped.
+        unsafe { shared.as_ref() }
+    }
+
+    /// Resolves `path` in `trie`, resuming from the cached prefix node when
+    /// the previous lookup shared it.
+    ///
+    /// Falls back to a cold walk if the cache is disabled or contended, or
+    /// if the trie hashes its paths (hashed paths share no prefixes).
+    fn node_at<'t>(&self, installed: &'t Installed, path: &[u8]) -> Option<&'t TrieNode> {
+        let trie = &installed.trie;
+        if trie.path_encoding() == PathEncoding::Hashed {
+            return trie.get_node_at_path(path);
+        }
+        let Some(mut cache) = self.prefix_cache.as_ref().and_then(|c| c.try_lock().ok()) else {
+            return trie.get_node_at_path(path);
+        };
+        let split = path.iter().rposition(|&b| b == b'/').map_or(0, |i| i + 1);
+        let prefix = &path[..split];
+
+        // An eviction pass reassigns indices, so a cached node is only
+        // reusable on the same trie between passes.
+        let fresh = cache.swap == installed.generation && cache.generation == trie.evictions();
+        let node = if fresh && cache.prefix == prefix {
+            cache.node
+        } else {
+            let node = if fresh && prefix.starts_with(&cache.prefix) {
+                cache.node.and_then(|from| trie.resume_lookup(from, cache.prefix.len(), prefix))
+            } else {
+                trie.node_index_at_path(prefix)
+            };
+            cache.swap = installed.generation;
+            cache.generation = trie.evictions();
+            cache.prefix.clear();
+            cache.prefix.extend_from_slice(prefix);
+            cache.node = node;
+            node
+        };
+        drop(cache);
+        trie.get_node(trie.resume_lookup(node?, split, path)?)
+    }
+
+    /// Resolves `path` to its route's `(payload_handle, version_id)`.
+    ///
+    /// Unlike `predict_for_path`, consumes no session credit.
+    pub fn lookup(&self, path: &[u8]) -> Option<(u32, u32)> {
+        let guard = epoch::pin();
+        let node = self.node_at(self.active_trie(&guard)?, path)?;
+        (node.payload_handle > 0).then_some((node.payload_handle, node.version_id))
+    }
+
+    /// Runs `f` against the active trie under an epoch guard.
+    ///
+    /// Returns `None` if no trie is installed. Intended for cold-path
+    /// inspection; `f` must not block, as it delays reclamation of swapped tries.
+    pub fn inspect_trie<R>(&self, f: impl FnOnce(&LinearIntentTrie) -> R) -> Option<R> {
+        let guard = epoch::pin();
+        self.active_trie(&guard).map(|installed| f(&installed.trie))
+    }
+
+    /// Digest of the active trie, comparable across nodes. `None` if no trie is installed.
+    pub fn snapshot_digest(&self) -> Option<u64> {
+        self.inspect_trie(|trie| trie.digest())
+    }
+
+    /// Sequence number of the model generation currently being served.
+    ///
+    /// A single epoch-guarded load; never blocks concurrent readers or a
+    /// racing `swap_weights`. Returns 0 if no trie is installed.
+    pub fn active_sequence(&self) -> u64 {
+        self.inspect_trie(|trie| trie.sequence_number).unwrap_or(0)
     }
 
     /// Evaluates the current context and triggers a push if the probability 
@@ -57,7 +287,7 @@ impl PredictiveEngine {
     /// Performs an Acquire-load on the atomic pointer. Lookup is O(k).
     /// Zero-Blocking and Zero-Locking.
     pub fn fire_push_if_likely(&self, session: &crate::session::Session, current_context: &[u8]) -> Option<bool> {
-        if !self.active { return None; }
+        if !self.active || self.predictive_depth == 0 { return None; }
 
         // Initial Intent Window (IIW) Throttling
         if !session.has_credit() || session.is_canceled() {
@@ -66,17 +296,16 @@ impl PredictiveEngine {
             } else {
                 tracing::warn!("IIW: No credits for {}. Predictive Drop.", session.addr);
             }
+            #[cfg(feature = "decision-trace")]
+            self.record_decision(current_context, None, None, false);
             return None;
         }
         
         let guard = epoch::pin();
-        // # Safety: Acquire ordering ensures we see a fully initialized Trie.
         // The `guard` ensures that even if a `swap_weights` occurs concurrently,
-        // the memory pointed to by `trie_shared` will NOT be reclaimed until this
-        // guard is dropped, thus preventing a Use-After-Free (UAF).
-        let trie_shared = self.trie.load(Ordering::Acquire, &guard);
-        
-        let Some(trie) = (unsafe { trie_shared.as_ref() }) else { return None; };
+        // the active Trie will NOT be reclaimed until this guard is dropped,
+        // thus preventing a Use-After-Free (UAF).
+        let Some(Installed { trie, .. }) = self.active_trie(&guard) else { return None; };
         
         // Check probability of next logical intent bit
         let p_true = trie.get_probability(current_context, true);
@@ -93,12 +322,68 @@ impl PredictiveEngine {
         if decision.is_some() {
             // # Mechanical Sympathy: Credit consumption is atomic and lock-free.
             if !session.consume_credit() {
+                #[cfg(feature = "decision-trace")]
+                self.record_decision(current_context, Some((p_true, p_false)), None, false);
                 return None; // Race condition: credit consumed by parallel branch
             }
         }
+        #[cfg(feature = "decision-trace")]
+        self.record_decision(current_context, Some((p_true, p_false)), decision, true);
         decision
     }
 
+    /// Probabilistic alternative to `fire_push_if_likely`.
+    ///
+    /// Instead of the hard threshold cliff, the dominant prediction is pushed
+    /// with probability equal to its likelihood: a 0.6 path pushes ~60% of
+    /// the time. Draws come from the session's xorshift stream. Off the
+    /// default path; callers opt in explicitly.
+    pub fn fire_push_probabilistic(&self, session: &crate::session::Session, current_context: &[u8]) -> Option<bool> {
+        if !self.active || self.predictive_depth == 0 { return None; }
+        if !session.has_credit() || session.is_canceled() { return None; }
+
+        let (p_true, p_false) = self.inspect_trie(|trie| {
+            (trie.get_probability(current_context, true), trie.get_probability(current_context, false))
+        })?;
+        let (bit, p) = if p_true >= p_false { (true, p_true) } else { (false, p_false) };
+
+        if session.next_unit() >= p || !session.consume_credit() {
+            return None;
+        }
+        Some(bit)
+    }
+
+    /// Resolves up to `out.len()` pushes for a matched path.
+    ///
+    /// The exact route (if any) comes first, followed by the strongest
+    /// predicted continuations within `predictive_depth` bytes whose hit
+    /// probability clears the push threshold. Each entry consumes one session credit;
+    /// resolution stops at the first credit that cannot be taken.
+    pub fn predict_top_k(&self, session: &crate::session::Session, path: &[u8], out: &mut [(u32, u32)]) -> usize {
+        if !self.active || out.is_empty() { return 0; }
+        if !session.has_credit() || session.is_canceled() { return 0; }
+
+        let guard = epoch::pin();
+        let Some(installed) = self.active_trie(&guard) else { return 0; };
+        let trie = &installed.trie;
+
+        let mut n = 0;
+        if let Some(node) = self.node_at(installed, path) {
+            if node.payload_handle > 0 {
+                out[0] = (node.payload_handle, node.version_id);
+                n = 1;
+            }
+        }
+        n += trie.top_continuations_within(path, self.predictive_depth, self.threshold, &mut out[n..]);
+
+        for i in 0..n {
+            if !session.consume_credit() {
+                return i;
+            }
+        }
+        n
+    }
+
     /// Predicts payload and version for a given URI path.
     /// Used by the SAI layer to resolve incoming requests to Fast-Path handles.
     pub fn predict_for_path(&self, session: &crate::session::Session, path: &[u8]) -> Option<(u32, u32)> {
@@ -106,10 +391,7 @@ impl PredictiveEngine {
         if !session.has_credit() || session.is_canceled() { return None; }
         
         let guard = epoch::pin();
-        let trie_shared = self.trie.load(Ordering::Acquire, &guard);
-        let trie = unsafe { trie_shared.as_ref() }?;
-        
-        let node = trie.get_node_at_path(path)?;
+        let node = self.node_at(self.active_trie(&guard)?, path)?;
         if node.payload_handle > 0 {
              if session.consume_credit() {
                  return Some((node.payload_handle, node.version_id));
@@ -123,27 +405,22 @@ impl PredictiveEngine {
     /// ## Adaptive Weighting
     /// In `SovereignAutonomous` mode, we apply a 2.0x multiplier to local updates,
     /// as we "trust ourselves more" when cluster gossip is unavailable.
+    /// Workers apply the same weight on the served path by tagging their
+    /// `LearnEvent`s, which the orchestrator folds into the shadow trie.
     pub fn train(&self, session: &crate::session::Session, context: &[u8], response_bit: bool) {
         if !self.active { return; }
         
         let guard = epoch::pin();
-        let trie_shared = self.trie.load(Ordering::Acquire, &guard);
         
         // # Hallucination Check: We use the background shadow-trie for merging,
         // but local training still updates the active trie (conceptually).
         // Since get_mut isn't possible on an AtomicPtr, we'd normally update the shadow trie.
         // For this task, we'll simulate the multiplier by observing multiple times.
         
-        if let Some(trie) = unsafe { trie_shared.as_ref() } {
+        if let Some(Installed { trie, .. }) = self.active_trie(&guard) {
             // Note: In production, we'd use a lock on the shadow trie or per-core buffers.
             // For the fast-path hardening, we use this direct observation pattern.
-            let multiplier = if session.mode == SessionMode::SovereignAutonomous {
-                2
-            } else {
-                1
-            };
-            
-            for _ in 0..multiplier {
+            for _ in 0..session.mode().learning_weight() {
                 // Casting away const-ness for this simulation (in production, use Mutex/RefCell on nodes)
                 unsafe {
                     let trie_mut = (trie as *const LinearIntentTrie as *mut LinearIntentTrie).as_mut().unwrap();
@@ -153,7 +430,10 @@ impl PredictiveEngine {
         }
     }
 
-    /// Cancels all active predictive pushes for the given source address.
+    /// Records a Priority-Zero pivot for the given source address.
+    ///
+    /// The engine owns no ring; in-flight SQEs are cancelled by the owning
+    /// `CoreDispatcher::cancel_for`, which issues the `AsyncCancel`s.
     pub fn cancel_for(&self, _addr: &std::net::SocketAddr) {
         tracing::warn!("PredictiveEngine: Canceled active pushes for {}", _addr);
     }
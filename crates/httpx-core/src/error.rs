@@ -5,6 +5,30 @@ pub enum HttpXError {
     IntentMismatch,
     CreditExhausted,
     CodecError(String),
+    /// A startup self-test stage (`slab`, `route`, `burst`, `completion`) failed.
+    SelfTest { stage: &'static str, reason: String },
+}
+
+impl std::fmt::Display for HttpXError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Transport(err) => write!(f, "transport: {}", err),
+            Self::ProtocolViolation(msg) => write!(f, "protocol violation: {}", msg),
+            Self::IntentMismatch => write!(f, "intent mismatch"),
+            Self::CreditExhausted => write!(f, "intent credits exhausted"),
+            Self::CodecError(msg) => write!(f, "codec: {}", msg),
+            Self::SelfTest { stage, reason } => write!(f, "self-test failed at {} stage: {}", stage, reason),
+        }
+    }
+}
+
+impl std::error::Error for HttpXError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Transport(err) => Some(err),
+            _ => None,
+        }
+    }
 }
 
 impl From<std::io::Error> for HttpXError {
@@ -0,0 +1,296 @@
+//! Read-only snapshots of worker state for on-call inspection.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use hdrhistogram::Histogram;
+use httpx_dsa::trie::TrieRoute;
+use crate::engine::PredictiveEngine;
+
+/// Per-worker data-plane counters.
+///
+/// Each `CoreDispatcher` owns its own instance, aligned to a cache line (the
+/// `CacheAlignedAtomic` pattern from `bridge.rs`) so that neighbouring workers
+/// never share a line. Only the owning core writes, hence `Relaxed` increments;
+/// readers sum per-core snapshots on demand.
+///
+/// Push latency is counted in atomic log-linear buckets, so recording a reaped
+/// completion is one `Relaxed` increment; `latency` builds a
+/// `LatencyHistogram` from the buckets on read.
+#[derive(Debug, Default)]
+#[repr(align(64))]
+pub struct DispatcherStats {
+    packets_received: AtomicU64,
+    pushes_submitted: AtomicU64,
+    completions_reaped: AtomicU64,
+    pushes_canceled: AtomicU64,
+    latency: LatencyBuckets,
+    /// `monotonic_nanos` of the last `run_loop` iteration; 0 until the first.
+    heartbeat: AtomicU64,
+}
+
+/// Nanoseconds since a process-wide monotonic epoch, offset by one so a
+/// stored 0 can mean "never".
+///
+/// Reads tokio's clock, which is the system clock except under a paused
+/// test runtime, where heartbeat ages follow `tokio::time::advance`.
+fn monotonic_nanos() -> u64 {
+    static EPOCH: std::sync::OnceLock<tokio::time::Instant> = std::sync::OnceLock::new();
+    let epoch = *EPOCH.get_or_init(tokio::time::Instant::now);
+    u64::try_from(epoch.elapsed().as_nanos()).unwrap_or(u64::MAX - 1) + 1
+}
+
+impl DispatcherStats {
+    #[inline(always)]
+    pub fn record_packet(&self) {
+        self.packets_received.fetch_add(1, Ordering::Relaxed);
+    }
+
+    #[inline(always)]
+    pub fn record_push(&self) {
+        self.pushes_submitted.fetch_add(1, Ordering::Relaxed);
+    }
+
+    #[inline(always)]
+    pub fn record_completion(&self) {
+        self.completions_reaped.fetch_add(1, Ordering::Relaxed);
+    }
+
+    #[inline(always)]
+    pub fn record_cancel(&self) {
+        self.pushes_canceled.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Marks the owning worker's event loop as alive; called once per iteration.
+    #[inline(always)]
+    pub fn beat(&self) {
+        self.heartbeat.store(monotonic_nanos(), Ordering::Relaxed);
+    }
+
+    /// Time since the last `beat`, or `None` if the loop never ran.
+    pub fn since_heartbeat(&self) -> Option<Duration> {
+        match self.heartbeat.load(Ordering::Relaxed) {
+            0 => None,
+            last => Some(Duration::from_nanos(monotonic_nanos().saturating_sub(last))),
+        }
+    }
+
+    /// Records the submit -> reap latency of one completed push.
+    #[inline(always)]
+    pub fn record_latency(&self, latency: Duration) {
+        self.latency.record(latency);
+    }
+
+    /// Copy of the push latency histogram, mergeable across workers.
+    pub fn latency(&self) -> LatencyHistogram {
+        self.latency.histogram()
+    }
+
+    /// Reads the current counter values.
+    pub fn snapshot(&self) -> StatsSnapshot {
+        StatsSnapshot {
+            packets_received: self.packets_received.load(Ordering::Relaxed),
+            pushes_submitted: self.pushes_submitted.load(Ordering::Relaxed),
+            completions_reaped: self.completions_reaped.load(Ordering::Relaxed),
+            pushes_canceled: self.pushes_canceled.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Longest latency tracked at full precision; slower pushes are clamped to it.
+const LATENCY_MAX_NS: u64 = 10_000_000_000;
+
+/// Latencies below `1 << LINEAR_BITS` ns get one bucket per nanosecond.
+const LINEAR_BITS: u32 = 8;
+/// Each power of two above the linear range is split into
+/// `1 << SUB_BUCKET_BITS` buckets (under 1% relative error).
+const SUB_BUCKET_BITS: u32 = 7;
+const LATENCY_BUCKETS: usize = bucket_index(LATENCY_MAX_NS) + 1;
+
+/// Bucket of a latency of `ns` (at most `LATENCY_MAX_NS`).
+const fn bucket_index(ns: u64) -> usize {
+    if ns < 1 << LINEAR_BITS {
+        return ns as usize;
+    }
+    let exp = 63 - ns.leading_zeros();
+    let sub = (ns >> (exp - SUB_BUCKET_BITS)) & ((1 << SUB_BUCKET_BITS) - 1);
+    (1 << LINEAR_BITS) + (((exp - LINEAR_BITS) as usize) << SUB_BUCKET_BITS) + sub as usize
+}
+
+/// Midpoint of the latencies counted in bucket `index`.
+fn bucket_value(index: usize) -> u64 {
+    let Some(rel) = index.checked_sub(1 << LINEAR_BITS) else {
+        return index as u64;
+    };
+    let exp = (rel >> SUB_BUCKET_BITS) as u32 + LINEAR_BITS;
+    let sub = (rel & ((1 << SUB_BUCKET_BITS) - 1)) as u64;
+    let width = 1u64 << (exp - SUB_BUCKET_BITS);
+    (((1 << SUB_BUCKET_BITS) + sub) * width + width / 2).min(LATENCY_MAX_NS)
+}
+
+/// Lock-free push latency counts, one `AtomicU64` per log-linear bucket.
+struct LatencyBuckets(Box<[AtomicU64]>);
+
+impl Default for LatencyBuckets {
+    fn default() -> Self {
+        Self((0..LATENCY_BUCKETS).map(|_| AtomicU64::new(0)).collect())
+    }
+}
+
+impl core::fmt::Debug for LatencyBuckets {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let samples: u64 = self.0.iter().map(|b| b.load(Ordering::Relaxed)).sum();
+        f.debug_struct("LatencyBuckets").field("samples", &samples).finish()
+    }
+}
+
+impl LatencyBuckets {
+    #[inline(always)]
+    fn record(&self, latency: Duration) {
+        let ns = u64::try_from(latency.as_nanos()).unwrap_or(u64::MAX).clamp(1, LATENCY_MAX_NS);
+        self.0[bucket_index(ns)].fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn histogram(&self) -> LatencyHistogram {
+        let mut histogram = LatencyHistogram::new();
+        for (index, bucket) in self.0.iter().enumerate() {
+            let count = bucket.load(Ordering::Relaxed);
+            if count > 0 {
+                histogram.0.saturating_record_n(bucket_value(index).max(1), count);
+            }
+        }
+        histogram
+    }
+}
+
+/// HdrHistogram of end-to-end push latency (submit -> reap), in nanoseconds,
+/// at 3 significant digits.
+#[derive(Debug, Clone)]
+pub struct LatencyHistogram(Histogram<u64>);
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self(Histogram::new_with_bounds(1, LATENCY_MAX_NS, 3).expect("valid histogram bounds"))
+    }
+}
+
+impl LatencyHistogram {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, latency: Duration) {
+        let ns = u64::try_from(latency.as_nanos()).unwrap_or(u64::MAX);
+        self.0.saturating_record(ns.max(1));
+    }
+
+    /// Folds another worker's samples into this histogram.
+    pub fn merge(&mut self, other: &Self) {
+        // Same bounds on both sides, so the add cannot fail.
+        let _ = self.0.add(&other.0);
+    }
+
+    /// Number of recorded samples.
+    pub fn len(&self) -> u64 {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Latency at `quantile` (`0.0..=1.0`); zero if nothing was recorded.
+    pub fn quantile(&self, quantile: f64) -> Duration {
+        Duration::from_nanos(self.0.value_at_quantile(quantile))
+    }
+
+    pub fn percentiles(&self) -> LatencyPercentiles {
+        LatencyPercentiles {
+            count: self.len(),
+            p50: self.quantile(0.50),
+            p99: self.quantile(0.99),
+            p999: self.quantile(0.999),
+            max: Duration::from_nanos(self.0.max()),
+        }
+    }
+}
+
+/// SLO summary of a `LatencyHistogram`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LatencyPercentiles {
+    pub count: u64,
+    pub p50: Duration,
+    pub p99: Duration,
+    pub p999: Duration,
+    pub max: Duration,
+}
+
+/// Plain-value copy of `DispatcherStats`, summable across workers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StatsSnapshot {
+    /// Datagrams received on the worker socket.
+    pub packets_received: u64,
+    /// Bursts successfully pushed onto the submission queue.
+    pub pushes_submitted: u64,
+    /// Completions reaped from the completion queue.
+    pub completions_reaped: u64,
+    /// Bursts whose completion reported `-ECANCELED` (Priority-Zero pivots).
+    pub pushes_canceled: u64,
+}
+
+impl core::ops::Add for StatsSnapshot {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            packets_received: self.packets_received + rhs.packets_received,
+            pushes_submitted: self.pushes_submitted + rhs.pushes_submitted,
+            completions_reaped: self.completions_reaped + rhs.completions_reaped,
+            pushes_canceled: self.pushes_canceled + rhs.pushes_canceled,
+        }
+    }
+}
+
+impl core::iter::Sum for StatsSnapshot {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), |acc, s| acc + s)
+    }
+}
+
+/// A point-in-time view of a single worker, returned via `ControlSignal::Inspect`.
+#[derive(Debug, Clone)]
+pub struct WorkerSnapshot {
+    pub core_id: usize,
+    /// Payload-bearing routes in the worker's active trie.
+    pub routes: Vec<TrieRoute>,
+    /// Digest of the worker's active trie.
+    pub trie_digest: u64,
+    /// Sequence number of the worker's active trie.
+    pub trie_sequence: u64,
+    pub stats: StatsSnapshot,
+}
+
+/// Cross-node agreement check over learned models.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConvergenceReport {
+    /// Active-trie digest of each engine, in input order.
+    pub digests: Vec<u64>,
+    /// `true` if every engine has a trie and all digests match.
+    pub converged: bool,
+}
+
+impl ConvergenceReport {
+    /// Snapshots the active-trie digest of each engine and compares them.
+    pub fn collect<'a>(engines: impl IntoIterator<Item = &'a PredictiveEngine>) -> Self {
+        let mut missing = false;
+        let digests: Vec<u64> = engines
+            .into_iter()
+            .filter_map(|engine| {
+                let digest = engine.snapshot_digest();
+                missing |= digest.is_none();
+                digest
+            })
+            .collect();
+        let converged = !missing && !digests.is_empty() && digests.windows(2).all(|w| w[0] == w[1]);
+        Self { digests, converged }
+    }
+}
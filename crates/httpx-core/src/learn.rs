@@ -0,0 +1,333 @@
+//! Bounded learning channel between worker cores and the orchestrator.
+//!
+//! ## Mechanical Sympathy: Graceful Degradation
+//! The data plane must never wait on the control plane. Every `LearnSender`
+//! owns its own lock-free ring (`LearnRing`), so workers never contend
+//! with each other and only briefly with the orchestrator. When the
+//! orchestrator falls behind, the oldest events are dropped and counted
+//! instead of growing the queue, so memory stays fixed at `capacity` events
+//! per sender regardless of load and what is kept is the freshest traffic.
+
+use std::cell::UnsafeCell;
+use std::mem::MaybeUninit;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+
+/// Longest context carried by a learning event; longer paths are truncated.
+pub const MAX_LEARN_PATH: usize = 128;
+
+/// Default number of pending learning events per sender before the oldest are dropped.
+pub const DEFAULT_LEARN_CAPACITY: usize = 4096;
+
+/// A single learning observation, stored inline (no per-packet allocation).
+#[derive(Clone, Copy)]
+pub struct LearnEvent {
+    path: [u8; MAX_LEARN_PATH],
+    len: u8,
+    pub success: bool,
+    /// Observations this event counts as; see `SessionMode::learning_weight`.
+    pub weight: u8,
+}
+
+impl LearnEvent {
+    /// Keeps the first `MAX_LEARN_PATH` bytes of `path`.
+    ///
+    /// Like the trie past its depth cap, a longer context is learned on its
+    /// prefix rather than lost.
+    pub fn new(path: &[u8], success: bool) -> Self {
+        let len = path.len().min(MAX_LEARN_PATH);
+        let mut buf = [0u8; MAX_LEARN_PATH];
+        buf[..len].copy_from_slice(&path[..len]);
+        Self { path: buf, len: len as u8, success, weight: 1 }
+    }
+
+    /// Counts the event as `weight` observations (at least one).
+    pub fn with_weight(mut self, weight: u8) -> Self {
+        self.weight = weight.max(1);
+        self
+    }
+
+    /// The request context.
+    pub fn path(&self) -> &[u8] {
+        &self.path[..self.len as usize]
+    }
+}
+
+impl std::fmt::Debug for LearnEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LearnEvent")
+            .field("path", &String::from_utf8_lossy(self.path()))
+            .field("success", &self.success)
+            .field("weight", &self.weight)
+            .finish()
+    }
+}
+
+/// One cell of a `LearnRing`.
+struct LearnSlot {
+    /// Position the slot is ready for: `pos` when free for the push at
+    /// `pos`, `pos + 1` once that push has landed.
+    seq: AtomicUsize,
+    event: UnsafeCell<MaybeUninit<LearnEvent>>,
+}
+
+/// Bounded drop-oldest ring with one producer (its `LearnSender`).
+///
+/// A Vyukov-style queue: each slot carries a sequence number, so a pop
+/// claims a slot by advancing `tail` with a CAS and only the claimant
+/// reads it. That lets the producer evict the oldest event itself when the
+/// ring is full, racing the receiver safely instead of waiting for it.
+struct LearnRing {
+    slots: Box<[LearnSlot]>,
+    mask: usize,
+    /// Next push position; written only by the producer.
+    head: AtomicUsize,
+    /// Next pop position; advanced by the receiver and by evictions.
+    tail: AtomicUsize,
+}
+
+// # Safety: a slot's event is written only by the producer while it owns the
+// slot (`seq == pos`) and read only by the pop that claimed it.
+unsafe impl Sync for LearnRing {}
+
+impl LearnRing {
+    fn new(capacity: usize) -> Self {
+        assert!(capacity.is_power_of_two(), "LearnRing: capacity must be a power of two");
+        let slots = (0..capacity)
+            .map(|i| LearnSlot { seq: AtomicUsize::new(i), event: UnsafeCell::new(MaybeUninit::uninit()) })
+            .collect();
+        Self { slots, mask: capacity - 1, head: AtomicUsize::new(0), tail: AtomicUsize::new(0) }
+    }
+
+    /// Pushes `event`, evicting the oldest one if the ring is full.
+    ///
+    /// Returns whether an event was evicted. Producer only. If the receiver
+    /// is mid-pop on the slot being reused, spins for the length of its copy.
+    fn push_evicting(&self, event: LearnEvent) -> bool {
+        let pos = self.head.load(Ordering::Relaxed);
+        let slot = &self.slots[pos & self.mask];
+        let mut evicted = false;
+        while slot.seq.load(Ordering::Acquire) != pos {
+            // Full: make room. Otherwise a pop has claimed the slot and is copying out.
+            if !evicted && self.len() > self.mask && self.pop().is_some() {
+                evicted = true;
+            } else {
+                std::hint::spin_loop();
+            }
+        }
+        // # Safety: `seq == pos` hands the slot to the producer until it
+        // publishes `pos + 1`; no pop reads it in between.
+        unsafe { (*slot.event.get()).write(event) };
+        slot.seq.store(pos + 1, Ordering::Release);
+        self.head.store(pos + 1, Ordering::Release);
+        evicted
+    }
+
+    /// Pops the oldest event. Safe to race between the receiver and the producer.
+    fn pop(&self) -> Option<LearnEvent> {
+        let mut pos = self.tail.load(Ordering::Relaxed);
+        loop {
+            let slot = &self.slots[pos & self.mask];
+            let seq = slot.seq.load(Ordering::Acquire);
+            if seq != pos + 1 {
+                // Either empty, or another pop claimed `pos` and moved on.
+                let tail = self.tail.load(Ordering::Relaxed);
+                if tail == pos {
+                    return None;
+                }
+                pos = tail;
+                continue;
+            }
+            match self.tail.compare_exchange_weak(pos, pos + 1, Ordering::Relaxed, Ordering::Relaxed) {
+                Ok(_) => {
+                    // # Safety: the CAS made this pop the slot's only reader,
+                    // and `seq == pos + 1` means the push has landed.
+                    let event = unsafe { (*slot.event.get()).assume_init_read() };
+                    slot.seq.store(pos + self.mask + 1, Ordering::Release);
+                    return Some(event);
+                }
+                Err(tail) => pos = tail,
+            }
+        }
+    }
+
+    /// Events pushed and not yet popped.
+    fn len(&self) -> usize {
+        let tail = self.tail.load(Ordering::Acquire);
+        self.head.load(Ordering::Acquire).wrapping_sub(tail)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+struct Shared {
+    /// One ring per live (or not yet drained) sender. Locked only when a
+    /// sender is created and by the receiver; never on `send`.
+    rings: Mutex<Vec<Arc<LearnRing>>>,
+    /// Events each ring holds; a power of two.
+    capacity: usize,
+    notify: Notify,
+    dropped: AtomicU64,
+    truncated: AtomicU64,
+    /// Live `LearnSender`s; the channel closes when this reaches zero.
+    senders: AtomicUsize,
+}
+
+impl Shared {
+    fn new_ring(&self) -> Arc<LearnRing> {
+        let ring = Arc::new(LearnRing::new(self.capacity));
+        self.rings.lock().unwrap().push(ring.clone());
+        ring
+    }
+}
+
+/// Creates a bounded, drop-oldest learning channel.
+///
+/// `capacity` is per sender and rounded up to a power of two.
+pub fn learn_channel(capacity: usize) -> (LearnSender, LearnReceiver) {
+    assert!(capacity > 0, "learn_channel: capacity must be non-zero");
+    let shared = Arc::new(Shared {
+        rings: Mutex::new(Vec::new()),
+        capacity: capacity.next_power_of_two(),
+        notify: Notify::new(),
+        dropped: AtomicU64::new(0),
+        truncated: AtomicU64::new(0),
+        senders: AtomicUsize::new(1),
+    });
+    let ring = shared.new_ring();
+    (LearnSender { shared: shared.clone(), ring }, LearnReceiver { shared, next: 0 })
+}
+
+/// Worker-side handle. Sending never waits for the receiver.
+///
+/// Each clone gets its own ring, so give every worker its own clone.
+pub struct LearnSender {
+    shared: Arc<Shared>,
+    /// Produced into only by this handle; `send` takes `&mut self` to keep it that way.
+    ring: Arc<LearnRing>,
+}
+
+impl LearnSender {
+    /// Enqueues an observation, dropping this sender's oldest pending event
+    /// if its ring is full. Paths longer than `MAX_LEARN_PATH` are truncated.
+    ///
+    /// Lock-free: one copy into the ring and a notify. It waits only if the
+    /// receiver is copying out the very slot being reused.
+    pub fn send(&mut self, path: &[u8], success: bool) {
+        self.send_weighted(path, success, 1);
+    }
+
+    /// Like `send`, but the event counts as `weight` observations.
+    pub fn send_weighted(&mut self, path: &[u8], success: bool, weight: u8) {
+        if path.len() > MAX_LEARN_PATH {
+            self.shared.truncated.fetch_add(1, Ordering::Relaxed);
+        }
+        if self.ring.push_evicting(LearnEvent::new(path, success).with_weight(weight)) {
+            self.shared.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+        self.shared.notify.notify_one();
+    }
+
+    /// Number of events evicted because a sender's ring was full.
+    pub fn dropped(&self) -> u64 {
+        self.shared.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Number of events whose path was cut to `MAX_LEARN_PATH`.
+    pub fn truncated(&self) -> u64 {
+        self.shared.truncated.load(Ordering::Relaxed)
+    }
+
+    /// Number of events currently pending across all senders.
+    pub fn len(&self) -> usize {
+        self.shared.rings.lock().unwrap().iter().map(|ring| ring.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Maximum number of pending events per sender.
+    pub fn capacity(&self) -> usize {
+        self.shared.capacity
+    }
+}
+
+impl Clone for LearnSender {
+    fn clone(&self) -> Self {
+        self.shared.senders.fetch_add(1, Ordering::Relaxed);
+        Self { shared: self.shared.clone(), ring: self.shared.new_ring() }
+    }
+}
+
+impl Drop for LearnSender {
+    fn drop(&mut self) {
+        // Wake the receiver so it can observe closure. The count must drop
+        // first, or a receiver woken early would go back to sleep for good.
+        // The ring stays registered until the receiver has drained it.
+        self.shared.senders.fetch_sub(1, Ordering::Release);
+        self.shared.notify.notify_one();
+    }
+}
+
+/// Orchestrator-side handle.
+pub struct LearnReceiver {
+    shared: Arc<Shared>,
+    /// Ring to poll first, so one busy sender cannot starve the rest.
+    next: usize,
+}
+
+impl LearnReceiver {
+    /// Waits for the next event. Returns `None` once all senders are gone
+    /// and every ring is drained.
+    pub async fn recv(&mut self) -> Option<LearnEvent> {
+        loop {
+            if let Some(event) = self.try_recv() {
+                return Some(event);
+            }
+            if self.shared.senders.load(Ordering::Acquire) == 0 {
+                // A send racing the last drop may have landed after `try_recv`.
+                return self.try_recv();
+            }
+            self.shared.notify.notified().await;
+        }
+    }
+
+    /// Pops the next event without waiting, polling the rings round-robin.
+    ///
+    /// Rings of dropped senders are released once empty.
+    pub fn try_recv(&mut self) -> Option<LearnEvent> {
+        let mut rings = self.shared.rings.lock().unwrap();
+        // Only the registry holds a dropped sender's ring. The count is read
+        // before the emptiness check, and the fence pairs with the sender's
+        // release on drop, so a last push cannot slip in between.
+        rings.retain(|ring| {
+            Arc::strong_count(ring) > 1 || {
+                std::sync::atomic::fence(Ordering::Acquire);
+                !ring.is_empty()
+            }
+        });
+        let len = rings.len();
+        for i in 0..len {
+            let idx = (self.next + i) % len;
+            if let Some(event) = rings[idx].pop() {
+                self.next = idx + 1;
+                return Some(event);
+            }
+        }
+        None
+    }
+
+    /// Number of events evicted because a sender's ring was full.
+    pub fn dropped(&self) -> u64 {
+        self.shared.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Number of events whose path was cut to `MAX_LEARN_PATH`.
+    pub fn truncated(&self) -> u64 {
+        self.shared.truncated.load(Ordering::Relaxed)
+    }
+}
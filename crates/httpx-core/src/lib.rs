@@ -4,12 +4,20 @@ pub mod registry;
 pub mod bridge;
 pub mod engine;
 pub mod session;
+pub mod inspect;
+pub mod learn;
+pub mod watchdog;
 
-pub use config::ServerConfig;
+pub use config::{RuntimeFlavor, ServerConfig};
 pub use engine::PredictiveEngine;
+#[cfg(feature = "decision-trace")]
+pub use engine::DecisionRecord;
 pub use session::{Session, SessionMode};
 pub use error::HttpXError;
-pub use registry::ResourceRegistry;
+pub use registry::{PayloadRegistry, ResourceRegistry};
+pub use inspect::{ConvergenceReport, DispatcherStats, LatencyHistogram, LatencyPercentiles, StatsSnapshot, WorkerSnapshot};
+pub use learn::{learn_channel, LearnEvent, LearnReceiver, LearnSender};
+pub use watchdog::Watchdog;
 use std::net::SocketAddr;
 use std::sync::Arc;
 
@@ -17,7 +25,19 @@ use std::sync::Arc;
 pub enum ControlSignal {
     Pivot(SocketAddr),
     KillAll,
+    /// Stops new predictive pushes, reaps in-flight bursts, then exits the worker loop.
+    Drain,
     SwapTrie(Arc<httpx_dsa::LinearIntentTrie>),
+    /// Zeroes the learned weights of the active trie, keeping its routes.
+    ResetLearning,
+    /// Recalibrates the worker's congestion controller baseline RTT (nanos).
+    SetBaseRtt(u64),
+    /// Trust mode applied to sessions from now on; follows the cluster mode.
+    SetSessionMode(SessionMode),
+    /// Cancels in-flight pushes of the payload routed at this path.
+    CancelPath(Vec<u8>),
+    /// Requests a read-only `WorkerSnapshot` on the enclosed channel.
+    Inspect(tokio::sync::mpsc::Sender<WorkerSnapshot>),
 }
 
 /// A unified builder for Sovereign HTTP-X servers.
@@ -44,6 +64,25 @@ impl ServerBuilder {
         self
     }
 
+    /// Fallible `route`: rejects a path deeper than the trie's cap.
+    pub fn try_route(mut self, path: &str, handle: u32, version: u32) -> Result<Self, httpx_dsa::TrieError> {
+        self.registry.try_route(path, handle, version)?;
+        Ok(self)
+    }
+
+    /// Caps the route trie at `bits` levels. Set before registering routes,
+    /// so over-long routes are rejected rather than truncated.
+    pub fn with_max_trie_depth_bits(mut self, bits: usize) -> Self {
+        self.registry = self.registry.with_max_depth_bits(bits);
+        self
+    }
+
+    /// Registers a batch of `(path, handle, version)` routes in one pass.
+    pub fn routes(mut self, routes: &[(&str, u32, u32)]) -> Self {
+        self.registry.route_many(routes);
+        self
+    }
+
     /// Overrides the default server configuration.
     pub fn with_config(mut self, config: ServerConfig) -> Self {
         self.config = config;
@@ -1,4 +1,6 @@
-use httpx_dsa::LinearIntentTrie;
+use httpx_dsa::{LinearIntentTrie, SecureSlab, SlabError, TrieError};
+use std::collections::HashMap;
+use std::sync::Arc;
 
 /// The ResourceRegistry bridges application URIs to the Fast-Path engine.
 /// 
@@ -17,19 +19,60 @@ impl ResourceRegistry {
         }
     }
 
+    /// Caps the trie depth; see `LinearIntentTrie::with_max_depth_bits`.
+    pub fn with_max_depth_bits(mut self, bits: usize) -> Self {
+        self.trie = self.trie.with_max_depth_bits(bits);
+        self
+    }
+
     /// Registers a route and pre-populates its bit-path in the trie.
     ///
     /// ## Constraint: No Dynamic Dispatch
     /// We use u32 handles for payloads and templates, preserving the
     /// zero-blocking static resolution model.
+    ///
+    /// Panics if the path is deeper than the trie's cap; see `try_route`.
     pub fn route(&mut self, path: &str, payload_handle: u32, version_id: u32) {
+        if let Err(e) = self.try_route(path, payload_handle, version_id) {
+            panic!("ResourceRegistry: cannot register {:?}: {}", path, e);
+        }
+    }
+
+    /// Fallible `route`: rejects paths the depth cap would truncate, and
+    /// surfaces any failure to bind the payload to the warmed terminal.
+    pub fn try_route(&mut self, path: &str, payload_handle: u32, version_id: u32) -> Result<(), TrieError> {
         let bytes = path.as_bytes();
+        self.trie.check_depth(bytes)?;
         
         // 1. Warm the trie: Ensure all segments exist in the radix structure.
         self.trie.warm(bytes);
         
         // 2. Associate payload: Bind the handle and version to the terminal node.
-        self.trie.associate_payload(bytes, payload_handle, version_id);
+        self.trie.associate_payload(bytes, payload_handle, version_id)
+    }
+
+    /// Registers a batch of `(path, payload_handle, version_id)` routes.
+    ///
+    /// Equivalent to calling `route` for each entry, but shared prefixes
+    /// are traversed once (see `LinearIntentTrie::warm_many`).
+    ///
+    /// Panics if any path is deeper than the trie's cap or the trie runs out
+    /// of nodes; see `try_route_many`.
+    pub fn route_many(&mut self, routes: &[(&str, u32, u32)]) {
+        if let Err(e) = self.try_route_many(routes) {
+            panic!("ResourceRegistry: cannot register routes: {}", e);
+        }
+    }
+
+    /// Fallible `route_many`; nothing is registered if any path is too deep.
+    /// `CapacityExhausted` is passed through from `warm_many`.
+    pub fn try_route_many(&mut self, routes: &[(&str, u32, u32)]) -> Result<(), TrieError> {
+        for &(path, _, _) in routes {
+            self.trie.check_depth(path.as_bytes())?;
+        }
+        let routes: Vec<(&[u8], u32, u32)> = routes.iter().map(|&(path, handle, version)| (path.as_bytes(), handle, version)).collect();
+        self.trie.warm_many(&routes)?;
+        Ok(())
     }
 
     /// Consumes the registry and returns the fully warmed trie.
@@ -37,3 +80,72 @@ impl ResourceRegistry {
         self.trie
     }
 }
+
+/// Maps payload names to slab handles, so routes refer to `"hello_payload"`
+/// instead of magic slot numbers.
+///
+/// Cold-path only: names are resolved once at registration time and the
+/// data plane keeps working with the raw `u32` handles.
+pub struct PayloadRegistry {
+    slab: Arc<SecureSlab>,
+    names: HashMap<String, u32>,
+}
+
+impl PayloadRegistry {
+    pub fn new(slab: Arc<SecureSlab>) -> Self {
+        Self {
+            slab,
+            names: HashMap::new(),
+        }
+    }
+
+    /// Stores `data` under `name` and returns its handle.
+    ///
+    /// Panics if the slab is full or `data` exceeds a slot; see `try_register`.
+    pub fn register(&mut self, name: &str, data: &[u8]) -> u32 {
+        match self.try_register(name, data) {
+            Ok(handle) => handle,
+            Err(e) => panic!("PayloadRegistry: cannot register {:?}: {}", name, e),
+        }
+    }
+
+    /// Fallible `register`.
+    ///
+    /// A new name claims a free slot; re-registering a name rewrites its
+    /// existing slot in place (bumping the slot version).
+    pub fn try_register(&mut self, name: &str, data: &[u8]) -> Result<u32, SlabError> {
+        if let Some(&handle) = self.names.get(name) {
+            self.slab.write_slot(handle as usize, 0, data)?;
+            // A shorter revision must not ship the old tail.
+            self.slab.set_payload_len(handle as usize, data.len());
+            return Ok(handle);
+        }
+        let idx = self.slab.allocate_slot()?;
+        if let Err(e) = self.slab.write_slot(idx, 0, data) {
+            self.slab.free_slot(idx);
+            return Err(e);
+        }
+        let handle = idx as u32;
+        self.names.insert(name.to_string(), handle);
+        Ok(handle)
+    }
+
+    /// Resolves a payload name to its slab handle.
+    pub fn handle_of(&self, name: &str) -> Option<u32> {
+        self.names.get(name).copied()
+    }
+
+    /// Current version of the named payload, for `ResourceRegistry::route`.
+    pub fn version_of(&self, name: &str) -> Option<u32> {
+        self.handle_of(name).map(|handle| self.slab.get_version(handle as usize))
+    }
+
+    /// Number of registered names.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
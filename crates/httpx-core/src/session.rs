@@ -1,4 +1,4 @@
-use core::sync::atomic::{AtomicUsize, AtomicBool, Ordering};
+use core::sync::atomic::{AtomicUsize, AtomicBool, AtomicU8, AtomicU64, Ordering};
 use std::net::SocketAddr;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -7,26 +7,101 @@ pub enum SessionMode {
     SovereignAutonomous,
 }
 
+impl SessionMode {
+    /// How many observations one local learning event counts as.
+    ///
+    /// Sovereign nodes cannot hear the cluster, so they trust their own
+    /// traffic 2x.
+    pub fn learning_weight(self) -> u8 {
+        match self {
+            SessionMode::ClusterIntegrated => 1,
+            SessionMode::SovereignAutonomous => 2,
+        }
+    }
+
+    fn from_u8(raw: u8) -> Self {
+        match raw {
+            0 => SessionMode::ClusterIntegrated,
+            _ => SessionMode::SovereignAutonomous,
+        }
+    }
+}
+
+/// Credits a session starts with, and `replenish_credits` resets to.
+pub const INITIAL_IIW_CREDITS: usize = 10;
+/// Default ceiling for `set_credits` / `add_credits`.
+pub const DEFAULT_MAX_CREDITS: usize = 1000;
+
 pub struct Session {
     pub addr: SocketAddr,
-    pub mode: SessionMode,
+    /// `SessionMode` as `u8`; flipped by the cluster while the session is live.
+    mode: AtomicU8,
     /// Initial Intent Window (IIW) credits.
     /// Decremented on each predictive push, replenished on IntentAck.
     pub iiw_credit: AtomicUsize,
     /// Priority-Zero Pivot: If true, all predictive pushes are blocked.
     pub canceled: AtomicBool,
+    /// Ceiling for granted credits.
+    max_credits: usize,
+    /// xorshift64 state for probabilistic push decisions (never zero).
+    rng: AtomicU64,
 }
 
 impl Session {
     pub fn new(addr: SocketAddr) -> Self {
         Self {
             addr,
-            mode: SessionMode::ClusterIntegrated,
-            iiw_credit: AtomicUsize::new(10), // Start with foundational 10 credits
+            mode: AtomicU8::new(SessionMode::ClusterIntegrated as u8),
+            iiw_credit: AtomicUsize::new(INITIAL_IIW_CREDITS),
             canceled: AtomicBool::new(false),
+            max_credits: DEFAULT_MAX_CREDITS,
+            rng: AtomicU64::new(seed_for(&addr)),
         }
     }
 
+    /// Caps the credits `set_credits` and `add_credits` may grant.
+    pub fn with_max_credits(mut self, max: usize) -> Self {
+        self.max_credits = max;
+        self
+    }
+
+    /// Reseeds the session's random stream (e.g. for reproducible tests).
+    pub fn with_seed(self, seed: u64) -> Self {
+        // xorshift has an all-zero fixed point.
+        self.rng.store(seed.max(1), Ordering::Relaxed);
+        self
+    }
+
+    /// Advances the session's xorshift64 stream.
+    pub fn next_random(&self) -> u64 {
+        let step = |mut x: u64| {
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            x
+        };
+        let prev = self.rng
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |x| Some(step(x)))
+            .unwrap_or_else(|x| x);
+        step(prev)
+    }
+
+    /// Uniform sample in `[0, 1)` from the session's stream.
+    pub fn next_unit(&self) -> f32 {
+        // Top 24 bits: exactly representable in an f32 mantissa.
+        (self.next_random() >> 40) as f32 / (1u32 << 24) as f32
+    }
+
+    /// Current trust mode; decides the local-learning weight in `PredictiveEngine::train`.
+    pub fn mode(&self) -> SessionMode {
+        SessionMode::from_u8(self.mode.load(Ordering::Acquire))
+    }
+
+    /// Switches the session's trust mode, e.g. when the cluster goes Sovereign.
+    pub fn set_mode(&self, mode: SessionMode) {
+        self.mode.store(mode as u8, Ordering::Release);
+    }
+
     pub fn cancel(&self) {
         self.canceled.store(true, Ordering::Release);
     }
@@ -41,7 +116,25 @@ impl Session {
 
     /// Replenishes IIW credits upon receiving an IntentAck.
     pub fn replenish_credits(&self) {
-        self.iiw_credit.store(10, Ordering::Release);
+        self.iiw_credit.store(INITIAL_IIW_CREDITS, Ordering::Release);
+    }
+
+    /// Sets the credit count outright, clamped to the session's maximum.
+    pub fn set_credits(&self, n: usize) {
+        self.iiw_credit.store(n.min(self.max_credits), Ordering::Release);
+    }
+
+    /// Grants `n` more credits (e.g. a partial IntentAck), saturating at the maximum.
+    pub fn add_credits(&self, n: usize) {
+        let max = self.max_credits;
+        let _ = self.iiw_credit.fetch_update(Ordering::AcqRel, Ordering::Acquire, |current| {
+            Some(current.saturating_add(n).min(max))
+        });
+    }
+
+    /// Current number of IIW credits.
+    pub fn credits(&self) -> usize {
+        self.iiw_credit.load(Ordering::Acquire)
     }
 
     /// Consumes one IIW credit for a predictive push.
@@ -68,3 +161,14 @@ impl Session {
         self.iiw_credit.load(Ordering::Acquire) > 0
     }
 }
+
+/// Derives a per-session seed from the peer address (SplitMix64 finalizer).
+fn seed_for(addr: &SocketAddr) -> u64 {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    addr.hash(&mut hasher);
+    let mut z = hasher.finish().wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    (z ^ (z >> 31)).max(1)
+}
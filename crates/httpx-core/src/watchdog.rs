@@ -0,0 +1,67 @@
+//! Liveness checks for worker event loops.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use crate::inspect::DispatcherStats;
+
+/// Flags workers whose `run_loop` stopped advancing its heartbeat.
+///
+/// A wedged loop (an io_uring hang, a deadlock) leaves the server looking
+/// healthy while its core serves nothing. Each `CoreDispatcher` beats its
+/// `DispatcherStats` every iteration and wakes at least every
+/// `httpx_transport::dispatcher::HEARTBEAT_INTERVAL` even when idle, so a
+/// heartbeat older than `timeout` means the loop is stuck. Workers that have not started yet are not flagged.
+pub struct Watchdog {
+    workers: Vec<Arc<DispatcherStats>>,
+    timeout: Duration,
+    /// Last verdict per worker, so stalls and recoveries are logged once.
+    stalled: Vec<AtomicBool>,
+}
+
+impl Watchdog {
+    pub fn new(workers: Vec<Arc<DispatcherStats>>, timeout: Duration) -> Self {
+        let stalled = workers.iter().map(|_| AtomicBool::new(false)).collect();
+        Self { workers, timeout, stalled }
+    }
+
+    /// Re-evaluates every worker and returns the indices currently stalled.
+    ///
+    /// Logs an error when a worker first stalls and a notice when it recovers.
+    pub fn check(&self) -> Vec<usize> {
+        let mut stalled = Vec::new();
+        for (idx, stats) in self.workers.iter().enumerate() {
+            let age = stats.since_heartbeat();
+            let stuck = age.is_some_and(|age| age > self.timeout);
+            let was_stuck = self.stalled[idx].swap(stuck, Ordering::AcqRel);
+            if stuck {
+                if !was_stuck {
+                    tracing::error!("Watchdog: worker {} stalled, no heartbeat for {:?}.", idx, age.unwrap_or_default());
+                }
+                stalled.push(idx);
+            } else if was_stuck {
+                tracing::warn!("Watchdog: worker {} recovered.", idx);
+            }
+        }
+        stalled
+    }
+
+    /// Whether worker `idx` was stalled at the last `check`.
+    pub fn is_stalled(&self, idx: usize) -> bool {
+        self.stalled[idx].load(Ordering::Acquire)
+    }
+
+    /// Indices of the workers stalled at the last `check`.
+    pub fn stalled_workers(&self) -> Vec<usize> {
+        (0..self.stalled.len()).filter(|&idx| self.is_stalled(idx)).collect()
+    }
+
+    /// Checks every half `timeout`, forever; abort the task to stop it.
+    pub async fn run(self: Arc<Self>) {
+        let mut ticker = tokio::time::interval(self.timeout / 2);
+        loop {
+            ticker.tick().await;
+            self.check();
+        }
+    }
+}
@@ -3,11 +3,47 @@
 //! ## Performance Contract
 //! - **Symmetric Transform**: ~0.8 cycles/byte (ChaCha20-Poly1305).
 //! - **Overhead**: 0-RTT latency (Handshake-less initialization).
+//!
+//! ## Cipher Suites
+//! `AEADStack` (ChaCha20-Poly1305) and `AesGcmStack` (AES-256-GCM) share
+//! the 32-byte key, 12-byte nonce and 16-byte tag, so either fits the
+//! `SecureInPlaceAEAD` signatures; `CipherSuite` picks one at runtime.
+//!
+//! `XAEADStack` (XChaCha20-Poly1305) takes a 24-byte nonce through the
+//! sibling `SecureInPlaceXAEAD` trait. At 192 bits a randomly drawn nonce
+//! will not collide in practice, so per-core workers can pick nonces
+//! without coordinating a counter.
+//!
+//! ## Key Schedule
+//! `derive_session_key` expands one master secret into a key per session
+//! (HKDF-SHA256), so operators distribute a single secret and rotating it
+//! re-keys every session at once.
+//!
+//! ## Slab Pages
+//! `seal_slot` / `open_slot` transform a `SecureSlab` payload in place and
+//! append its tag right after the ciphertext, inside the slot's payload
+//! length, so the dispatcher ships ciphertext and tag straight from the slab.
 
-use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce, Tag};
+use aes_gcm::Aes256Gcm;
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce, Tag, XChaCha20Poly1305, XNonce};
 use chacha20poly1305::aead::{AeadInPlace, KeyInit};
+use chacha20poly1305::consts::{U12, U16};
+use hkdf::Hkdf;
+use httpx_dsa::{SecureSlab, SlabError};
+use sha2::Sha256;
+use subtle::ConstantTimeEq;
 use zeroize::Zeroizing;
 
+/// Compares two byte strings (tags, AAD) in constant time.
+///
+/// Runtime depends only on the lengths, never on the contents, so a
+/// mismatching first byte costs the same as a mismatching last byte.
+/// Any tag comparison performed by this crate must go through here.
+#[inline]
+pub fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    a.ct_eq(b).into()
+}
+
 /// A trait for high-performance, in-place Authenticated Encryption.
 ///
 /// Designed to work directly within io_uring or DPDK registered buffers.
@@ -22,6 +58,10 @@ pub trait SecureInPlaceAEAD {
     ) -> Result<Tag, CryptoError>;
 
     /// Decrypts data directly within the provided buffer.
+    ///
+    /// An empty buffer is a valid (AAD-only) frame. A tag that does not
+    /// authenticate the frame yields `IntegrityCheckFailed`, or `EmptyBuffer`
+    /// if the buffer was empty (a frame truncated down to its tag).
     fn open_in_place(
         &self,
         key: &Zeroizing<[u8; 32]>,
@@ -30,15 +70,237 @@ pub trait SecureInPlaceAEAD {
         buffer: &mut [u8],
         tag: &Tag,
     ) -> Result<(), CryptoError>;
+
+    /// Encrypts `buffer` under the next nonce of `sequencer`.
+    ///
+    /// Returns the nonce (which travels with the frame) and the tag. Frame
+    /// code should seal through here rather than pick nonces itself, so a
+    /// key never sees the same nonce twice. Fails with `NonceExhausted`,
+    /// leaving `buffer` untouched, once the sequencer runs out.
+    fn seal_next(
+        &self,
+        sequencer: &mut NonceSequencer,
+        key: &Zeroizing<[u8; 32]>,
+        aad: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<([u8; 12], Tag), CryptoError> {
+        let nonce = sequencer.next_nonce()?;
+        let tag = self.seal_in_place(key, &nonce, aad, buffer)?;
+        Ok((nonce, tag))
+    }
 }
 
-#[derive(Debug)]
+/// `SecureInPlaceAEAD` with a 24-byte nonce, safe to draw at random.
+///
+/// Same in-place, detached-tag shape; only the nonce width differs.
+pub trait SecureInPlaceXAEAD {
+    /// Encrypts data directly within the provided buffer.
+    fn seal_in_place(
+        &self,
+        key: &Zeroizing<[u8; 32]>,
+        nonce: &[u8; 24],
+        aad: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<Tag, CryptoError>;
+
+    /// Decrypts data directly within the provided buffer, with the same
+    /// failure causes as `SecureInPlaceAEAD::open_in_place`.
+    fn open_in_place(
+        &self,
+        key: &Zeroizing<[u8; 32]>,
+        nonce: &[u8; 24],
+        aad: &[u8],
+        buffer: &mut [u8],
+        tag: &Tag,
+    ) -> Result<(), CryptoError>;
+}
+
+/// The nonce of the frame an error refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameNonce {
+    /// 96-bit nonce (`SecureInPlaceAEAD`).
+    Standard([u8; 12]),
+    /// 192-bit nonce (`SecureInPlaceXAEAD`).
+    Extended([u8; 24]),
+}
+
+impl From<[u8; 12]> for FrameNonce {
+    fn from(nonce: [u8; 12]) -> Self {
+        Self::Standard(nonce)
+    }
+}
+
+impl From<[u8; 24]> for FrameNonce {
+    fn from(nonce: [u8; 24]) -> Self {
+        Self::Extended(nonce)
+    }
+}
+
+impl std::fmt::Display for FrameNonce {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Standard(nonce) => Hex(nonce).fmt(f),
+            Self::Extended(nonce) => Hex(nonce).fmt(f),
+        }
+    }
+}
+
+/// Why a seal or open failed.
+///
+/// Failures tied to a frame carry its nonce, so incident logs can correlate
+/// the error with the frame on the wire. These are only built on the
+/// failure branch; a successful call constructs nothing.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CryptoError {
     HandshakeFailure,
-    IntegrityCheckFailed,
+    /// The tag did not authenticate the ciphertext and AAD: the frame was
+    /// tampered with, truncated, or sealed under another key or nonce.
+    IntegrityCheckFailed { nonce: FrameNonce },
+    /// A zero-length buffer failed to authenticate: most likely a frame
+    /// whose payload was truncated away. Empty frames that do authenticate
+    /// open normally.
+    EmptyBuffer { nonce: FrameNonce },
+    /// The cipher refused to seal the buffer (longer than the AEAD allows).
+    SealFailed { nonce: FrameNonce, len: usize },
     KeyZeroizeError,
+    /// The `NonceSequencer` issued its last nonce; the key must be rotated.
+    NonceExhausted,
+    /// A slab slot could not be transformed in place (bad handle, poisoned,
+    /// or a payload that would overlap the tag region).
+    Slab(SlabError),
+    /// The slot is still held by an in-flight submission; transforming it
+    /// now would tear the send.
+    SlotInFlight(usize),
 }
 
+/// Lowercase hex, for nonces in log lines.
+struct Hex<'a>(&'a [u8]);
+
+impl std::fmt::Display for Hex<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.iter().try_for_each(|b| write!(f, "{:02x}", b))
+    }
+}
+
+impl std::fmt::Display for CryptoError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::HandshakeFailure => write!(f, "handshake failed"),
+            Self::IntegrityCheckFailed { nonce } => {
+                write!(f, "integrity check failed for frame with nonce {}", nonce)
+            }
+            Self::EmptyBuffer { nonce } => write!(f, "empty frame with nonce {}", nonce),
+            Self::SealFailed { nonce, len } => {
+                write!(f, "cannot seal {}-byte frame with nonce {}", len, nonce)
+            }
+            Self::KeyZeroizeError => write!(f, "key zeroization failed"),
+            Self::NonceExhausted => write!(f, "nonce sequence exhausted; rotate the key"),
+            Self::Slab(e) => write!(f, "slab: {}", e),
+            Self::SlotInFlight(idx) => write!(f, "slab slot {} is in flight", idx),
+        }
+    }
+}
+
+impl std::error::Error for CryptoError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Slab(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<SlabError> for CryptoError {
+    fn from(e: SlabError) -> Self {
+        Self::Slab(e)
+    }
+}
+
+/// Issues a fresh 96-bit nonce per seal for one key.
+///
+/// A nonce is the fixed 4-byte prefix followed by a 64-bit big-endian
+/// counter. The prefix separates senders sharing a key (e.g. one per
+/// direction); the counter never repeats, and once `u64::MAX` has been
+/// issued every further request fails with `CryptoError::NonceExhausted`
+/// instead of wrapping.
+#[derive(Debug)]
+pub struct NonceSequencer {
+    prefix: [u8; 4],
+    next: u64,
+    exhausted: bool,
+}
+
+impl NonceSequencer {
+    pub fn new(prefix: [u8; 4]) -> Self {
+        Self::starting_at(prefix, 0)
+    }
+
+    /// Resumes a sequence whose next counter value is `counter`.
+    ///
+    /// Only safe if no nonce at or past `counter` was ever issued under the
+    /// same key and prefix.
+    pub fn starting_at(prefix: [u8; 4], counter: u64) -> Self {
+        Self { prefix, next: counter, exhausted: false }
+    }
+
+    /// Returns the next nonce and advances the counter.
+    pub fn next_nonce(&mut self) -> Result<[u8; 12], CryptoError> {
+        if self.exhausted {
+            return Err(CryptoError::NonceExhausted);
+        }
+        let mut nonce = [0u8; 12];
+        nonce[..4].copy_from_slice(&self.prefix);
+        nonce[4..].copy_from_slice(&self.next.to_be_bytes());
+        match self.next.checked_add(1) {
+            Some(next) => self.next = next,
+            None => self.exhausted = true,
+        }
+        Ok(nonce)
+    }
+
+    /// Nonces still available before exhaustion.
+    pub fn remaining(&self) -> u128 {
+        if self.exhausted { 0 } else { u64::MAX as u128 - self.next as u128 + 1 }
+    }
+}
+
+/// Seals with an already keyed 96-bit-nonce cipher.
+#[inline(always)]
+fn seal_with<C: AeadInPlace<NonceSize = U12, TagSize = U16>>(
+    cipher: &C,
+    nonce: &[u8; 12],
+    aad: &[u8],
+    buffer: &mut [u8],
+) -> Result<Tag, CryptoError> {
+    cipher.encrypt_in_place_detached(Nonce::from_slice(nonce), aad, buffer)
+        .map_err(|_| CryptoError::SealFailed { nonce: (*nonce).into(), len: buffer.len() })
+}
+
+/// Opens with an already keyed 96-bit-nonce cipher.
+#[inline(always)]
+fn open_with<C: AeadInPlace<NonceSize = U12, TagSize = U16>>(
+    cipher: &C,
+    nonce: &[u8; 12],
+    aad: &[u8],
+    buffer: &mut [u8],
+    tag: &Tag,
+) -> Result<(), CryptoError> {
+    cipher.decrypt_in_place_detached(Nonce::from_slice(nonce), aad, buffer, tag)
+        .map_err(|_| open_failure((*nonce).into(), buffer))
+}
+
+/// The error for a frame that failed to authenticate; see `EmptyBuffer`.
+#[cold]
+fn open_failure(nonce: FrameNonce, buffer: &[u8]) -> CryptoError {
+    if buffer.is_empty() {
+        CryptoError::EmptyBuffer { nonce }
+    } else {
+        CryptoError::IntegrityCheckFailed { nonce }
+    }
+}
+
+/// One-shot ChaCha20-Poly1305: keys a fresh cipher on every call. Hot paths
+/// sealing many small frames under one key should hold an `AeadSession`.
 pub struct AEADStack;
 
 impl SecureInPlaceAEAD for AEADStack {
@@ -50,11 +312,7 @@ impl SecureInPlaceAEAD for AEADStack {
         aad: &[u8],
         buffer: &mut [u8],
     ) -> Result<Tag, CryptoError> {
-        let cipher = ChaCha20Poly1305::new(Key::from_slice(&**key));
-        let nonce = Nonce::from_slice(nonce);
-        
-        cipher.encrypt_in_place_detached(nonce, aad, buffer)
-            .map_err(|_| CryptoError::IntegrityCheckFailed)
+        seal_with(&ChaCha20Poly1305::new(Key::from_slice(&**key)), nonce, aad, buffer)
     }
 
     #[inline(always)]
@@ -66,10 +324,321 @@ impl SecureInPlaceAEAD for AEADStack {
         buffer: &mut [u8],
         tag: &Tag,
     ) -> Result<(), CryptoError> {
-        let cipher = ChaCha20Poly1305::new(Key::from_slice(&**key));
-        let nonce = Nonce::from_slice(nonce);
+        open_with(&ChaCha20Poly1305::new(Key::from_slice(&**key)), nonce, aad, buffer, tag)
+    }
+}
+
+/// AES-256-GCM, the hardware-accelerated path on CPUs with AES-NI and CLMUL.
+pub struct AesGcmStack;
+
+impl SecureInPlaceAEAD for AesGcmStack {
+    #[inline(always)]
+    fn seal_in_place(
+        &self,
+        key: &Zeroizing<[u8; 32]>,
+        nonce: &[u8; 12],
+        aad: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<Tag, CryptoError> {
+        seal_with(&Aes256Gcm::new(aes_gcm::Key::<Aes256Gcm>::from_slice(&**key)), nonce, aad, buffer)
+    }
+
+    #[inline(always)]
+    fn open_in_place(
+        &self,
+        key: &Zeroizing<[u8; 32]>,
+        nonce: &[u8; 12],
+        aad: &[u8],
+        buffer: &mut [u8],
+        tag: &Tag,
+    ) -> Result<(), CryptoError> {
+        open_with(&Aes256Gcm::new(aes_gcm::Key::<Aes256Gcm>::from_slice(&**key)), nonce, aad, buffer, tag)
+    }
+}
+
+/// XChaCha20-Poly1305: ChaCha20-Poly1305 keyed per nonce through HChaCha20,
+/// taking a 24-byte nonce that may be drawn at random.
+pub struct XAEADStack;
+
+impl SecureInPlaceXAEAD for XAEADStack {
+    #[inline(always)]
+    fn seal_in_place(
+        &self,
+        key: &Zeroizing<[u8; 32]>,
+        nonce: &[u8; 24],
+        aad: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<Tag, CryptoError> {
+        let cipher = XChaCha20Poly1305::new(Key::from_slice(&**key));
+
+        cipher.encrypt_in_place_detached(XNonce::from_slice(nonce), aad, buffer)
+            .map_err(|_| CryptoError::SealFailed { nonce: (*nonce).into(), len: buffer.len() })
+    }
+
+    #[inline(always)]
+    fn open_in_place(
+        &self,
+        key: &Zeroizing<[u8; 32]>,
+        nonce: &[u8; 24],
+        aad: &[u8],
+        buffer: &mut [u8],
+        tag: &Tag,
+    ) -> Result<(), CryptoError> {
+        let cipher = XChaCha20Poly1305::new(Key::from_slice(&**key));
+
+        cipher.decrypt_in_place_detached(XNonce::from_slice(nonce), aad, buffer, tag)
+            .map_err(|_| open_failure((*nonce).into(), buffer))
+    }
+}
+
+/// The AEAD a connection uses, chosen once at startup.
+///
+/// Implements `SecureInPlaceAEAD` by dispatching to `AEADStack` or
+/// `AesGcmStack`. Both peers must agree on the suite.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CipherSuite {
+    ChaCha20Poly1305,
+    Aes256Gcm,
+}
+
+impl CipherSuite {
+    /// The fastest suite on this CPU.
+    ///
+    /// AES-256-GCM when the CPU has AES-NI and PCLMULQDQ; otherwise
+    /// ChaCha20-Poly1305, which outruns software AES and, unlike table-based
+    /// AES, runs in constant time without hardware support.
+    pub fn detect() -> Self {
+        #[cfg(target_arch = "x86_64")]
+        if std::arch::is_x86_feature_detected!("aes") && std::arch::is_x86_feature_detected!("pclmulqdq") {
+            return Self::Aes256Gcm;
+        }
+        Self::ChaCha20Poly1305
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::ChaCha20Poly1305 => "chacha20-poly1305",
+            Self::Aes256Gcm => "aes-256-gcm",
+        }
+    }
+}
+
+impl SecureInPlaceAEAD for CipherSuite {
+    #[inline(always)]
+    fn seal_in_place(
+        &self,
+        key: &Zeroizing<[u8; 32]>,
+        nonce: &[u8; 12],
+        aad: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<Tag, CryptoError> {
+        match self {
+            Self::ChaCha20Poly1305 => AEADStack.seal_in_place(key, nonce, aad, buffer),
+            Self::Aes256Gcm => AesGcmStack.seal_in_place(key, nonce, aad, buffer),
+        }
+    }
+
+    #[inline(always)]
+    fn open_in_place(
+        &self,
+        key: &Zeroizing<[u8; 32]>,
+        nonce: &[u8; 12],
+        aad: &[u8],
+        buffer: &mut [u8],
+        tag: &Tag,
+    ) -> Result<(), CryptoError> {
+        match self {
+            Self::ChaCha20Poly1305 => AEADStack.open_in_place(key, nonce, aad, buffer, tag),
+            Self::Aes256Gcm => AesGcmStack.open_in_place(key, nonce, aad, buffer, tag),
+        }
+    }
+}
+
+/// HKDF `info` prefix binding derived keys to this schedule and version.
+const SESSION_KEY_LABEL: &[u8] = b"httpx session key v1";
+
+/// Derives the key for one session from `master` (HKDF-SHA256).
+///
+/// `context` names the session, e.g. the peer `SocketAddr` rendered to
+/// bytes or a connection id. The same master and context always give the
+/// same key; any other context gives an unrelated one, so a key leaked
+/// from one session reveals nothing about the others or the master.
+pub fn derive_session_key(master: &Zeroizing<[u8; 32]>, context: &[u8]) -> Zeroizing<[u8; 32]> {
+    let hkdf = Hkdf::<Sha256>::new(None, &**master);
+    let mut key = Zeroizing::new([0u8; 32]);
+    hkdf.expand_multi_info(&[SESSION_KEY_LABEL, context], &mut *key)
+        .expect("32 bytes is within HKDF-SHA256's output limit");
+    key
+}
+
+/// A cipher keyed once, for sealing and opening many frames under one key.
+///
+/// The `SecureInPlaceAEAD` stacks re-run key setup on every call, which
+/// dominates the cost of small frames (headers, acks). A session pays it
+/// once at `new`; `seal`/`open` then only take the nonce, AAD and buffer,
+/// and fail exactly as the one-shot stacks do.
+pub struct AeadSession {
+    cipher: SessionCipher,
+}
+
+enum SessionCipher {
+    ChaCha20Poly1305(ChaCha20Poly1305),
+    // Boxed: the expanded AES key schedule is several times the ChaCha key.
+    Aes256Gcm(Box<Aes256Gcm>),
+}
+
+impl AeadSession {
+    pub fn new(suite: CipherSuite, key: &Zeroizing<[u8; 32]>) -> Self {
+        let cipher = match suite {
+            CipherSuite::ChaCha20Poly1305 => SessionCipher::ChaCha20Poly1305(ChaCha20Poly1305::new(Key::from_slice(&**key))),
+            CipherSuite::Aes256Gcm => {
+                SessionCipher::Aes256Gcm(Box::new(Aes256Gcm::new(aes_gcm::Key::<Aes256Gcm>::from_slice(&**key))))
+            }
+        };
+        Self { cipher }
+    }
+
+    pub fn suite(&self) -> CipherSuite {
+        match self.cipher {
+            SessionCipher::ChaCha20Poly1305(_) => CipherSuite::ChaCha20Poly1305,
+            SessionCipher::Aes256Gcm(_) => CipherSuite::Aes256Gcm,
+        }
+    }
+
+    /// Encrypts `buffer` in place under the session key.
+    #[inline(always)]
+    pub fn seal(&self, nonce: &[u8; 12], aad: &[u8], buffer: &mut [u8]) -> Result<Tag, CryptoError> {
+        match &self.cipher {
+            SessionCipher::ChaCha20Poly1305(cipher) => seal_with(cipher, nonce, aad, buffer),
+            SessionCipher::Aes256Gcm(cipher) => seal_with(&**cipher, nonce, aad, buffer),
+        }
+    }
+
+    /// Authenticates and decrypts `buffer` in place under the session key.
+    #[inline(always)]
+    pub fn open(&self, nonce: &[u8; 12], aad: &[u8], buffer: &mut [u8], tag: &Tag) -> Result<(), CryptoError> {
+        match &self.cipher {
+            SessionCipher::ChaCha20Poly1305(cipher) => open_with(cipher, nonce, aad, buffer, tag),
+            SessionCipher::Aes256Gcm(cipher) => open_with(&**cipher, nonce, aad, buffer, tag),
+        }
+    }
+}
+
+/// Bytes `seal_slot` appends after a slot's ciphertext for its tag.
+pub const SLOT_TAG_LEN: usize = 16;
+
+/// Encrypts the first `len` bytes of slab slot `handle` in place and writes
+/// the tag to `[len, len + SLOT_TAG_LEN)`.
+///
+/// `len` may be less than a full page, but at most
+/// `slot_size() - SLOT_TAG_LEN`. Seal before the slot is submitted: a slot
+/// with an in-flight send is refused. On success the payload length becomes
+/// `len + SLOT_TAG_LEN`, so a push ships the tag with the ciphertext; the
+/// slot version is bumped, since pushes committed against the plaintext are
+/// stale, and the checksum is refreshed when enabled.
+pub fn seal_slot<A: SecureInPlaceAEAD + ?Sized>(
+    aead: &A,
+    slab: &SecureSlab,
+    handle: usize,
+    len: usize,
+    key: &Zeroizing<[u8; 32]>,
+    nonce: &[u8; 12],
+    aad: &[u8],
+) -> Result<(), CryptoError> {
+    let (payload, tag_region) = slot_regions(slab, handle, len)?;
+    let tag = aead.seal_in_place(key, nonce, aad, payload)?;
+    tag_region.copy_from_slice(&tag);
+    slab.set_payload_len(handle, len + SLOT_TAG_LEN);
+    slab.increment_version(handle);
+    Ok(())
+}
+
+/// Reverses `seal_slot`: authenticates the first `len` bytes of slot
+/// `handle` against the tag that follows them and decrypts them in place.
+///
+/// `len` is the plaintext length, i.e. the sealed payload length minus
+/// `SLOT_TAG_LEN`. On success the payload length drops back to `len` and
+/// the version is bumped again.
+pub fn open_slot<A: SecureInPlaceAEAD + ?Sized>(
+    aead: &A,
+    slab: &SecureSlab,
+    handle: usize,
+    len: usize,
+    key: &Zeroizing<[u8; 32]>,
+    nonce: &[u8; 12],
+    aad: &[u8],
+) -> Result<(), CryptoError> {
+    let (payload, tag_region) = slot_regions(slab, handle, len)?;
+    aead.open_in_place(key, nonce, aad, payload, Tag::from_slice(tag_region))?;
+    slab.set_payload_len(handle, len);
+    slab.increment_version(handle);
+    Ok(())
+}
+
+/// Splits slot `handle` into its first `len` payload bytes and the
+/// `SLOT_TAG_LEN` bytes after them.
+#[allow(clippy::mut_from_ref)] // slab slots are shared memory handed out by pointer
+fn slot_regions(slab: &SecureSlab, handle: usize, len: usize) -> Result<(&mut [u8], &mut [u8]), CryptoError> {
+    if handle >= slab.slots() {
+        return Err(SlabError::InvalidSlot(handle).into());
+    }
+    if slab.is_poisoned(handle) {
+        return Err(SlabError::Poisoned(handle).into());
+    }
+    match len.checked_add(SLOT_TAG_LEN) {
+        Some(end) if end <= slab.slot_size() => {}
+        _ => return Err(SlabError::OutOfBounds { offset: 0, len: len.saturating_add(SLOT_TAG_LEN) }.into()),
+    }
+    if slab.is_in_flight(handle) {
+        return Err(CryptoError::SlotInFlight(handle));
+    }
+    let base = slab.get_slot(handle);
+    // # Safety: both ranges lie inside the slot and do not overlap; the
+    // slot is not in flight, so the kernel holds no reference to it.
+    unsafe {
+        Ok((
+            core::slice::from_raw_parts_mut(base, len),
+            core::slice::from_raw_parts_mut(base.add(len), SLOT_TAG_LEN),
+        ))
+    }
+}
+
+/// Reproducible nonce stream for known-answer test vectors.
+///
+/// **Not for production traffic**: the stream is fully determined by the
+/// seed, so two peers seeded alike reuse nonces. It exists so tests and
+/// cross-version compatibility checks can produce stable ciphertexts.
+///
+/// ## PRNG
+/// SplitMix64 (Steele, Lea & Flood, 2014): the state advances by
+/// `0x9E3779B97F4A7C15` and each output is mixed with the multipliers
+/// `0xBF58476D1CE4E5B9` and `0x94D049BB133111EB`. A nonce is the first
+/// output as 8 little-endian bytes followed by the low 4 little-endian
+/// bytes of the second. This layout is part of the fixture contract and
+/// must not change.
+#[derive(Debug, Clone)]
+pub struct DeterministicNonce {
+    state: u64,
+}
+
+impl DeterministicNonce {
+    pub fn from_seed(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
 
-        cipher.decrypt_in_place_detached(nonce, aad, buffer, tag)
-            .map_err(|_| CryptoError::IntegrityCheckFailed)
+    /// Returns the next 96-bit nonce of the stream (valid for either suite).
+    pub fn next_nonce(&mut self) -> [u8; 12] {
+        let mut nonce = [0u8; 12];
+        nonce[..8].copy_from_slice(&self.next_u64().to_le_bytes());
+        nonce[8..].copy_from_slice(&self.next_u64().to_le_bytes()[..4]);
+        nonce
     }
 }
@@ -0,0 +1,230 @@
+//! # Trie Node Arena
+//!
+//! `LinearIntentTrie` keeps its nodes in a `NodeArena`: a growable array
+//! whose memory comes from a pluggable `NodeAllocator`. `HeapNodes` (the
+//! default) uses the global allocator. `GuardedNodes` maps the array between
+//! `PROT_NONE` guard pages, the isolation `SecureSlab` gives payload slots,
+//! so a stray write running off the end of the node array faults instead of
+//! silently corrupting the routing structure.
+
+use crate::trie::TrieNode;
+use alloc::alloc::{alloc, dealloc, Layout};
+use alloc::sync::Arc;
+use core::ops::{Deref, DerefMut};
+use core::ptr::NonNull;
+use nix::libc;
+
+const PAGE_SIZE: usize = 4096;
+
+/// Source of backing memory for a `NodeArena`.
+pub trait NodeAllocator: Send + Sync {
+    /// Returns uninitialised room for exactly `capacity` nodes (at least 1),
+    /// aligned for `TrieNode`, or `None` if it cannot be provided.
+    fn allocate(&self, capacity: usize) -> Option<NonNull<TrieNode>>;
+
+    /// Releases memory returned by `allocate`.
+    ///
+    /// # Safety
+    /// `ptr` must come from `self.allocate(capacity)` and not be used afterwards.
+    unsafe fn deallocate(&self, ptr: NonNull<TrieNode>, capacity: usize);
+}
+
+/// Node storage on the global heap (the default).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HeapNodes;
+
+impl NodeAllocator for HeapNodes {
+    fn allocate(&self, capacity: usize) -> Option<NonNull<TrieNode>> {
+        let layout = Layout::array::<TrieNode>(capacity).ok()?;
+        // # Safety: `capacity >= 1` and `TrieNode` is 64 bytes, so the layout is non-zero.
+        NonNull::new(unsafe { alloc(layout) } as *mut TrieNode)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<TrieNode>, capacity: usize) {
+        dealloc(ptr.as_ptr() as *mut u8, Layout::array::<TrieNode>(capacity).unwrap());
+    }
+}
+
+/// Node storage mapped between `guard_pages` `PROT_NONE` pages on each side.
+///
+/// The array is placed flush against the trailing guard, so the first byte
+/// past the last node faults. Leading slack (when the array is not a whole
+/// number of pages) sits between the front guard and node 0. Every growth
+/// is a fresh mapping plus a copy.
+#[derive(Debug, Clone, Copy)]
+pub struct GuardedNodes {
+    guard_pages: usize,
+}
+
+impl GuardedNodes {
+    /// Panics if `guard_pages` is 0.
+    pub fn new(guard_pages: usize) -> Self {
+        assert!(guard_pages > 0, "GuardedNodes: guard band must be at least one page");
+        Self { guard_pages }
+    }
+
+    pub fn guard_pages(&self) -> usize {
+        self.guard_pages
+    }
+
+    /// `(mapping length, offset of node 0)` for `capacity` nodes.
+    fn layout(&self, capacity: usize) -> Option<(usize, usize)> {
+        let bytes = capacity.checked_mul(core::mem::size_of::<TrieNode>())?;
+        let data = bytes.checked_next_multiple_of(PAGE_SIZE)?;
+        let guard = self.guard_pages.checked_mul(PAGE_SIZE)?;
+        let total = guard.checked_mul(2)?.checked_add(data)?;
+        Some((total, guard + data - bytes))
+    }
+}
+
+impl Default for GuardedNodes {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl NodeAllocator for GuardedNodes {
+    fn allocate(&self, capacity: usize) -> Option<NonNull<TrieNode>> {
+        let (total, offset) = self.layout(capacity)?;
+        let flags = libc::MAP_PRIVATE | libc::MAP_ANONYMOUS | libc::MAP_NORESERVE;
+        // # Safety: a fresh anonymous mapping; nothing else aliases it.
+        let base = unsafe { libc::mmap(core::ptr::null_mut(), total, libc::PROT_NONE, flags, -1, 0) };
+        if base == libc::MAP_FAILED {
+            return None;
+        }
+        let guard = self.guard_pages * PAGE_SIZE;
+        // # Safety: the data pages lie strictly inside the mapping.
+        let data = unsafe { (base as *mut u8).add(guard) };
+        if unsafe { libc::mprotect(data as *mut libc::c_void, total - 2 * guard, libc::PROT_READ | libc::PROT_WRITE) } != 0 {
+            unsafe { libc::munmap(base, total) };
+            return None;
+        }
+        // Node 0 starts at a multiple of 64 because the slack is whole nodes.
+        NonNull::new(unsafe { (base as *mut u8).add(offset) } as *mut TrieNode)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<TrieNode>, capacity: usize) {
+        let (total, offset) = self.layout(capacity).unwrap();
+        libc::munmap((ptr.as_ptr() as *mut u8).sub(offset) as *mut libc::c_void, total);
+    }
+}
+
+/// Growable node array backed by a `NodeAllocator`; derefs to `[TrieNode]`.
+pub struct NodeArena {
+    ptr: NonNull<TrieNode>,
+    len: usize,
+    capacity: usize,
+    allocator: Arc<dyn NodeAllocator>,
+}
+
+// # Safety: the arena uniquely owns its nodes, which are plain data, and the
+// allocator is `Send + Sync`.
+unsafe impl Send for NodeArena {}
+unsafe impl Sync for NodeArena {}
+
+impl NodeArena {
+    /// An empty heap-backed arena with room for `capacity` nodes.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_in(capacity, Arc::new(HeapNodes))
+    }
+
+    /// An empty arena with room for `capacity` nodes from `allocator`.
+    ///
+    /// Panics if the allocator cannot provide the memory.
+    pub fn with_capacity_in(capacity: usize, allocator: Arc<dyn NodeAllocator>) -> Self {
+        let capacity = capacity.max(1);
+        let ptr = allocator.allocate(capacity).expect("NodeArena: node allocation failed");
+        Self { ptr, len: 0, capacity, allocator }
+    }
+
+    /// The allocator new storage for this arena comes from.
+    pub fn allocator(&self) -> &Arc<dyn NodeAllocator> {
+        &self.allocator
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn push(&mut self, node: TrieNode) {
+        if self.len == self.capacity {
+            self.grow_to(self.capacity * 2);
+        }
+        // # Safety: `len < capacity` after growing.
+        unsafe { self.ptr.as_ptr().add(self.len).write(node) };
+        self.len += 1;
+    }
+
+    /// Ensures room for at least `additional` more nodes.
+    pub fn reserve(&mut self, additional: usize) {
+        let needed = self.len.checked_add(additional).expect("NodeArena: capacity overflow");
+        if needed > self.capacity {
+            self.grow_to(needed.max(self.capacity * 2));
+        }
+    }
+
+    /// Moves the nodes into a fresh allocation of `capacity` nodes.
+    fn grow_to(&mut self, capacity: usize) {
+        let ptr = self.allocator.allocate(capacity).expect("NodeArena: node allocation failed");
+        // # Safety: both regions hold at least `len` nodes and do not overlap;
+        // the old one is released exactly once.
+        unsafe {
+            core::ptr::copy_nonoverlapping(self.ptr.as_ptr(), ptr.as_ptr(), self.len);
+            self.allocator.deallocate(self.ptr, self.capacity);
+        }
+        self.ptr = ptr;
+        self.capacity = capacity;
+    }
+}
+
+impl Deref for NodeArena {
+    type Target = [TrieNode];
+
+    fn deref(&self) -> &[TrieNode] {
+        // # Safety: the first `len` nodes are initialised.
+        unsafe { core::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl DerefMut for NodeArena {
+    fn deref_mut(&mut self) -> &mut [TrieNode] {
+        // # Safety: the first `len` nodes are initialised and uniquely borrowed.
+        unsafe { core::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<'a> IntoIterator for &'a NodeArena {
+    type Item = &'a TrieNode;
+    type IntoIter = core::slice::Iter<'a, TrieNode>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut NodeArena {
+    type Item = &'a mut TrieNode;
+    type IntoIter = core::slice::IterMut<'a, TrieNode>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// Clones into a new allocation from the same allocator.
+impl Clone for NodeArena {
+    fn clone(&self) -> Self {
+        let mut arena = Self::with_capacity_in(self.capacity, self.allocator.clone());
+        // # Safety: the new arena holds `capacity >= len` nodes.
+        unsafe { core::ptr::copy_nonoverlapping(self.ptr.as_ptr(), arena.ptr.as_ptr(), self.len) };
+        arena.len = self.len;
+        arena
+    }
+}
+
+impl Drop for NodeArena {
+    fn drop(&mut self) {
+        // # Safety: `ptr` came from this allocator with this capacity.
+        unsafe { self.allocator.deallocate(self.ptr, self.capacity) };
+    }
+}
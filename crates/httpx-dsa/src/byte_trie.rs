@@ -0,0 +1,271 @@
+//! # Byte-Indexed Intent Trie
+//!
+//! `LinearIntentTrie` branches on one bit per node, so resolving a 32-byte
+//! path costs 256 dependent loads. `ByteIntentTrie` branches on a whole
+//! byte: every node owns a 256-entry child table, and the same path costs
+//! 32. The price is size: each node carries 1 KiB of child links, so this
+//! layout suits route sets of thousands of nodes, not millions.
+//!
+//! Child tables and node metadata live in parallel pools. A lookup touches
+//! one table entry per byte and reads the `TrieNode` only at the end.
+
+use crate::trie::{FlagBit, IntentTrie, TrieError, TrieNode, DEFAULT_MAX_DEPTH_BITS, MAX_TRIE_NODES};
+use alloc::vec::Vec;
+use core::fmt;
+
+const NULL_NODE: u32 = u32::MAX;
+
+/// Child links of one node, indexed by the next path byte.
+type Fanout = [u32; 256];
+
+const EMPTY_FANOUT: Fanout = [NULL_NODE; 256];
+
+/// Default pool ceiling: 4096 nodes, i.e. 4 MiB of child tables.
+pub const DEFAULT_NODE_LIMIT: usize = 4096;
+
+/// An intent trie that consumes one path byte per hop.
+///
+/// Answers the `IntentTrie` calls exactly as `LinearIntentTrie` does for
+/// the same history. Paths are stored as-is (no hashed encoding) and
+/// `TrieNode::children` is unused: links live in the fan-out pool.
+#[derive(Clone)]
+pub struct ByteIntentTrie {
+    /// Child tables, parallel to `nodes`.
+    fanout: Vec<Fanout>,
+    /// Weights, payload binding and flags of each node.
+    nodes: Vec<TrieNode>,
+    /// Deepest byte level `observe`/`warm` will allocate.
+    max_depth_bytes: usize,
+    /// Unique sequence number to prevent stale learning updates.
+    pub sequence_number: u64,
+    /// Hard pool ceiling; see `with_node_limit`.
+    node_limit: usize,
+    /// Whether the infallible inserts have already logged `CapacityExhausted`.
+    exhaustion_logged: bool,
+}
+
+impl fmt::Debug for ByteIntentTrie {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ByteIntentTrie")
+            .field("nodes_len", &self.nodes.len())
+            .field("sequence_number", &self.sequence_number)
+            .field("max_depth_bytes", &self.max_depth_bytes)
+            .field("node_limit", &self.node_limit)
+            .finish()
+    }
+}
+
+impl ByteIntentTrie {
+    pub fn new(capacity: usize) -> Self {
+        let mut fanout = Vec::with_capacity(capacity.max(1));
+        let mut nodes = Vec::with_capacity(capacity.max(1));
+        // Root node
+        fanout.push(EMPTY_FANOUT);
+        nodes.push(TrieNode::EMPTY);
+        Self {
+            fanout,
+            nodes,
+            max_depth_bytes: DEFAULT_MAX_DEPTH_BITS / 8,
+            sequence_number: 0,
+            node_limit: DEFAULT_NODE_LIMIT,
+            exhaustion_logged: false,
+        }
+    }
+
+    /// Caps the trie at `bytes` levels below the root.
+    ///
+    /// Longer contexts are truncated, as with
+    /// `LinearIntentTrie::with_max_depth_bits`.
+    pub fn with_max_depth_bytes(mut self, bytes: usize) -> Self {
+        self.max_depth_bytes = bytes;
+        self
+    }
+
+    pub fn max_depth_bytes(&self) -> usize {
+        self.max_depth_bytes
+    }
+
+    /// Sets the hard pool ceiling (default `DEFAULT_NODE_LIMIT`, at most
+    /// `MAX_TRIE_NODES`). Each node costs 1 KiB of child links, so raise it
+    /// with care. Panics if `limit` is 0.
+    pub fn with_node_limit(mut self, limit: usize) -> Self {
+        assert!(limit > 0, "ByteIntentTrie: node limit must leave room for the root");
+        self.node_limit = limit.min(MAX_TRIE_NODES);
+        self
+    }
+
+    /// The hard pool ceiling (`DEFAULT_NODE_LIMIT` unless changed).
+    pub fn node_limit(&self) -> usize {
+        self.node_limit
+    }
+
+    /// Number of nodes in the pool (including the root).
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// `path` cut off after `max_depth_bytes`.
+    #[inline(always)]
+    fn stored<'a>(&self, path: &'a [u8]) -> &'a [u8] {
+        &path[..path.len().min(self.max_depth_bytes)]
+    }
+
+    /// Retrieves the transition probability for a specific context path.
+    #[inline(always)]
+    pub fn get_probability(&self, context: &[u8], next_bit: bool) -> f32 {
+        let Some(curr) = self.node_index_at_path(context) else {
+            return 0.0;
+        };
+        let node = &self.nodes[curr];
+        let weight = node.weights[next_bit as usize];
+        let total = node.weights[0] as u32 + node.weights[1] as u32;
+
+        if total == 0 {
+            0.0
+        } else {
+            weight as f32 / total as f32
+        }
+    }
+
+    /// Inserts or updates an intent sequence with a Markov weight increment.
+    ///
+    /// If the pool is full the observation is dropped and logged; see
+    /// `try_observe`.
+    pub fn observe(&mut self, context: &[u8], next_bit: bool) {
+        if let Err(e) = self.try_observe(context, next_bit) {
+            self.log_exhausted(e);
+        }
+    }
+
+    /// `observe`, failing with `CapacityExhausted` instead of dropping the
+    /// observation when `context` needs a node past the pool limit.
+    pub fn try_observe(&mut self, context: &[u8], next_bit: bool) -> Result<(), TrieError> {
+        let curr = self.insert(context)?;
+        let weight = &mut self.nodes[curr].weights[next_bit as usize];
+        *weight = weight.saturating_add(1);
+        Ok(())
+    }
+
+    /// Pre-populates a path in the trie without modifying weights.
+    ///
+    /// If the pool fills up part way, the rest of the path is dropped and
+    /// logged; see `try_warm`.
+    pub fn warm(&mut self, path: &[u8]) {
+        if let Err(e) = self.try_warm(path) {
+            self.log_exhausted(e);
+        }
+    }
+
+    /// `warm`, failing with `CapacityExhausted` when `path` needs a node
+    /// past the pool limit. The prefix allocated before the failure stays.
+    pub fn try_warm(&mut self, path: &[u8]) -> Result<(), TrieError> {
+        self.insert(path).map(|_| ())
+    }
+
+    /// Associates a payload handle and version with the node at `context`.
+    ///
+    /// Fails with `UnknownPath` (leaving the trie untouched) if `context` was
+    /// never warmed or observed. Like `LinearIntentTrie`, the depth it
+    /// reports is in bits.
+    pub fn associate_payload(&mut self, context: &[u8], handle: u32, version_id: u32) -> Result<(), TrieError> {
+        let mut curr = 0;
+        for (depth, &byte) in self.stored(context).iter().enumerate() {
+            let next = self.fanout[curr][byte as usize];
+            if next == NULL_NODE {
+                return Err(TrieError::UnknownPath { depth: depth * 8 });
+            }
+            curr = next as usize;
+        }
+        let node = &mut self.nodes[curr];
+        node.payload_handle = handle;
+        node.version_id = version_id;
+        node.flags |= FlagBit::PINNED.mask() | FlagBit::TERMINAL.mask();
+        Ok(())
+    }
+
+    /// Returns the node at the end of the given path.
+    pub fn get_node_at_path(&self, path: &[u8]) -> Option<&TrieNode> {
+        self.node_index_at_path(path).map(|idx| &self.nodes[idx])
+    }
+
+    /// Pool index of the node at the end of the given path.
+    #[inline(always)]
+    pub fn node_index_at_path(&self, path: &[u8]) -> Option<usize> {
+        let mut curr = 0;
+        for &byte in self.stored(path) {
+            let next = self.fanout[curr][byte as usize];
+            if next == NULL_NODE {
+                return None;
+            }
+            curr = next as usize;
+        }
+        Some(curr)
+    }
+
+    /// Walks `path`, allocating missing nodes, and returns the terminal index.
+    ///
+    /// Fails with `CapacityExhausted` rather than hand out an index at or
+    /// past the limit, which at `MAX_TRIE_NODES` would alias `NULL_NODE`.
+    fn insert(&mut self, path: &[u8]) -> Result<usize, TrieError> {
+        let mut curr = 0;
+        for &byte in self.stored(path) {
+            let next = self.fanout[curr][byte as usize];
+            curr = if next != NULL_NODE {
+                next as usize
+            } else {
+                if self.nodes.len() >= self.node_limit {
+                    return Err(TrieError::CapacityExhausted { nodes: self.nodes.len() });
+                }
+                let new_idx = self.nodes.len();
+                self.fanout.push(EMPTY_FANOUT);
+                self.nodes.push(TrieNode::EMPTY);
+                self.fanout[curr][byte as usize] = new_idx as u32;
+                new_idx
+            };
+        }
+        Ok(curr)
+    }
+
+    /// Logs the first `CapacityExhausted` hit by an infallible insert.
+    #[cold]
+    fn log_exhausted(&mut self, e: TrieError) {
+        if !self.exhaustion_logged {
+            self.exhaustion_logged = true;
+            tracing::warn!("ByteIntentTrie: {}; further inserts are dropped", e);
+        }
+    }
+}
+
+impl IntentTrie for ByteIntentTrie {
+    fn observe(&mut self, context: &[u8], next_bit: bool) {
+        ByteIntentTrie::observe(self, context, next_bit)
+    }
+
+    fn try_observe(&mut self, context: &[u8], next_bit: bool) -> Result<(), TrieError> {
+        ByteIntentTrie::try_observe(self, context, next_bit)
+    }
+
+    fn get_probability(&self, context: &[u8], next_bit: bool) -> f32 {
+        ByteIntentTrie::get_probability(self, context, next_bit)
+    }
+
+    fn warm(&mut self, path: &[u8]) {
+        ByteIntentTrie::warm(self, path)
+    }
+
+    fn try_warm(&mut self, path: &[u8]) -> Result<(), TrieError> {
+        ByteIntentTrie::try_warm(self, path)
+    }
+
+    fn node_limit(&self) -> usize {
+        ByteIntentTrie::node_limit(self)
+    }
+
+    fn associate_payload(&mut self, context: &[u8], handle: u32, version_id: u32) -> Result<(), TrieError> {
+        ByteIntentTrie::associate_payload(self, context, handle, version_id)
+    }
+
+    fn get_node_at_path(&self, path: &[u8]) -> Option<&TrieNode> {
+        ByteIntentTrie::get_node_at_path(self, path)
+    }
+}
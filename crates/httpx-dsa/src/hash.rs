@@ -0,0 +1,51 @@
+//! # Canonical Context Hashing
+//!
+//! Every component that names a context by hash (gossip deltas, the
+//! reconciliation buffer, hash -> path tables) must agree on the function,
+//! or peers silently apply learnings to the wrong paths. This is that function.
+
+pub(crate) const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+pub(crate) const FNV_PRIME: u64 = 0x100000001b3;
+
+/// 64-bit FNV-1a hash of a context path.
+///
+/// Stable across processes, builds and architectures (unlike
+/// `std`'s `DefaultHasher`), so it is safe to put on the wire.
+#[inline]
+pub fn context_hash(path: &[u8]) -> u64 {
+    let mut hash = FNV_OFFSET;
+    for &b in path {
+        hash ^= b as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    hash
+}
+
+/// Reflected CRC-32 (IEEE 802.3) lookup table, built at compile time.
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// CRC-32 (IEEE 802.3, as used by zlib and Ethernet) of `data`.
+///
+/// Detects corruption, not tampering: anyone can recompute it.
+#[inline]
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &b in data {
+        crc = CRC32_TABLE[((crc ^ b as u32) & 0xFF) as usize] ^ (crc >> 8);
+    }
+    !crc
+}
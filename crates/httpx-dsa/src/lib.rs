@@ -2,9 +2,15 @@
 extern crate alloc;
 
 pub mod trie;
+pub mod byte_trie;
+pub mod arena;
 pub mod slab;
 pub mod numa;
+pub mod hash;
 
-pub use trie::LinearIntentTrie;
-pub use slab::SecureSlab;
-pub use numa::NumaPinnedSlab;
+pub use arena::{GuardedNodes, HeapNodes, NodeAllocator, NodeArena};
+pub use byte_trie::ByteIntentTrie;
+pub use trie::{FlagBit, IntentTrie, LinearIntentTrie, NodeDiff, NodeState, PathEncoding, TrieError};
+pub use slab::{GuardPages, HugePages, SecureSlab, SlabError, SlabLike, DEFAULT_SLOT_SIZE};
+pub use numa::{NumaPinnedSlab, NumaPolicy};
+pub use hash::{context_hash, crc32};
@@ -4,8 +4,149 @@
 
 use core::ptr::NonNull;
 use core::ffi::c_void;
+use alloc::vec::Vec;
+use nix::errno::Errno;
 use nix::libc;
 
+/// `get_mempolicy` flags (not exported by `libc`).
+const MPOL_F_NODE: libc::c_ulong = 1 << 0;
+const MPOL_F_ADDR: libc::c_ulong = 1 << 1;
+
+/// Nodemask words passed to `mbind` (1024 nodes on 64-bit).
+const NODEMASK_WORDS: usize = 16;
+
+/// The memory placement policy applied to a `NumaPinnedSlab` via `mbind`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NumaPolicy {
+    /// `MPOL_BIND`: frames come only from this node (the default).
+    Bind(i32),
+    /// `MPOL_INTERLEAVE`: pages are spread round-robin across these nodes,
+    /// balancing load on the memory controllers for uniformly accessed slabs.
+    Interleave(Vec<i32>),
+    /// `MPOL_PREFERRED`: frames come from this node while it has free memory.
+    Preferred(i32),
+}
+
+impl NumaPolicy {
+    fn mode(&self) -> libc::c_int {
+        match self {
+            NumaPolicy::Bind(_) => libc::MPOL_BIND,
+            NumaPolicy::Interleave(_) => libc::MPOL_INTERLEAVE,
+            NumaPolicy::Preferred(_) => libc::MPOL_PREFERRED,
+        }
+    }
+
+    fn nodes(&self) -> &[i32] {
+        match self {
+            NumaPolicy::Bind(node) | NumaPolicy::Preferred(node) => core::slice::from_ref(node),
+            NumaPolicy::Interleave(nodes) => nodes,
+        }
+    }
+}
+
+/// Applies `policy` to `[addr, addr + len)`.
+fn bind(addr: *mut c_void, len: usize, policy: &NumaPolicy) -> Result<(), Errno> {
+    let bits = libc::c_ulong::BITS as usize;
+    let mut mask = [0 as libc::c_ulong; NODEMASK_WORDS];
+    for &node in policy.nodes() {
+        let node = usize::try_from(node)
+            .ok()
+            .filter(|&n| n < NODEMASK_WORDS * bits)
+            .ok_or(Errno::EINVAL)?;
+        mask[node / bits] |= 1 << (node % bits);
+    }
+    if mask.iter().all(|&word| word == 0) {
+        return Err(Errno::EINVAL);
+    }
+    // mbind(addr, len, mode, nodemask, maxnode, flags)
+    let res = unsafe {
+        libc::syscall(libc::SYS_mbind, addr, len, policy.mode(), mask.as_ptr(), NODEMASK_WORDS * bits + 1, 0u32)
+    };
+    Errno::result(res).map(drop)
+}
+
+/// Returns the NUMA node backing the page at `addr`, faulting it in if needed.
+///
+/// `None` if `get_mempolicy` is unavailable (no `CONFIG_NUMA`) or fails.
+pub(crate) fn node_of_addr(addr: *const c_void) -> Option<i32> {
+    let mut node: libc::c_int = -1;
+    // get_mempolicy(mode, nodemask, maxnode, addr, flags)
+    let res = unsafe {
+        libc::syscall(
+            libc::SYS_get_mempolicy,
+            &mut node as *mut libc::c_int,
+            core::ptr::null_mut::<libc::c_ulong>(),
+            0usize,
+            addr,
+            MPOL_F_NODE | MPOL_F_ADDR,
+        )
+    };
+    (res == 0 && node >= 0).then_some(node)
+}
+
+/// Adds a sysfs cpulist such as `0-3,8,10-11` to `set`.
+fn parse_cpulist(list: &[u8], set: &mut libc::cpu_set_t) -> Option<()> {
+    let list = core::str::from_utf8(list).ok()?.trim();
+    for range in list.split(',').filter(|r| !r.is_empty()) {
+        let (lo, hi) = range.split_once('-').unwrap_or((range, range));
+        let (lo, hi): (usize, usize) = (lo.parse().ok()?, hi.parse().ok()?);
+        for cpu in lo..=hi.min(libc::CPU_SETSIZE as usize - 1) {
+            // # Safety: `cpu` is below `CPU_SETSIZE`.
+            unsafe { libc::CPU_SET(cpu, set) };
+        }
+    }
+    Some(())
+}
+
+/// CPUs local to `nodes`, read from sysfs.
+///
+/// `None` if a list is unreadable or the nodes have no CPUs (memory-only nodes).
+fn node_cpus(nodes: &[i32]) -> Option<libc::cpu_set_t> {
+    let mut set: libc::cpu_set_t = unsafe { core::mem::zeroed() };
+    for &node in nodes {
+        let path = alloc::format!("/sys/devices/system/node/node{}/cpulist\0", node);
+        let mut buf = [0u8; 4096];
+        let len = unsafe {
+            let fd = libc::open(path.as_ptr() as *const libc::c_char, libc::O_RDONLY | libc::O_CLOEXEC);
+            if fd < 0 {
+                return None;
+            }
+            let n = libc::read(fd, buf.as_mut_ptr() as *mut c_void, buf.len());
+            libc::close(fd);
+            usize::try_from(n).ok()?
+        };
+        parse_cpulist(&buf[..len], &mut set)?;
+    }
+    (unsafe { libc::CPU_COUNT(&set) } > 0).then_some(set)
+}
+
+/// Pins the calling thread to the CPUs of `nodes` and returns its previous
+/// affinity, to be restored with `restore_affinity`.
+///
+/// `None`, with the thread left where it was, if the node CPUs are unknown
+/// or the kernel refuses the mask (e.g. outside the thread's cpuset).
+fn pin_to_nodes(nodes: &[i32]) -> Option<libc::cpu_set_t> {
+    let cpus = node_cpus(nodes)?;
+    let size = core::mem::size_of::<libc::cpu_set_t>();
+    let mut previous: libc::cpu_set_t = unsafe { core::mem::zeroed() };
+    unsafe {
+        if libc::sched_getaffinity(0, size, &mut previous) != 0 {
+            return None;
+        }
+        if libc::sched_setaffinity(0, size, &cpus) != 0 {
+            return None;
+        }
+    }
+    Some(previous)
+}
+
+fn restore_affinity(previous: &libc::cpu_set_t) {
+    let size = core::mem::size_of::<libc::cpu_set_t>();
+    if unsafe { libc::sched_setaffinity(0, size, previous) } != 0 {
+        tracing::warn!("NUMA: could not restore thread affinity after pre-faulting");
+    }
+}
+
 /// A NUMA-Pinned Slab for architectural affinity.
 /// 
 /// ## Performance Guarantee
@@ -14,7 +155,7 @@ use nix::libc;
 pub struct NumaPinnedSlab {
     base: NonNull<c_void>,
     total_len: usize,
-    _numa_node: i32,
+    policy: NumaPolicy,
 }
 
 impl NumaPinnedSlab {
@@ -24,6 +165,28 @@ impl NumaPinnedSlab {
     /// Uses `libc::mmap` for reservation and `libc::mbind` for physical binding.
     /// Requires `CAP_SYS_NICE` or root for specific binding flags.
     pub fn new(slots: usize, numa_node: i32) -> Self {
+        Self::with_prefault(slots, numa_node, false)
+    }
+
+    /// Creates a new NUMA-bound slab, optionally pre-faulting every page.
+    ///
+    /// ## First-Touch Placement
+    /// Linux allocates physical frames on first access, on the node of the
+    /// touching CPU. With `prefault` set, one byte per page is written
+    /// immediately after binding, so frames are committed up front instead of
+    /// on a later (possibly remote) access. The calling thread is pinned to
+    /// `numa_node`'s CPUs for the pass and its affinity restored afterwards.
+    pub fn with_prefault(slots: usize, numa_node: i32, prefault: bool) -> Self {
+        Self::with_policy(slots, NumaPolicy::Bind(numa_node), prefault)
+    }
+
+    /// Creates a new slab placed according to `policy`, optionally pre-faulting.
+    ///
+    /// The policy is applied before any page is touched, and pre-faulting
+    /// runs pinned to the CPUs of the policy's nodes, so frames land locally
+    /// even under `Preferred` or when `mbind` fails. If the node CPUs cannot
+    /// be determined or pinned, the pass runs on the calling core.
+    pub fn with_policy(slots: usize, policy: NumaPolicy, prefault: bool) -> Self {
         let page_size = 4096;
         let total_len = slots * page_size;
 
@@ -44,22 +207,60 @@ impl NumaPinnedSlab {
 
         let base = NonNull::new(addr).expect("mmap returned null");
 
-        // Attempt to bind to NUMA node
-        // mbind(void *addr, unsigned long len, int mode, const unsigned long *nodemask, unsigned long maxnode, unsigned flags)
-        // Implementation note: This is a syscall wrapper. In a full implementation, 
-        // we'd use the `numa` crate or direct syscall(SYS_mbind, ...).
-        tracing::debug!("NUMA: Binding {} bytes to Node {}", total_len, numa_node);
+        // Bind before any page is touched so first-touch cannot place frames elsewhere.
+        tracing::debug!("NUMA: Binding {} bytes with {:?}", total_len, policy);
+        if let Err(err) = bind(addr, total_len, &policy) {
+            tracing::warn!("NUMA: mbind with {:?} failed ({}); placement falls back to first-touch", policy, err);
+        }
+
+        if prefault {
+            // Pin first: first-touch places each frame on the touching CPU's node.
+            let previous = pin_to_nodes(policy.nodes());
+            if previous.is_none() {
+                tracing::warn!("NUMA: could not pin to {:?}; pre-faulting from the calling core", policy);
+            }
+            // # Safety: Every offset is a page start strictly within the mapping.
+            for offset in (0..total_len).step_by(page_size) {
+                unsafe {
+                    core::ptr::write_volatile((addr as *mut u8).add(offset), 0);
+                }
+            }
+            if let Some(previous) = previous {
+                restore_affinity(&previous);
+            }
+            tracing::debug!("NUMA: Pre-faulted {} pages under {:?}", slots, policy);
+        }
 
         Self {
             base,
             total_len,
-            _numa_node: numa_node,
+            policy,
         }
     }
 
     pub fn as_ptr(&self) -> *mut u8 {
         self.base.as_ptr() as *mut u8
     }
+
+    /// Returns the placement policy requested at construction.
+    pub fn policy(&self) -> &NumaPolicy {
+        &self.policy
+    }
+
+    /// Returns the total mapped length in bytes.
+    pub fn total_len(&self) -> usize {
+        self.total_len
+    }
+
+    /// Returns the NUMA node that slot `idx`'s physical frame resides on.
+    ///
+    /// Queries the kernel (`get_mempolicy` with `MPOL_F_NODE | MPOL_F_ADDR`)
+    /// rather than trusting the requested binding; an untouched page is
+    /// faulted in first. `None` where `get_mempolicy` is unavailable.
+    pub fn numa_node_of(&self, idx: usize) -> Option<i32> {
+        assert!(idx < self.total_len / 4096);
+        node_of_addr(unsafe { self.base.as_ptr().byte_add(idx * 4096) })
+    }
 }
 
 impl Drop for NumaPinnedSlab {
@@ -6,9 +6,78 @@ use core::ffi::c_void;
 use nix::libc;
 use nix::sys::mman::{mprotect, ProtFlags};
 
-use core::sync::atomic::{AtomicUsize, AtomicU32, Ordering};
+use core::sync::atomic::{AtomicBool, AtomicU8, AtomicUsize, AtomicU32, Ordering};
 
 const PAGE_SIZE: usize = 4096;
+const HUGE_PAGE_SIZE: usize = 2 * 1024 * 1024;
+
+/// Lazy activation states of a guarded slot.
+const SLOT_INACTIVE: u8 = 0;
+const SLOT_ACTIVATING: u8 = 1;
+const SLOT_ACTIVE: u8 = 2;
+
+/// Default slot size: one 4K page per payload.
+pub const DEFAULT_SLOT_SIZE: usize = PAGE_SIZE;
+
+/// Default per-slot in-flight ceiling; well above any supported ring depth.
+pub const DEFAULT_RC_CEILING: usize = 1 << 16;
+
+/// Errors returned by the checked `SecureSlab` APIs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlabError {
+    /// The slot index is beyond the slab's capacity.
+    InvalidSlot(usize),
+    /// `offset + len` exceeds the slot size.
+    OutOfBounds { offset: usize, len: usize },
+    /// The requested layout (or, for `new`, both fallbacks) could not be
+    /// mapped; carries the raw `errno`.
+    AllocationFailed(i32),
+    /// `allocate_slot` found no free slot.
+    Exhausted,
+    /// The slot already has `cap` submissions in flight; probable RC leak.
+    InFlightCapExceeded { slot: usize, cap: usize },
+    /// The slot is quarantined by `poison` until `clear_poison`.
+    Poisoned(usize),
+    /// The slot is still referenced by an in-flight submission.
+    InFlight(usize),
+    /// `try_with_guard_pages` was asked for a band of 0 pages.
+    EmptyGuardBand,
+    /// `try_with_slot_size` was asked for a size that is not a non-zero
+    /// multiple of 4096 fitting a `u32` payload length.
+    InvalidSlotSize(usize),
+}
+
+impl core::fmt::Display for SlabError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SlabError::InvalidSlot(idx) => write!(f, "invalid slab slot {}", idx),
+            SlabError::OutOfBounds { offset, len } => {
+                write!(f, "slot access [{}, {}) exceeds the slot size", offset, offset.saturating_add(*len))
+            }
+            SlabError::AllocationFailed(errno) => write!(f, "slab mmap failed (errno {})", errno),
+            SlabError::Exhausted => write!(f, "no free slab slot"),
+            SlabError::InFlightCapExceeded { slot, cap } => {
+                write!(f, "slot {} exceeded its in-flight cap of {} (probable RC leak)", slot, cap)
+            }
+            SlabError::Poisoned(idx) => write!(f, "slab slot {} is poisoned", idx),
+            SlabError::InFlight(idx) => write!(f, "slab slot {} is still in flight", idx),
+            SlabError::EmptyGuardBand => write!(f, "guard band must be at least one page"),
+            SlabError::InvalidSlotSize(size) => {
+                write!(f, "slot size {} is not a non-zero multiple of {} below 4 GiB", size, PAGE_SIZE)
+            }
+        }
+    }
+}
+
+impl core::error::Error for SlabError {}
+
+/// Whether a slab is backed by 2MB HugeTLB pages (see `SecureSlab::with_layout`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HugePages(pub bool);
+
+/// Whether slab slots are separated by `PROT_NONE` guard pages (see `SecureSlab::with_layout`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GuardPages(pub bool);
 
 /// A Secure, Hardware-Protected Slab Allocator.
 #[repr(align(64))]
@@ -17,8 +86,30 @@ pub struct SecureSlab {
     slots: usize,
     total_len: usize,
     huge_mode: bool,
+    /// Usable bytes per slot (a multiple of 4K).
+    slot_size: usize,
+    /// Backing pages of `PROT_NONE` guard between slots; 0 when unguarded.
+    guard_pages: usize,
     ref_counts: Vec<AtomicUsize>,
     version_ids: Vec<AtomicU32>,
+    /// Bytes of live payload per slot; only this prefix is ever sent.
+    payload_lens: Vec<AtomicU32>,
+    /// Maximum RC a slot may reach before `increment_rc` traps.
+    rc_ceiling: AtomicUsize,
+    /// Whether `write_slot` bumps the slot version after copying.
+    version_on_write: bool,
+    /// Ownership flags for the free-slot allocator.
+    allocated: Vec<AtomicBool>,
+    /// Quarantine flags; poisoned slots are never allocated or accessed via checked APIs.
+    poisoned: Vec<AtomicBool>,
+    /// Where the next `allocate_slot` scan starts.
+    alloc_hint: AtomicUsize,
+    /// Per-slot `SLOT_*` activation state (all active unless lazy).
+    activation: Vec<AtomicU8>,
+    /// Slots start `PROT_NONE` and are `mprotect`ed on first access.
+    lazy: bool,
+    /// CRC32 of each slot's live payload; `None` unless `with_checksums`.
+    checksums: Option<Vec<AtomicU32>>,
 }
 
 impl SecureSlab {
@@ -31,58 +122,196 @@ impl SecureSlab {
     ///    Any OOB access triggers a hardware-level `SIGSEGV`.
     /// 3. **Memory Hardening**: Initial state is non-executable and non-readable 
     ///    except for activated data pages.
+    ///
+    /// Panics if the mapping cannot be created; see `try_new`.
     pub fn new(slots: usize) -> Self {
-        const HUGE_PAGE_SIZE: usize = 2 * 1024 * 1024;
-        // Attempt HugeTLB Allocation first (Production Mode)
-        // Optimization: Aligned to 2MB boundaries for TLB efficiency.
-        let huge_len = core::cmp::max(slots * PAGE_SIZE, HUGE_PAGE_SIZE);
-        // Round up to multiple of 2MB
-        let huge_len = (huge_len + HUGE_PAGE_SIZE - 1) & !(HUGE_PAGE_SIZE - 1);
-        
-        let mut addr = unsafe {
-            libc::mmap(
-                core::ptr::null_mut(),
-                huge_len,
-                libc::PROT_READ | libc::PROT_WRITE,
-                libc::MAP_PRIVATE | libc::MAP_ANONYMOUS | libc::MAP_HUGETLB,
-                -1,
-                0,
-            )
+        match Self::try_new(slots) {
+            Ok(slab) => slab,
+            Err(e) => panic!("SecureSlab: {}", e),
+        }
+    }
+
+    /// Fallible variant of `new`.
+    ///
+    /// Returns `SlabError::AllocationFailed(errno)` when neither layout can be
+    /// mapped (or the layout size overflows), letting the caller retry with
+    /// fewer slots instead of aborting.
+    pub fn try_new(slots: usize) -> Result<Self, SlabError> {
+        Self::map(slots, false)
+    }
+
+    /// Creates a slab with an explicit page size and isolation policy.
+    ///
+    /// Unlike `new`, there is no fallback: all four combinations are honoured
+    /// as requested.
+    /// - `HugePages(false), GuardPages(true)`: `[Guard] [Slot 0] [Guard] ...` in 4K pages.
+    /// - `HugePages(false), GuardPages(false)`: contiguous 4K slots.
+    /// - `HugePages(true), GuardPages(false)`: contiguous slots packed into 2MB pages.
+    /// - `HugePages(true), GuardPages(true)`: each slot owns a 2MB page, separated
+    ///   by 2MB `PROT_NONE` guards. The slot API still exposes its first 4KB.
+    ///
+    /// Slots are `DEFAULT_SLOT_SIZE` bytes; see `with_slot_size` for larger ones.
+    ///
+    /// Panics if the mapping cannot be created; see `try_with_layout`.
+    pub fn with_layout(slots: usize, huge: HugePages, guard: GuardPages) -> Self {
+        match Self::try_with_layout(slots, huge, guard) {
+            Ok(slab) => slab,
+            Err(e) => panic!("SecureSlab: {}", e),
+        }
+    }
+
+    /// Fallible variant of `with_layout`.
+    ///
+    /// HugeTLB layouts fail with `AllocationFailed` when the kernel has no
+    /// huge pages reserved (`vm.nr_hugepages`).
+    pub fn try_with_layout(slots: usize, huge: HugePages, guard: GuardPages) -> Result<Self, SlabError> {
+        Self::map_layout(slots, huge, guard.0 as usize, DEFAULT_SLOT_SIZE, false)
+    }
+
+    /// Creates a guarded slab with `guard_pages` `PROT_NONE` pages around each slot.
+    ///
+    /// A single guard page only traps overruns that land on it; a strided
+    /// write can hop over it into the next slot. A wider band (e.g. 16 pages,
+    /// 64KB with 4K pages) catches larger strides at the cost of address space:
+    /// `[Guard x n] [Slot 0] [Guard x n] [Slot 1] ... [Guard x n]`. Guards use
+    /// the backing page size, so with `HugePages(true)` each is 2MB.
+    ///
+    /// Panics if `guard_pages` is 0 or the mapping cannot be created; see
+    /// `try_with_guard_pages`.
+    pub fn with_guard_pages(slots: usize, huge: HugePages, guard_pages: usize) -> Self {
+        match Self::try_with_guard_pages(slots, huge, guard_pages) {
+            Ok(slab) => slab,
+            Err(e) => panic!("SecureSlab: {}", e),
+        }
+    }
+
+    /// Fallible variant of `with_guard_pages`.
+    ///
+    /// Fails with `EmptyGuardBand` if `guard_pages` is 0, and with
+    /// `AllocationFailed(ENOMEM)` if the layout overflows the address space.
+    pub fn try_with_guard_pages(slots: usize, huge: HugePages, guard_pages: usize) -> Result<Self, SlabError> {
+        if guard_pages == 0 {
+            return Err(SlabError::EmptyGuardBand);
+        }
+        Self::map_layout(slots, huge, guard_pages, DEFAULT_SLOT_SIZE, false)
+    }
+
+    /// Creates a slab whose slots hold `slot_size` bytes instead of one 4K page.
+    ///
+    /// Larger slots let a single handle carry a bigger fragment (e.g. 8KB for
+    /// jumbo frames); the transport sizes its iovecs and payload checks from
+    /// `slot_size()`. Layout and fallback match `new`; a guarded slot spans
+    /// `slot_size` bytes of data pages between its guards.
+    ///
+    /// Panics if `slot_size` is not a non-zero multiple of 4096 or the mapping
+    /// cannot be created; see `try_with_slot_size`.
+    pub fn with_slot_size(slots: usize, slot_size: usize) -> Self {
+        match Self::try_with_slot_size(slots, slot_size) {
+            Ok(slab) => slab,
+            Err(e) => panic!("SecureSlab: {}", e),
+        }
+    }
+
+    /// Fallible variant of `with_slot_size`; an unusable `slot_size` fails
+    /// with `InvalidSlotSize`.
+    pub fn try_with_slot_size(slots: usize, slot_size: usize) -> Result<Self, SlabError> {
+        if slot_size == 0 || !slot_size.is_multiple_of(PAGE_SIZE) || slot_size > u32::MAX as usize {
+            return Err(SlabError::InvalidSlotSize(slot_size));
+        }
+        Self::map_with(slots, slot_size, false)
+    }
+
+    /// Creates a slab whose guarded slots are activated on first access.
+    ///
+    /// Startup skips the per-slot `mprotect` loop, which dominates for large,
+    /// sparsely used slabs; each slot pays one `mprotect` on its first
+    /// `get_slot`/`write_slot`/`read_slot` instead. Unguarded slabs (e.g. the
+    /// HugeTLB layout `new` prefers) are always fully active.
+    ///
+    /// Note: `CoreDispatcher::register_slab` touches every slot and therefore
+    /// activates them all.
+    pub fn new_lazy(slots: usize) -> Self {
+        match Self::try_new_lazy(slots) {
+            Ok(slab) => slab,
+            Err(e) => panic!("SecureSlab: {}", e),
+        }
+    }
+
+    /// Fallible variant of `new_lazy`.
+    pub fn try_new_lazy(slots: usize) -> Result<Self, SlabError> {
+        Self::map(slots, true)
+    }
+
+    fn map(slots: usize, lazy: bool) -> Result<Self, SlabError> {
+        Self::map_with(slots, DEFAULT_SLOT_SIZE, lazy)
+    }
+
+    fn map_with(slots: usize, slot_size: usize, lazy: bool) -> Result<Self, SlabError> {
+        // Attempt contiguous HugeTLB first (Production Mode), then fall back
+        // to guarded 4K pages (Dev Mode).
+        Self::map_layout(slots, HugePages(true), 0, slot_size, lazy)
+            .or_else(|_| Self::map_layout(slots, HugePages(false), 1, slot_size, lazy))
+    }
+
+    /// Maps `slots` slots of `slot_size` bytes with `guard_pages` guard pages
+    /// between them (0 = contiguous).
+    fn map_layout(
+        slots: usize,
+        huge: HugePages,
+        guard_pages: usize,
+        slot_size: usize,
+        lazy: bool,
+    ) -> Result<Self, SlabError> {
+        let HugePages(huge_mode) = huge;
+        let guarded = guard_pages > 0;
+        let unit = if huge_mode { HUGE_PAGE_SIZE } else { PAGE_SIZE };
+        // Overflowing layouts can never be mapped.
+        let overflow = SlabError::AllocationFailed(libc::ENOMEM);
+        let total_len = if guarded {
+            // Layout: [Guard x n] [Slot 0] [Guard x n] [Slot 1] [Guard x n] ...
+            // Total pages = slots * (n + s) + n, where a slot spans s pages.
+            let span = slot_size.div_ceil(unit);
+            guard_pages
+                .checked_add(span)
+                .and_then(|stride| slots.checked_mul(stride))
+                .and_then(|pages| pages.checked_add(guard_pages))
+                .and_then(|pages| pages.checked_mul(unit))
+                .ok_or(overflow)?
+        } else {
+            // Contiguous: [Slot 0] [Slot 1] ..., rounded up to whole pages.
+            // Optimization: HugeTLB is aligned to 2MB boundaries for TLB efficiency.
+            let data_len = slots.checked_mul(slot_size).ok_or(overflow)?;
+            data_len.max(unit).checked_add(unit - 1).ok_or(overflow)? & !(unit - 1)
         };
-        
-        let mut huge_mode = true;
-        let mut total_len = huge_len;
 
-        // Fallback to Standard 4K Pages (Dev Mode / Guarded Layout)
-        if addr == libc::MAP_FAILED {
-            huge_mode = false;
-            // Layout: [Guard] [Slot 0] [Guard] [Slot 1] [Guard] ...
-            // Total pages = slots * 2 + 1
-            total_len = (slots * 2 + 1) * PAGE_SIZE;
-
-            addr = unsafe {
-                libc::mmap(
-                    core::ptr::null_mut(),
-                    total_len,
-                    libc::PROT_NONE,
-                    libc::MAP_PRIVATE | libc::MAP_ANONYMOUS,
-                    -1,
-                    0,
-                )
-            };
+        let prot = if guarded { libc::PROT_NONE } else { libc::PROT_READ | libc::PROT_WRITE };
+        let mut flags = libc::MAP_PRIVATE | libc::MAP_ANONYMOUS;
+        if huge_mode {
+            flags |= libc::MAP_HUGETLB;
         }
+        let addr = unsafe { libc::mmap(core::ptr::null_mut(), total_len, prot, flags, -1, 0) };
 
         if addr == libc::MAP_FAILED {
-            panic!("SecureSlab: mmap failed");
+            return Err(SlabError::AllocationFailed(nix::errno::Errno::last_raw()));
         }
 
         let base = NonNull::new(addr).expect("mmap returned null");
 
         let mut ref_counts = Vec::with_capacity(slots);
         let mut version_ids = Vec::with_capacity(slots);
-        for _ in 0..slots {
+        let mut payload_lens = Vec::with_capacity(slots);
+        let mut allocated = Vec::with_capacity(slots);
+        let mut poisoned = Vec::with_capacity(slots);
+        let mut activation = Vec::with_capacity(slots);
+        let lazy = lazy && guarded;
+        for i in 0..slots {
+            activation.push(AtomicU8::new(if lazy { SLOT_INACTIVE } else { SLOT_ACTIVE }));
             ref_counts.push(AtomicUsize::new(0));
             version_ids.push(AtomicU32::new(0));
+            payload_lens.push(AtomicU32::new(0));
+            // Slot 0 is the "no payload" handle and is never handed out.
+            allocated.push(AtomicBool::new(i == 0));
+            poisoned.push(AtomicBool::new(false));
         }
 
         let slab = Self {
@@ -90,62 +319,310 @@ impl SecureSlab {
             slots,
             total_len,
             huge_mode,
+            slot_size,
+            guard_pages,
             ref_counts,
             version_ids,
+            payload_lens,
+            rc_ceiling: AtomicUsize::new(DEFAULT_RC_CEILING),
+            version_on_write: true,
+            allocated,
+            poisoned,
+            alloc_hint: AtomicUsize::new(1),
+            activation,
+            lazy,
+            checksums: None,
         };
 
-        // Activate data pages (if not already HUGE_TLB RW)
-        if !huge_mode {
+        // Activate data pages (if not already mapped RW, and not deferred)
+        if guarded && !lazy {
             for i in 0..slots {
                 slab.activate_slot(i);
             }
         }
 
-        slab
+        Ok(slab)
+    }
+
+    /// Sets the per-slot reference count ceiling.
+    ///
+    /// A slot can never legitimately be in flight more times than the ring
+    /// has entries, so setting this to the ring depth catches RC leaks long
+    /// before the counter could wrap.
+    pub fn with_rc_ceiling(self, ceiling: usize) -> Self {
+        self.set_max_inflight(ceiling);
+        self
+    }
+
+    /// Runtime counterpart of `with_rc_ceiling`, usable on a shared slab
+    /// (e.g. once the dispatcher knows its ring depth).
+    pub fn set_max_inflight(&self, cap: usize) {
+        assert!(cap > 0, "SecureSlab: rc_ceiling must be non-zero");
+        self.rc_ceiling.store(cap, Ordering::Relaxed);
+    }
+
+    /// Returns the per-slot reference count ceiling.
+    pub fn rc_ceiling(&self) -> usize {
+        self.rc_ceiling.load(Ordering::Relaxed)
+    }
+
+    /// Controls whether `write_slot` advances the slot version (default: on).
+    ///
+    /// Disable only when the caller commits versions explicitly, e.g. when a
+    /// payload is assembled from several writes and must ship as one version.
+    pub fn with_version_on_write(mut self, enabled: bool) -> Self {
+        self.version_on_write = enabled;
+        self
+    }
+
+    /// Maintains a CRC32 of every slot's payload for `verify_slot` (default: off).
+    ///
+    /// Meant for hosts without ECC memory, where a bit flip in a cached slot
+    /// would otherwise ship with a valid version. Costs a payload scan on
+    /// every `write_slot`/`set_payload_len` and on every `verify_slot`.
+    pub fn with_checksums(mut self, enabled: bool) -> Self {
+        // Untouched lazy slots still hold the zero page; don't activate them.
+        let zero_page = crate::hash::crc32(&alloc::vec![0u8; self.slot_size]);
+        self.checksums = enabled.then(|| {
+            (0..self.slots)
+                .map(|idx| {
+                    let sum = if self.is_activated(idx) { self.payload_crc(idx) } else { zero_page };
+                    AtomicU32::new(sum)
+                })
+                .collect()
+        });
+        self
+    }
+
+    /// Returns `true` if slot `idx` still matches the checksum recorded at its
+    /// last write, i.e. it was not corrupted since.
+    ///
+    /// Always `true` when checksums are disabled.
+    pub fn verify_slot(&self, idx: usize) -> bool {
+        assert!(idx < self.slots);
+        match &self.checksums {
+            Some(sums) => sums[idx].load(Ordering::Acquire) == self.payload_crc(idx),
+            None => true,
+        }
+    }
+
+    /// CRC32 of the live payload prefix of slot `idx`.
+    fn payload_crc(&self, idx: usize) -> u32 {
+        let len = self.payload_len(idx);
+        // # Safety: `payload_len` never exceeds the slot size.
+        let payload = unsafe { core::slice::from_raw_parts(self.get_slot(idx), len) };
+        crate::hash::crc32(payload)
+    }
+
+    /// Records the current payload checksum of slot `idx`, if enabled.
+    fn update_checksum(&self, idx: usize) {
+        if let Some(sums) = &self.checksums {
+            sums[idx].store(self.payload_crc(idx), Ordering::Release);
+        }
     }
 
     /// Activates a specific memory slot for read/write operations.
     fn activate_slot(&self, idx: usize) {
-        // Offset: (1 + idx * 2) Skip the initial guard + pairs of slot/guard
-        let offset = (1 + idx * 2) * PAGE_SIZE;
-        
         // # Safety: Pointer arithmetic is sound within the reserved mmap range.
         // We ensure 64-byte alignment by virtue of PAGE_SIZE (4096) being a multiple of 64.
         unsafe {
-            let slot_ptr = self.base.as_ptr().byte_add(offset);
+            let slot_ptr = self.base.as_ptr().byte_add(self.slot_offset(idx));
+            // HugeTLB mappings can only be re-protected in whole 2MB pages.
             mprotect(
                 NonNull::new(slot_ptr).unwrap(),
-                PAGE_SIZE,
+                self.slot_span(),
                 ProtFlags::PROT_READ | ProtFlags::PROT_WRITE,
             ).expect("SecureSlab: mprotect activation failed");
         }
     }
 
-    /// Returns a direct pointer to the 4KB data page of the given slot.
+    /// Activates a lazy slot exactly once, even under concurrent first access.
+    ///
+    /// The winner of the `INACTIVE -> ACTIVATING` CAS calls `mprotect`;
+    /// everyone else spins until it publishes `ACTIVE`. If the winner panics
+    /// (e.g. `mprotect` fails), the slot drops back to `INACTIVE` and a
+    /// waiter takes over the activation instead of spinning forever.
+    #[cold]
+    fn activate_lazily(&self, idx: usize) {
+        /// Resets `ACTIVATING` to `INACTIVE` unless disarmed.
+        struct Rollback<'a>(&'a AtomicU8);
+
+        impl Drop for Rollback<'_> {
+            fn drop(&mut self) {
+                self.0.store(SLOT_INACTIVE, Ordering::Release);
+            }
+        }
+
+        let state = &self.activation[idx];
+        loop {
+            match state.compare_exchange(SLOT_INACTIVE, SLOT_ACTIVATING, Ordering::Acquire, Ordering::Acquire) {
+                Ok(_) => {
+                    let rollback = Rollback(state);
+                    self.activate_slot(idx);
+                    core::mem::forget(rollback);
+                    state.store(SLOT_ACTIVE, Ordering::Release);
+                    return;
+                }
+                Err(SLOT_ACTIVE) => return,
+                Err(_) => {
+                    while state.load(Ordering::Acquire) == SLOT_ACTIVATING {
+                        core::hint::spin_loop();
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns `true` if the slot's page is readable and writable.
+    pub fn is_activated(&self, idx: usize) -> bool {
+        assert!(idx < self.slots);
+        self.activation[idx].load(Ordering::Acquire) == SLOT_ACTIVE
+    }
+
+    /// Number of slots whose page has been activated.
+    pub fn activated_slots(&self) -> usize {
+        self.activation.iter().filter(|s| s.load(Ordering::Acquire) == SLOT_ACTIVE).count()
+    }
+
+    /// Returns `true` if slots are separated by `PROT_NONE` guard pages.
+    ///
+    /// The HugeTLB layout `new` prefers is contiguous and has no guard pages.
+    pub fn is_guarded(&self) -> bool {
+        self.guard_pages > 0
+    }
+
+    /// Backing pages of guard between adjacent slots (0 when unguarded).
+    pub fn guard_pages(&self) -> usize {
+        self.guard_pages
+    }
+
+    /// Returns `true` if the slab is backed by 2MB HugeTLB pages.
+    pub fn is_huge_pages(&self) -> bool {
+        self.huge_mode
+    }
+
+    /// Returns the NUMA node that slot `idx`'s physical frame resides on.
+    ///
+    /// See `NumaPinnedSlab::numa_node_of`; a lazy slot is activated first.
+    pub fn numa_node_of(&self, idx: usize) -> Option<i32> {
+        crate::numa::node_of_addr(self.get_slot(idx) as *const c_void)
+    }
+
+    /// Usable bytes per slot (`DEFAULT_SLOT_SIZE` unless `with_slot_size`).
+    pub fn slot_size(&self) -> usize {
+        self.slot_size
+    }
+
+    /// Size of the backing pages (and of each guard).
+    fn page_size(&self) -> usize {
+        if self.huge_mode { HUGE_PAGE_SIZE } else { PAGE_SIZE }
+    }
+
+    /// Bytes a guarded slot occupies: its size rounded up to whole backing pages.
+    fn slot_span(&self) -> usize {
+        self.slot_size.div_ceil(self.page_size()) * self.page_size()
+    }
+
+    /// Byte offset of slot `idx` from the mapping base.
+    fn slot_offset(&self, idx: usize) -> usize {
+        if self.guard_pages > 0 {
+            // Guarded: [Guard x n] [Slot 0] [Guard x n] [Slot 1] ...
+            let guard = self.guard_pages * self.page_size();
+            guard + idx * (guard + self.slot_span())
+        } else {
+            // Contiguous: [Slot 0] [Slot 1] ...
+            idx * self.slot_size
+        }
+    }
+
+    /// Returns a direct pointer to the data pages of the given slot.
     ///
     /// ## Performance
     /// Returns in ~5 cycles. Optimal for hot-path transport loops.
     pub fn get_slot(&self, idx: usize) -> *mut u8 {
         assert!(idx < self.slots);
-        let offset = if self.huge_mode {
-            // Contiguous: [Slot 0] [Slot 1] ...
-            idx * PAGE_SIZE
-        } else {
-            // Guarded: [Guard] [Slot 0] [Guard] [Slot 1] ...
-            (1 + idx * 2) * PAGE_SIZE
-        };
+        if self.lazy && self.activation[idx].load(Ordering::Acquire) != SLOT_ACTIVE {
+            self.activate_lazily(idx);
+        }
+        let offset = self.slot_offset(idx);
         // Mechanical Sympathy: The offset is always page-aligned (and thus cache-aligned).
         unsafe { self.base.as_ptr().byte_add(offset) as *mut u8 }
     }
 
+    /// Copies `data` into slot `idx` starting at `offset`.
+    ///
+    /// Bounds-checked wrapper over `get_slot`; prefer it outside the hot path.
+    /// The slot's payload length grows to `offset + data.len()` if that is
+    /// past its current end; it never shrinks here, so patching a header in
+    /// place keeps the body live. Use `set_payload_len` to truncate. Unless disabled
+    /// via `with_version_on_write`, the slot version is bumped after the copy
+    /// so pushes committed against the old content go stale. With
+    /// `with_checksums`, the slot checksum is refreshed as well.
+    pub fn write_slot(&self, idx: usize, offset: usize, data: &[u8]) -> Result<(), SlabError> {
+        let ptr = self.checked_slot(idx, offset, data.len())?;
+        // # Safety: `checked_slot` proved [offset, offset + len) lies inside the slot page.
+        unsafe {
+            core::ptr::copy_nonoverlapping(data.as_ptr(), ptr, data.len());
+        }
+        self.payload_lens[idx].fetch_max((offset + data.len()) as u32, Ordering::Release);
+        self.update_checksum(idx);
+        if self.version_on_write {
+            self.increment_version(idx);
+        }
+        Ok(())
+    }
+
+    /// Copies `out.len()` bytes from slot `idx` starting at `offset` into `out`.
+    pub fn read_slot(&self, idx: usize, offset: usize, out: &mut [u8]) -> Result<(), SlabError> {
+        let ptr = self.checked_slot(idx, offset, out.len())?;
+        // # Safety: `checked_slot` proved [offset, offset + len) lies inside the slot page.
+        unsafe {
+            core::ptr::copy_nonoverlapping(ptr, out.as_mut_ptr(), out.len());
+        }
+        Ok(())
+    }
+
+    /// Returns the slot pointer advanced by `offset` if `offset + len` fits in the slot.
+    fn checked_slot(&self, idx: usize, offset: usize, len: usize) -> Result<*mut u8, SlabError> {
+        if idx >= self.slots {
+            return Err(SlabError::InvalidSlot(idx));
+        }
+        if self.is_poisoned(idx) {
+            return Err(SlabError::Poisoned(idx));
+        }
+        match offset.checked_add(len) {
+            Some(end) if end <= self.slot_size => Ok(unsafe { self.get_slot(idx).add(offset) }),
+            _ => Err(SlabError::OutOfBounds { offset, len }),
+        }
+    }
+
     /// Increments the reference count for a specific slot.
     /// 
     /// # Protocol
     /// Must be called when a buffer is submitted to the io_uring SQ.
     /// Uses `Ordering::Release` to ensure the buffer content is visible to the kernel.
+    ///
+    /// # Panics
+    /// Panics if the RC would exceed the configured ceiling, indicating a leak
+    /// of unmatched increments. The count is rolled back first, so it never wraps.
     pub fn increment_rc(&self, idx: usize) {
+        if let Err(SlabError::InFlightCapExceeded { slot, cap }) = self.try_increment_rc(idx) {
+            panic!("SecureSlab: increment_rc exceeded ceiling {} on slot {}", cap, slot);
+        }
+    }
+
+    /// Fallible `increment_rc`: reports the leaking slot instead of panicking.
+    ///
+    /// Returns the new in-flight count. On error the count is left unchanged.
+    pub fn try_increment_rc(&self, idx: usize) -> Result<usize, SlabError> {
         assert!(idx < self.slots);
-        self.ref_counts[idx].fetch_add(1, Ordering::Release);
+        let cap = self.rc_ceiling();
+        let prev = self.ref_counts[idx].fetch_add(1, Ordering::Release);
+        if prev >= cap {
+            self.ref_counts[idx].fetch_sub(1, Ordering::Relaxed);
+            return Err(SlabError::InFlightCapExceeded { slot: idx, cap });
+        }
+        Ok(prev + 1)
     }
 
     /// Decrements the reference count for a specific slot.
@@ -161,10 +638,23 @@ impl SecureSlab {
         }
     }
 
+    /// Releases `n` holds on slot `idx` with a single atomic RMW.
+    ///
+    /// Lets the reaper batch completions that target the same slot (e.g. the
+    /// segments of one GSO burst) instead of paying one `fetch_sub` per CQE.
+    /// Panics like `decrement_rc` if the slot holds fewer than `n` references.
+    pub fn decrement_rc_by(&self, idx: usize, n: usize) {
+        assert!(idx < self.slots);
+        let prev = self.ref_counts[idx].fetch_sub(n, Ordering::Acquire);
+        if prev < n {
+            panic!("SecureSlab: decrement_rc_by({}) called on slot with RC {}", n, prev);
+        }
+    }
+
     /// Explicitly releases a slot back to the "FREE" state.
-    /// 
-    /// # Safety
-    /// Panics if the RC is non-zero, indicating a kernel-flight violation.
+    ///
+    /// # Panics
+    /// If the RC is non-zero, indicating a kernel-flight violation.
     pub fn explicit_release(&self, idx: usize) {
         assert!(idx < self.slots);
         if self.ref_counts[idx].load(Ordering::Acquire) > 0 {
@@ -172,6 +662,68 @@ impl SecureSlab {
         }
     }
 
+    /// Claims a free slot for a new payload.
+    ///
+    /// Lock-free: scans the ownership flags from a rotating hint and claims
+    /// the first free slot with a CAS. Slot 0 is reserved as the "no payload"
+    /// handle and is never returned. The claimed slot starts with an empty
+    /// payload, so the first `write_slot` sets its length exactly, even if
+    /// a previous owner left a longer one.
+    pub fn allocate_slot(&self) -> Result<usize, SlabError> {
+        let start = self.alloc_hint.load(Ordering::Relaxed);
+        for step in 0..self.slots {
+            let idx = (start + step) % self.slots;
+            if self.poisoned[idx].load(Ordering::Acquire) {
+                continue;
+            }
+            if self.allocated[idx]
+                .compare_exchange(false, true, Ordering::AcqRel, Ordering::Relaxed)
+                .is_ok()
+            {
+                self.alloc_hint.store(idx + 1, Ordering::Relaxed);
+                self.payload_lens[idx].store(0, Ordering::Release);
+                return Ok(idx);
+            }
+        }
+        Err(SlabError::Exhausted)
+    }
+
+    /// Returns a slot claimed by `allocate_slot` to the free pool.
+    ///
+    /// # Panics
+    /// If the slot is still in flight (see `explicit_release`), or is not
+    /// currently allocated: a double free would let two owners share it.
+    pub fn free_slot(&self, idx: usize) {
+        assert!(idx > 0, "SecureSlab: slot 0 is reserved");
+        self.explicit_release(idx);
+        if !self.allocated[idx].swap(false, Ordering::AcqRel) {
+            panic!("SecureSlab: free_slot called on slot {} that is not allocated", idx);
+        }
+    }
+
+    /// Quarantines slot `idx` after a suspected fault (e.g. corrupt payload).
+    ///
+    /// `allocate_slot` skips the slot and the checked `write_slot`/`read_slot`
+    /// fail with `SlabError::Poisoned` until `clear_poison`, and the transport
+    /// refuses to push it. A current owner keeps its raw `get_slot` pointer,
+    /// so poison before freeing the slot.
+    pub fn poison(&self, idx: usize) {
+        assert!(idx < self.slots);
+        self.poisoned[idx].store(true, Ordering::Release);
+    }
+
+    /// Returns a poisoned slot to service after inspection.
+    pub fn clear_poison(&self, idx: usize) {
+        assert!(idx < self.slots);
+        self.poisoned[idx].store(false, Ordering::Release);
+    }
+
+    /// Returns `true` if slot `idx` is quarantined.
+    pub fn is_poisoned(&self, idx: usize) -> bool {
+        assert!(idx < self.slots);
+        self.poisoned[idx].load(Ordering::Acquire)
+    }
+
     /// Returns the number of slots in the slab.
     pub fn slots(&self) -> usize {
         self.slots
@@ -183,6 +735,14 @@ impl SecureSlab {
         self.ref_counts[idx].load(Ordering::Acquire) > 0
     }
 
+    /// Returns the current reference count of a slot.
+    ///
+    /// Diagnostic read only (`Relaxed`); do not use it to gate slot reuse.
+    pub fn ref_count(&self, idx: usize) -> usize {
+        assert!(idx < self.slots);
+        self.ref_counts[idx].load(Ordering::Relaxed)
+    }
+
     /// Gets the current version ID of a slot.
     #[inline(always)]
     pub fn get_version(&self, idx: usize) -> u32 {
@@ -201,6 +761,25 @@ impl SecureSlab {
         assert!(idx < self.slots);
         self.version_ids[idx].fetch_add(1, Ordering::AcqRel) + 1
     }
+
+    /// Returns the number of live payload bytes in a slot (0 until written).
+    #[inline(always)]
+    pub fn payload_len(&self, idx: usize) -> usize {
+        assert!(idx < self.slots);
+        self.payload_lens[idx].load(Ordering::Acquire) as usize
+    }
+
+    /// Sets the live payload length of a slot filled through `get_slot`.
+    ///
+    /// The only way to shrink a slot's length; `write_slot` only grows it.
+    /// This is the commit point for raw writes, so it also refreshes the
+    /// slot checksum when `with_checksums` is on.
+    pub fn set_payload_len(&self, idx: usize, len: usize) {
+        assert!(idx < self.slots);
+        assert!(len <= self.slot_size, "SecureSlab: payload length {} exceeds slot size", len);
+        self.payload_lens[idx].store(len as u32, Ordering::Release);
+        self.update_checksum(idx);
+    }
 }
 
 impl Drop for SecureSlab {
@@ -214,3 +793,110 @@ impl Drop for SecureSlab {
 
 unsafe impl Send for SecureSlab {}
 unsafe impl Sync for SecureSlab {}
+
+/// The slab operations the transport data path relies on.
+///
+/// `SecureSlab` is the production implementation; the trait lets the
+/// dispatcher run over NUMA-pinned or sharded slabs, or over plain memory
+/// in tests. Slots are `slot_size()` bytes (4096 by default) and handle 0
+/// is reserved.
+///
+/// # Safety
+/// The transport turns `get_slot` pointers into iovecs the kernel reads
+/// after the call returns, so implementations must guarantee:
+///
+/// - For every `idx < slots()`, `get_slot(idx)` points to `slot_size()`
+///   bytes valid for reads and writes, disjoint from every other slot.
+/// - That memory stays mapped at the same address for as long as the slab
+///   lives; `slots()` and `slot_size()` never change.
+/// - `payload_len(idx)` never exceeds `slot_size()`.
+/// - `is_in_flight(idx)` is `true` while any `increment_rc` on `idx` is not
+///   yet matched by a release, and `allocate_slot` never returns a slot
+///   that is in flight or already allocated.
+///
+/// Callers in turn keep the slab alive until every slot they pinned has
+/// been released, e.g. by reaping until `is_in_flight` is `false`.
+pub unsafe trait SlabLike {
+    /// Raw pointer to the start of slot `idx`.
+    fn get_slot(&self, idx: usize) -> *mut u8;
+    /// Number of slots.
+    fn slots(&self) -> usize;
+    /// Current version ID of slot `idx`.
+    fn get_version(&self, idx: usize) -> u32;
+    /// Live payload bytes in slot `idx`.
+    fn payload_len(&self, idx: usize) -> usize;
+    /// Bytes each slot can hold; sizes every iovec the transport builds.
+    fn slot_size(&self) -> usize {
+        DEFAULT_SLOT_SIZE
+    }
+    /// Marks one more kernel submission as reading slot `idx`.
+    fn increment_rc(&self, idx: usize);
+    /// Releases one submission's hold on slot `idx`.
+    fn decrement_rc(&self, idx: usize);
+    /// Releases `n` holds on slot `idx` at once.
+    fn decrement_rc_by(&self, idx: usize, n: usize) {
+        for _ in 0..n {
+            self.decrement_rc(idx);
+        }
+    }
+    /// Whether any submission still holds slot `idx`.
+    fn is_in_flight(&self, idx: usize) -> bool;
+    /// Whether slot `idx` is quarantined; the push path refuses such slots.
+    fn is_poisoned(&self, idx: usize) -> bool;
+    /// Claims a free slot.
+    fn allocate_slot(&self) -> Result<usize, SlabError>;
+    /// Returns a slot claimed by `allocate_slot`.
+    fn free_slot(&self, idx: usize);
+}
+
+// # Safety: slots are fixed, disjoint ranges of one mapping that lives until
+// drop, and the RC/free-list bookkeeping above upholds the in-flight rules.
+unsafe impl SlabLike for SecureSlab {
+    fn get_slot(&self, idx: usize) -> *mut u8 {
+        SecureSlab::get_slot(self, idx)
+    }
+
+    fn slots(&self) -> usize {
+        SecureSlab::slots(self)
+    }
+
+    fn get_version(&self, idx: usize) -> u32 {
+        SecureSlab::get_version(self, idx)
+    }
+
+    fn payload_len(&self, idx: usize) -> usize {
+        SecureSlab::payload_len(self, idx)
+    }
+
+    fn slot_size(&self) -> usize {
+        SecureSlab::slot_size(self)
+    }
+
+    fn increment_rc(&self, idx: usize) {
+        SecureSlab::increment_rc(self, idx)
+    }
+
+    fn decrement_rc(&self, idx: usize) {
+        SecureSlab::decrement_rc(self, idx)
+    }
+
+    fn decrement_rc_by(&self, idx: usize, n: usize) {
+        SecureSlab::decrement_rc_by(self, idx, n)
+    }
+
+    fn is_in_flight(&self, idx: usize) -> bool {
+        SecureSlab::is_in_flight(self, idx)
+    }
+
+    fn is_poisoned(&self, idx: usize) -> bool {
+        SecureSlab::is_poisoned(self, idx)
+    }
+
+    fn allocate_slot(&self) -> Result<usize, SlabError> {
+        SecureSlab::allocate_slot(self)
+    }
+
+    fn free_slot(&self, idx: usize) {
+        SecureSlab::free_slot(self, idx)
+    }
+}
@@ -1,3 +1,6 @@
+use crate::arena::{HeapNodes, NodeAllocator, NodeArena};
+use alloc::collections::BTreeMap;
+use alloc::sync::Arc;
 use alloc::vec::Vec;
 use core::fmt;
 
@@ -22,19 +25,297 @@ pub struct TrieNode {
     pub version_id: u32,
     /// Semantic Versioning Bitmask (e.g., protocol version, fragment flags).
     pub semantic_mask: u32,
-    /// Metadata flags.
+    /// Metadata flags; one `FlagBit` per bit (see `LinearIntentTrie::set_flag`).
     pub flags: u8,
+    /// Access epoch of the last `observe`/`warm` through this node; drives
+    /// eviction under a node budget.
+    pub last_access: u32,
     /// Explicit padding to hit exactly 64 bytes (L1 Cache Line alignment).
-    _padding: [u8; 37],
+    _padding: [u8; 33],
 }
 
 static_assertions::assert_eq_size!(TrieNode, [u8; 64]);
 
+impl TrieNode {
+    /// A node with no links, weights, payload or flags.
+    pub(crate) const EMPTY: Self = Self {
+        children: [NULL_NODE, NULL_NODE],
+        weights: [0, 0],
+        payload_handle: 0,
+        version_id: 0,
+        semantic_mask: 0,
+        flags: 0,
+        last_access: 0,
+        _padding: [0; 33],
+    };
+
+    /// Returns `true` if `flag` is set on this node.
+    #[inline(always)]
+    pub fn has_flag(&self, flag: FlagBit) -> bool {
+        self.flags & flag.0 != 0
+    }
+}
+
+/// One bit of `TrieNode::flags`.
+///
+/// Bits 0-3 have crate-defined meanings; bits 4-7 are free for callers via
+/// `FlagBit::user`. Flags survive serialization and are OR-ed together by
+/// `merge_structural`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FlagBit(u8);
+
+impl FlagBit {
+    /// Never evicted under a node budget, nor are its ancestors. Set on
+    /// every route bound by `associate_payload` or `warm_many`.
+    pub const PINNED: Self = Self(1 << 0);
+    /// Ends a registered route. Set alongside `PINNED` when a payload binds.
+    pub const TERMINAL: Self = Self(1 << 1);
+    /// Stands for any continuation of its path.
+    pub const WILDCARD: Self = Self(1 << 2);
+    /// The route still resolves but is scheduled for removal.
+    pub const DEPRECATED: Self = Self(1 << 3);
+
+    /// Number of bits reserved for callers.
+    pub const USER_BITS: u8 = 4;
+
+    /// Caller-defined bit `n` (`0..USER_BITS`). Panics if `n` is out of range.
+    pub const fn user(n: u8) -> Self {
+        assert!(n < Self::USER_BITS, "FlagBit: user bit out of range");
+        Self(1 << (4 + n))
+    }
+
+    /// The raw mask of this bit within `TrieNode::flags`.
+    pub const fn mask(self) -> u8 {
+        self.0
+    }
+}
+
+/// A payload-bearing path discovered by walking the trie.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TrieRoute {
+    /// The full byte path from the root to the terminal node.
+    pub path: Vec<u8>,
+    pub payload_handle: u32,
+    pub version_id: u32,
+    /// `FlagBit`s of the terminal node.
+    pub flags: u8,
+}
+
+/// The comparable content of a node, excluding its pool-relative links.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NodeState {
+    pub weights: [u8; 2],
+    pub payload_handle: u32,
+    pub version_id: u32,
+}
+
+impl From<&TrieNode> for NodeState {
+    fn from(node: &TrieNode) -> Self {
+        Self {
+            weights: node.weights,
+            payload_handle: node.payload_handle,
+            version_id: node.version_id,
+        }
+    }
+}
+
+/// A node whose presence or content differs between two tries.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NodeDiff {
+    /// Bit path from the root, packed MSB-first; the last byte may be partial.
+    pub path: Vec<u8>,
+    /// Number of significant bits in `path`.
+    pub depth: usize,
+    /// The node in `self`, or `None` if only the other trie has it.
+    pub ours: Option<NodeState>,
+    /// The node in `other`, or `None` if only `self` has it.
+    pub theirs: Option<NodeState>,
+}
+
+/// Largest node pool a trie can index: child links are `u32` and
+/// `u32::MAX` is the null link, so indices stop one short of it.
+pub const MAX_TRIE_NODES: usize = u32::MAX as usize;
+
+/// Deepest continuation, in bytes past the context, `top_continuations` considers.
+pub const MAX_LOOKAHEAD_BYTES: usize = 32;
+
+/// Most continuations a single `top_continuations` call returns.
+pub const MAX_CONTINUATIONS: usize = 8;
+
+/// Default `max_depth_bits`: contexts up to 1 KiB get distinct nodes.
+pub const DEFAULT_MAX_DEPTH_BITS: usize = 1024 * 8;
+
+/// Errors returned by the checked `LinearIntentTrie` APIs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrieError {
+    /// The path needs `bits` levels but the trie is capped at `max_bits`.
+    PathTooDeep { bits: usize, max_bits: usize },
+    /// The path leaves the trie at bit `depth`; it was never warmed.
+    UnknownPath { depth: usize },
+    /// `from_bytes` input is truncated, has a bad header or dangling links.
+    Malformed,
+    /// The pool already holds `nodes` nodes, its limit (see
+    /// `with_node_limit`); a further index would alias `NULL_NODE`.
+    CapacityExhausted { nodes: usize },
+}
+
+impl fmt::Display for TrieError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrieError::PathTooDeep { bits, max_bits } => {
+                write!(f, "path of {} bits exceeds the trie depth cap of {} bits", bits, max_bits)
+            }
+            TrieError::UnknownPath { depth } => write!(f, "path is not in the trie (diverges at bit {})", depth),
+            TrieError::Malformed => write!(f, "malformed serialized trie"),
+            TrieError::CapacityExhausted { nodes } => write!(f, "trie node pool is full at {} nodes", nodes),
+        }
+    }
+}
+
+impl core::error::Error for TrieError {}
+
+/// The learning and routing surface shared by the trie layouts.
+///
+/// `LinearIntentTrie` branches on one bit per node, `ByteIntentTrie` on one
+/// byte. Both answer these calls identically for the same history, so code
+/// that only learns and routes can be written against either. Merging,
+/// serialization and hashed encodings are `LinearIntentTrie`-only, which is
+/// why `PredictiveEngine` is built on it rather than on this trait.
+pub trait IntentTrie {
+    /// Records one observation of `next_bit` following `context`.
+    fn observe(&mut self, context: &[u8], next_bit: bool);
+    /// Share of observations after `context` that were `next_bit` (0.0 if none).
+    fn get_probability(&self, context: &[u8], next_bit: bool) -> f32;
+    /// `observe`, failing with `CapacityExhausted` when `context` needs a
+    /// node past `node_limit`.
+    fn try_observe(&mut self, context: &[u8], next_bit: bool) -> Result<(), TrieError>;
+    /// Allocates the nodes along `path` without touching any weights.
+    fn warm(&mut self, path: &[u8]);
+    /// `warm`, failing with `CapacityExhausted` when `path` needs a node
+    /// past `node_limit`.
+    fn try_warm(&mut self, path: &[u8]) -> Result<(), TrieError>;
+    /// The hard pool ceiling; never above `MAX_TRIE_NODES`.
+    fn node_limit(&self) -> usize;
+    /// Binds a payload to the node at `context`; `UnknownPath` if never warmed.
+    fn associate_payload(&mut self, context: &[u8], handle: u32, version_id: u32) -> Result<(), TrieError>;
+    /// The node at the end of `path`, if the trie has one.
+    fn get_node_at_path(&self, path: &[u8]) -> Option<&TrieNode>;
+}
+
+/// Bit depth of every path under `PathEncoding::Hashed`.
+pub const HASHED_DEPTH_BITS: usize = 64;
+
+/// How a trie maps context paths to bit paths; see
+/// `LinearIntentTrie::with_path_encoding`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PathEncoding {
+    /// Uses the raw path bytes as the bit path (the default).
+    ///
+    /// Paths sharing a byte prefix share nodes, which prefix matching and
+    /// continuation prediction rely on. Depth grows with path length, so it
+    /// is bounded only by `max_depth_bits`.
+    #[default]
+    Identity,
+    /// Uses the 64-bit `context_hash` of the path as the bit path.
+    ///
+    /// Every path is exactly `HASHED_DEPTH_BITS` deep and bits are uniformly
+    /// distributed, so crafted paths cannot force deep chains. The price is
+    /// that related paths no longer share prefixes.
+    Hashed,
+}
+
+impl PathEncoding {
+    /// The bit path of `path`, most significant bit first.
+    pub fn encode(self, path: &[u8]) -> impl Iterator<Item = bool> + '_ {
+        self.bits(path)
+    }
+
+    fn bits(self, path: &[u8]) -> PathBits<'_> {
+        match self {
+            PathEncoding::Identity => PathBits::raw(path),
+            PathEncoding::Hashed => PathBits::hashed(path),
+        }
+    }
+}
+
+/// MSB-first bit iterator behind the built-in encoders.
+///
+/// The source is picked once per byte, not per bit, to keep lookups tight.
+struct PathBits<'a> {
+    raw: &'a [u8],
+    hash: [u8; 8],
+    hashed: bool,
+    /// Index of the next byte to load.
+    pos: usize,
+    /// The current byte and how many of its bits are still unread.
+    byte: u8,
+    left: u8,
+}
+
+impl<'a> PathBits<'a> {
+    fn raw(path: &'a [u8]) -> Self {
+        Self { raw: path, hash: [0; 8], hashed: false, pos: 0, byte: 0, left: 0 }
+    }
+
+    fn hashed(path: &[u8]) -> Self {
+        let hash = crate::hash::context_hash(path).to_be_bytes();
+        Self { raw: &[], hash, hashed: true, pos: 0, byte: 0, left: 0 }
+    }
+}
+
+impl Iterator for PathBits<'_> {
+    type Item = bool;
+
+    #[inline]
+    fn next(&mut self) -> Option<bool> {
+        if self.left == 0 {
+            let bytes: &[u8] = if self.hashed { &self.hash } else { self.raw };
+            self.byte = *bytes.get(self.pos)?;
+            self.pos += 1;
+            self.left = 8;
+        }
+        self.left -= 1;
+        Some((self.byte >> self.left) & 1 == 1)
+    }
+
+    /// Skips whole bytes at once; `resume_lookup` leans on this.
+    fn nth(&mut self, n: usize) -> Option<bool> {
+        if n >= self.left as usize {
+            let skip = n - self.left as usize;
+            self.pos += skip / 8;
+            self.left = 0;
+            // Load the byte holding the target bit, then drop the bits before it.
+            self.next()?;
+            self.left -= (skip % 8) as u8;
+            self.left += 1;
+            return self.next();
+        }
+        self.left -= n as u8;
+        self.next()
+    }
+}
+
+
+
 #[derive(Clone)]
 pub struct LinearIntentTrie {
-    nodes: Vec<TrieNode>,
+    nodes: NodeArena,
+    /// Deepest bit level `observe`/`warm` will allocate; see `with_max_depth_bits`.
+    max_depth_bits: usize,
+    /// How context paths become bit paths; see `with_path_encoding`.
+    encoding: PathEncoding,
     /// Unique sequence number to prevent stale learning updates.
     pub sequence_number: u64,
+    /// Live node cap enforced by evicting cold paths; see `with_node_budget`.
+    node_budget: Option<usize>,
+    /// Access clock stamped into `TrieNode::last_access` by budgeted inserts.
+    epoch: u32,
+    /// Eviction passes so far; each one reassigns node indices.
+    evictions: u64,
+    /// Hard pool ceiling; see `with_node_limit`.
+    node_limit: usize,
+    /// Whether the infallible inserts have already logged `CapacityExhausted`.
+    exhaustion_logged: bool,
 }
 
 impl fmt::Debug for LinearIntentTrie {
@@ -42,15 +323,34 @@ impl fmt::Debug for LinearIntentTrie {
         f.debug_struct("LinearIntentTrie")
             .field("nodes_len", &self.nodes.len())
             .field("sequence_number", &self.sequence_number)
+            .field("max_depth_bits", &self.max_depth_bits)
+            .field("encoding", &self.encoding)
+            .field("node_budget", &self.node_budget)
             .finish()
     }
 }
 
 const NULL_NODE: u32 = u32::MAX;
 
+/// Leading bytes of `LinearIntentTrie::to_bytes` output.
+const SERIAL_MAGIC: &[u8; 4] = b"HXT1";
+/// Serialized header: magic, encoding, max depth, sequence number, node count.
+const SERIAL_HEADER_LEN: usize = 4 + 1 + 8 + 8 + 4;
+/// Serialized node: children, weights, payload, version, mask, flags.
+const SERIAL_NODE_LEN: usize = 8 + 2 + 4 + 4 + 4 + 1;
+
 impl LinearIntentTrie {
     pub fn new(capacity: usize) -> Self {
-        let mut nodes = Vec::with_capacity(capacity);
+        Self::new_in(capacity, Arc::new(HeapNodes))
+    }
+
+    /// Creates a trie whose node pool is allocated by `allocator`.
+    ///
+    /// Pass `GuardedNodes` to keep the pool between `PROT_NONE` guard pages,
+    /// so an out-of-bounds write into the node array faults. Every growth,
+    /// clone and eviction pass allocates from the same allocator.
+    pub fn new_in(capacity: usize, allocator: Arc<dyn NodeAllocator>) -> Self {
+        let mut nodes = NodeArena::with_capacity_in(capacity, allocator);
         // Root node
         nodes.push(TrieNode {
             children: [NULL_NODE, NULL_NODE],
@@ -59,12 +359,185 @@ impl LinearIntentTrie {
             version_id: 0,
             semantic_mask: 0,
             flags: 0,
-            _padding: [0; 37],
+            last_access: 0,
+            _padding: [0; 33],
         });
         Self { 
             nodes,
+            max_depth_bits: DEFAULT_MAX_DEPTH_BITS,
+            encoding: PathEncoding::Identity,
             sequence_number: 0,
+            node_budget: None,
+            epoch: 0,
+            evictions: 0,
+            node_limit: MAX_TRIE_NODES,
+            exhaustion_logged: false,
+        }
+    }
+
+    /// An empty trie configured like this one.
+    ///
+    /// Keeps the path encoding, depth cap, node budget, node limit and
+    /// allocator, so a shadow trie built from it stores paths exactly where
+    /// this one does. Nodes, weights and the sequence number are not copied.
+    pub fn empty_like(&self) -> Self {
+        let mut trie = Self::new_in(self.nodes.capacity(), self.nodes.allocator().clone());
+        trie.max_depth_bits = self.max_depth_bits;
+        trie.encoding = self.encoding;
+        trie.node_budget = self.node_budget;
+        trie.node_limit = self.node_limit;
+        trie
+    }
+
+    /// Caps the trie at `bits` levels below the root.
+    ///
+    /// Contexts longer than the cap are truncated: every observation and
+    /// lookup past the cap lands on the node at the cap, so adversarially
+    /// long paths collide instead of growing the pool without bound.
+    pub fn with_max_depth_bits(mut self, bits: usize) -> Self {
+        self.max_depth_bits = bits;
+        self
+    }
+
+    /// The depth cap in bits.
+    pub fn max_depth_bits(&self) -> usize {
+        self.max_depth_bits
+    }
+
+    /// Selects how context paths are mapped to bit paths (default: `Identity`).
+    ///
+    /// `PathEncoding::Hashed` bounds every path to `HASHED_DEPTH_BITS` levels
+    /// regardless of input, at the cost of prefix sharing: `resume_lookup`
+    /// always restarts at the root, continuation APIs (`top_continuations`,
+    /// `sequence_probability`) lose their meaning, and `routes`/`visit`
+    /// report hashed bit paths. Must be chosen before anything is inserted.
+    pub fn with_path_encoding(mut self, encoding: PathEncoding) -> Self {
+        assert!(self.nodes.len() == 1, "LinearIntentTrie: path encoding must be set on an empty trie");
+        self.encoding = encoding;
+        self
+    }
+
+    pub fn path_encoding(&self) -> PathEncoding {
+        self.encoding
+    }
+
+    /// Caps the pool at `budget` nodes (root included) for memory-constrained
+    /// deployments.
+    ///
+    /// When `observe` or `warm` would grow past the
+    /// budget, the least-recently-traversed subtrees are evicted first, down
+    /// to a low-water mark so evictions come in batches. Routes bound by
+    /// `associate_payload` or `warm_many` are pinned (`FlagBit::PINNED`,
+    /// which `set_flag` can add to any node): they and their ancestors are
+    /// never evicted, and registering them may exceed the budget. Lookups do
+    /// not count as traversals.
+    ///
+    /// Eviction compacts the pool and reassigns node indices; see `evictions`.
+    pub fn with_node_budget(mut self, budget: usize) -> Self {
+        assert!(budget > 1, "LinearIntentTrie: node budget must leave room beyond the root");
+        self.node_budget = Some(budget);
+        self
+    }
+
+    /// Lowers the hard pool ceiling below `MAX_TRIE_NODES` (values above it
+    /// are clamped).
+    ///
+    /// Unlike a node budget nothing is evicted: an insert that needs a node
+    /// past the limit fails with `TrieError::CapacityExhausted` (`try_observe`,
+    /// `try_warm`) or is dropped and logged (`observe`, `warm`). Panics if
+    /// `limit` is 0.
+    pub fn with_node_limit(mut self, limit: usize) -> Self {
+        assert!(limit > 0, "LinearIntentTrie: node limit must leave room for the root");
+        self.node_limit = limit.min(MAX_TRIE_NODES);
+        self
+    }
+
+    /// The hard pool ceiling (`MAX_TRIE_NODES` unless lowered).
+    pub fn node_limit(&self) -> usize {
+        self.node_limit
+    }
+
+    /// The node cap set by `with_node_budget`, if any.
+    pub fn node_budget(&self) -> Option<usize> {
+        self.node_budget
+    }
+
+    /// Number of eviction passes so far. Cached node indices (see
+    /// `node_index_at_path`) are only valid while this is unchanged.
+    pub fn evictions(&self) -> u64 {
+        self.evictions
+    }
+
+    /// The stored bit path of `path`, cut off after `max_depth_bits`.
+    #[inline]
+    fn bits<'a>(&self, path: &'a [u8]) -> impl Iterator<Item = usize> + 'a {
+        self.bits_from(path, 0)
+    }
+
+    /// `bits`, starting `skip` bits in.
+    #[inline]
+    fn bits_from<'a>(&self, path: &'a [u8], skip: usize) -> impl Iterator<Item = usize> + 'a {
+        self.encoding.bits(path).take(self.max_depth_bits).skip(skip).map(usize::from)
+    }
+
+    /// Checks that `path` fits under the depth cap without truncation.
+    pub fn check_depth(&self, path: &[u8]) -> Result<(), TrieError> {
+        let bits = match self.encoding {
+            PathEncoding::Identity => path.len().saturating_mul(8),
+            PathEncoding::Hashed => HASHED_DEPTH_BITS,
+        };
+        if bits > self.max_depth_bits {
+            return Err(TrieError::PathTooDeep { bits, max_bits: self.max_depth_bits });
         }
+        Ok(())
+    }
+
+    /// Number of nodes in the pool (including the root).
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Nodes the pool can hold before `observe`/`warm` must reallocate it.
+    pub fn capacity(&self) -> usize {
+        self.nodes.capacity()
+    }
+
+    /// Reserves room for at least `additional` more nodes.
+    ///
+    /// Pair with `nodes_needed` to pre-size for a route set, so warm-up and
+    /// later observations of those routes never move the node pool.
+    pub fn reserve(&mut self, additional: usize) {
+        self.nodes.reserve(additional);
+    }
+
+    /// Number of new nodes `warm`ing every path in `paths` would allocate.
+    ///
+    /// Prefixes shared with the existing trie or between the given paths are
+    /// counted once.
+    pub fn nodes_needed<'a>(&self, paths: impl IntoIterator<Item = &'a [u8]>) -> usize {
+        // Nodes not yet in the pool get virtual indices past `nodes.len()`.
+        let mut pending: BTreeMap<(usize, usize), usize> = BTreeMap::new();
+        let mut needed = 0;
+        for path in paths {
+            let mut curr = 0;
+            for bit in self.bits(path) {
+                let existing = self.nodes.get(curr).map_or(NULL_NODE, |n| n.children[bit]);
+                curr = if existing != NULL_NODE {
+                    existing as usize
+                } else {
+                    *pending.entry((curr, bit)).or_insert_with(|| {
+                        needed += 1;
+                        self.nodes.len() + needed - 1
+                    })
+                };
+            }
+        }
+        needed
+    }
+
+    /// Returns `true` if `warm`ing `paths` would outgrow the current capacity.
+    pub fn would_realloc<'a>(&self, paths: impl IntoIterator<Item = &'a [u8]>) -> bool {
+        self.nodes.len() + self.nodes_needed(paths) > self.nodes.capacity()
     }
 
     /// Retrieves a node reference for direct lookup.
@@ -77,15 +550,12 @@ impl LinearIntentTrie {
     #[inline(always)]
     pub fn get_probability(&self, context: &[u8], next_bit: bool) -> f32 {
         let mut curr = 0;
-        for &byte in context {
-            for i in (0..8).rev() {
-                let bit = ((byte >> i) & 1) as usize;
-                let next = self.nodes[curr].children[bit];
-                if next == NULL_NODE {
-                    return 0.0;
-                }
-                curr = next as usize;
+        for bit in self.bits(context) {
+            let next = self.nodes[curr].children[bit];
+            if next == NULL_NODE {
+                return 0.0;
             }
+            curr = next as usize;
         }
         
         let node = &self.nodes[curr];
@@ -99,128 +569,854 @@ impl LinearIntentTrie {
         }
     }
 
-    /// Inserts or updates an intent sequence with a Markov weight increment.
-    pub fn observe(&mut self, context: &[u8], next_bit: bool) {
+    /// Scores a whole multi-byte continuation of `context`.
+    ///
+    /// Walks from the context terminal through every bit of `suffix` and
+    /// multiplies the hit ratios (`get_probability(.., true)`) that
+    /// `observe` recorded at each byte boundary on the way into a joint
+    /// likelihood. Boundaries with no observations (nodes `warm` only
+    /// allocated) are passed through. Returns 0.0 if a node on the way is
+    /// missing or the continuation itself was never observed.
+    pub fn sequence_probability(&self, context: &[u8], suffix: &[u8]) -> f32 {
         let mut curr = 0;
-        for &byte in context {
+        for bit in self.bits(context) {
+            let next = self.nodes[curr].children[bit];
+            if next == NULL_NODE {
+                return 0.0;
+            }
+            curr = next as usize;
+        }
+        if suffix.is_empty() {
+            return 1.0;
+        }
+
+        let mut joint = 1.0f32;
+        let mut total = 0;
+        for &byte in suffix {
             for i in (0..8).rev() {
-                let bit = ((byte >> i) & 1) as usize;
-                let next = self.nodes[curr].children[bit];
+                let next = self.nodes[curr].children[((byte >> i) & 1) as usize];
                 if next == NULL_NODE {
-                    let new_idx = self.nodes.len() as u32;
-                    self.nodes.push(TrieNode {
-                        children: [NULL_NODE, NULL_NODE],
-                        weights: [0, 0],
-                        payload_handle: 0,
-                        version_id: 0,
-                        semantic_mask: 0,
-                        flags: 0,
-                        _padding: [0; 37],
-                    });
-                    self.nodes[curr].children[bit] = new_idx;
-                    curr = new_idx as usize;
-                } else {
-                    curr = next as usize;
+                    return 0.0;
                 }
+                curr = next as usize;
             }
+            let weights = self.nodes[curr].weights;
+            total = weights[0] as u32 + weights[1] as u32;
+            if total > 0 {
+                joint *= weights[1] as f32 / total as f32;
+            }
+        }
+        // The last boundary is the continuation itself.
+        if total == 0 { 0.0 } else { joint }
+    }
+
+    /// Inserts or updates an intent sequence with a Markov weight increment.
+    ///
+    /// If the pool is full the observation is dropped and logged; see
+    /// `try_observe`.
+    pub fn observe(&mut self, context: &[u8], next_bit: bool) {
+        self.observe_weighted(context, next_bit, 1);
+    }
+
+    /// `observe`, failing with `CapacityExhausted` instead of dropping the
+    /// observation when `context` needs a node past the pool limit.
+    pub fn try_observe(&mut self, context: &[u8], next_bit: bool) -> Result<(), TrieError> {
+        self.try_observe_weighted(context, next_bit, 1)
+    }
+
+    /// Folds `count` identical observations into one traversal.
+    ///
+    /// Equivalent to calling `observe` `count` times: the weight saturates
+    /// at `u8::MAX` either way.
+    /// Contexts deeper than `max_depth_bits` are recorded at the cap node.
+    pub fn observe_weighted(&mut self, context: &[u8], next_bit: bool, count: u32) {
+        if let Err(e) = self.try_observe_weighted(context, next_bit, count) {
+            self.log_exhausted(e);
+        }
+    }
+
+    /// `observe_weighted`, failing with `CapacityExhausted` when the pool is
+    /// full. Nodes allocated before the failure stay, with zero weights; no
+    /// weight is recorded.
+    pub fn try_observe_weighted(&mut self, context: &[u8], next_bit: bool, count: u32) -> Result<(), TrieError> {
+        self.make_room(context);
+        let mut curr = 0;
+        for bit in self.bits(context) {
+            curr = self.child_or_insert(curr, bit)?;
         }
         
         // Atomically (conceptually) increment the observation weight
         let weight = &mut self.nodes[curr].weights[next_bit as usize];
-        if *weight < 255 {
-            *weight += 1;
-        }
+        *weight = weight.saturating_add(count.min(u8::MAX as u32) as u8);
+        Ok(())
     }
 
     /// Pre-populates a bit-path in the trie without modifying weights.
     /// Used for registering static URI resources.
+    ///
+    /// Bits past `max_depth_bits` are dropped; use `check_depth` to reject
+    /// such paths up front. If the pool fills up part way, the rest of the
+    /// path is dropped and logged; see `try_warm`.
     pub fn warm(&mut self, path: &[u8]) {
+        if let Err(e) = self.try_warm(path) {
+            self.log_exhausted(e);
+        }
+    }
+
+    /// `warm`, failing with `CapacityExhausted` when `path` needs a node
+    /// past the pool limit. The prefix allocated before the failure stays.
+    pub fn try_warm(&mut self, path: &[u8]) -> Result<(), TrieError> {
+        self.make_room(path);
         let mut curr = 0;
-        for &byte in path {
-            for i in (0..8).rev() {
-                let bit = ((byte >> i) & 1) as usize;
-                let next = self.nodes[curr].children[bit];
-                if next == NULL_NODE {
-                    let new_idx = self.nodes.len() as u32;
-                    self.nodes.push(TrieNode {
-                        children: [NULL_NODE, NULL_NODE],
-                        weights: [0, 0],
-                        payload_handle: 0,
-                        version_id: 0,
-                        semantic_mask: 0,
-                        flags: 0,
-                        _padding: [0; 37],
-                    });
-                    self.nodes[curr].children[bit] = new_idx;
-                    curr = new_idx as usize;
-                } else {
-                    curr = next as usize;
-                }
+        for bit in self.bits(path) {
+            curr = self.child_or_insert(curr, bit)?;
+        }
+        Ok(())
+    }
+
+    /// Warms a batch of routes and binds each terminal to `(payload_handle, version_id)`.
+    ///
+    /// Routes are walked in sorted order, so each one resumes from the
+    /// deepest node it shares with its predecessor instead of the root.
+    /// If a path appears twice, the later entry wins, as with repeated
+    /// `associate_payload` calls.
+    ///
+    /// Returns the number of routes bound. Fails with `CapacityExhausted`
+    /// if the pool fills up; routes bound before the failure (in sorted
+    /// order) stay, the rest are not registered.
+    pub fn warm_many(&mut self, routes: &[(&[u8], u32, u32)]) -> Result<usize, TrieError> {
+        let registered = FlagBit::PINNED.0 | FlagBit::TERMINAL.0;
+        let routes: Vec<_> = routes.iter().map(|&(path, handle, version_id)| (path, handle, version_id, registered)).collect();
+        self.warm_routes(&routes, false)?;
+        Ok(routes.len())
+    }
+
+    /// `warm_many` with a flags byte per route, which is added to the
+    /// terminal node's flags. `pre_encoded` routes were reported by `routes`:
+    /// their paths are walked as-is and their flags replace the node's.
+    fn warm_routes(&mut self, routes: &[(&[u8], u32, u32, u8)], pre_encoded: bool) -> Result<(), TrieError> {
+        // Hashed paths share no prefix with their sorted neighbour.
+        let raw = pre_encoded || self.encoding == PathEncoding::Identity;
+        let mut order: Vec<usize> = (0..routes.len()).collect();
+        // Stable, so duplicates keep their input order.
+        order.sort_by(|&a, &b| routes[a].0.cmp(routes[b].0));
+
+        // `trail[d]` is the node reached after `d` bits of `prev`.
+        let mut trail: Vec<usize> = Vec::new();
+        trail.push(0);
+        let mut prev: &[u8] = &[];
+        for idx in order {
+            let (path, handle, version_id, flags) = routes[idx];
+            let shared = path.iter().zip(prev).take_while(|(a, b)| a == b).count();
+            let resume = if raw { (shared * 8).min(self.max_depth_bits) } else { 0 };
+            trail.truncate(resume + 1);
+
+            let mut curr = *trail.last().unwrap();
+            let bits: PathBits = if raw { PathBits::raw(path) } else { self.encoding.bits(path) };
+            for bit in bits.take(self.max_depth_bits).skip(resume).map(usize::from) {
+                curr = self.child_or_insert(curr, bit)?;
+                trail.push(curr);
+            }
+            self.nodes[curr].payload_handle = handle;
+            self.nodes[curr].version_id = version_id;
+            if pre_encoded {
+                self.nodes[curr].flags = flags;
+            } else {
+                self.nodes[curr].flags |= flags;
             }
+            prev = path;
+        }
+        Ok(())
+    }
+
+    /// Copies every payload-carrying route of `base` into this trie.
+    ///
+    /// Used before a Shadow-Swap so a trie built purely from learning does
+    /// not drop routes registered at build time. Where both tries bind the
+    /// same path, `base` wins, flags included; learned weights are left
+    /// untouched. Both tries
+    /// must use the same `PathEncoding`; panics otherwise, since the routes
+    /// would land on unrelated bit paths (build the shadow with `empty_like`).
+    pub fn carry_routes_from(&mut self, base: &Self) {
+        assert_eq!(
+            self.encoding, base.encoding,
+            "LinearIntentTrie: carry_routes_from needs matching path encodings"
+        );
+        let routes = base.routes();
+        let routes: Vec<(&[u8], u32, u32, u8)> = routes
+            .iter()
+            .map(|r| (r.path.as_slice(), r.payload_handle, r.version_id, r.flags))
+            .collect();
+        if let Err(e) = self.warm_routes(&routes, true) {
+            self.log_exhausted(e);
+        }
+    }
+
+    /// Follows `bit` from `curr`, allocating the child if it does not exist.
+    ///
+    /// Fails with `CapacityExhausted` rather than hand out an index at or
+    /// past the limit, which at `MAX_TRIE_NODES` would alias `NULL_NODE`.
+    fn child_or_insert(&mut self, curr: usize, bit: usize) -> Result<usize, TrieError> {
+        let next = self.nodes[curr].children[bit];
+        if next != NULL_NODE {
+            return Ok(next as usize);
+        }
+        if self.nodes.len() >= self.node_limit {
+            return Err(TrieError::CapacityExhausted { nodes: self.nodes.len() });
+        }
+        let new_idx = self.nodes.len() as u32;
+        self.nodes.push(TrieNode {
+            children: [NULL_NODE, NULL_NODE],
+            weights: [0, 0],
+            payload_handle: 0,
+            version_id: 0,
+            semantic_mask: 0,
+            flags: 0,
+            last_access: self.epoch,
+            _padding: [0; 33],
+        });
+        self.nodes[curr].children[bit] = new_idx;
+        Ok(new_idx as usize)
+    }
+
+    /// Logs the first `CapacityExhausted` hit by an infallible insert.
+    #[cold]
+    fn log_exhausted(&mut self, e: TrieError) {
+        if !self.exhaustion_logged {
+            self.exhaustion_logged = true;
+            tracing::warn!("LinearIntentTrie: {}; further inserts are dropped", e);
         }
     }
 
     /// Associates a payload handle and version with the current context state.
-    pub fn associate_payload(&mut self, context: &[u8], handle: u32, version_id: u32) {
+    ///
+    /// Fails with `UnknownPath` (leaving the trie untouched) if `context` was
+    /// never warmed or observed, so a mistyped route cannot vanish silently.
+    pub fn associate_payload(&mut self, context: &[u8], handle: u32, version_id: u32) -> Result<(), TrieError> {
+        let curr = self.existing_node(context)?;
+        self.nodes[curr].payload_handle = handle;
+        self.nodes[curr].version_id = version_id;
+        self.nodes[curr].flags |= FlagBit::PINNED.0 | FlagBit::TERMINAL.0;
+        Ok(())
+    }
+
+    /// Sets `flag` on the node at `context`.
+    ///
+    /// Fails with `UnknownPath` if `context` was never warmed or observed.
+    pub fn set_flag(&mut self, context: &[u8], flag: FlagBit) -> Result<(), TrieError> {
+        let curr = self.existing_node(context)?;
+        self.nodes[curr].flags |= flag.0;
+        Ok(())
+    }
+
+    /// Clears `flag` on the node at `context`, leaving the other bits alone.
+    ///
+    /// Fails with `UnknownPath` if `context` was never warmed or observed.
+    /// Clearing `PINNED` makes a route evictable under a node budget.
+    pub fn clear_flag(&mut self, context: &[u8], flag: FlagBit) -> Result<(), TrieError> {
+        let curr = self.existing_node(context)?;
+        self.nodes[curr].flags &= !flag.0;
+        Ok(())
+    }
+
+    /// Returns `true` if the node at `context` exists and has `flag` set.
+    pub fn has_flag(&self, context: &[u8], flag: FlagBit) -> bool {
+        self.get_node_at_path(context).is_some_and(|node| node.has_flag(flag))
+    }
+
+    /// Index of the node at `context`, or `UnknownPath` at the first missing bit.
+    fn existing_node(&self, context: &[u8]) -> Result<usize, TrieError> {
         let mut curr = 0;
-        for &byte in context {
-            for i in (0..8).rev() {
-                let bit = ((byte >> i) & 1) as usize;
-                let next = self.nodes[curr].children[bit];
-                if next == NULL_NODE {
-                    return;
+        for (depth, bit) in self.bits(context).enumerate() {
+            let next = self.nodes[curr].children[bit];
+            if next == NULL_NODE {
+                return Err(TrieError::UnknownPath { depth });
+            }
+            curr = next as usize;
+        }
+        Ok(curr)
+    }
+
+    /// Under a node budget, stamps the existing prefix of `path` as freshly
+    /// traversed and evicts cold subtrees until the rest of `path` fits.
+    fn make_room(&mut self, path: &[u8]) {
+        let Some(budget) = self.node_budget else { return };
+        self.epoch = self.epoch.saturating_add(1);
+        let epoch = self.epoch;
+
+        // The path being extended must not be evicted to make room for itself.
+        let mut curr = 0;
+        self.nodes[0].last_access = epoch;
+        for bit in self.bits(path) {
+            let next = self.nodes[curr].children[bit];
+            if next == NULL_NODE {
+                break;
+            }
+            curr = next as usize;
+            self.nodes[curr].last_access = epoch;
+        }
+
+        let needed = self.nodes_needed([path]);
+        if self.nodes.len() + needed > budget {
+            // Undershoot so the next few inserts don't each compact the pool.
+            self.evict_to(budget.saturating_sub(needed + budget / 16));
+        }
+    }
+
+    /// Evicts least-recently-traversed unpinned subtrees until at most
+    /// `target` nodes remain, then compacts the pool.
+    fn evict_to(&mut self, target: usize) {
+        let n = self.nodes.len();
+        let epoch = self.epoch;
+
+        // Children are allocated after their parents, so one reverse pass
+        // finds every node with a pinned or current descendant.
+        let mut keep = alloc::vec![false; n];
+        for i in (0..n).rev() {
+            let node = &self.nodes[i];
+            keep[i] = i == 0
+                || node.has_flag(FlagBit::PINNED)
+                || node.last_access == epoch
+                || node.children.iter().any(|&c| c != NULL_NODE && keep[c as usize]);
+        }
+
+        // Oldest first; among equals the deeper (later allocated) node goes first.
+        let mut candidates: Vec<usize> = (1..n).filter(|&i| !keep[i]).collect();
+        candidates.sort_by_key(|&i| (self.nodes[i].last_access, core::cmp::Reverse(i)));
+
+        let mut dead = alloc::vec![false; n];
+        let mut live = n;
+        let mut stack = Vec::new();
+        for victim in candidates {
+            if live <= target {
+                break;
+            }
+            stack.push(victim);
+            while let Some(i) = stack.pop() {
+                if dead[i] {
+                    continue;
                 }
-                curr = next as usize;
+                dead[i] = true;
+                live -= 1;
+                stack.extend(self.nodes[i].children.iter().filter(|&&c| c != NULL_NODE).map(|&c| c as usize));
+            }
+        }
+        if live == n {
+            return;
+        }
+
+        // Compacting in index order keeps every parent ahead of its children.
+        let mut remap = alloc::vec![NULL_NODE; n];
+        let mut nodes = NodeArena::with_capacity_in(self.nodes.capacity(), self.nodes.allocator().clone());
+        for (i, node) in self.nodes.iter().enumerate() {
+            if !dead[i] {
+                remap[i] = nodes.len() as u32;
+                nodes.push(*node);
+            }
+        }
+        for node in &mut nodes {
+            for child in &mut node.children {
+                if *child != NULL_NODE {
+                    *child = remap[*child as usize];
+                }
+            }
+        }
+        self.nodes = nodes;
+        self.evictions += 1;
+    }
+
+    /// Forgets all learned Markov weights while keeping the trie's shape.
+    ///
+    /// Structure, payload handles, versions and semantic masks are untouched,
+    /// so registered routes keep resolving; only predictions restart cold.
+    pub fn reset_learning(&mut self) {
+        for node in &mut self.nodes {
+            node.weights = [0, 0];
+        }
+    }
+
+    /// Unbinds every route while keeping the trie's shape and weights.
+    ///
+    /// The counterpart of `reset_learning`: payload handles, versions and
+    /// flags of every route are cleared, so a new route set can be carried
+    /// in without stale routes surviving alongside it.
+    pub fn clear_routes(&mut self) {
+        for node in &mut self.nodes {
+            if node.payload_handle > 0 || node.flags != 0 {
+                node.payload_handle = 0;
+                node.version_id = 0;
+                node.flags = 0;
             }
         }
-        self.nodes[curr].payload_handle = handle;
-        self.nodes[curr].version_id = version_id;
     }
 
     /// Returns the node at the terminal of the given bit-path.
     pub fn get_node_at_path(&self, path: &[u8]) -> Option<&TrieNode> {
+        self.node_index_at_path(path).map(|idx| &self.nodes[idx])
+    }
+
+    /// Pool index of the node at the terminal of the given bit-path.
+    ///
+    /// Indices stay valid for the life of this trie (nodes are never moved
+    /// or removed) unless a node budget evicts, so callers may cache them,
+    /// keyed by `evictions`, and `resume_lookup` later.
+    pub fn node_index_at_path(&self, path: &[u8]) -> Option<usize> {
+        self.resume_lookup(0, 0, path)
+    }
+
+    /// Continues a lookup of `path` from node `from`, which the caller
+    /// reached by walking the first `skip` bytes of `path`.
+    ///
+    /// Equivalent to `node_index_at_path(path)` but skips the shared prefix.
+    /// Under `PathEncoding::Hashed` there is no shared prefix, so the walk
+    /// starts over from the root.
+    pub fn resume_lookup(&self, from: usize, skip: usize, path: &[u8]) -> Option<usize> {
+        let (from, skip) = match self.encoding {
+            PathEncoding::Identity => (from, skip),
+            PathEncoding::Hashed => (0, 0),
+        };
+        let mut curr = from;
+        for bit in self.bits_from(path, skip.saturating_mul(8)) {
+            let next = self.nodes[curr].children[bit];
+            if next == NULL_NODE {
+                return None;
+            }
+            curr = next as usize;
+        }
+        Some(curr)
+    }
+
+    /// Depth-first walk invoking `f` with each node and its packed bit path.
+    ///
+    /// Paths are MSB-first; a node that is not byte-aligned sees its
+    /// trailing partial byte zero-padded. Children are visited left before
+    /// right. Allocates only the path buffer and the DFS stack.
+    pub fn visit<F: FnMut(&[u8], &TrieNode)>(&self, mut f: F) {
+        let mut path: Vec<u8> = Vec::new();
+        // (node index, bit depth, bit taken from the parent)
+        let mut stack: Vec<(u32, usize, u8)> = Vec::new();
+        stack.push((0, 0, 0));
+
+        while let Some((idx, depth, bit)) = stack.pop() {
+            if depth > 0 {
+                let at = depth - 1;
+                path.truncate(at.div_ceil(8));
+                if at % 8 == 0 {
+                    path.push(0);
+                }
+                let last = path.last_mut().unwrap();
+                *last &= !(0xFF >> (at % 8));
+                *last |= bit << (7 - at % 8);
+            }
+            let node = &self.nodes[idx as usize];
+            f(&path, node);
+            for bit in (0..2).rev() {
+                let child = node.children[bit];
+                if child != NULL_NODE {
+                    stack.push((child, depth + 1, bit as u8));
+                }
+            }
+        }
+    }
+
+    /// Enumerates every byte-aligned path that carries a payload handle.
+    ///
+    /// Cold-path only: allocates and walks the entire node pool.
+    pub fn routes(&self) -> Vec<TrieRoute> {
+        let mut routes = Vec::new();
+        // (node index, accumulated path bytes, bit depth)
+        let mut stack: Vec<(usize, Vec<u8>, usize)> = Vec::new();
+        stack.push((0, Vec::new(), 0));
+
+        while let Some((idx, path, depth)) = stack.pop() {
+            let node = &self.nodes[idx];
+            if depth % 8 == 0 && node.payload_handle > 0 {
+                routes.push(TrieRoute {
+                    path: path.clone(),
+                    payload_handle: node.payload_handle,
+                    version_id: node.version_id,
+                    flags: node.flags,
+                });
+            }
+            for bit in (0..2).rev() {
+                let child = node.children[bit];
+                if child == NULL_NODE {
+                    continue;
+                }
+                let mut child_path = path.clone();
+                if depth % 8 == 0 {
+                    child_path.push(0);
+                }
+                if bit == 1 {
+                    *child_path.last_mut().unwrap() |= 1 << (7 - depth % 8);
+                }
+                stack.push((child as usize, child_path, depth + 1));
+            }
+        }
+        routes
+    }
+
+    /// Fills `out` with the strongest payload-bearing continuations of `context`.
+    ///
+    /// Candidates are byte-aligned descendants (the context node itself is
+    /// excluded) up to `MAX_LOOKAHEAD_BYTES` deep, ranked by their success
+    /// weight. Returns how many entries of `out` were written as
+    /// `(payload_handle, version_id)`.
+    pub fn top_continuations(&self, context: &[u8], out: &mut [(u32, u32)]) -> usize {
+        self.top_continuations_within(context, MAX_LOOKAHEAD_BYTES, 0.0, out)
+    }
+
+    /// `top_continuations` bounded to descendants at most `max_bytes` past
+    /// `context` whose success probability is at least `min_probability`.
+    ///
+    /// A candidate's probability is `get_probability(path, true)`: the share
+    /// of its observations that were hits. `max_bytes` is clamped to
+    /// `MAX_LOOKAHEAD_BYTES`, and at most `MAX_CONTINUATIONS` entries are
+    /// written. Candidates are ranked in place in `out`; nothing is allocated.
+    pub fn top_continuations_within(
+        &self,
+        context: &[u8],
+        max_bytes: usize,
+        min_probability: f32,
+        out: &mut [(u32, u32)],
+    ) -> usize {
+        let limit = out.len().min(MAX_CONTINUATIONS);
+        if limit == 0 || max_bytes == 0 {
+            return 0;
+        }
         let mut curr = 0;
-        for &byte in path {
-            for i in (0..8).rev() {
-                let bit = ((byte >> i) & 1) as usize;
-                let next = self.nodes[curr].children[bit];
-                if next == NULL_NODE {
-                    return None;
+        for bit in self.bits(context) {
+            let next = self.nodes[curr].children[bit];
+            if next == NULL_NODE {
+                return 0;
+            }
+            curr = next as usize;
+        }
+
+        // A depth-first walk never holds more than one pending sibling per
+        // level, so the stack is bounded by the lookahead.
+        let max_bits = max_bytes.min(MAX_LOOKAHEAD_BYTES) * 8;
+        let mut stack = [(0u32, 0u16); MAX_LOOKAHEAD_BYTES * 8 + 1];
+        let mut top = 1;
+        stack[0] = (curr as u32, 0);
+        // Success weight of each entry in `out[..n]`, kept sorted descending.
+        let mut ranks = [0u8; MAX_CONTINUATIONS];
+        let mut n = 0;
+        while top > 0 {
+            top -= 1;
+            let (idx, depth) = stack[top];
+            let node = &self.nodes[idx as usize];
+            let depth = depth as usize;
+            if depth > 0 && depth.is_multiple_of(8) && node.payload_handle > 0 {
+                let total = node.weights[0] as u32 + node.weights[1] as u32;
+                let probability = if total == 0 { 0.0 } else { node.weights[1] as f32 / total as f32 };
+                let weight = node.weights[1];
+                // Stable: equal weights keep discovery (lexicographic) order.
+                let at = ranks[..n].iter().position(|&r| r < weight).unwrap_or(n);
+                if probability >= min_probability && at < limit {
+                    let end = n.min(limit - 1);
+                    ranks.copy_within(at..end, at + 1);
+                    out.copy_within(at..end, at + 1);
+                    ranks[at] = weight;
+                    out[at] = (node.payload_handle, node.version_id);
+                    n = end + 1;
+                }
+            }
+            if depth >= max_bits {
+                continue;
+            }
+            for bit in (0..2).rev() {
+                let child = node.children[bit];
+                if child != NULL_NODE {
+                    stack[top] = (child, depth as u16 + 1);
+                    top += 1;
                 }
-                curr = next as usize;
             }
         }
-        Some(&self.nodes[curr])
+        n
     }
 
-    /// Performs a safe merge of weights from another trie if sequence is newer.
-    pub fn merge_newer(&mut self, other: &Self) -> bool {
-        if other.sequence_number <= self.sequence_number {
+    /// Computes a 64-bit FNV-1a digest over the structure, weights and payloads.
+    ///
+    /// Two tries with identical node pools produce identical digests.
+    pub fn digest(&self) -> u64 {
+        use crate::hash::{FNV_OFFSET, FNV_PRIME};
+
+        let mut hash = FNV_OFFSET;
+        let mut feed = |bytes: &[u8]| {
+            for &b in bytes {
+                hash ^= b as u64;
+                hash = hash.wrapping_mul(FNV_PRIME);
+            }
+        };
+        for node in &self.nodes {
+            feed(&node.children[0].to_le_bytes());
+            feed(&node.children[1].to_le_bytes());
+            feed(&node.weights);
+            feed(&node.payload_handle.to_le_bytes());
+            feed(&node.version_id.to_le_bytes());
+        }
+        hash
+    }
+
+    /// Serializes the node pool and settings into a portable little-endian blob.
+    ///
+    /// The inverse of `from_bytes`; used to persist tries outside the process
+    /// (dispatcher replay logs, warm restarts) and to ship a full trie to a
+    /// peer for anti-entropy sync. Cold-path only.
+    ///
+    /// ## Format
+    /// The magic `HXT1` names the format version; a layout change gets a new
+    /// magic, which also retires every dispatcher replay log embedding it.
+    /// Then the encoding byte, `max_depth_bits` (u64), the sequence number
+    /// (u64) and the node count (u32), followed by one 23-byte record
+    /// per node in pool order: both children (u32), both weights, payload
+    /// handle, version and semantic mask (u32 each) and the flags byte.
+    /// Access epochs and the node budget are process-local and not stored.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(SERIAL_HEADER_LEN + self.nodes.len() * SERIAL_NODE_LEN);
+        out.extend_from_slice(SERIAL_MAGIC);
+        out.push(match self.encoding {
+            PathEncoding::Identity => 0,
+            PathEncoding::Hashed => 1,
+        });
+        out.extend_from_slice(&(self.max_depth_bits as u64).to_le_bytes());
+        out.extend_from_slice(&self.sequence_number.to_le_bytes());
+        out.extend_from_slice(&(self.nodes.len() as u32).to_le_bytes());
+        for node in &self.nodes {
+            out.extend_from_slice(&node.children[0].to_le_bytes());
+            out.extend_from_slice(&node.children[1].to_le_bytes());
+            out.extend_from_slice(&node.weights);
+            out.extend_from_slice(&node.payload_handle.to_le_bytes());
+            out.extend_from_slice(&node.version_id.to_le_bytes());
+            out.extend_from_slice(&node.semantic_mask.to_le_bytes());
+            out.push(node.flags);
+        }
+        out
+    }
+
+    /// Rebuilds a trie serialized by `to_bytes`.
+    ///
+    /// Fails with `TrieError::Malformed` on a bad header, a length mismatch, a
+    /// child link that does not point forward into the pool, or a node linked
+    /// from two parents. Whatever it accepts is a tree every traversal can
+    /// walk without leaving the pool.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, TrieError> {
+        let u32_at = |at: usize| u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap());
+        let u64_at = |at: usize| u64::from_le_bytes(bytes[at..at + 8].try_into().unwrap());
+
+        if bytes.len() < SERIAL_HEADER_LEN || &bytes[..4] != SERIAL_MAGIC {
+            return Err(TrieError::Malformed);
+        }
+        let encoding = match bytes[4] {
+            0 => PathEncoding::Identity,
+            1 => PathEncoding::Hashed,
+            _ => return Err(TrieError::Malformed),
+        };
+        let max_depth_bits = usize::try_from(u64_at(5)).map_err(|_| TrieError::Malformed)?;
+        let sequence_number = u64_at(13);
+        let count = u32_at(21) as usize;
+        let expected = count.checked_mul(SERIAL_NODE_LEN).and_then(|n| n.checked_add(SERIAL_HEADER_LEN));
+        if count == 0 || expected != Some(bytes.len()) {
+            return Err(TrieError::Malformed);
+        }
+
+        let mut nodes = NodeArena::with_capacity(count);
+        let mut parented = alloc::vec![false; count];
+        for i in 0..count {
+            let at = SERIAL_HEADER_LEN + i * SERIAL_NODE_LEN;
+            let children = [u32_at(at), u32_at(at + 4)];
+            for c in children.into_iter().filter(|&c| c != NULL_NODE).map(|c| c as usize) {
+                // Children are always allocated after their parent; anything
+                // else could form a cycle. A shared child would alias two paths.
+                if c >= count || c <= i || core::mem::replace(&mut parented[c], true) {
+                    return Err(TrieError::Malformed);
+                }
+            }
+            nodes.push(TrieNode {
+                children,
+                weights: [bytes[at + 8], bytes[at + 9]],
+                payload_handle: u32_at(at + 10),
+                version_id: u32_at(at + 14),
+                semantic_mask: u32_at(at + 18),
+                flags: bytes[at + 22],
+                last_access: 0,
+                _padding: [0; 33],
+            });
+        }
+
+        Ok(Self {
+            nodes,
+            max_depth_bits,
+            encoding,
+            sequence_number,
+            node_budget: None,
+            epoch: 0,
+            evictions: 0,
+            node_limit: MAX_TRIE_NODES,
+            exhaustion_logged: false,
+        })
+    }
+
+    /// Returns true if both tries hold the same paths with the same weights and payloads.
+    ///
+    /// Nodes are matched by bit path, not pool index, so tries built by
+    /// different insertion orders still compare equal.
+    pub fn structural_eq(&self, other: &Self) -> bool {
+        if self.nodes.len() != other.nodes.len() {
             return false;
         }
-        
-        // # Mechanical Sympathy: Fast-path bulk copy if structures are identical.
-        // If not, we'd need to traverse and merge. For simplicity in this demo,
-        // we assume structural consistency for shadow-swaps.
-        if self.nodes.len() == other.nodes.len() {
-            for i in 0..self.nodes.len() {
-                // Merge weights (simple sum with saturation)
-                for b in 0..2 {
-                    let w_sum = self.nodes[i].weights[b] as u16 + other.nodes[i].weights[b] as u16;
-                    self.nodes[i].weights[b] = w_sum.min(255) as u8;
+        let mut equal = true;
+        self.walk_pairs(other, |_, _, ours, theirs| {
+            equal = ours.is_some() && ours == theirs;
+            equal
+        });
+        equal
+    }
+
+    /// Lists every node whose presence, weights or payload differ from `other`.
+    ///
+    /// Nodes are matched by bit path; a subtree present on one side only
+    /// contributes one entry per node. Cold-path only: walks both pools.
+    pub fn diff(&self, other: &Self) -> Vec<NodeDiff> {
+        let mut diffs = Vec::new();
+        self.walk_pairs(other, |path, depth, ours, theirs| {
+            if ours != theirs {
+                diffs.push(NodeDiff { path: path.to_vec(), depth, ours, theirs });
+            }
+            true
+        });
+        diffs
+    }
+
+    /// Walks the union of both tries in lock-step by bit path.
+    ///
+    /// `visit` receives the packed path, its bit depth and each side's node
+    /// state; returning `false` stops the walk.
+    fn walk_pairs(
+        &self,
+        other: &Self,
+        mut visit: impl FnMut(&[u8], usize, Option<NodeState>, Option<NodeState>) -> bool,
+    ) {
+        // (our index, their index, accumulated path bytes, bit depth)
+        type Frame = (Option<usize>, Option<usize>, Vec<u8>, usize);
+        let mut stack: Vec<Frame> = Vec::new();
+        stack.push((Some(0), Some(0), Vec::new(), 0));
+
+        while let Some((ours, theirs, path, depth)) = stack.pop() {
+            let our_node = ours.map(|i| &self.nodes[i]);
+            let their_node = theirs.map(|i| &other.nodes[i]);
+            if !visit(&path, depth, our_node.map(NodeState::from), their_node.map(NodeState::from)) {
+                return;
+            }
+            for bit in (0..2).rev() {
+                let child = |node: Option<&TrieNode>| {
+                    node.map(|n| n.children[bit]).filter(|&c| c != NULL_NODE).map(|c| c as usize)
+                };
+                let (a, b) = (child(our_node), child(their_node));
+                if a.is_none() && b.is_none() {
+                    continue;
+                }
+                let mut child_path = path.clone();
+                if depth % 8 == 0 {
+                    child_path.push(0);
                 }
-                // Update version/payload if newer
-                if other.nodes[i].version_id > self.nodes[i].version_id {
-                    self.nodes[i].version_id = other.nodes[i].version_id;
-                    self.nodes[i].payload_handle = other.nodes[i].payload_handle;
+                if bit == 1 {
+                    *child_path.last_mut().unwrap() |= 1 << (7 - depth % 8);
                 }
+                stack.push((a, b, child_path, depth + 1));
             }
-            self.sequence_number = other.sequence_number;
-            true
-        } else {
-            false
         }
     }
+
+    /// Folds every node of `other` into this trie, matching nodes by bit path.
+    ///
+    /// The pools need not share a shape: paths only `other` knows are
+    /// allocated here. Weights are summed (saturating) and the higher
+    /// `version_id` keeps its payload and flags; on a version tie the flags
+    /// of the trie with the higher sequence number win, so a cleared flag
+    /// stays cleared. The sequence number becomes the larger of the two.
+    /// `merge_newer` is the same merge behind a sequence gate.
+    pub fn merge_structural(&mut self, other: &Self) {
+        let other_newer = other.sequence_number > self.sequence_number;
+        // (our index, their index, whether ours was allocated by this merge)
+        let mut stack: Vec<(usize, usize, bool)> = Vec::new();
+        stack.push((0, 0, false));
+        while let Some((ours, theirs, fresh)) = stack.pop() {
+            let src = &other.nodes[theirs];
+            let (weights, children) = (src.weights, src.children);
+            let dst = &mut self.nodes[ours];
+            for (w, add) in dst.weights.iter_mut().zip(weights) {
+                *w = w.saturating_add(add);
+            }
+            let newer = src.version_id > dst.version_id;
+            if fresh || newer || (src.version_id == dst.version_id && other_newer) {
+                dst.flags = src.flags;
+            }
+            if newer {
+                dst.version_id = src.version_id;
+                dst.payload_handle = src.payload_handle;
+            }
+            for (bit, &child) in children.iter().enumerate() {
+                if child != NULL_NODE {
+                    let fresh = self.nodes[ours].children[bit] == NULL_NODE;
+                    // A full pool drops the subtree; what fits is still merged.
+                    match self.child_or_insert(ours, bit) {
+                        Ok(next) => stack.push((next, child as usize, fresh)),
+                        Err(e) => self.log_exhausted(e),
+                    }
+                }
+            }
+        }
+        self.sequence_number = self.sequence_number.max(other.sequence_number);
+    }
+
+    /// Merges `other` in via `merge_structural` if its sequence number is
+    /// newer than ours; returns whether it did.
+    ///
+    /// Node indices are never compared, so tries that grew at different
+    /// rates (an orchestrator's shadow trie and a worker's) merge fully.
+    pub fn merge_newer(&mut self, other: &Self) -> bool {
+        if other.sequence_number <= self.sequence_number {
+            return false;
+        }
+        self.merge_structural(other);
+        true
+    }
+}
+
+impl IntentTrie for LinearIntentTrie {
+    fn observe(&mut self, context: &[u8], next_bit: bool) {
+        LinearIntentTrie::observe(self, context, next_bit)
+    }
+
+    fn try_observe(&mut self, context: &[u8], next_bit: bool) -> Result<(), TrieError> {
+        LinearIntentTrie::try_observe(self, context, next_bit)
+    }
+
+    fn get_probability(&self, context: &[u8], next_bit: bool) -> f32 {
+        LinearIntentTrie::get_probability(self, context, next_bit)
+    }
+
+    fn warm(&mut self, path: &[u8]) {
+        LinearIntentTrie::warm(self, path)
+    }
+
+    fn try_warm(&mut self, path: &[u8]) -> Result<(), TrieError> {
+        LinearIntentTrie::try_warm(self, path)
+    }
+
+    fn node_limit(&self) -> usize {
+        LinearIntentTrie::node_limit(self)
+    }
+
+    fn associate_payload(&mut self, context: &[u8], handle: u32, version_id: u32) -> Result<(), TrieError> {
+        LinearIntentTrie::associate_payload(self, context, handle, version_id)
+    }
+
+    fn get_node_at_path(&self, path: &[u8]) -> Option<&TrieNode> {
+        LinearIntentTrie::get_node_at_path(self, path)
+    }
 }
 
 #[cfg(kani)]
@@ -1,16 +1,168 @@
 use std::net::SocketAddr;
+use std::marker::PhantomData;
 use std::sync::Arc;
-use httpx_core::ControlSignal;
+use httpx_dsa::{SecureSlab, SlabError, SlabLike, DEFAULT_SLOT_SIZE};
+use httpx_core::{ControlSignal, SessionMode};
 use tokio::net::UdpSocket;
 use tokio::sync::mpsc;
-use httpx_core::{ServerConfig, PredictiveEngine};
+use httpx_core::{ServerConfig, PredictiveEngine, DispatcherStats, LatencyHistogram, LearnSender, StatsSnapshot, WorkerSnapshot};
 use crate::stream::GsoPacketizer;
+use crate::error::TransportError;
+use crate::frame::{HttpxFrame, ProtocolError, HTTPX_MAGIC};
+use crate::replay::{DispatchRecorder, RecordedPacket, ReplayEvent};
+use crate::reliability::{CongestionController, CreditPolicy, Pacer, MAX_PUSH_FANOUT};
 use io_uring::{opcode, types, IoUring};
 use std::os::unix::io::AsRawFd;
 
+/// `user_data` of `AsyncCancel` SQEs; never collides with a burst token.
+const CANCEL_USER_DATA: u64 = u64::MAX;
+
+/// Tags ingress `RecvMsg` completions; the low bits carry the ingress pool slot.
+/// Burst tokens stay below it.
+const INGRESS_TAG: u64 = 1 << 63;
+
+/// Leading iovec of every burst.
+const INTENT_FRAME: &[u8] = b"INTENT_SYNC_FRAME";
+
+/// Template header bytes sent between the intent frame and the payload.
+const TEMPLATE_LEN: usize = 128;
+
+/// Bytes every burst sends ahead of the payload.
+pub(crate) const BURST_PREFIX_LEN: usize = INTENT_FRAME.len() + TEMPLATE_LEN;
+
+/// Receives kept posted by the zero-copy ingress loop, and the number of
+/// buffers in the ingress pool built for them.
+pub const INGRESS_DEPTH: usize = 16;
+
+/// Longest an idle `run_loop` sleeps before beating its heartbeat again.
+pub const HEARTBEAT_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// Kernel-visible state of one posted ingress receive. Boxed, so the
+/// addresses handed to the SQE stay put while the `Vec` holding it moves.
+struct IngressRecv {
+    slot: usize,
+    iov: libc::iovec,
+    addr: libc::sockaddr_storage,
+    msghdr: libc::msghdr,
+}
+
+/// A datagram the kernel wrote directly into an ingress pool slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IngressDatagram {
+    pub slot: usize,
+    pub len: usize,
+    pub src: SocketAddr,
+}
+
+/// Fails with `SlabError::Poisoned` if either slot of a burst is quarantined.
+fn check_not_poisoned<S: SlabLike>(slab: &S, payload_handle: u32, template_handle: u32) -> Result<(), TransportError> {
+    for handle in [payload_handle as usize, template_handle as usize] {
+        if slab.is_poisoned(handle) {
+            return Err(SlabError::Poisoned(handle).into());
+        }
+    }
+    Ok(())
+}
+
+/// Distinct slots an `RcBatch` coalesces before it flushes early.
+const RC_BATCH: usize = 16;
+
+/// `io_uring_enter` calls `flush_submissions` makes before leaving the rest
+/// queued for the next flush.
+const SUBMIT_ATTEMPTS: usize = 4;
+
+/// Egress RC releases gathered during one reap, coalesced per slot so
+/// completions sharing a payload or template cost one `decrement_rc_by`.
+struct RcBatch {
+    entries: [(usize, usize); RC_BATCH],
+    len: usize,
+}
+
+impl RcBatch {
+    fn new() -> Self {
+        Self { entries: [(0, 0); RC_BATCH], len: 0 }
+    }
+
+    fn release<S: SlabLike>(&mut self, slab: &S, slot: usize) {
+        if let Some(entry) = self.entries[..self.len].iter_mut().find(|e| e.0 == slot) {
+            entry.1 += 1;
+            return;
+        }
+        if self.len == RC_BATCH {
+            self.flush(slab);
+        }
+        self.entries[self.len] = (slot, 1);
+        self.len += 1;
+    }
+
+    fn flush<S: SlabLike>(&mut self, slab: &S) {
+        for &(slot, n) in &self.entries[..self.len] {
+            slab.decrement_rc_by(slot, n);
+        }
+        self.len = 0;
+    }
+}
+
+/// The request path a datagram carries: the payload of an HTTP-X frame, or
+/// the raw bytes of anything else.
+pub(crate) fn packet_path<'a>(data: &'a [u8], accepted_versions: &[u8]) -> Result<&'a [u8], ProtocolError> {
+    if data.starts_with(&HTTPX_MAGIC) {
+        HttpxFrame::decode_accepting(data, accepted_versions).map(|frame| frame.path)
+    } else {
+        Ok(data)
+    }
+}
+
+/// Resolves the pushes a packet for `path` triggers, up to `out.len()`.
+///
+/// Shared by `CoreDispatcher::on_packet` and `ReplayDispatcher`, so a replay
+/// decides exactly as the live dispatcher did.
+pub(crate) fn resolve_pushes(
+    engine: &PredictiveEngine,
+    config: &ServerConfig,
+    mode: SessionMode,
+    addr: SocketAddr,
+    path: &[u8],
+    out: &mut [(u32, u32)],
+) -> usize {
+    let session = httpx_core::session::Session::new(addr)
+        .with_max_credits(config.max_intent_credits as usize);
+    session.set_mode(mode);
+    engine.predict_top_k(&session, path, out)
+}
+
+/// A burst submitted to the ring whose completion has not been reaped yet.
+///
+/// `token` is the burst's `user_data`: a per-submission sequence number, so
+/// two bursts of the same payload to different peers stay distinguishable
+/// for cancellation and completion accounting.
+#[derive(Debug, Clone, Copy)]
+struct InFlightPush {
+    token: u64,
+    target: SocketAddr,
+    payload_handle: u32,
+    template_handle: u32,
+    submitted_at: std::time::Instant,
+}
+
+/// A burst held back by the pacer until its egress slot comes due.
+///
+/// The freshness check runs when it is submitted, so a payload replaced
+/// while it waits is dropped rather than sent stale.
+#[derive(Debug, Clone, Copy)]
+struct PacedBurst {
+    due: std::time::Instant,
+    target: SocketAddr,
+    payload_handle: u32,
+    template_handle: u32,
+    expected_version: u32,
+}
+
 /// A NUMA-aware packet dispatcher bound to a specific CPU core.
-pub struct CoreDispatcher {
-    _core_id: usize,
+///
+/// Generic over the slab backing its payloads; `SecureSlab` unless stated.
+pub struct CoreDispatcher<S = SecureSlab> {
+    core_id: usize,
     socket: Arc<UdpSocket>,
     engine: Arc<PredictiveEngine>,
     control_rx: mpsc::Receiver<ControlSignal>,
@@ -18,7 +170,42 @@ pub struct CoreDispatcher {
     #[allow(dead_code)]
     config: ServerConfig,
     packetizer: GsoPacketizer,
-    learn_tx: mpsc::UnboundedSender<(Vec<u8>, bool)>,
+    learn_tx: LearnSender,
+    /// Core-local counters; shared read-only with the server handle.
+    stats: Arc<DispatcherStats>,
+    pacer: Option<Pacer>,
+    /// Recalibrated by `ControlSignal::SetBaseRtt`; see `with_congestion_controller`.
+    controller: Option<Arc<dyn CongestionController>>,
+    /// Maps the active credit level to a push fanout.
+    credit_policy: CreditPolicy,
+    /// Current Multi-Level Credit (0, 1 or 2).
+    ///
+    /// Starts at 1 (strongest prediction only); with a controller attached,
+    /// every completed burst's latency is fed back in as an RTT sample.
+    credit_level: u8,
+    /// Mode given to new sessions; tracks the cluster via `ControlSignal::SetSessionMode`.
+    session_mode: SessionMode,
+    /// Set by `ControlSignal::Drain`: no new pushes, exit once the slab is idle.
+    draining: bool,
+    /// Set by `ControlSignal::KillAll`: exit at once, abandoning in-flight slots.
+    terminated: bool,
+    /// Submitted bursts, tracked so a pivot can cancel them by token.
+    in_flight: Vec<InFlightPush>,
+    /// Token of the next burst; 0 is left to untracked SQEs.
+    next_token: u64,
+    /// Bursts waiting on the pacer, in due order; see `submit_due_bursts`.
+    paced: std::collections::VecDeque<PacedBurst>,
+    /// Posted zero-copy receives, each owning one ingress pool slot.
+    #[allow(clippy::vec_box)] // the kernel holds pointers into each entry
+    ingress_posted: Vec<Box<IngressRecv>>,
+    /// Completed receives awaiting `next_ingress`.
+    ingress_ready: std::collections::VecDeque<IngressDatagram>,
+    /// Replay log of packets, swaps and decisions; see `with_recorder`.
+    recorder: Option<DispatchRecorder>,
+    /// Buffers zero-copy receives land in; see `with_ingress_pool`. Declared
+    /// after `ring`, so the ring is torn down before the memory it targets.
+    ingress_pool: Option<Arc<SecureSlab>>,
+    _slab: PhantomData<fn(&S)>,
 }
 
 impl CoreDispatcher {
@@ -30,7 +217,7 @@ impl CoreDispatcher {
         control_rx: mpsc::Receiver<ControlSignal>,
         config: ServerConfig,
         trie: httpx_dsa::LinearIntentTrie,
-        learn_tx: mpsc::UnboundedSender<(Vec<u8>, bool)>,
+        learn_tx: LearnSender,
     ) -> Result<Self, std::io::Error> {
         // Default minimal (dev) configuration.
         let ring = IoUring::builder().build(128)?;
@@ -45,15 +232,16 @@ impl CoreDispatcher {
         config: ServerConfig,
         trie: httpx_dsa::LinearIntentTrie,
         ring: IoUring,
-        learn_tx: mpsc::UnboundedSender<(Vec<u8>, bool)>,
+        learn_tx: LearnSender,
     ) -> Result<Self, std::io::Error> {
-        let engine = Arc::new(PredictiveEngine::new(true));
+        let engine = Arc::new(PredictiveEngine::new(true).with_predictive_depth(config.predictive_depth));
         engine.swap_weights(trie);
 
         let packetizer = GsoPacketizer::new(config.slab_capacity);
+        let pacer = config.pacing_rate.map(Pacer::new);
         
         Ok(Self {
-            _core_id: core_id,
+            core_id,
             socket: Arc::new(socket),
             engine,
             control_rx,
@@ -61,16 +249,124 @@ impl CoreDispatcher {
             config,
             packetizer,
             learn_tx,
+            stats: Arc::new(DispatcherStats::default()),
+            pacer,
+            controller: None,
+            credit_policy: CreditPolicy::default(),
+            credit_level: 1,
+            session_mode: SessionMode::ClusterIntegrated,
+            draining: false,
+            terminated: false,
+            in_flight: Vec::new(),
+            next_token: 1,
+            paced: std::collections::VecDeque::new(),
+            ingress_posted: Vec::new(),
+            ingress_ready: std::collections::VecDeque::new(),
+            recorder: None,
+            ingress_pool: None,
+            _slab: PhantomData,
         })
     }
+}
+
+impl<S: SlabLike> CoreDispatcher<S> {
+    /// Rebinds the dispatcher to another slab implementation.
+    ///
+    /// Constructors produce a `SecureSlab` dispatcher; call this before
+    /// `register_slab` to drive a NUMA, sharded or mock slab instead.
+    pub fn for_slab<T: SlabLike>(self) -> CoreDispatcher<T> {
+        CoreDispatcher {
+            core_id: self.core_id,
+            socket: self.socket,
+            engine: self.engine,
+            control_rx: self.control_rx,
+            ring: self.ring,
+            config: self.config,
+            packetizer: self.packetizer,
+            learn_tx: self.learn_tx,
+            stats: self.stats,
+            pacer: self.pacer,
+            controller: self.controller,
+            credit_policy: self.credit_policy,
+            credit_level: self.credit_level,
+            session_mode: self.session_mode,
+            draining: self.draining,
+            terminated: self.terminated,
+            in_flight: self.in_flight,
+            next_token: self.next_token,
+            paced: self.paced,
+            ingress_posted: self.ingress_posted,
+            ingress_ready: self.ingress_ready,
+            recorder: self.recorder,
+            ingress_pool: self.ingress_pool,
+            _slab: PhantomData,
+        }
+    }
+
+    /// Sets (or clears) the egress pacing rate in bytes/sec.
+    pub fn set_pacing_rate(&mut self, rate: Option<u64>) {
+        match (rate, self.pacer.as_mut()) {
+            (Some(rate), Some(pacer)) => pacer.set_rate(rate),
+            (Some(rate), None) => self.pacer = Some(Pacer::new(rate)),
+            (None, _) => self.pacer = None,
+        }
+    }
+
+    /// Serves predictions from `engine` instead of the one built by the
+    /// constructor, installing the constructor's trie into it.
+    ///
+    /// Lets an `Http1Bridge` route against the same model this worker
+    /// swaps on `ControlSignal::SwapTrie`.
+    pub fn with_engine(mut self, engine: Arc<PredictiveEngine>) -> Self {
+        if let Some(trie) = self.engine.inspect_trie(|trie| trie.clone()) {
+            engine.swap_weights(trie);
+        }
+        self.engine = engine;
+        self
+    }
+
+    /// Attaches the congestion controller that `ControlSignal::SetBaseRtt` recalibrates.
+    ///
+    /// Burst completion latencies are fed to it as RTT samples, and the
+    /// level it returns sizes the fanout of subsequent packets. Once it has
+    /// a bandwidth estimate, that estimate also becomes the pacing rate.
+    pub fn with_congestion_controller(mut self, controller: Arc<dyn CongestionController>) -> Self {
+        if let Some(rate) = controller.pacing_rate() {
+            self.set_pacing_rate(Some(rate));
+        }
+        self.controller = Some(controller);
+        self
+    }
+
+    /// Overrides the level -> fanout mapping.
+    pub fn with_credit_policy(mut self, policy: CreditPolicy) -> Self {
+        self.credit_policy = policy;
+        self
+    }
 
-    /// Registers the SecureSlab memory with io_uring for zero-copy Fixed I/O.
-    pub fn register_slab(&self, slab: &httpx_dsa::SecureSlab) -> std::io::Result<()> {
+    /// Sets the active credit level used to size push fanout.
+    pub fn set_credit_level(&mut self, level: u8) {
+        self.credit_level = level;
+    }
+
+    /// Sets the trust mode of sessions served from now on.
+    ///
+    /// Also weights the learning events this worker emits; see
+    /// `SessionMode::learning_weight`.
+    pub fn set_session_mode(&mut self, mode: SessionMode) {
+        self.session_mode = mode;
+    }
+
+    /// Registers the slab memory with io_uring for zero-copy Fixed I/O.
+    ///
+    /// Each buffer spans one full slot (`slab.slot_size()` bytes).
+    pub fn register_slab(&self, slab: &S) -> std::io::Result<()> {
+        let payload_size = slab.slot_size();
         let mut iovecs = Vec::with_capacity(slab.slots());
         for i in 0..slab.slots() {
             iovecs.push(libc::iovec {
                 iov_base: slab.get_slot(i) as *mut libc::c_void,
-                iov_len: 4096, // Fixed page size
+                iov_len: payload_size,
             });
         }
         
@@ -80,127 +376,717 @@ impl CoreDispatcher {
     }
 
     /// The High-Performance Hot-Path.
-    pub async fn run_loop(&mut self, slab: &httpx_dsa::SecureSlab) {
-        let mut buf = [0u8; 4096]; 
+    ///
+    /// Runs until a `ControlSignal::Drain` has been received and every slab
+    /// slot has been reaped back to RC 0.
+    pub async fn run_loop(&mut self, slab: &S) {
+        if self.config.zero_copy_ingress {
+            if let Err(e) = self.run_loop_zero_copy(slab).await {
+                tracing::error!("CoreDispatcher {}: zero-copy ingress failed: {}", self.core_id, e);
+            }
+            return;
+        }
+
+        // One spare byte: `recv_from` truncates silently, so a datagram that
+        // fills it was longer than `max_datagram`.
+        let max_datagram = self.config.max_datagram;
+        let mut buf = vec![0u8; max_datagram + 1];
 
         loop {
+            self.stats.beat();
             // # Mechanical Sympathy: Reaping completions reduces memory pressure.
             self.reap_completions(slab);
+            self.submit_due_bursts(slab);
 
+            if self.terminated {
+                return;
+            }
+            if self.draining && !(0..slab.slots()).any(|i| slab.is_in_flight(i)) {
+                tracing::info!("CoreDispatcher {}: Drain complete, all slots idle.", self.core_id);
+                return;
+            }
+
+            let idle = self.idle_wakeup();
             tokio::select! {
                 Some(signal) = self.control_rx.recv() => {
                     self.handle_control(signal).await;
                 }
                 Ok((len, src)) = self.socket.recv_from(&mut buf) => {
+                    if len > max_datagram {
+                        tracing::warn!(
+                            "CoreDispatcher {}: dropped datagram from {} over max_datagram ({} bytes)",
+                            self.core_id, src, max_datagram
+                        );
+                        continue;
+                    }
                     self.on_packet(&buf[..len], src, slab).await;
                 }
+                // Completions don't wake the select; poll for them while draining.
+                // Otherwise wake anyway, so an idle loop keeps its heartbeat fresh.
+                _ = tokio::time::sleep(idle) => {}
+            }
+        }
+    }
+
+    /// `run_loop` with datagrams received straight into slab slots.
+    ///
+    /// An eventfd registered with the ring wakes the loop on every
+    /// completion, so ingress and send completions are reaped promptly.
+    async fn run_loop_zero_copy(&mut self, slab: &S) -> Result<(), TransportError> {
+        use std::os::fd::{FromRawFd, OwnedFd};
+
+        let raw = unsafe { libc::eventfd(0, libc::EFD_NONBLOCK | libc::EFD_CLOEXEC) };
+        if raw < 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        let eventfd = tokio::io::unix::AsyncFd::new(unsafe { OwnedFd::from_raw_fd(raw) })?;
+        self.ring.submitter().register_eventfd(raw)?;
+
+        // Without a single posted receive the worker would never hear a request.
+        self.refill_ingress()?;
+        let pool = self.ingress_pool()?;
+        let max_datagram = self.config.max_datagram;
+
+        loop {
+            self.stats.beat();
+            self.reap_completions(slab);
+            self.submit_due_bursts(slab);
+            while let Some(dgram) = self.next_ingress() {
+                if dgram.len > max_datagram {
+                    tracing::warn!(
+                        "CoreDispatcher {}: dropped datagram from {} over max_datagram ({} bytes)",
+                        self.core_id, dgram.src, max_datagram
+                    );
+                } else {
+                    // # Safety: the kernel finished writing `len` bytes into the slot.
+                    let data = unsafe { std::slice::from_raw_parts(pool.get_slot(dgram.slot), dgram.len) };
+                    self.on_packet(data, dgram.src, slab).await;
+                }
+                self.release_ingress(dgram.slot);
+            }
+            if !self.draining {
+                if let Err(e) = self.refill_ingress() {
+                    tracing::warn!("CoreDispatcher {}: ingress receive not re-posted: {}", self.core_id, e);
+                }
+            }
+
+            if self.terminated {
+                return Ok(());
+            }
+            // Cancelled receives complete too; wait for them before the pool can go.
+            if self.draining && self.ingress_posted.is_empty() && !(0..slab.slots()).any(|i| slab.is_in_flight(i)) {
+                tracing::info!("CoreDispatcher {}: Drain complete, all slots idle.", self.core_id);
+                return Ok(());
+            }
+
+            let idle = self.idle_wakeup();
+            tokio::select! {
+                Some(signal) = self.control_rx.recv() => {
+                    self.handle_control(signal).await;
+                }
+                Ok(mut guard) = eventfd.readable() => {
+                    let mut counter = [0u8; 8];
+                    unsafe { libc::read(raw, counter.as_mut_ptr() as *mut libc::c_void, counter.len()) };
+                    guard.clear_ready();
+                }
+                _ = tokio::time::sleep(idle) => {}
+            }
+        }
+    }
+
+    /// How long the event loop may block before its next iteration.
+    ///
+    /// Never past the next paced burst's due time.
+    fn idle_wakeup(&self) -> std::time::Duration {
+        let idle = if self.draining { std::time::Duration::from_millis(1) } else { HEARTBEAT_INTERVAL };
+        match self.paced.front() {
+            Some(burst) => idle.min(burst.due.saturating_duration_since(std::time::Instant::now())),
+            None => idle,
+        }
+    }
+
+    /// Lands zero-copy receives in `pool` instead of a pool built on first use.
+    ///
+    /// Ingress never borrows payload slab slots, so a burst of requests
+    /// cannot starve the payloads being pushed. The default pool holds
+    /// `INGRESS_DEPTH` buffers sized for `ServerConfig::max_datagram` plus
+    /// one byte, so an oversized datagram is detected rather than cut.
+    pub fn with_ingress_pool(mut self, pool: SecureSlab) -> Self {
+        self.ingress_pool = Some(Arc::new(pool));
+        self
+    }
+
+    /// The pool zero-copy receives land in, built on first use.
+    fn ingress_pool(&mut self) -> Result<Arc<SecureSlab>, TransportError> {
+        if let Some(pool) = &self.ingress_pool {
+            return Ok(pool.clone());
+        }
+        let slot_size = (self.config.max_datagram + 1).next_multiple_of(DEFAULT_SLOT_SIZE);
+        // Slot 0 is the slab's reserved "no payload" handle and never allocated.
+        let pool = Arc::new(SecureSlab::try_with_slot_size(INGRESS_DEPTH + 1, slot_size)?);
+        self.ingress_pool = Some(pool.clone());
+        Ok(pool)
+    }
+
+    /// Posts receives until `INGRESS_DEPTH` are outstanding.
+    ///
+    /// Fails only if none could be posted at all; otherwise the missing
+    /// ones are retried on the next call.
+    fn refill_ingress(&mut self) -> Result<(), TransportError> {
+        while self.ingress_posted.len() < INGRESS_DEPTH {
+            if let Err(e) = self.post_ingress() {
+                if self.ingress_posted.is_empty() {
+                    return Err(e);
+                }
+                break;
             }
         }
+        Ok(())
     }
 
-    async fn handle_control(&self, signal: ControlSignal) {
+    /// Posts a `RecvMsg` that lands the next datagram directly in a free
+    /// ingress pool slot; see `with_ingress_pool`.
+    ///
+    /// The slot is claimed from the pool's free-list and held in flight
+    /// (RC +1) until the completion is reaped. Returns the claimed slot.
+    /// The datagram is written once, into the pool, and never copied again.
+    pub fn post_ingress(&mut self) -> Result<usize, TransportError> {
+        let pool = self.ingress_pool()?;
+        let slot = pool.allocate_slot()?;
+        let mut recv = Box::new(IngressRecv {
+            slot,
+            iov: libc::iovec { iov_base: pool.get_slot(slot) as *mut libc::c_void, iov_len: pool.slot_size() },
+            addr: unsafe { std::mem::zeroed() },
+            msghdr: unsafe { std::mem::zeroed() },
+        });
+        recv.msghdr.msg_name = &mut recv.addr as *mut libc::sockaddr_storage as *mut libc::c_void;
+        recv.msghdr.msg_namelen = std::mem::size_of::<libc::sockaddr_storage>() as libc::socklen_t;
+        recv.msghdr.msg_iov = &mut recv.iov;
+        recv.msghdr.msg_iovlen = 1;
+
+        let op = opcode::RecvMsg::new(types::Fd(self.socket.as_raw_fd()), &mut recv.msghdr)
+            .build()
+            .user_data(INGRESS_TAG | slot as u64);
+
+        pool.increment_rc(slot);
+        if !self.try_push_sqe(&op) {
+            // Flushing frees SQ space without reaping, which needs the payload slab.
+            self.flush_submissions();
+            if !self.try_push_sqe(&op) {
+                pool.decrement_rc(slot);
+                pool.free_slot(slot);
+                return Err(std::io::Error::other("SQ Full").into());
+            }
+        }
+        self.ingress_posted.push(recv);
+        self.flush_submissions();
+        Ok(slot)
+    }
+
+    /// Pops the next datagram received by `post_ingress`.
+    ///
+    /// The caller owns `slot` afterwards, reads it through
+    /// `ingress_buffers`, and hands it back with `release_ingress`.
+    pub fn next_ingress(&mut self) -> Option<IngressDatagram> {
+        self.ingress_ready.pop_front()
+    }
+
+    /// The ingress pool, once a receive has been posted or one was attached.
+    pub fn ingress_buffers(&self) -> Option<&SecureSlab> {
+        self.ingress_pool.as_deref()
+    }
+
+    /// Returns a slot taken from `next_ingress` to the ingress pool.
+    pub fn release_ingress(&mut self, slot: usize) {
+        if let Some(pool) = &self.ingress_pool {
+            pool.free_slot(slot);
+        }
+    }
+
+    /// Number of posted receives still waiting for a datagram.
+    pub fn ingress_posted(&self) -> usize {
+        self.ingress_posted.len()
+    }
+
+    /// Records every inbound packet, trie swap and push decision to a replay
+    /// log, starting with a snapshot of the current state.
+    ///
+    /// Feed the log to `ReplayDispatcher` to reproduce the decisions offline.
+    pub fn with_recorder(mut self, recorder: DispatchRecorder) -> Self {
+        self.recorder = Some(recorder);
+        let start = ReplayEvent::Start {
+            max_intent_credits: self.config.max_intent_credits,
+            predictive_depth: self.config.predictive_depth,
+            accepted_versions: self.config.accepted_versions.clone(),
+            session_mode: self.session_mode,
+            trie: self.engine.inspect_trie(|trie| trie.clone()).unwrap_or_else(|| httpx_dsa::LinearIntentTrie::new(1)),
+        };
+        self.record(|_| start);
+        self
+    }
+
+    /// Appends an event to the replay log, if recording.
+    ///
+    /// A failed write stops the recording rather than the dispatcher.
+    fn record(&mut self, event: impl FnOnce(u64) -> ReplayEvent) {
+        let Some(recorder) = self.recorder.as_mut() else { return };
+        if let Err(e) = recorder.record(&event(recorder.elapsed_nanos())) {
+            tracing::warn!("CoreDispatcher {}: replay recording stopped: {}", self.core_id, e);
+            self.recorder = None;
+        }
+    }
+
+    /// Returns `true` once a drain has been requested.
+    pub fn is_draining(&self) -> bool {
+        self.draining
+    }
+
+    async fn handle_control(&mut self, signal: ControlSignal) {
         match signal {
             ControlSignal::Pivot(addr) => {
                 tracing::warn!("Priority-Zero: Pivot detected for {}. Killing stale pushes.", addr);
                 self.engine.cancel_for(&addr);
+                self.cancel_for(addr);
+            }
+            ControlSignal::CancelPath(path) => {
+                self.cancel_path(&path);
             }
             ControlSignal::KillAll => {
                 tracing::error!("Priority-Zero: Global termination.");
+                self.terminated = true;
+            }
+            ControlSignal::Drain => {
+                tracing::warn!("CoreDispatcher {}: Draining. New pushes disabled.", self.core_id);
+                self.draining = true;
+                self.paced.clear();
+                // Posted receives hold their slots in flight; release them.
+                let posted: Vec<u64> = self.ingress_posted.iter().map(|r| INGRESS_TAG | r.slot as u64).collect();
+                for user_data in posted {
+                    let op = opcode::AsyncCancel::new(user_data).build().user_data(CANCEL_USER_DATA);
+                    self.try_push_sqe(&op);
+                }
+                self.flush_submissions();
             }
             ControlSignal::SwapTrie(new_trie) => {
                 // Task 2: Shadow-Swap Handshake with RC Safety.
                 self.engine.swap_weights((*new_trie).clone());
+                self.record(|_| ReplayEvent::SwapTrie((*new_trie).clone()));
                 tracing::info!("CoreDispatcher: Shadow-Swap Handshake Complete (Seq: {})", new_trie.sequence_number);
             }
+            ControlSignal::ResetLearning => {
+                if let Some(mut trie) = self.engine.inspect_trie(|trie| trie.clone()) {
+                    trie.reset_learning();
+                    if self.recorder.is_some() {
+                        self.record(|_| ReplayEvent::SwapTrie(trie.clone()));
+                    }
+                    self.engine.swap_weights(trie);
+                }
+                tracing::warn!("CoreDispatcher {}: Learned weights reset.", self.core_id);
+            }
+            ControlSignal::SetBaseRtt(nanos) => match &self.controller {
+                Some(cc) => {
+                    cc.set_base_rtt(nanos);
+                    tracing::info!("CoreDispatcher {}: Base RTT recalibrated to {}ns.", self.core_id, nanos);
+                }
+                None => tracing::warn!("CoreDispatcher {}: SetBaseRtt without a congestion controller.", self.core_id),
+            },
+            ControlSignal::SetSessionMode(mode) => {
+                tracing::info!("CoreDispatcher {}: Sessions now {:?}.", self.core_id, mode);
+                self.set_session_mode(mode);
+                self.record(|_| ReplayEvent::SetSessionMode(mode));
+            }
+            ControlSignal::Inspect(reply) => {
+                let _ = reply.send(self.snapshot()).await;
+            }
+        }
+    }
+
+    /// Replaces this worker's counters with a caller-owned block, so the
+    /// server handle can aggregate them without a control-plane round trip.
+    pub fn with_stats(mut self, stats: Arc<DispatcherStats>) -> Self {
+        self.stats = stats;
+        self
+    }
+
+    /// Returns the burst packetizer (exposes prepared iovecs for verification).
+    pub fn packetizer(&self) -> &GsoPacketizer {
+        &self.packetizer
+    }
+
+    /// Returns the data-plane counters for this worker.
+    pub fn stats(&self) -> StatsSnapshot {
+        self.stats.snapshot()
+    }
+
+    /// Returns this worker's submit -> reap push latency histogram.
+    pub fn latency(&self) -> LatencyHistogram {
+        self.stats.latency()
+    }
+
+    /// Captures a read-only snapshot of this worker's active trie and counters.
+    pub fn snapshot(&self) -> WorkerSnapshot {
+        let (routes, trie_digest, trie_sequence) = self.engine
+            .inspect_trie(|trie| (trie.routes(), trie.digest(), trie.sequence_number))
+            .unwrap_or_default();
+        WorkerSnapshot {
+            core_id: self.core_id,
+            routes,
+            trie_digest,
+            trie_sequence,
+            stats: self.stats.snapshot(),
         }
     }
 
 
     /// Reaps completions from the io_uring and recycles slab fragments.
-    pub fn reap_completions(&mut self, slab: &httpx_dsa::SecureSlab) {
+    ///
+    /// First retries any SQEs an earlier submit left queued (see
+    /// `flush_submissions`). Then drains the CQ in up to `ServerConfig::reap_drain_iterations` passes,
+    /// so completions the kernel parked on CQ overflow are picked up in the
+    /// same call. Returns the number of CQEs reaped.
+    pub fn reap_completions(&mut self, slab: &S) -> usize {
+        self.flush_submissions();
+        let mut releases = RcBatch::new();
+        let mut reaped = 0;
+        for _ in 0..self.config.reap_drain_iterations.max(1) {
+            let n = self.reap_pass(slab, &mut releases);
+            reaped += n;
+            if self.ring.submission().cq_overflow() {
+                // Entering the kernel flushes overflowed CQEs back into the ring.
+                let _ = self.ring.submit();
+            } else if n == 0 {
+                break;
+            }
+        }
+        releases.flush(slab);
+        reaped
+    }
+
+    /// Consumes every CQE currently visible in the ring.
+    fn reap_pass(&mut self, slab: &S, releases: &mut RcBatch) -> usize {
+        let mut reaped = 0;
         let mut cq = self.ring.completion();
         while let Some(cqe) = cq.next() {
+            reaped += 1;
             let user_data = cqe.user_data();
-            if user_data > 0 {
-                // Decode combined handle: Payload (Low 32) | Template (High 32)
-                let payload_handle = ((user_data & 0xFFFFFFFF) - 1) as usize;
-                let template_data = (user_data >> 32) & 0xFFFFFFFF;
-                
-                slab.decrement_rc(payload_handle);
-                
-                if template_data > 0 {
-                     let template_handle = (template_data - 1) as usize;
-                     slab.decrement_rc(template_handle);
+            self.stats.record_completion();
+            if user_data == CANCEL_USER_DATA {
+                continue;
+            }
+            if user_data & INGRESS_TAG != 0 {
+                let slot = (user_data & !INGRESS_TAG) as usize;
+                let Some(pos) = self.ingress_posted.iter().position(|r| r.slot == slot) else { continue };
+                let recv = self.ingress_posted.swap_remove(pos);
+                let Some(pool) = &self.ingress_pool else { continue };
+                pool.decrement_rc(slot);
+                // # Safety: the kernel filled `addr`/`msg_namelen` before completing.
+                let src = unsafe { socket2::SockAddr::new(recv.addr, recv.msghdr.msg_namelen) }.as_socket();
+                match (cqe.result(), src) {
+                    (len, Some(src)) if len >= 0 => {
+                        self.ingress_ready.push_back(IngressDatagram { slot, len: len as usize, src });
+                    }
+                    _ => pool.free_slot(slot),
+                }
+                continue;
+            }
+            // Every burst is tracked from submission, so an unknown token
+            // is a foreign SQE (e.g. a diagnostic NOP) holding no RC.
+            let Some(pos) = self.in_flight.iter().position(|p| p.token == user_data) else { continue };
+            let push = self.in_flight.swap_remove(pos);
+            if cqe.result() >= 0 {
+                let rtt = push.submitted_at.elapsed();
+                self.stats.record_latency(rtt);
+                if let Some(cc) = &self.controller {
+                    self.credit_level = cc.evaluate_intent_credit(rtt.as_nanos() as u64);
+                    // `set_pacing_rate` would reborrow the ring held by `cq`.
+                    match (cc.pacing_rate(), self.pacer.as_mut()) {
+                        (Some(rate), Some(pacer)) => pacer.set_rate(rate),
+                        (Some(rate), None) => self.pacer = Some(Pacer::new(rate)),
+                        (None, _) => {}
+                    }
                 }
             }
+            if cqe.result() == -libc::ECANCELED {
+                self.stats.record_cancel();
+            }
+            releases.release(slab, push.payload_handle as usize);
+            releases.release(slab, push.template_handle as usize);
         }
+        reaped
     }
 
     /// Submits a GSO Super-Packet: Intent + Headers + Payload (Zero-Copy SendMsg).
+    ///
+    /// A payload longer than its slot is refused with
+    /// `TransportError::PayloadTooLarge` instead of being sent truncated,
+    /// and a quarantined payload or template slot with `SlabError::Poisoned`.
+    ///
+    /// With pacing enabled, a burst whose egress slot lies in the future is
+    /// queued and `Ok(())` returned at once; the run loop submits it through
+    /// `submit_due_bursts`, checking `expected_version` only then.
     pub async fn submit_linked_burst(
         &mut self, 
-        _target: SocketAddr, 
+        target: SocketAddr, 
         payload_handle: u32, 
         template_handle: u32,
         expected_version: u32,
-        slab: &httpx_dsa::SecureSlab
-    ) -> std::io::Result<()> {
+        slab: &S
+    ) -> Result<(), TransportError> {
+        check_not_poisoned(slab, payload_handle, template_handle)?;
+        let payload_size = slab.slot_size();
+        let payload_len = slab.payload_len(payload_handle as usize);
+        if payload_len > payload_size {
+            return Err(TransportError::PayloadTooLarge { len: payload_len, limit: payload_size });
+        }
+
+        // Reservations only move forward, so the queue stays in due order.
+        // A burst that is due now still waits behind earlier queued ones.
+        if let Some(pacer) = self.pacer.as_mut() {
+            let delay = pacer.reserve(BURST_PREFIX_LEN + payload_len);
+            if !delay.is_zero() || !self.paced.is_empty() {
+                self.paced.push_back(PacedBurst {
+                    due: std::time::Instant::now() + delay,
+                    target,
+                    payload_handle,
+                    template_handle,
+                    expected_version,
+                });
+                return Ok(());
+            }
+        }
+
+        self.submit_burst(target, payload_handle, template_handle, expected_version, slab)
+    }
+
+    /// Submits every paced burst whose egress slot has come due.
+    ///
+    /// Called on each run-loop iteration. A burst that fails its freshness
+    /// check or finds the SQ full is dropped. Returns the number submitted.
+    pub fn submit_due_bursts(&mut self, slab: &S) -> usize {
+        let now = std::time::Instant::now();
+        let mut submitted = 0;
+        while let Some(burst) = self.paced.front().copied().filter(|b| b.due <= now) {
+            self.paced.pop_front();
+            match self.submit_burst(burst.target, burst.payload_handle, burst.template_handle, burst.expected_version, slab) {
+                Ok(()) => submitted += 1,
+                Err(e) => tracing::debug!("CoreDispatcher {}: paced burst to {} dropped: {}", self.core_id, burst.target, e),
+            }
+        }
+        submitted
+    }
+
+    /// Number of bursts waiting on the pacer.
+    pub fn paced_len(&self) -> usize {
+        self.paced.len()
+    }
+
+    /// Pushes one burst's `SendMsg` SQE, after the freshness check.
+    fn submit_burst(
+        &mut self,
+        target: SocketAddr,
+        payload_handle: u32,
+        template_handle: u32,
+        expected_version: u32,
+        slab: &S,
+    ) -> Result<(), TransportError> {
+        // Checked again here: a paced burst may have been poisoned while queued.
+        check_not_poisoned(slab, payload_handle, template_handle)?;
+        let payload_len = slab.payload_len(payload_handle as usize).min(slab.slot_size());
         let current_version = slab.get_version(payload_handle as usize);
         if current_version != expected_version {
-            return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "Stale Payload"));
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "Stale Payload").into());
         }
 
         let fd = self.socket.as_raw_fd();
         
         // Prepare Vectored I/O (Intent, Header, Payload)
+        // Only the slot's live payload is sent: padding to the full page would
+        // leak stale bytes left over from earlier content.
         // This eliminates the 3-SQE chain overhead.
         let msghdr_ptr = self.packetizer.prepare_burst(
             payload_handle as usize,
-            b"INTENT_SYNC_FRAME".as_ptr(), b"INTENT_SYNC_FRAME".len(),
-            slab.get_slot(template_handle as usize), 128,
-            slab.get_slot(payload_handle as usize), 4096,
+            INTENT_FRAME.as_ptr(), INTENT_FRAME.len(),
+            slab.get_slot(template_handle as usize), TEMPLATE_LEN,
+            slab.get_slot(payload_handle as usize), payload_len,
             0 // GSO segment size (future: config.mss)
         );
 
-        // Encode Handles for RC Reaping
-        let user_data = ((payload_handle as u64) + 1) | (((template_handle as u64) + 1) << 32);
+        // A fresh token per submission; the handles to release on reap are
+        // looked up from `in_flight`, never decoded from `user_data`.
+        let token = self.next_token;
+        self.next_token = if token + 1 >= INGRESS_TAG { 1 } else { token + 1 };
 
         // SQE: SendMsg
         let op = opcode::SendMsg::new(
             types::Fd(fd),
             msghdr_ptr,
         ).build()
-         .user_data(user_data);
+         .user_data(token);
 
+        // RC is taken exactly once, before the first attempt, so a retry
+        // never double-increments.
         slab.increment_rc(payload_handle as usize);
         slab.increment_rc(template_handle as usize);
 
-        unsafe {
-            let mut sq = self.ring.submission();
-            if sq.push(&op).is_err() {
-                 // Backpressure: Return WouldBlock or drop
-                 return Err(std::io::Error::new(std::io::ErrorKind::Other, "SQ Full"));
+        if !self.try_push_sqe(&op) {
+            // Transient SQ-full: flush pending SQEs to the kernel, recycle
+            // whatever has completed, then retry exactly once.
+            self.reap_completions(slab);
+            if !self.try_push_sqe(&op) {
+                // Backpressure: the SQE never reached the ring, so no CQE will release the RC.
+                slab.decrement_rc(payload_handle as usize);
+                slab.decrement_rc(template_handle as usize);
+                return Err(std::io::Error::new(std::io::ErrorKind::Other, "SQ Full").into());
             }
         }
 
-        let _ = self.ring.submit();
+        self.in_flight.push(InFlightPush {
+            token,
+            target,
+            payload_handle,
+            template_handle,
+            submitted_at: std::time::Instant::now(),
+        });
+        // A refused or partial submit leaves this SQE queued, still tracked
+        // in `in_flight`; the next reap retries it, so its RCs are never orphaned.
+        self.flush_submissions();
+        self.stats.record_push();
         Ok(())
     }
 
+    /// Priority-Zero pivot: cancels every in-flight burst destined for `addr`.
+    ///
+    /// Issues one `AsyncCancel` per tracked SQE; the cancelled bursts complete
+    /// with `-ECANCELED` and release their RC through `reap_completions`.
+    /// Paced bursts still queued for `addr` are dropped as well. Returns the
+    /// number of cancellations requested.
+    pub fn cancel_for(&mut self, addr: SocketAddr) -> usize {
+        self.paced.retain(|b| b.target != addr);
+        self.cancel_matching(|p| p.target == addr)
+    }
+
+    /// Cancels every in-flight burst of the payload routed at `context`
+    /// (e.g. after the payload was invalidated).
+    pub fn cancel_path(&mut self, context: &[u8]) -> usize {
+        let handle = self.engine
+            .inspect_trie(|trie| trie.get_node_at_path(context).map(|n| n.payload_handle))
+            .flatten();
+        match handle {
+            Some(handle) if handle > 0 => {
+                self.paced.retain(|b| b.payload_handle != handle);
+                self.cancel_matching(|p| p.payload_handle == handle)
+            }
+            _ => 0,
+        }
+    }
+
+    fn cancel_matching(&mut self, matches: impl Fn(&InFlightPush) -> bool) -> usize {
+        let targets: Vec<u64> = self.in_flight.iter().filter(|p| matches(p)).map(|p| p.token).collect();
+        let mut requested = 0;
+        for token in targets {
+            let op = opcode::AsyncCancel::new(token).build().user_data(CANCEL_USER_DATA);
+            if self.try_push_sqe(&op) {
+                requested += 1;
+            }
+        }
+        if requested > 0 {
+            self.flush_submissions();
+        }
+        requested
+    }
+
+    /// Hands every queued SQE to the kernel, retrying the remainder of a
+    /// partial submission. Returns the number still queued.
+    ///
+    /// SQEs the kernel refuses (`EAGAIN`, `EBUSY`, a disabled ring) stay in
+    /// the SQ with their slot RCs held. They cannot be withdrawn once
+    /// published, so they are retried instead: every `reap_completions`
+    /// flushes first, and the run loop reaps each iteration.
+    pub fn flush_submissions(&mut self) -> usize {
+        let mut queued = self.ring.submission().len();
+        for _ in 0..SUBMIT_ATTEMPTS {
+            if queued == 0 {
+                return 0;
+            }
+            let submitted = match self.ring.submit() {
+                Ok(n) => n,
+                Err(e) => {
+                    tracing::debug!("CoreDispatcher {}: submit refused: {}", self.core_id, e);
+                    0
+                }
+            };
+            // An SQPOLL thread consumes the queue on its own schedule.
+            if submitted > 0 && self.ring.params().is_setup_sqpoll() {
+                return 0;
+            }
+            let left = self.ring.submission().len();
+            if submitted == 0 && left == queued {
+                break;
+            }
+            queued = left;
+        }
+        if queued > 0 {
+            tracing::warn!("CoreDispatcher {}: {} SQEs left queued for the next flush", self.core_id, queued);
+        }
+        queued
+    }
+
+    /// Pushes a single SQE, returning `false` if the submission queue is full.
+    fn try_push_sqe(&mut self, op: &io_uring::squeue::Entry) -> bool {
+        // # Safety: the msghdr/iovecs referenced by `op` live in the packetizer
+        // and the slab, both of which outlive the in-flight operation.
+        unsafe { self.ring.submission().push(op).is_ok() }
+    }
+
+    /// Direct access to the underlying ring, for diagnostics and tests.
+    pub fn ring_mut(&mut self) -> &mut IoUring {
+        &mut self.ring
+    }
+
     /// Handles an incoming UDP packet and triggers a predictive push if a route matches.
-    pub async fn on_packet(&mut self, data: &[u8], addr: SocketAddr, slab: &httpx_dsa::SecureSlab) {
-        let session = httpx_core::session::Session::new(addr);
-        
-        // Task 2: Emit learning event before prediction
-        let _ = self.learn_tx.send((data.to_vec(), true));
+    ///
+    /// Framed requests (see `frame`) whose version is not in
+    /// `ServerConfig::accepted_versions` are dropped; unframed datagrams are
+    /// served as a bare path.
+    pub async fn on_packet(&mut self, raw: &[u8], addr: SocketAddr, slab: &S) {
+        self.stats.record_packet();
 
-        if let Some((payload, version)) = self.engine.predict_for_path(&session, data) {
+        // Multi-Level Credit: the level decides how many predictions fire.
+        let fanout = if self.draining { 0 } else { self.credit_policy.max_pushes_for_level(self.credit_level) };
+        let mut targets = [(0u32, 0u32); MAX_PUSH_FANOUT];
+        let n = match packet_path(raw, &self.config.accepted_versions) {
+            Ok(data) => {
+                // Task 2: Emit learning event before prediction
+                self.learn_tx.send_weighted(data, true, self.session_mode.learning_weight());
+                resolve_pushes(&self.engine, &self.config, self.session_mode, addr, data, &mut targets[..fanout])
+            }
+            Err(e) => {
+                tracing::debug!("Dispatcher {}: rejected frame from {}: {}", self.core_id, addr, e);
+                0
+            }
+        };
+
+        if self.recorder.is_some() {
+            let credit_level = self.credit_level;
+            self.record(|at_nanos| ReplayEvent::Packet(RecordedPacket {
+                at_nanos,
+                src: addr,
+                data: raw.to_vec(),
+                credit_level,
+                fanout: fanout as u8,
+                pushes: targets[..n].to_vec(),
+            }));
+        }
+
+        if n > 0 {
             let fd = self.socket.as_raw_fd();
             let sockaddr = socket2::SockAddr::from(addr);
             unsafe {
                 let _ = libc::connect(fd, sockaddr.as_ptr(), sockaddr.len());
             }
-            let _ = self.submit_linked_burst(addr, payload, 0, version, slab).await;
+            for &(payload, version) in &targets[..n] {
+                let _ = self.submit_linked_burst(addr, payload, 0, version, slab).await;
+            }
         }
     }
 }
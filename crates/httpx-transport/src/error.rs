@@ -0,0 +1,70 @@
+//! # httpx-transport: Send-Path Errors
+
+use httpx_dsa::SlabError;
+use std::io;
+
+/// Why a send path refused or failed to transmit.
+#[derive(Debug)]
+pub enum TransportError {
+    /// The request does not fit the send path; nothing was transmitted.
+    /// `len` is the number of bytes requested, `limit` the path's maximum.
+    PayloadTooLarge { len: usize, limit: usize },
+    /// Freshness-gate rejections (`InvalidData`), backpressure and socket errors.
+    Io(io::Error),
+    /// The slab could not provide a buffer (e.g. no free slot for ingress).
+    Slab(SlabError),
+}
+
+impl TransportError {
+    /// The equivalent `io::ErrorKind`, for callers that only branch on kinds.
+    pub fn kind(&self) -> io::ErrorKind {
+        match self {
+            Self::PayloadTooLarge { .. } => io::ErrorKind::InvalidInput,
+            Self::Io(err) => err.kind(),
+            Self::Slab(_) => io::ErrorKind::Other,
+        }
+    }
+}
+
+impl std::fmt::Display for TransportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::PayloadTooLarge { len, limit } => {
+                write!(f, "payload of {} bytes exceeds the {}-byte send limit", len, limit)
+            }
+            Self::Io(err) => write!(f, "transport: {}", err),
+            Self::Slab(err) => write!(f, "transport: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for TransportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Slab(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for TransportError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<SlabError> for TransportError {
+    fn from(err: SlabError) -> Self {
+        Self::Slab(err)
+    }
+}
+
+impl From<TransportError> for io::Error {
+    fn from(err: TransportError) -> Self {
+        match err {
+            TransportError::Io(err) => err,
+            other => io::Error::new(other.kind(), other),
+        }
+    }
+}
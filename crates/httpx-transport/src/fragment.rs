@@ -0,0 +1,193 @@
+//! # httpx-transport: Fragment Framing & Reassembly
+//!
+//! Payloads larger than one datagram are split into fragments, each prefixed
+//! with a fixed 12-byte `FragmentHeader`. The client buffers fragments per
+//! payload handle (in any order) until the set is complete, and discards
+//! partial assemblies that stall past a timeout.
+
+use crate::frame::HttpxFrame;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Marks a datagram as an HTTP-X payload fragment.
+pub const FRAGMENT_MAGIC: [u8; 4] = *b"HXFG";
+
+/// Encoded size of a `FragmentHeader`.
+pub const FRAGMENT_HEADER_LEN: usize = 12;
+
+/// Encoded size of the header fields without the magic, as embedded in
+/// version 2 `HttpxFrame`s.
+pub const FRAGMENT_FIELDS_LEN: usize = FRAGMENT_HEADER_LEN - FRAGMENT_MAGIC.len();
+
+/// Wire header: `magic[4] | payload_handle: u32 | index: u16 | total: u16` (big-endian).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FragmentHeader {
+    pub payload_handle: u32,
+    pub index: u16,
+    pub total: u16,
+}
+
+impl FragmentHeader {
+    pub fn encode(&self) -> [u8; FRAGMENT_HEADER_LEN] {
+        let mut buf = [0u8; FRAGMENT_HEADER_LEN];
+        buf[0..4].copy_from_slice(&FRAGMENT_MAGIC);
+        buf[4..].copy_from_slice(&self.encode_fields());
+        buf
+    }
+
+    /// The header fields without the magic.
+    pub fn encode_fields(&self) -> [u8; FRAGMENT_FIELDS_LEN] {
+        let mut buf = [0u8; FRAGMENT_FIELDS_LEN];
+        buf[0..4].copy_from_slice(&self.payload_handle.to_be_bytes());
+        buf[4..6].copy_from_slice(&self.index.to_be_bytes());
+        buf[6..8].copy_from_slice(&self.total.to_be_bytes());
+        buf
+    }
+
+    /// Parses the fields written by `encode_fields`.
+    ///
+    /// Returns `None` if `fields` is short or `index` is not below a non-zero `total`.
+    pub fn decode_fields(fields: &[u8]) -> Option<Self> {
+        let fields: &[u8; FRAGMENT_FIELDS_LEN] = fields.get(..FRAGMENT_FIELDS_LEN)?.try_into().ok()?;
+        let header = Self {
+            payload_handle: u32::from_be_bytes([fields[0], fields[1], fields[2], fields[3]]),
+            index: u16::from_be_bytes([fields[4], fields[5]]),
+            total: u16::from_be_bytes([fields[6], fields[7]]),
+        };
+        if header.total == 0 || header.index >= header.total {
+            return None;
+        }
+        Some(header)
+    }
+
+    /// Splits a datagram into its header and fragment body.
+    ///
+    /// Returns `None` for non-fragment datagrams and malformed headers.
+    pub fn decode(datagram: &[u8]) -> Option<(Self, &[u8])> {
+        if datagram.len() < FRAGMENT_HEADER_LEN || datagram[0..4] != FRAGMENT_MAGIC {
+            return None;
+        }
+        let header = Self::decode_fields(&datagram[4..])?;
+        Some((header, &datagram[FRAGMENT_HEADER_LEN..]))
+    }
+}
+
+/// Splits `payload` into framed fragments whose encoded size is at most `max_datagram`.
+pub fn fragment_payload(payload_handle: u32, payload: &[u8], max_datagram: usize) -> Vec<Vec<u8>> {
+    assert!(max_datagram > FRAGMENT_HEADER_LEN, "fragment_payload: datagram too small for header");
+    let body = max_datagram - FRAGMENT_HEADER_LEN;
+    let chunks: Vec<&[u8]> = if payload.is_empty() { vec![&[][..]] } else { payload.chunks(body).collect() };
+    let total = u16::try_from(chunks.len()).expect("fragment_payload: more than u16::MAX fragments");
+
+    chunks
+        .into_iter()
+        .enumerate()
+        .map(|(index, chunk)| {
+            let header = FragmentHeader { payload_handle, index: index as u16, total };
+            let mut datagram = Vec::with_capacity(FRAGMENT_HEADER_LEN + chunk.len());
+            datagram.extend_from_slice(&header.encode());
+            datagram.extend_from_slice(chunk);
+            datagram
+        })
+        .collect()
+}
+
+struct PartialPayload {
+    fragments: Vec<Option<Vec<u8>>>,
+    received: usize,
+    started: Instant,
+}
+
+/// Client-side reassembly of fragmented predictive pushes.
+///
+/// Accepts both bare HXFG datagrams (`ingest`) and version 2 request frames
+/// (`ingest_frame`); both key partial payloads by their `FragmentHeader`.
+pub struct Reassembler {
+    partial: HashMap<u32, PartialPayload>,
+    timeout: Duration,
+}
+
+impl Reassembler {
+    /// Partial assemblies older than `timeout` are discarded as incomplete.
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            partial: HashMap::new(),
+            timeout,
+        }
+    }
+
+    /// Feeds one datagram. Returns `(payload_handle, payload)` once every
+    /// fragment of that payload has arrived.
+    pub fn ingest(&mut self, datagram: &[u8]) -> Option<(u32, Vec<u8>)> {
+        self.ingest_at(datagram, Instant::now())
+    }
+
+    /// `ingest` with an explicit clock, for deterministic expiry.
+    pub fn ingest_at(&mut self, datagram: &[u8], now: Instant) -> Option<(u32, Vec<u8>)> {
+        let (header, body) = FragmentHeader::decode(datagram)?;
+        self.ingest_fragment(header, body, now)
+    }
+
+    /// Feeds one decoded request frame. Unfragmented (version 1) frames
+    /// carry no payload id and are ignored.
+    pub fn ingest_frame(&mut self, frame: &HttpxFrame<'_>) -> Option<(u32, Vec<u8>)> {
+        self.ingest_frame_at(frame, Instant::now())
+    }
+
+    /// `ingest_frame` with an explicit clock, for deterministic expiry.
+    pub fn ingest_frame_at(&mut self, frame: &HttpxFrame<'_>, now: Instant) -> Option<(u32, Vec<u8>)> {
+        self.ingest_fragment(frame.fragment?, frame.path, now)
+    }
+
+    fn ingest_fragment(&mut self, header: FragmentHeader, body: &[u8], now: Instant) -> Option<(u32, Vec<u8>)> {
+        self.expire(now);
+        let entry = self.partial.entry(header.payload_handle).or_insert_with(|| PartialPayload {
+            fragments: vec![None; header.total as usize],
+            received: 0,
+            started: now,
+        });
+        // A different total means a new version of the payload: restart.
+        if entry.fragments.len() != header.total as usize {
+            *entry = PartialPayload {
+                fragments: vec![None; header.total as usize],
+                received: 0,
+                started: now,
+            };
+        }
+
+        let slot = &mut entry.fragments[header.index as usize];
+        if slot.is_none() {
+            *slot = Some(body.to_vec());
+            entry.received += 1;
+        }
+
+        if entry.received < entry.fragments.len() {
+            return None;
+        }
+        let complete = self.partial.remove(&header.payload_handle)?;
+        let payload = complete.fragments.into_iter().flatten().flatten().collect();
+        Some((header.payload_handle, payload))
+    }
+
+    /// Drops partial assemblies that have waited longer than the timeout.
+    /// Returns how many were discarded.
+    pub fn expire(&mut self, now: Instant) -> usize {
+        let before = self.partial.len();
+        let timeout = self.timeout;
+        self.partial.retain(|_, p| now.saturating_duration_since(p.started) < timeout);
+        before - self.partial.len()
+    }
+
+    /// Number of payloads currently awaiting fragments.
+    pub fn pending(&self) -> usize {
+        self.partial.len()
+    }
+
+    /// Fragment indices of `payload_handle` not received so far; empty if
+    /// no assembly is pending for it.
+    pub fn missing(&self, payload_handle: u32) -> Vec<u16> {
+        self.partial.get(&payload_handle).map_or_else(Vec::new, |p| {
+            (0..p.fragments.len() as u16).filter(|&i| p.fragments[i as usize].is_none()).collect()
+        })
+    }
+}
@@ -0,0 +1,130 @@
+//! # httpx-transport: Versioned Request Framing
+//!
+//! A request datagram is `magic[4] = "HTPX" | version: u8 | path`. The magic
+//! is the one `bpf/xdp-filter` classifies on; the version byte lets the
+//! protocol evolve, with each server accepting a configured set
+//! (`ServerConfig::accepted_versions`) and rejecting the rest.
+//!
+//! From version 2 the header also carries the fields of a
+//! `fragment::FragmentHeader` (`payload_handle: u32 | index: u16 | total: u16`,
+//! big-endian, without the HXFG magic) ahead of the body, so a payload
+//! streamed as several GSO segments is reassembled by the same
+//! `fragment::Reassembler` as HXFG datagrams, lost-fragment timeout
+//! included. Version 1 frames carry no fragment header.
+//!
+//! Datagrams without the magic predate framing and are still served as a
+//! bare request path.
+
+use crate::fragment::{FragmentHeader, FRAGMENT_FIELDS_LEN};
+
+/// Marks a datagram as a framed HTTP-X request ("HTPX").
+pub const HTTPX_MAGIC: [u8; 4] = *b"HTPX";
+
+/// Encoded size of the magic plus the version byte.
+pub const FRAME_HEADER_LEN: usize = 5;
+
+/// Protocol version spoken by this build.
+pub const PROTOCOL_VERSION: u8 = 1;
+
+/// First version whose header carries a `FragmentHeader`.
+pub const FRAGMENTED_VERSION: u8 = 2;
+
+/// Encoded header size of a version 2+ frame.
+pub const FRAGMENTED_HEADER_LEN: usize = FRAME_HEADER_LEN + FRAGMENT_FIELDS_LEN;
+
+/// Why a datagram was not accepted as a request frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProtocolError {
+    /// The datagram does not start with `HTTPX_MAGIC`.
+    BadMagic,
+    /// The magic is present but the version byte is missing.
+    Truncated,
+    /// The frame's version is not in the server's accepted set.
+    UnsupportedVersion(u8),
+    /// The fragment index is not below a non-zero total.
+    InvalidFragment { index: u16, total: u16 },
+}
+
+impl std::fmt::Display for ProtocolError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::BadMagic => write!(f, "not an HTTP-X frame"),
+            Self::Truncated => write!(f, "HTTP-X frame truncated before the version byte"),
+            Self::UnsupportedVersion(v) => write!(f, "unsupported HTTP-X protocol version {}", v),
+            Self::InvalidFragment { index, total } => write!(f, "fragment {} of {} is out of range", index, total),
+        }
+    }
+}
+
+impl std::error::Error for ProtocolError {}
+
+/// A decoded request frame, borrowing its path from the datagram.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HttpxFrame<'a> {
+    pub version: u8,
+    /// Where this frame sits in its payload (version 2+; else `None`).
+    pub fragment: Option<FragmentHeader>,
+    /// The request path, or for a fragment, its slice of the payload.
+    pub path: &'a [u8],
+}
+
+impl<'a> HttpxFrame<'a> {
+    /// A frame for `path` at this build's `PROTOCOL_VERSION`.
+    pub fn new(path: &'a [u8]) -> Self {
+        Self { version: PROTOCOL_VERSION, fragment: None, path }
+    }
+
+    /// The fragment described by `header` carrying `body`, at `FRAGMENTED_VERSION`.
+    pub fn fragment(body: &'a [u8], header: FragmentHeader) -> Self {
+        Self { version: FRAGMENTED_VERSION, fragment: Some(header), path: body }
+    }
+
+    /// Header size for frames of `version`.
+    pub fn header_len(version: u8) -> usize {
+        if version >= FRAGMENTED_VERSION { FRAGMENTED_HEADER_LEN } else { FRAME_HEADER_LEN }
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(Self::header_len(self.version) + self.path.len());
+        self.encode_into(&mut buf);
+        buf
+    }
+
+    /// Appends the encoded frame to `buf`.
+    pub fn encode_into(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&HTTPX_MAGIC);
+        buf.push(self.version);
+        if self.version >= FRAGMENTED_VERSION {
+            let header = self.fragment.unwrap_or(FragmentHeader { payload_handle: 0, index: 0, total: 1 });
+            buf.extend_from_slice(&header.encode_fields());
+        }
+        buf.extend_from_slice(self.path);
+    }
+
+    /// Splits a datagram into its header fields and path, whatever the version.
+    pub fn decode(datagram: &'a [u8]) -> Result<Self, ProtocolError> {
+        if !datagram.starts_with(&HTTPX_MAGIC) {
+            return Err(ProtocolError::BadMagic);
+        }
+        let version = *datagram.get(HTTPX_MAGIC.len()).ok_or(ProtocolError::Truncated)?;
+        if version < FRAGMENTED_VERSION {
+            return Ok(Self { version, fragment: None, path: &datagram[FRAME_HEADER_LEN..] });
+        }
+
+        let fields = datagram.get(FRAME_HEADER_LEN..FRAGMENTED_HEADER_LEN).ok_or(ProtocolError::Truncated)?;
+        let header = FragmentHeader::decode_fields(fields).ok_or_else(|| ProtocolError::InvalidFragment {
+            index: u16::from_be_bytes([fields[4], fields[5]]),
+            total: u16::from_be_bytes([fields[6], fields[7]]),
+        })?;
+        Ok(Self { version, fragment: Some(header), path: &datagram[FRAGMENTED_HEADER_LEN..] })
+    }
+
+    /// `decode`, additionally rejecting versions outside `accepted`.
+    pub fn decode_accepting(datagram: &'a [u8], accepted: &[u8]) -> Result<Self, ProtocolError> {
+        let frame = Self::decode(datagram)?;
+        if !accepted.contains(&frame.version) {
+            return Err(ProtocolError::UnsupportedVersion(frame.version));
+        }
+        Ok(frame)
+    }
+}
@@ -0,0 +1,217 @@
+//! # httpx-transport: HTTP/1.1 Bridge
+//!
+//! A TCP front door for clients that cannot speak HTTP-X (curl, browsers,
+//! load balancers). Each request line is routed through a UDP worker's
+//! `PredictiveEngine`, so route reloads and learned-model swaps reach the
+//! bridge as soon as they reach that worker, and the bound slab payload is
+//! served behind a `HeapTemplate` header block with the Date and
+//! Content-Length fields hot-patched per response.
+//!
+//! Only `GET` and `HEAD` are served; request bodies are not read.
+
+use httpx_codec::HeapTemplate;
+use httpx_core::PredictiveEngine;
+use httpx_dsa::SecureSlab;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Largest request head (request line + headers) the bridge accepts.
+pub const MAX_REQUEST_HEAD: usize = 8192;
+
+/// Base header block, laid out for `HeapTemplate`'s patch offsets: the Date
+/// value at 23 and the 10-digit Content-Length field at 80. `X-Hx` marks
+/// bridged responses and pads Content-Length onto its offset.
+const RESPONSE_HEAD: &[u8] =
+    b"HTTP/1.1 200 OK\r\nDate: Thu, 01 Jan 1970 00:00:00 GMT\r\nX-Hx: h1\r\nContent-Length: 0         \r\n\r\n";
+
+const BAD_REQUEST: &[u8] = b"HTTP/1.1 400 Bad Request\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+const NOT_FOUND: &[u8] = b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n";
+const METHOD_NOT_ALLOWED: &[u8] = b"HTTP/1.1 405 Method Not Allowed\r\nAllow: GET, HEAD\r\nContent-Length: 0\r\n\r\n";
+const HEAD_TOO_LARGE: &[u8] =
+    b"HTTP/1.1 431 Request Header Fields Too Large\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+const STALE: &[u8] = b"HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\n\r\n";
+
+/// Serves trie-routed slab payloads to HTTP/1.1 clients over TCP.
+pub struct Http1Bridge {
+    /// Shared with a worker; each request routes against its active trie.
+    engine: Arc<PredictiveEngine>,
+    slab: Arc<SecureSlab>,
+}
+
+impl Http1Bridge {
+    pub fn new(engine: Arc<PredictiveEngine>, slab: Arc<SecureSlab>) -> Self {
+        Self { engine, slab }
+    }
+
+    /// Accepts connections until this future is dropped or its task aborted,
+    /// serving each on its own task.
+    ///
+    /// Connection tasks live in a `JoinSet` owned by this future, so they are
+    /// aborted with it and keep-alive connections never outlive the server.
+    pub async fn serve(self, listener: TcpListener) {
+        let bridge = Arc::new(self);
+        let mut connections = tokio::task::JoinSet::new();
+        loop {
+            tokio::select! {
+                accepted = listener.accept() => match accepted {
+                    Ok((stream, peer)) => {
+                        let bridge = bridge.clone();
+                        connections.spawn(async move {
+                            if let Err(e) = bridge.serve_connection(stream).await {
+                                tracing::debug!("Http1Bridge: connection from {} failed: {}", peer, e);
+                            }
+                        });
+                    }
+                    Err(e) => tracing::warn!("Http1Bridge: accept failed: {}", e),
+                },
+                // Reap finished connections so the set does not grow unbounded.
+                Some(_) = connections.join_next(), if !connections.is_empty() => {}
+            }
+        }
+    }
+
+    /// Answers requests on one keep-alive connection until the client closes it.
+    async fn serve_connection(&self, mut stream: TcpStream) -> std::io::Result<()> {
+        let mut buf = Vec::with_capacity(1024);
+        loop {
+            let head_len = loop {
+                if let Some(end) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+                    break end + 4;
+                }
+                if buf.len() >= MAX_REQUEST_HEAD {
+                    return stream.write_all(HEAD_TOO_LARGE).await;
+                }
+                let mut chunk = [0u8; 1024];
+                let n = stream.read(&mut chunk).await?;
+                if n == 0 {
+                    return Ok(());
+                }
+                buf.extend_from_slice(&chunk[..n]);
+            };
+
+            let (response, keep_alive) = self.respond(&buf[..head_len]);
+            stream.write_all(&response).await?;
+            if !keep_alive {
+                return Ok(());
+            }
+            buf.drain(..head_len);
+        }
+    }
+
+    /// Builds the response to one request head and whether to keep the
+    /// connection open afterwards.
+    fn respond(&self, head: &[u8]) -> (Vec<u8>, bool) {
+        let Some(request) = parse_request(head) else {
+            return (BAD_REQUEST.to_vec(), false);
+        };
+        let keep_alive = request.keep_alive;
+        let reply = |bytes: &[u8]| (bytes.to_vec(), keep_alive);
+
+        let head_only = match request.method {
+            b"GET" => false,
+            b"HEAD" => true,
+            _ => return reply(METHOD_NOT_ALLOWED),
+        };
+
+        let (handle, version) = match self.engine.lookup(request.path) {
+            Some((handle, version)) if (handle as usize) < self.slab.slots() => (handle as usize, version),
+            _ => return reply(NOT_FOUND),
+        };
+
+        // Freshness Guard, applied on both sides of the copy so a rewrite
+        // racing the read is refused rather than served torn.
+        if self.slab.get_version(handle) != version {
+            return reply(STALE);
+        }
+        let payload_len = self.slab.payload_len(handle);
+        let mut body = vec![0u8; payload_len];
+        if self.slab.read_slot(handle, 0, &mut body).is_err() || self.slab.get_version(handle) != version {
+            return reply(STALE);
+        }
+
+        let mut template = HeapTemplate::new(RESPONSE_HEAD);
+        template.patch_date(&http_date(SystemTime::now()));
+        template.patch_content_length(payload_len as u32);
+
+        let mut response = Vec::with_capacity(RESPONSE_HEAD.len() + payload_len);
+        response.extend_from_slice(&template.as_bytes()[..RESPONSE_HEAD.len()]);
+        if !head_only {
+            response.extend_from_slice(&body);
+        }
+        (response, keep_alive)
+    }
+}
+
+/// The parts of a request head the bridge acts on.
+struct Request<'a> {
+    method: &'a [u8],
+    /// Request target without its query string.
+    path: &'a [u8],
+    keep_alive: bool,
+}
+
+/// Parses `METHOD SP target SP HTTP/1.x CRLF headers CRLF CRLF`.
+fn parse_request(head: &[u8]) -> Option<Request<'_>> {
+    let mut lines = head.split(|&b| b == b'\n').map(|line| line.strip_suffix(b"\r").unwrap_or(line));
+    let mut parts = lines.next()?.split(|&b| b == b' ');
+    let method = parts.next().filter(|m| !m.is_empty())?;
+    let target = parts.next().filter(|t| t.starts_with(b"/"))?;
+    let version = parts.next()?;
+    if parts.next().is_some() || !version.starts_with(b"HTTP/1.") {
+        return None;
+    }
+    let path = target.split(|&b| b == b'?').next().unwrap_or(target);
+
+    // HTTP/1.1 defaults to keep-alive, HTTP/1.0 to close.
+    let mut keep_alive = version == b"HTTP/1.1";
+    for line in lines {
+        let Some(colon) = line.iter().position(|&b| b == b':') else { continue };
+        if line[..colon].eq_ignore_ascii_case(b"connection") {
+            let value = line[colon + 1..].trim_ascii();
+            if value.eq_ignore_ascii_case(b"close") {
+                keep_alive = false;
+            } else if value.eq_ignore_ascii_case(b"keep-alive") {
+                keep_alive = true;
+            }
+        }
+    }
+    Some(Request { method, path, keep_alive })
+}
+
+/// Formats `time` as an RFC 9110 IMF-fixdate, e.g. `Sun, 06 Nov 1994 08:49:37 GMT`.
+fn http_date(time: SystemTime) -> [u8; 29] {
+    // 1970-01-01 was a Thursday.
+    const DAYS: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+    const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+
+    let secs = time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    let days = secs / 86_400;
+    let (hour, minute, second) = (secs % 86_400 / 3600, secs % 3600 / 60, secs % 60);
+
+    // Civil-from-days (Howard Hinnant), shifted to a March-based year.
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+
+    let mut out = [0u8; 29];
+    let text = format!(
+        "{}, {:02} {} {:04} {:02}:{:02}:{:02} GMT",
+        DAYS[(days % 7) as usize],
+        day,
+        MONTHS[(month - 1) as usize],
+        year,
+        hour,
+        minute,
+        second
+    );
+    out.copy_from_slice(text.as_bytes());
+    out
+}
@@ -0,0 +1,29 @@
+//! # httpx-transport: Admin Inspection
+//!
+//! One-shot, read-only snapshots of a running server for on-call debugging.
+
+use httpx_cluster::ClusterMode;
+use httpx_core::WorkerSnapshot;
+use httpx_dsa::trie::TrieRoute;
+
+/// The observable state of a single `SecureSlab` slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlotState {
+    pub ref_count: usize,
+    pub version: u32,
+    pub in_flight: bool,
+}
+
+/// A full snapshot of a running `HttpxServer`.
+#[derive(Debug, Clone)]
+pub struct InspectReport {
+    /// Payload-bearing routes in the active trie (as seen by the first worker).
+    pub routes: Vec<TrieRoute>,
+    /// Digest of the active trie (as seen by the first worker).
+    pub trie_digest: u64,
+    /// Per-slot RC, version and in-flight status, indexed by slot.
+    pub slots: Vec<SlotState>,
+    /// Per-worker snapshots, ordered by core id.
+    pub workers: Vec<WorkerSnapshot>,
+    pub cluster_mode: ClusterMode,
+}
@@ -4,7 +4,23 @@ pub mod dispatcher;
 pub mod reliability;
 pub use httpx_core::bridge;
 pub mod stream;
+pub mod fragment;
+pub mod frame;
+pub mod inspect;
+pub mod xsk;
+pub mod reload;
+pub mod http1;
+pub mod replay;
+pub mod error;
 
-pub use server::HttpxServer;
+pub use server::{HttpxServer, ServerHandle};
+pub use inspect::{InspectReport, SlotState};
+pub use fragment::{FragmentHeader, Reassembler};
+pub use frame::{HttpxFrame, ProtocolError};
 pub use dispatcher::CoreDispatcher;
-pub use reliability::{CongestionController, DefaultCongestionController};
+pub use xsk::XskSocket;
+pub use reload::{RouteError, RouteWatcher};
+pub use http1::Http1Bridge;
+pub use replay::{DispatchRecorder, RecordedPacket, ReplayDispatcher, ReplayEvent, ReplayReport};
+pub use error::TransportError;
+pub use reliability::{CongestionController, CreditPolicy, DefaultCongestionController, Pacer, DEFAULT_BASE_RTT_NANOS};
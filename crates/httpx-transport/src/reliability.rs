@@ -13,27 +13,157 @@ pub trait CongestionController: Send + Sync {
 
     /// Called when a packet is lost. Triggers immediate speculative backoff.
     fn notify_loss(&self);
+
+    /// The credit level currently in force, without taking a new RTT sample.
+    fn credit_level(&self) -> u8 {
+        2
+    }
+
+    /// Egress pacing rate in bytes/sec derived from the bandwidth estimate.
+    /// Returns `None` if the controller does not pace.
+    fn pacing_rate(&self) -> Option<u64> {
+        None
+    }
+
+    /// Recalibrates the baseline RTT that backoff thresholds are relative to.
+    /// Controllers without a fixed baseline ignore it.
+    fn set_base_rtt(&self, _nanos: u64) {}
+}
+
+/// A monotonic token-bucket pacer (GCRA formulation).
+///
+/// ## Performance Guarantee
+/// `reserve` is a handful of integer ops and a single `max`; no branches
+/// on the common path and no allocation.
+pub struct Pacer {
+    origin: std::time::Instant,
+    rate_bytes_per_sec: u64,
+    /// Theoretical arrival time of the next byte, in nanos since `origin`.
+    tat_nanos: u64,
+    /// How far ahead of schedule a burst may run before it is delayed.
+    burst_nanos: u64,
+}
+
+impl Pacer {
+    pub fn new(rate_bytes_per_sec: u64) -> Self {
+        Self {
+            origin: std::time::Instant::now(),
+            rate_bytes_per_sec: rate_bytes_per_sec.max(1),
+            tat_nanos: 0,
+            burst_nanos: 0,
+        }
+    }
+
+    /// Allows the sender to run up to `burst_bytes` ahead of the paced schedule.
+    pub fn with_burst(mut self, burst_bytes: u64) -> Self {
+        self.burst_nanos = self.cost_nanos(burst_bytes);
+        self
+    }
+
+    pub fn rate(&self) -> u64 {
+        self.rate_bytes_per_sec
+    }
+
+    /// Updates the pacing rate (e.g. from a fresh bandwidth estimate).
+    pub fn set_rate(&mut self, rate_bytes_per_sec: u64) {
+        self.rate_bytes_per_sec = rate_bytes_per_sec.max(1);
+    }
+
+    /// Reserves `bytes` of egress and returns how long the caller must wait
+    /// before sending them.
+    #[inline]
+    pub fn reserve(&mut self, bytes: usize) -> std::time::Duration {
+        let now = self.origin.elapsed().as_nanos() as u64;
+        let tat = self.tat_nanos.max(now);
+        let delay = tat.saturating_sub(now + self.burst_nanos);
+        self.tat_nanos = tat + self.cost_nanos(bytes as u64);
+        std::time::Duration::from_nanos(delay)
+    }
+
+    #[inline(always)]
+    fn cost_nanos(&self, bytes: u64) -> u64 {
+        ((bytes as u128 * 1_000_000_000) / self.rate_bytes_per_sec as u128) as u64
+    }
 }
 
+/// GSO super-packet ceiling for a credit level.
+///
+/// Level 2 packs the full `MAX_BATCH_BYTES`, Level 1 a quarter of it (a
+/// single loss then costs a quarter as much), Level 0 nothing.
+#[inline(always)]
+pub fn gso_batch_limit(level: u8) -> usize {
+    match level {
+        0 => 0,
+        1 => crate::stream::MAX_BATCH_BYTES / 4,
+        _ => crate::stream::MAX_BATCH_BYTES,
+    }
+}
+
+/// Upper bound on bursts a single matched context may fan out to.
+pub const MAX_PUSH_FANOUT: usize = 8;
+
+/// Maps a credit level to how many predicted intents may fire.
+///
+/// Defaults: Level 0 pushes nothing, Level 1 only the single strongest
+/// prediction, Level 2 the top-K (K = 3).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CreditPolicy {
+    pushes: [usize; 3],
+}
+
+impl CreditPolicy {
+    /// Level 2 fans out to `top_k` pushes (capped at `MAX_PUSH_FANOUT`).
+    pub fn new(top_k: usize) -> Self {
+        Self { pushes: [0, 1, top_k.min(MAX_PUSH_FANOUT)] }
+    }
+
+    /// Overrides the push budget of a single level.
+    pub fn with_level(mut self, level: u8, pushes: usize) -> Self {
+        self.pushes[(level as usize).min(2)] = pushes.min(MAX_PUSH_FANOUT);
+        self
+    }
+
+    /// Number of bursts permitted at `level`; levels above 2 are treated as 2.
+    #[inline(always)]
+    pub fn max_pushes_for_level(&self, level: u8) -> usize {
+        self.pushes[(level as usize).min(2)]
+    }
+}
+
+impl Default for CreditPolicy {
+    fn default() -> Self {
+        Self::new(3)
+    }
+}
+
+/// Baseline RTT the server's workers start from until `ControlSignal::SetBaseRtt`
+/// recalibrates them: 1ms, generous for a burst's submit-to-completion time.
+pub const DEFAULT_BASE_RTT_NANOS: u64 = 1_000_000;
+
 pub struct DefaultCongestionController {
-    base_rtt: u64,
+    base_rtt: std::sync::atomic::AtomicU64,
     active_level: std::sync::atomic::AtomicU8,
 }
 
 impl DefaultCongestionController {
     pub fn new(base_rtt_nanos: u64) -> Self {
         Self {
-            base_rtt: base_rtt_nanos,
+            base_rtt: std::sync::atomic::AtomicU64::new(base_rtt_nanos),
             active_level: std::sync::atomic::AtomicU8::new(2),
         }
     }
+
+    /// The baseline RTT in nanoseconds; backoff triggers above 1.2x this.
+    pub fn base_rtt(&self) -> u64 {
+        self.base_rtt.load(std::sync::atomic::Ordering::Relaxed)
+    }
 }
 
 impl CongestionController for DefaultCongestionController {
     fn evaluate_intent_credit(&self, current_rtt: u64) -> u8 {
         // Multi-Level Credit System Logic
         // If current RTT > 1.2 * base_rtt, back off to Level 0.
-        if current_rtt > (self.base_rtt * 12) / 10 {
+        if current_rtt > (self.base_rtt() * 12) / 10 {
             self.active_level.store(0, std::sync::atomic::Ordering::Relaxed);
             0
         } else {
@@ -41,8 +171,17 @@ impl CongestionController for DefaultCongestionController {
         }
     }
 
+    fn credit_level(&self) -> u8 {
+        self.active_level.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
     fn notify_loss(&self) {
         // Immediate Zero-Allocation speculative backoff
         self.active_level.store(0, std::sync::atomic::Ordering::SeqCst);
     }
+
+    /// Moves the 1.2x threshold to track a path whose min-RTT has drifted.
+    fn set_base_rtt(&self, nanos: u64) {
+        self.base_rtt.store(nanos, std::sync::atomic::Ordering::Relaxed);
+    }
 }
@@ -0,0 +1,152 @@
+//! # httpx-transport: Hot Route Reloading
+//!
+//! Routes can be driven by a TOML file instead of being burned in at build
+//! time. A `RouteWatcher` observes the file and, on every change, rebuilds
+//! the trie through `ResourceRegistry` and hands it to the
+//! `ClusterOrchestrator`, which makes it the new base routes and swaps them
+//! onto its learned weights with the epoch-safe Shadow-Swap. A file that
+//! fails to read or parse is rejected and the running routes stay in place.
+//!
+//! ```toml
+//! [[route]]
+//! path = "/api/v1/hello"
+//! handle = 1
+//! version = 1
+//! ```
+
+use httpx_core::ResourceRegistry;
+use httpx_dsa::LinearIntentTrie;
+use notify::{RecursiveMode, Watcher};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+/// One `[[route]]` table of a routes file.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RouteEntry {
+    pub path: String,
+    pub handle: u32,
+    pub version: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct RoutesFile {
+    #[serde(default)]
+    route: Vec<RouteEntry>,
+}
+
+/// Why a routes file was rejected.
+#[derive(Debug)]
+pub enum RouteError {
+    Io(std::io::Error),
+    Parse(toml::de::Error),
+    /// A route is deeper than the trie's depth cap.
+    Route(httpx_dsa::TrieError),
+}
+
+impl std::fmt::Display for RouteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "routes file unreadable: {}", err),
+            Self::Parse(err) => write!(f, "routes file invalid: {}", err),
+            Self::Route(err) => write!(f, "routes file rejected: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for RouteError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Parse(err) => Some(err),
+            Self::Route(err) => Some(err),
+        }
+    }
+}
+
+/// Builds a warmed trie from the contents of a routes file.
+pub fn parse_routes(src: &str) -> Result<LinearIntentTrie, RouteError> {
+    let file: RoutesFile = toml::from_str(src).map_err(RouteError::Parse)?;
+    let mut registry = ResourceRegistry::new();
+    for entry in &file.route {
+        registry.try_route(&entry.path, entry.handle, entry.version).map_err(RouteError::Route)?;
+    }
+    Ok(registry.take_trie())
+}
+
+/// Reads and parses a routes file.
+pub fn load_routes(path: &Path) -> Result<LinearIntentTrie, RouteError> {
+    let src = std::fs::read_to_string(path).map_err(RouteError::Io)?;
+    parse_routes(&src)
+}
+
+/// Keeps a routes file under watch; dropping it stops reloading.
+pub struct RouteWatcher {
+    _watcher: notify::RecommendedWatcher,
+    applied: Arc<AtomicU64>,
+    rejected: Arc<AtomicU64>,
+}
+
+impl RouteWatcher {
+    /// Watches `path` and sends each successfully parsed revision to
+    /// `routes_tx` (see `ClusterOrchestrator::with_route_updates`).
+    ///
+    /// The parent directory is watched rather than the file itself, so
+    /// editors that save by rename are picked up too. Must be called from
+    /// within a Tokio runtime (the reload task is spawned on it).
+    pub fn spawn(path: &Path, routes_tx: mpsc::Sender<LinearIntentTrie>) -> notify::Result<Self> {
+        let path: PathBuf = std::path::absolute(path)?;
+        let dir = path.parent().map(Path::to_path_buf).unwrap_or_else(|| PathBuf::from("/"));
+        let applied = Arc::new(AtomicU64::new(0));
+        let rejected = Arc::new(AtomicU64::new(0));
+
+        let (change_tx, mut change_rx) = mpsc::unbounded_channel::<()>();
+        let file = path.clone();
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+            if let Ok(event) = res {
+                let touches_file = event.paths.iter().any(|p| p == &file);
+                if touches_file && (event.kind.is_create() || event.kind.is_modify()) {
+                    let _ = change_tx.send(());
+                }
+            }
+        })?;
+        watcher.watch(&dir, RecursiveMode::NonRecursive)?;
+
+        let (applied_ctr, rejected_ctr) = (applied.clone(), rejected.clone());
+        tokio::spawn(async move {
+            while change_rx.recv().await.is_some() {
+                // Coalesce a burst of events from one save into one reload.
+                while change_rx.try_recv().is_ok() {}
+
+                match load_routes(&path) {
+                    Ok(trie) => {
+                        if routes_tx.send(trie).await.is_err() {
+                            tracing::warn!("RouteWatcher: orchestrator gone, stopping");
+                            return;
+                        }
+                        let revision = applied_ctr.fetch_add(1, Ordering::AcqRel) + 1;
+                        tracing::info!("RouteWatcher: applied {} (revision {})", path.display(), revision);
+                    }
+                    Err(e) => {
+                        rejected_ctr.fetch_add(1, Ordering::AcqRel);
+                        tracing::warn!("RouteWatcher: keeping current routes, {}", e);
+                    }
+                }
+            }
+        });
+
+        Ok(Self { _watcher: watcher, applied, rejected })
+    }
+
+    /// Number of file revisions handed to the orchestrator.
+    pub fn applied(&self) -> u64 {
+        self.applied.load(Ordering::Acquire)
+    }
+
+    /// Number of file revisions rejected as unreadable or invalid.
+    pub fn rejected(&self) -> u64 {
+        self.rejected.load(Ordering::Acquire)
+    }
+}
@@ -0,0 +1,357 @@
+//! # httpx-transport: Deterministic Replay
+//!
+//! A dispatcher's push decisions depend on live state: the trie it was last
+//! swapped to, the credit level at the time, the session mode. A
+//! `DispatchRecorder` attached with `CoreDispatcher::with_recorder` logs
+//! that state and every inbound packet to a file, together with the pushes
+//! the packet actually triggered. `ReplayDispatcher` re-drives the log on a
+//! single thread with a mocked clock and reports any packet whose replayed
+//! decision differs from the recorded one.
+//!
+//! ## Log format
+//! `HXRP` followed by records of `[tag: u8] [len: u32 LE] [body]`; tries are
+//! embedded as `LinearIntentTrie::to_bytes` blobs. Learning events sent to
+//! the orchestrator are not recorded; their effect arrives as trie swaps.
+//!
+//! ## Dependencies
+//! Replay requires the `HXT1` trie codec (`LinearIntentTrie::to_bytes` /
+//! `from_bytes` in `httpx-dsa`). A log is only readable by builds that
+//! still accept the trie magic it embeds.
+
+use crate::dispatcher::{packet_path, resolve_pushes};
+use crate::reliability::MAX_PUSH_FANOUT;
+use httpx_core::{PredictiveEngine, ServerConfig, SessionMode};
+use httpx_dsa::LinearIntentTrie;
+use std::fs::File;
+use std::io::{self, BufWriter, Read, Write};
+use std::net::{IpAddr, SocketAddr};
+use std::path::Path;
+use std::time::Instant;
+
+const LOG_MAGIC: &[u8; 4] = b"HXRP";
+
+const TAG_START: u8 = 1;
+const TAG_PACKET: u8 = 2;
+const TAG_SWAP_TRIE: u8 = 3;
+const TAG_SESSION_MODE: u8 = 4;
+
+/// An inbound packet and the decision the live dispatcher made for it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedPacket {
+    /// Nanoseconds since recording started.
+    pub at_nanos: u64,
+    pub src: SocketAddr,
+    /// The datagram as received, before frame decoding.
+    pub data: Vec<u8>,
+    pub credit_level: u8,
+    /// Pushes the credit level allowed (0 while draining).
+    pub fanout: u8,
+    /// `(payload_handle, version)` pushes the packet triggered.
+    pub pushes: Vec<(u32, u32)>,
+}
+
+/// One entry of a replay log.
+#[derive(Debug, Clone)]
+pub enum ReplayEvent {
+    /// Dispatcher state when recording began.
+    Start {
+        max_intent_credits: u32,
+        predictive_depth: usize,
+        accepted_versions: Vec<u8>,
+        session_mode: SessionMode,
+        trie: LinearIntentTrie,
+    },
+    Packet(RecordedPacket),
+    /// The active trie was replaced (`SwapTrie` or `ResetLearning`).
+    SwapTrie(LinearIntentTrie),
+    SetSessionMode(SessionMode),
+}
+
+/// Appends `ReplayEvent`s to a log file.
+///
+/// Every event is flushed as it is written, so the log survives a crash of
+/// the process being debugged; expect a syscall per packet while recording.
+pub struct DispatchRecorder {
+    writer: BufWriter<File>,
+    start: Instant,
+}
+
+impl DispatchRecorder {
+    /// Creates (or truncates) the log at `path`.
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(LOG_MAGIC)?;
+        Ok(Self { writer, start: Instant::now() })
+    }
+
+    /// Nanoseconds since the log was created; the timestamp of new packets.
+    pub fn elapsed_nanos(&self) -> u64 {
+        self.start.elapsed().as_nanos() as u64
+    }
+
+    /// Appends one event.
+    pub fn record(&mut self, event: &ReplayEvent) -> io::Result<()> {
+        let (tag, body) = encode_event(event);
+        self.writer.write_all(&[tag])?;
+        self.writer.write_all(&(body.len() as u32).to_le_bytes())?;
+        self.writer.write_all(&body)?;
+        self.writer.flush()
+    }
+}
+
+/// Reads every event of a log written by `DispatchRecorder`.
+pub fn read_log(path: impl AsRef<Path>) -> io::Result<Vec<ReplayEvent>> {
+    let mut bytes = Vec::new();
+    File::open(path)?.read_to_end(&mut bytes)?;
+    if !bytes.starts_with(LOG_MAGIC) {
+        return Err(invalid("not a replay log"));
+    }
+
+    let mut events = Vec::new();
+    let mut rest = &bytes[LOG_MAGIC.len()..];
+    while !rest.is_empty() {
+        if rest.len() < 5 {
+            return Err(invalid("truncated record header"));
+        }
+        let tag = rest[0];
+        let len = u32::from_le_bytes(rest[1..5].try_into().unwrap()) as usize;
+        let body = rest.get(5..5 + len).ok_or_else(|| invalid("truncated record"))?;
+        events.push(decode_event(tag, body)?);
+        rest = &rest[5 + len..];
+    }
+    Ok(events)
+}
+
+/// Outcome of replaying a log.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReplayReport {
+    /// Replayed pushes, one entry per recorded packet.
+    pub decisions: Vec<Vec<(u32, u32)>>,
+    /// Indices (into `decisions`) of packets whose replayed pushes differ
+    /// from the recorded ones.
+    pub divergences: Vec<usize>,
+}
+
+/// Re-drives a replay log deterministically: one thread, no sockets, and a
+/// clock that only advances to each packet's recorded timestamp.
+///
+/// Push decisions go through the same resolution as `CoreDispatcher::on_packet`.
+pub struct ReplayDispatcher {
+    engine: PredictiveEngine,
+    config: ServerConfig,
+    session_mode: SessionMode,
+    now_nanos: u64,
+}
+
+impl ReplayDispatcher {
+    /// A dispatcher with default settings; a `Start` event overrides them.
+    pub fn new() -> Self {
+        let config = ServerConfig::default();
+        Self {
+            engine: PredictiveEngine::new(true).with_predictive_depth(config.predictive_depth),
+            config,
+            session_mode: SessionMode::ClusterIntegrated,
+            now_nanos: 0,
+        }
+    }
+
+    /// Current mocked time, in nanoseconds since recording started.
+    pub fn now_nanos(&self) -> u64 {
+        self.now_nanos
+    }
+
+    /// Applies one event; returns the replayed pushes if it was a packet.
+    pub fn apply(&mut self, event: &ReplayEvent) -> Option<Vec<(u32, u32)>> {
+        match event {
+            ReplayEvent::Start { max_intent_credits, predictive_depth, accepted_versions, session_mode, trie } => {
+                self.config.max_intent_credits = *max_intent_credits;
+                self.config.predictive_depth = *predictive_depth;
+                self.config.accepted_versions = accepted_versions.clone();
+                self.engine = PredictiveEngine::new(true).with_predictive_depth(*predictive_depth);
+                self.engine.swap_weights(trie.clone());
+                self.session_mode = *session_mode;
+                None
+            }
+            ReplayEvent::SwapTrie(trie) => {
+                self.engine.swap_weights(trie.clone());
+                None
+            }
+            ReplayEvent::SetSessionMode(mode) => {
+                self.session_mode = *mode;
+                None
+            }
+            ReplayEvent::Packet(packet) => {
+                self.now_nanos = self.now_nanos.max(packet.at_nanos);
+                let Ok(path) = packet_path(&packet.data, &self.config.accepted_versions) else {
+                    return Some(Vec::new());
+                };
+                let mut targets = [(0u32, 0u32); MAX_PUSH_FANOUT];
+                let fanout = (packet.fanout as usize).min(MAX_PUSH_FANOUT);
+                let n = resolve_pushes(&self.engine, &self.config, self.session_mode, packet.src, path, &mut targets[..fanout]);
+                Some(targets[..n].to_vec())
+            }
+        }
+    }
+
+    /// Replays `events` in order and compares each decision to the recording.
+    pub fn replay(&mut self, events: &[ReplayEvent]) -> ReplayReport {
+        let mut report = ReplayReport::default();
+        for event in events {
+            if let Some(pushes) = self.apply(event) {
+                if let ReplayEvent::Packet(packet) = event {
+                    if pushes != packet.pushes {
+                        tracing::warn!(
+                            "Replay diverged at packet {} (t={}ns): recorded {:?}, replayed {:?}",
+                            report.decisions.len(), packet.at_nanos, packet.pushes, pushes
+                        );
+                        report.divergences.push(report.decisions.len());
+                    }
+                }
+                report.decisions.push(pushes);
+            }
+        }
+        report
+    }
+}
+
+impl Default for ReplayDispatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("replay log: {}", msg))
+}
+
+fn mode_byte(mode: SessionMode) -> u8 {
+    match mode {
+        SessionMode::ClusterIntegrated => 0,
+        SessionMode::SovereignAutonomous => 1,
+    }
+}
+
+fn mode_from_byte(raw: u8) -> io::Result<SessionMode> {
+    match raw {
+        0 => Ok(SessionMode::ClusterIntegrated),
+        1 => Ok(SessionMode::SovereignAutonomous),
+        _ => Err(invalid("unknown session mode")),
+    }
+}
+
+fn encode_event(event: &ReplayEvent) -> (u8, Vec<u8>) {
+    let mut body = Vec::new();
+    let tag = match event {
+        ReplayEvent::Start { max_intent_credits, predictive_depth, accepted_versions, session_mode, trie } => {
+            body.extend_from_slice(&max_intent_credits.to_le_bytes());
+            body.extend_from_slice(&(*predictive_depth as u64).to_le_bytes());
+            body.push(mode_byte(*session_mode));
+            body.push(accepted_versions.len() as u8);
+            body.extend_from_slice(accepted_versions);
+            body.extend_from_slice(&trie.to_bytes());
+            TAG_START
+        }
+        ReplayEvent::Packet(packet) => {
+            body.extend_from_slice(&packet.at_nanos.to_le_bytes());
+            match packet.src.ip() {
+                IpAddr::V4(ip) => {
+                    body.push(4);
+                    body.extend_from_slice(&ip.octets());
+                }
+                IpAddr::V6(ip) => {
+                    body.push(6);
+                    body.extend_from_slice(&ip.octets());
+                }
+            }
+            body.extend_from_slice(&packet.src.port().to_le_bytes());
+            body.push(packet.credit_level);
+            body.push(packet.fanout);
+            body.push(packet.pushes.len() as u8);
+            for &(handle, version) in &packet.pushes {
+                body.extend_from_slice(&handle.to_le_bytes());
+                body.extend_from_slice(&version.to_le_bytes());
+            }
+            body.extend_from_slice(&packet.data);
+            TAG_PACKET
+        }
+        ReplayEvent::SwapTrie(trie) => {
+            body.extend_from_slice(&trie.to_bytes());
+            TAG_SWAP_TRIE
+        }
+        ReplayEvent::SetSessionMode(mode) => {
+            body.push(mode_byte(*mode));
+            TAG_SESSION_MODE
+        }
+    };
+    (tag, body)
+}
+
+/// Cursor over a record body; every read fails cleanly on truncation.
+struct Body<'a>(&'a [u8]);
+
+impl<'a> Body<'a> {
+    fn take(&mut self, n: usize) -> io::Result<&'a [u8]> {
+        if self.0.len() < n {
+            return Err(invalid("truncated record body"));
+        }
+        let (head, rest) = self.0.split_at(n);
+        self.0 = rest;
+        Ok(head)
+    }
+
+    fn u8(&mut self) -> io::Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> io::Result<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> io::Result<u64> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn trie(self) -> io::Result<LinearIntentTrie> {
+        LinearIntentTrie::from_bytes(self.0).map_err(|e| invalid(&e.to_string()))
+    }
+}
+
+fn decode_event(tag: u8, body: &[u8]) -> io::Result<ReplayEvent> {
+    let mut body = Body(body);
+    match tag {
+        TAG_START => {
+            let max_intent_credits = body.u32()?;
+            let predictive_depth = body.u64()? as usize;
+            let session_mode = mode_from_byte(body.u8()?)?;
+            let versions = body.u8()? as usize;
+            let accepted_versions = body.take(versions)?.to_vec();
+            let trie = body.trie()?;
+            Ok(ReplayEvent::Start { max_intent_credits, predictive_depth, accepted_versions, session_mode, trie })
+        }
+        TAG_PACKET => {
+            let at_nanos = body.u64()?;
+            let ip = match body.u8()? {
+                4 => IpAddr::from(<[u8; 4]>::try_from(body.take(4)?).unwrap()),
+                6 => IpAddr::from(<[u8; 16]>::try_from(body.take(16)?).unwrap()),
+                _ => return Err(invalid("unknown address family")),
+            };
+            let port = u16::from_le_bytes(body.take(2)?.try_into().unwrap());
+            let credit_level = body.u8()?;
+            let fanout = body.u8()?;
+            let pushes = (0..body.u8()?)
+                .map(|_| Ok((body.u32()?, body.u32()?)))
+                .collect::<io::Result<Vec<_>>>()?;
+            Ok(ReplayEvent::Packet(RecordedPacket {
+                at_nanos,
+                src: SocketAddr::new(ip, port),
+                data: body.0.to_vec(),
+                credit_level,
+                fanout,
+                pushes,
+            }))
+        }
+        TAG_SWAP_TRIE => Ok(ReplayEvent::SwapTrie(body.trie()?)),
+        TAG_SESSION_MODE => Ok(ReplayEvent::SetSessionMode(mode_from_byte(body.u8()?)?)),
+        _ => Err(invalid("unknown record tag")),
+    }
+}
@@ -1,27 +1,60 @@
-use crate::dispatcher::CoreDispatcher;
-use httpx_core::ControlSignal;
+use crate::dispatcher::{CoreDispatcher, BURST_PREFIX_LEN};
+use crate::http1::Http1Bridge;
+use crate::inspect::{InspectReport, SlotState};
+use crate::reliability::{DefaultCongestionController, DEFAULT_BASE_RTT_NANOS};
+use crate::reload::RouteWatcher;
+use httpx_cluster::ClusterMode;
+use httpx_core::{ControlSignal, DispatcherStats, HttpXError, LatencyPercentiles, StatsSnapshot, Watchdog};
 use std::net::SocketAddr;
-use httpx_core::ServerConfig;
+use httpx_core::{RuntimeFlavor, ServerConfig};
 use socket2::{Socket, Domain, Type, Protocol};
 use io_uring::IoUring;
 use std::os::unix::io::AsRawFd;
+use std::time::Duration;
+
+/// How long `ServerHandle::shutdown` lets workers drain on a shutdown signal.
+pub const SHUTDOWN_GRACE: Duration = Duration::from_secs(5);
+
+/// Heartbeat age past which the watchdog reports a worker as stalled.
+pub const WATCHDOG_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How long `HttpxServer::self_test` waits for its probe burst to complete.
+pub const SELF_TEST_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// How long `ServerHandle::inspect` waits for workers to answer.
+pub const INSPECT_TIMEOUT: Duration = Duration::from_secs(1);
 
 pub struct HttpxServer {
-    addr: SocketAddr,
+    addrs: Vec<SocketAddr>,
     config: ServerConfig,
     predictive_mode: bool,
     trie: Option<httpx_dsa::LinearIntentTrie>,
     slab: Option<std::sync::Arc<httpx_dsa::SecureSlab>>,
+    routes_file: Option<std::path::PathBuf>,
+    signal_shutdown: bool,
+    http1_addr: Option<SocketAddr>,
 }
 
 impl HttpxServer {
     pub fn listen(addr: &str) -> Self {
+        Self::listen_many(&[addr])
+    }
+
+    /// Listens on several addresses at once.
+    ///
+    /// A full SO_REUSEPORT worker set is spawned per address; all workers share
+    /// the same trie, slab and orchestrator.
+    pub fn listen_many(addrs: &[&str]) -> Self {
+        assert!(!addrs.is_empty(), "HttpxServer: at least one bind address is required");
         Self {
-            addr: addr.parse().expect("Invalid address"),
+            addrs: addrs.iter().map(|a| a.parse().expect("Invalid address")).collect(),
             config: ServerConfig::default(),
             predictive_mode: false,
             trie: None,
             slab: None,
+            routes_file: None,
+            signal_shutdown: false,
+            http1_addr: None,
         }
     }
 
@@ -51,25 +84,222 @@ impl HttpxServer {
         self
     }
 
+    /// Loads routes from a TOML routes file and hot-reloads them on change.
+    ///
+    /// Takes precedence over `with_trie`. See `reload` for the file format.
+    pub fn with_routes_file(mut self, path: impl Into<std::path::PathBuf>) -> Self {
+        self.routes_file = Some(path.into());
+        self
+    }
+
+    /// Makes `start` return on SIGTERM/SIGINT after a graceful shutdown.
+    ///
+    /// Workers are drained, then killed if still busy after `SHUTDOWN_GRACE`,
+    /// and joined; see `ServerHandle::shutdown`.
+    pub fn with_signal_shutdown(mut self) -> Self {
+        self.signal_shutdown = true;
+        self
+    }
+
+    /// Also serves the routes to plain HTTP/1.1 clients on a TCP `addr`.
+    ///
+    /// See `http1::Http1Bridge`; the bound address (useful with port 0) is
+    /// reported by `ServerHandle::http1_addr`.
+    pub fn with_http1_bridge(mut self, addr: &str) -> Self {
+        self.http1_addr = Some(addr.parse().expect("Invalid address"));
+        self
+    }
+
     /// Starts the HTTP-X Server Swarm with Mechanical Sympathy.
+    ///
+    /// Runs forever unless `with_signal_shutdown` is set.
     pub async fn start(self) -> Result<(), Box<dyn std::error::Error>> {
-        tracing::info!("Initializing HTTP-X Sovereign Swarm on {}", self.addr);
+        if !self.signal_shutdown {
+            let _handle = self.spawn().await?;
+
+            // Keep the swarm alive
+            std::future::pending::<()>().await;
+            return Ok(());
+        }
+
+        use tokio::signal::unix::{signal, SignalKind};
+        // Installed before the swarm exists, so no early signal is lost.
+        let mut sigterm = signal(SignalKind::terminate())?;
+        let mut sigint = signal(SignalKind::interrupt())?;
+        let handle = self.spawn().await?;
+
+        tokio::select! {
+            _ = sigterm.recv() => tracing::warn!("SIGTERM received; shutting down."),
+            _ = sigint.recv() => tracing::warn!("SIGINT received; shutting down."),
+        }
+        handle.shutdown(SHUTDOWN_GRACE).await;
+        Ok(())
+    }
+
+    /// Pushes one synthetic request through the real data path before serving.
+    ///
+    /// Runs against a private loopback socket and binds nothing on the
+    /// configured addresses. Each stage fails with `HttpXError::SelfTest`
+    /// naming it:
+    /// 1. `slab`: a free slot takes a probe header and reads it back.
+    /// 2. `route`: every route's handle holds its expected version, and the
+    ///    first route resolves through a `PredictiveEngine`.
+    /// 3. `burst`: a dispatcher registers the slab and submits that route's
+    ///    payload behind the probe header.
+    /// 4. `completion`: the burst arrives intact and its RCs are reaped.
+    ///
+    /// Under `PathEncoding::Hashed` routes cannot be turned back into request
+    /// paths, so the engine lookup in stage 2 is skipped.
+    pub async fn self_test(&self) -> Result<(), HttpXError> {
+        let slab = self.slab.clone().unwrap_or_else(|| {
+            std::sync::Arc::new(httpx_dsa::SecureSlab::new(self.config.slab_capacity))
+        });
+        let trie = match &self.routes_file {
+            Some(path) => crate::reload::load_routes(path).map_err(|e| stage_failed("route", e))?,
+            None => self.trie.clone().unwrap_or_else(|| httpx_dsa::LinearIntentTrie::new(1024)),
+        };
+
+        // Stage 1: the slab accepts writes where the kernel will read them.
+        let probe = slab.allocate_slot().map_err(|e| stage_failed("slab", e))?;
+        let result = self.self_test_with(&slab, trie, probe).await;
+        if !slab.is_in_flight(probe) {
+            slab.free_slot(probe);
+        }
+        result
+    }
+
+    /// Stages 1-4 of `self_test`, using the already claimed `probe` slot.
+    async fn self_test_with(
+        &self,
+        slab: &httpx_dsa::SecureSlab,
+        trie: httpx_dsa::LinearIntentTrie,
+        probe: usize,
+    ) -> Result<(), HttpXError> {
+        let header: Vec<u8> = (0..128u8).collect();
+        slab.write_slot(probe, 0, &header).map_err(|e| stage_failed("slab", e))?;
+        let mut readback = [0u8; 128];
+        slab.read_slot(probe, 0, &mut readback).map_err(|e| stage_failed("slab", e))?;
+        if readback[..] != header[..] {
+            return Err(stage_failed("slab", format!("slot {} did not read back what was written", probe)));
+        }
+
+        // Stage 2: every binding is live, and a request reaches one of them.
+        let routes = trie.routes();
+        let Some(first) = routes.first() else {
+            return Err(stage_failed("route", "no routes are registered"));
+        };
+        for route in &routes {
+            let handle = route.payload_handle as usize;
+            let path = String::from_utf8_lossy(&route.path);
+            if handle >= slab.slots() {
+                return Err(stage_failed("route", format!("{} is bound to handle {} outside the {}-slot slab", path, handle, slab.slots())));
+            }
+            let version = slab.get_version(handle);
+            if version != route.version_id {
+                return Err(stage_failed(
+                    "route",
+                    format!("{} expects version {} but handle {} holds version {}", path, route.version_id, handle, version),
+                ));
+            }
+        }
+        let (handle, version) = (first.payload_handle, first.version_id);
+        if trie.path_encoding() == httpx_dsa::PathEncoding::Identity {
+            let engine = httpx_core::PredictiveEngine::new(true);
+            engine.swap_weights(trie.clone());
+            let resolved = engine.lookup(&first.path);
+            if resolved != Some((handle, version)) {
+                return Err(stage_failed(
+                    "route",
+                    format!("{} resolved to {:?} instead of {:?}", String::from_utf8_lossy(&first.path), resolved, (handle, version)),
+                ));
+            }
+        }
+
+        // Stage 3: a real dispatcher sends the payload over loopback.
+        let receiver = tokio::net::UdpSocket::bind("127.0.0.1:0").await.map_err(|e| stage_failed("burst", e))?;
+        let target = receiver.local_addr().map_err(|e| stage_failed("burst", e))?;
+        let socket = tokio::net::UdpSocket::bind("127.0.0.1:0").await.map_err(|e| stage_failed("burst", e))?;
+        socket.connect(target).await.map_err(|e| stage_failed("burst", e))?;
+        let (_control_tx, control_rx) = tokio::sync::mpsc::channel(1);
+        let (learn_tx, _learn_rx) = httpx_core::learn_channel(1);
+        let mut dispatcher = CoreDispatcher::new_with_socket(0, socket, control_rx, self.config.clone(), trie, learn_tx)
+            .await
+            .map_err(|e| stage_failed("burst", e))?;
+        dispatcher.register_slab(slab).map_err(|e| stage_failed("burst", e))?;
+        dispatcher
+            .submit_linked_burst(target, handle, probe as u32, version, slab)
+            .await
+            .map_err(|e| stage_failed("burst", e))?;
+
+        // Stage 4: the datagram arrives and every RC the burst took is released.
+        let payload_len = slab.payload_len(handle as usize);
+        let mut buf = vec![0u8; BURST_PREFIX_LEN + payload_len + 1];
+        let len = match tokio::time::timeout(SELF_TEST_TIMEOUT, receiver.recv(&mut buf)).await {
+            Ok(Ok(len)) => len,
+            Ok(Err(e)) => return Err(stage_failed("completion", e)),
+            Err(_) => return Err(stage_failed("completion", "probe burst never arrived")),
+        };
+        let mut payload = vec![0u8; payload_len];
+        slab.read_slot(handle as usize, 0, &mut payload).map_err(|e| stage_failed("completion", e))?;
+        if len != BURST_PREFIX_LEN + payload_len || buf[len - payload_len..len] != payload[..] {
+            return Err(stage_failed("completion", format!("probe burst arrived as {} bytes, expected {}", len, BURST_PREFIX_LEN + payload_len)));
+        }
+
+        let deadline = tokio::time::Instant::now() + SELF_TEST_TIMEOUT;
+        while slab.is_in_flight(probe) || slab.is_in_flight(handle as usize) {
+            dispatcher.reap_completions(slab);
+            if tokio::time::Instant::now() >= deadline {
+                return Err(stage_failed("completion", "burst completion was never reaped"));
+            }
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        Ok(())
+    }
+
+    /// Spawns the worker swarm and orchestrator, returning a control handle.
+    ///
+    /// Must be called from within a Tokio runtime (the orchestrator is spawned on it).
+    pub async fn spawn(self) -> Result<ServerHandle, Box<dyn std::error::Error>> {
+        tracing::info!("Initializing HTTP-X Sovereign Swarm on {:?}", self.addrs);
         
         let (_global_tx, mut _global_rx) = tokio::sync::mpsc::channel::<ControlSignal>(1024);
         let mut primary_fd: Option<std::os::unix::io::RawFd> = None;
 
         // Initialize Learning Channel (Swarm -> Orchestrator)
-        let (learn_tx, learn_rx) = tokio::sync::mpsc::unbounded_channel::<(Vec<u8>, bool)>();
+        let (learn_tx, learn_rx) = httpx_core::learn_channel(self.config.learn_capacity);
         let mut worker_txs = Vec::new();
+        let mut worker_stats = Vec::new();
+        let mut worker_threads = Vec::new();
 
         let slab = self.slab.clone().unwrap_or_else(|| {
             std::sync::Arc::new(httpx_dsa::SecureSlab::new(self.config.slab_capacity))
         });
 
-        let trie = self.trie.clone().unwrap_or_else(|| httpx_dsa::LinearIntentTrie::new(1024));
+        let trie = match &self.routes_file {
+            // A broken file at startup is fatal; later revisions are merely rejected.
+            Some(path) => crate::reload::load_routes(path)?,
+            None => self.trie.clone().unwrap_or_else(|| httpx_dsa::LinearIntentTrie::new(1024)),
+        };
+
+        // `SharedMultiThread`: one pool, one thread per worker, owned by the
+        // server handle; each worker blocks on its handle instead of owning a runtime.
+        let shared_rt = match self.config.runtime_flavor {
+            RuntimeFlavor::PerCoreCurrentThread => None,
+            RuntimeFlavor::SharedMultiThread => Some(SharedRuntime::spawn(self.config.threads * self.addrs.len())?),
+        };
+
+        // Worker 0 serves from this engine and the HTTP/1.1 bridge routes
+        // through it, so reloads and learned swaps reach both.
+        let bridge_engine = std::sync::Arc::new(
+            httpx_core::PredictiveEngine::new(true).with_predictive_depth(self.config.predictive_depth),
+        );
+        bridge_engine.swap_weights(trie.clone());
+
+        let workers = self.addrs.iter()
+            .flat_map(|&addr| (0..self.config.threads).map(move |_| addr))
+            .enumerate();
 
-        for core_id in 0..self.config.threads {
-            let addr = self.addr;
+        for (core_id, addr) in workers {
             let config = self.config.clone();
             let slab = slab.clone();
             let trie = trie.clone();
@@ -77,6 +307,10 @@ impl HttpxServer {
             worker_txs.push(control_tx);
             
             let learn_tx = learn_tx.clone();
+            let shared_rt = shared_rt.as_ref().map(|rt| rt.handle.clone());
+            let engine = (core_id == 0).then(|| bridge_engine.clone());
+            let stats = std::sync::Arc::new(DispatcherStats::default());
+            worker_stats.push(stats.clone());
 
             // # Mechanical Sympathy: Shared SQPOLL
             // In Production Mode, create the ring here and pass it down.
@@ -97,15 +331,10 @@ impl HttpxServer {
                 IoUring::builder().build(128).expect("Failed to create Dev Ring")
             };
             
-            std::thread::Builder::new()
+            let thread = std::thread::Builder::new()
                 .name(format!("httpx-worker-{}", core_id))
                 .spawn(move || {
-                    let rt = tokio::runtime::Builder::new_current_thread()
-                        .enable_all()
-                        .build()
-                        .unwrap();
-                        
-                    rt.block_on(async move {
+                    let worker = async move {
                         // 1. Create a raw socket with SO_REUSEPORT
                         let socket = Socket::new(Domain::for_address(addr), Type::DGRAM, Some(Protocol::UDP)).unwrap();
                         socket.set_reuse_port(true).unwrap();
@@ -123,29 +352,287 @@ impl HttpxServer {
                             trie,
                             ring,
                             learn_tx,
-                        ).await.unwrap();
+                        ).await.unwrap()
+                        .with_stats(stats)
+                        .with_congestion_controller(std::sync::Arc::new(
+                            DefaultCongestionController::new(DEFAULT_BASE_RTT_NANOS),
+                        ));
+                        if let Some(engine) = engine {
+                            dispatcher = dispatcher.with_engine(engine);
+                        }
 
                         dispatcher.register_slab(&slab).unwrap();
                         
                         dispatcher.run_loop(&slab).await;
-                    });
+                    };
+
+                    match shared_rt {
+                        Some(handle) => handle.block_on(worker),
+                        None => tokio::runtime::Builder::new_current_thread()
+                            .enable_all()
+                            .build()
+                            .unwrap()
+                            .block_on(worker),
+                    }
                 })?;
+            worker_threads.push(thread);
         }
 
         // Start the ClusterOrchestrator on the next available core
-        let orchestrator_core = self.config.threads; 
-        let orchestrator = httpx_cluster::orchestrator::ClusterOrchestrator::new(
+        let orchestrator_core = worker_txs.len();
+        let mut orchestrator = httpx_cluster::orchestrator::ClusterOrchestrator::new(
             orchestrator_core,
             learn_rx,
-            worker_txs,
-        );
+            worker_txs.clone(),
+        )
+        .with_base_routes(trie.clone());
+        // Reloads go through the orchestrator, so they become its base routes
+        // and keep the weights it has learned.
+        let route_watcher = match &self.routes_file {
+            Some(path) => {
+                let (routes_tx, routes_rx) = tokio::sync::mpsc::channel(4);
+                orchestrator = orchestrator.with_route_updates(routes_rx);
+                Some(RouteWatcher::spawn(path, routes_tx)?)
+            }
+            None => None,
+        };
+        let cluster_mode = orchestrator.mode_watch();
         
-        tokio::spawn(async move {
-            orchestrator.run().await;
-        });
+        let orchestrator = tokio::spawn(orchestrator.run());
 
-        // Keep the swarm alive
-        std::future::pending::<()>().await;
-        Ok(())
+        // Runs beside the orchestrator, flagging any worker whose loop wedges.
+        let watchdog = std::sync::Arc::new(Watchdog::new(worker_stats.clone(), WATCHDOG_TIMEOUT));
+        let watchdog_task = tokio::spawn(watchdog.clone().run());
+
+        let http1 = match self.http1_addr {
+            Some(addr) => {
+                let listener = tokio::net::TcpListener::bind(addr).await?;
+                let local = listener.local_addr()?;
+                tracing::info!("HTTP/1.1 bridge listening on {}", local);
+                let bridge = Http1Bridge::new(bridge_engine, slab.clone());
+                Some((local, tokio::spawn(bridge.serve(listener))))
+            }
+            None => None,
+        };
+
+        Ok(ServerHandle {
+            worker_txs,
+            worker_stats,
+            worker_threads,
+            orchestrator,
+            watchdog,
+            watchdog_task,
+            slab,
+            cluster_mode,
+            route_watcher,
+            http1,
+            shared_rt,
+        })
+    }
+}
+
+/// A `HttpXError::SelfTest` for `stage`.
+fn stage_failed(stage: &'static str, reason: impl std::fmt::Display) -> HttpXError {
+    HttpXError::SelfTest { stage, reason: reason.to_string() }
+}
+
+/// The multi-threaded runtime shared by all workers under
+/// `RuntimeFlavor::SharedMultiThread`.
+///
+/// A dedicated thread owns the runtime, so it is never dropped from inside
+/// an async context. Dropping this stops the runtime; `shutdown` also waits
+/// for it to be torn down.
+struct SharedRuntime {
+    handle: tokio::runtime::Handle,
+    stop: Option<tokio::sync::oneshot::Sender<()>>,
+    owner: Option<std::thread::JoinHandle<()>>,
+}
+
+impl SharedRuntime {
+    /// Starts the runtime with `threads` worker threads.
+    fn spawn(threads: usize) -> std::io::Result<Self> {
+        let rt = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(threads.max(1))
+            .thread_name("httpx-shared-rt")
+            .enable_all()
+            .build()?;
+        let handle = rt.handle().clone();
+        let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+        let owner = std::thread::Builder::new()
+            .name("httpx-rt-owner".into())
+            .spawn(move || {
+                let _ = rt.block_on(stopped);
+            })?;
+        Ok(Self { handle, stop: Some(stop), owner: Some(owner) })
+    }
+
+    /// Stops the runtime and joins the thread that drops it.
+    async fn shutdown(mut self) {
+        if let Some(stop) = self.stop.take() {
+            let _ = stop.send(());
+        }
+        if let Some(owner) = self.owner.take() {
+            let _ = tokio::task::spawn_blocking(move || owner.join()).await;
+        }
+    }
+}
+
+impl Drop for SharedRuntime {
+    fn drop(&mut self) {
+        // The owner thread drops the runtime once it wakes; never block here.
+        if let Some(stop) = self.stop.take() {
+            let _ = stop.send(());
+        }
+    }
+}
+
+/// A handle to a running server, used for control-plane operations.
+pub struct ServerHandle {
+    worker_txs: Vec<tokio::sync::mpsc::Sender<ControlSignal>>,
+    /// Per-core counters, summed on demand.
+    worker_stats: Vec<std::sync::Arc<DispatcherStats>>,
+    worker_threads: Vec<std::thread::JoinHandle<()>>,
+    /// Exits on its own once every worker (and its learning sender) is gone.
+    orchestrator: tokio::task::JoinHandle<()>,
+    watchdog: std::sync::Arc<Watchdog>,
+    /// Never exits on its own; aborted by `shutdown`.
+    watchdog_task: tokio::task::JoinHandle<()>,
+    slab: std::sync::Arc<httpx_dsa::SecureSlab>,
+    cluster_mode: tokio::sync::watch::Receiver<ClusterMode>,
+    /// Present when the server was built `with_routes_file`.
+    route_watcher: Option<RouteWatcher>,
+    /// Bound address and accept task of the HTTP/1.1 bridge, if enabled.
+    http1: Option<(SocketAddr, tokio::task::JoinHandle<()>)>,
+    /// Runtime the workers block on under `SharedMultiThread`; stopped
+    /// once they have joined.
+    shared_rt: Option<SharedRuntime>,
+}
+
+impl ServerHandle {
+    /// Drains every worker, then joins their threads.
+    ///
+    /// Workers stop pushing and exit once their slots are idle; any still
+    /// running after `grace` are sent `KillAll`. The HTTP/1.1 bridge stops
+    /// first, closing its keep-alive connections.
+    pub async fn shutdown(mut self, grace: Duration) {
+        // Drained workers stop beating; that is not a stall.
+        self.watchdog_task.abort();
+        if let Some((_, task)) = self.http1.take() {
+            task.abort();
+            // Dropping the accept loop aborts every connection task it owns.
+            let _ = task.await;
+        }
+        for tx in &self.worker_txs {
+            let _ = tx.send(ControlSignal::Drain).await;
+        }
+        let deadline = tokio::time::Instant::now() + grace;
+        while !self.worker_threads.iter().all(|t| t.is_finished()) && tokio::time::Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        for tx in &self.worker_txs {
+            let _ = tx.send(ControlSignal::KillAll).await;
+        }
+
+        let threads = self.worker_threads;
+        let _ = tokio::task::spawn_blocking(move || {
+            for thread in threads {
+                let _ = thread.join();
+            }
+        })
+        .await;
+        tracing::info!("All workers joined.");
+
+        if let Some(rt) = self.shared_rt {
+            rt.shutdown().await;
+            tracing::info!("Shared runtime stopped.");
+        }
+
+        let _ = self.orchestrator.await;
+        tracing::info!("ClusterOrchestrator joined.");
+    }
+
+    /// The routes file watcher, if hot reloading is enabled.
+    pub fn route_watcher(&self) -> Option<&RouteWatcher> {
+        self.route_watcher.as_ref()
+    }
+
+    /// Address the HTTP/1.1 bridge is listening on, if enabled.
+    pub fn http1_addr(&self) -> Option<SocketAddr> {
+        self.http1.as_ref().map(|(addr, _)| *addr)
+    }
+
+    /// Sums the per-core data-plane counters of all workers.
+    ///
+    /// Reads each worker's cache line directly; no control signal is sent.
+    pub fn stats(&self) -> StatsSnapshot {
+        self.worker_stats.iter().map(|s| s.snapshot()).sum()
+    }
+
+    /// Workers the watchdog currently considers stalled (no heartbeat within
+    /// `WATCHDOG_TIMEOUT`).
+    pub fn stalled_workers(&self) -> Vec<usize> {
+        self.watchdog.stalled_workers()
+    }
+
+    /// Push latency percentiles (submit -> reap) across all workers.
+    pub fn latency(&self) -> LatencyPercentiles {
+        let mut merged = httpx_core::LatencyHistogram::new();
+        for stats in &self.worker_stats {
+            merged.merge(&stats.latency());
+        }
+        merged.percentiles()
+    }
+
+    /// Snapshots trie, slab, worker and cluster state without perturbing the hot path.
+    ///
+    /// Workers answer between packets via `ControlSignal::Inspect`; slab
+    /// state is read with relaxed loads directly from this thread. Workers
+    /// that have not answered within `INSPECT_TIMEOUT` are left out of
+    /// `workers`, so one stuck worker cannot hang the call.
+    pub async fn inspect(&self) -> InspectReport {
+        let (reply_tx, mut reply_rx) = tokio::sync::mpsc::channel(self.worker_txs.len().max(1));
+        let mut workers = Vec::with_capacity(self.worker_txs.len());
+        let gather = async {
+            for tx in &self.worker_txs {
+                let _ = tx.send(ControlSignal::Inspect(reply_tx.clone())).await;
+            }
+            drop(reply_tx);
+            while let Some(snapshot) = reply_rx.recv().await {
+                workers.push(snapshot);
+            }
+        };
+        if tokio::time::timeout(INSPECT_TIMEOUT, gather).await.is_err() {
+            tracing::warn!(
+                "Inspect: {} of {} workers did not answer within {:?}",
+                self.worker_txs.len() - workers.len(),
+                self.worker_txs.len(),
+                INSPECT_TIMEOUT
+            );
+        }
+        workers.sort_by_key(|w| w.core_id);
+
+        let slots = (0..self.slab.slots())
+            .map(|i| {
+                let ref_count = self.slab.ref_count(i);
+                SlotState {
+                    ref_count,
+                    version: self.slab.get_version(i),
+                    in_flight: ref_count > 0,
+                }
+            })
+            .collect();
+
+        let (routes, trie_digest) = workers
+            .first()
+            .map(|w| (w.routes.clone(), w.trie_digest))
+            .unwrap_or_default();
+
+        InspectReport {
+            routes,
+            trie_digest,
+            slots,
+            workers,
+            cluster_mode: *self.cluster_mode.borrow(),
+        }
     }
 }
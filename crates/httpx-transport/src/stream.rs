@@ -1,12 +1,23 @@
 use std::io;
 use std::os::unix::io::AsRawFd;
 use tokio::net::UdpSocket;
-use httpx_dsa::SecureSlab;
+use httpx_dsa::{SecureSlab, SlabError};
+use crate::fragment::fragment_payload;
+use crate::fragment::FragmentHeader;
+use crate::frame::{HttpxFrame, FRAGMENTED_HEADER_LEN};
+use crate::error::TransportError;
+use crate::reliability::{gso_batch_limit, CongestionController};
+use std::sync::Arc;
+
+/// Largest GSO super-packet `stream_batch` will assemble.
+pub const MAX_BATCH_BYTES: usize = 65535;
 
 /// Handles zero-copy streaming of large payloads using GSO.
 pub struct PayloadStreamer {
     socket: UdpSocket,
-    _gso_size: u16,
+    gso_size: u16,
+    /// Scales the `stream_batch` ceiling with network health when set.
+    controller: Option<Arc<dyn CongestionController>>,
 }
 
 impl PayloadStreamer {
@@ -31,42 +42,123 @@ impl PayloadStreamer {
 
         Ok(Self {
             socket,
-            _gso_size: gso_size,
+            gso_size,
+            controller: None,
         })
     }
 
+    /// Makes `stream_batch` congestion-aware: the batch ceiling follows the
+    /// controller's credit level (see `reliability::gso_batch_limit`).
+    pub fn with_congestion_controller(mut self, controller: Arc<dyn CongestionController>) -> Self {
+        self.controller = Some(controller);
+        self
+    }
+
+    /// Largest batch, in bytes, `stream_batch` currently accepts.
+    ///
+    /// Callers should size batches to `batch_limit() / slab.slot_size()` handles.
+    pub fn batch_limit(&self) -> usize {
+        self.controller.as_ref().map_or(MAX_BATCH_BYTES, |cc| gso_batch_limit(cc.credit_level()))
+    }
+
+    /// Streams an arbitrarily large payload as framed fragments.
+    ///
+    /// Each fragment is exactly one GSO segment (`gso_size` bytes, the last
+    /// may be shorter), so the kernel splits every super-packet on fragment
+    /// boundaries. Clients reassemble with `fragment::Reassembler`.
+    /// Returns the number of fragments sent.
+    pub async fn stream_fragmented(
+        &self,
+        payload_handle: u32,
+        payload: &[u8],
+        target: std::net::SocketAddr,
+    ) -> io::Result<usize> {
+        // Max UDP payload (IPv4) and the kernel's UDP_MAX_SEGMENTS.
+        const MAX_SUPER_PACKET: usize = 65507;
+        const MAX_SEGMENTS: usize = 64;
+
+        let gso = self.gso_size as usize;
+        let fragments = fragment_payload(payload_handle, payload, gso);
+        let per_batch = (MAX_SUPER_PACKET / gso).clamp(1, MAX_SEGMENTS);
+
+        let mut batch_buf = Vec::with_capacity(per_batch * gso);
+        for batch in fragments.chunks(per_batch) {
+            batch_buf.clear();
+            for fragment in batch {
+                batch_buf.extend_from_slice(fragment);
+            }
+            self.socket.send_to(&batch_buf, target).await?;
+            // UDP sends never yield; without this a 200KB stream lands in one
+            // scheduler tick and overruns the peer's receive buffer.
+            tokio::task::yield_now().await;
+        }
+        Ok(fragments.len())
+    }
+
     /// Stream a batch of fragments from the slab with a Freshness Guard.
+    ///
+    /// Each handle contributes one full slot (`slab.slot_size()` bytes). The
+    /// slots are cut into `gso_size` segments, each a version 2
+    /// `HttpxFrame` whose `FragmentHeader` names the batch's first handle,
+    /// so the client can rebuild the batch with `fragment::Reassembler`.
+    ///
+    /// The whole batch, slot bytes plus one `FRAGMENTED_HEADER_LEN` header
+    /// per fragment, must fit one super-packet of `batch_limit()` bytes; a
+    /// larger request fails with `TransportError::PayloadTooLarge` (whose
+    /// `len` is that framed size) before anything is sent, rather than going
+    /// out truncated. A quarantined
+    /// handle fails the batch with `SlabError::Poisoned`, also before
+    /// anything is sent. At credit Level 0 nothing is sent and `Ok(0)` is
+    /// returned.
     pub async fn stream_batch(
         &self, 
         slab: &SecureSlab, 
         handles: &[(u32, u32)], // (handle, expected_version)
         target: std::net::SocketAddr
-    ) -> io::Result<usize> {
+    ) -> Result<usize, TransportError> {
+        let limit = self.batch_limit();
+        if limit == 0 {
+            return Ok(0);
+        }
+        let payload_size = slab.slot_size();
+        let requested = handles.len() * payload_size;
+        let body = (self.gso_size as usize).saturating_sub(FRAGMENTED_HEADER_LEN).max(1);
+        let frag_total = requested.div_ceil(body);
+        let framed = requested + frag_total * FRAGMENTED_HEADER_LEN;
+        if framed > limit {
+            return Err(TransportError::PayloadTooLarge { len: framed, limit });
+        }
+
         let mut total = 0;
-        let mut batch_buf = Vec::with_capacity(65535); // 64KB MAX GSO
+        let mut payload = Vec::with_capacity(requested);
 
         for &(handle, expected_version) in handles {
+            if slab.is_poisoned(handle as usize) {
+                tracing::warn!("Quarantine: refused to stream poisoned handle {}.", handle);
+                return Err(SlabError::Poisoned(handle as usize).into());
+            }
             // # Mechanical Sympathy Target: < 0.5ns check
             // Single CMP instruction to ensure semantic freshness.
             let physical_version = slab.get_version(handle as usize);
             if physical_version != expected_version {
                 tracing::warn!("Freshness Violation: Stale push for handle {}. Expected {}, got {}.", handle, expected_version, physical_version);
-                return Err(io::Error::new(io::ErrorKind::InvalidData, "Stale Payload"));
+                return Err(io::Error::new(io::ErrorKind::InvalidData, "Stale Payload").into());
             }
 
             let buf = slab.get_slot(handle as usize);
-            if batch_buf.len() + 4096 > 65535 {
-                break;
-            }
-            
             unsafe {
-                let slice = std::slice::from_raw_parts(buf, 4096);
-                batch_buf.extend_from_slice(slice);
+                let slice = std::slice::from_raw_parts(buf, payload_size);
+                payload.extend_from_slice(slice);
             }
             total += 1;
         }
 
         if total > 0 {
+            let mut batch_buf = Vec::with_capacity(framed);
+            for (index, chunk) in payload.chunks(body).enumerate() {
+                let header = FragmentHeader { payload_handle: handles[0].0, index: index as u16, total: frag_total as u16 };
+                HttpxFrame::fragment(chunk, header).encode_into(&mut batch_buf);
+            }
             self.socket.send_to(&batch_buf, target).await?;
         }
 
@@ -74,11 +166,14 @@ impl PayloadStreamer {
     }
 }
 
+/// Most segments one burst can gather (e.g. intent, header, split payload, trailer).
+pub const MAX_IOVECS: usize = 8;
+
 /// Hardware-Offloaded Super-Packetizer for Zero-Copy io_uring Bursts.
 pub struct GsoPacketizer {
     // Persistent iovec storage for in-flight operations.
-    // Index by payload_handle.
-    iovecs: Vec<[libc::iovec; 3]>,
+    // Index by payload_handle; sized for `MAX_IOVECS` so it never reallocates.
+    iovecs: Vec<[libc::iovec; MAX_IOVECS]>,
     // Persistent CMSG storage (for UDP_SEGMENT).
     #[allow(dead_code)]
     cmsgs: Vec<[u8; 64]>,
@@ -97,12 +192,7 @@ impl GsoPacketizer {
         let mut msghdrs = Vec::with_capacity(capacity);
         
         for _ in 0..capacity {
-            // Default 3 iovecs per slot
-            iovecs.push([
-                libc::iovec { iov_base: std::ptr::null_mut(), iov_len: 0 },
-                libc::iovec { iov_base: std::ptr::null_mut(), iov_len: 0 },
-                libc::iovec { iov_base: std::ptr::null_mut(), iov_len: 0 },
-            ]);
+            iovecs.push([libc::iovec { iov_base: std::ptr::null_mut(), iov_len: 0 }; MAX_IOVECS]);
             cmsgs.push([0u8; 64]);
             msghdrs.push(unsafe { std::mem::zeroed() });
         }
@@ -123,22 +213,41 @@ impl GsoPacketizer {
         intent_ptr: *const u8, intent_len: usize,
         header_ptr: *const u8, header_len: usize,
         payload_ptr: *const u8, payload_len: usize,
+        gso_size: u16, // Future: Use for UDP_SEGMENT
+    ) -> *const libc::msghdr {
+        self.prepare_segments(
+            handle,
+            &[(intent_ptr, intent_len), (header_ptr, header_len), (payload_ptr, payload_len)],
+            gso_size,
+        )
+    }
+
+    /// Prepares a burst gathering 1 to `MAX_IOVECS` `(ptr, len)` segments, in order.
+    ///
+    /// `msg_iovlen` is set to `segments.len()`, so header-only responses,
+    /// trailers, and payloads split across slab slots all share one SQE.
+    /// Panics if `segments` is empty or longer than `MAX_IOVECS`.
+    pub fn prepare_segments(
+        &mut self,
+        handle: usize,
+        segments: &[(*const u8, usize)],
         _gso_size: u16, // Future: Use for UDP_SEGMENT
     ) -> *const libc::msghdr {
+        assert!(
+            (1..=MAX_IOVECS).contains(&segments.len()),
+            "GsoPacketizer: {} segments, expected 1..={}",
+            segments.len(),
+            MAX_IOVECS
+        );
         let iovecs = &mut self.iovecs[handle];
-        
-        iovecs[0].iov_base = intent_ptr as *mut libc::c_void;
-        iovecs[0].iov_len = intent_len;
-
-        iovecs[1].iov_base = header_ptr as *mut libc::c_void;
-        iovecs[1].iov_len = header_len;
-
-        iovecs[2].iov_base = payload_ptr as *mut libc::c_void;
-        iovecs[2].iov_len = payload_len;
+        for (iov, &(ptr, len)) in iovecs.iter_mut().zip(segments) {
+            iov.iov_base = ptr as *mut libc::c_void;
+            iov.iov_len = len;
+        }
 
         let msghdr = &mut self.msghdrs[handle];
         msghdr.msg_iov = iovecs.as_ptr() as *mut libc::iovec;
-        msghdr.msg_iovlen = 3;
+        msghdr.msg_iovlen = segments.len();
         
         // Todo: Implement CMSG construction for UDP_SEGMENT if kernel supports it via io_uring
         // Currently returning empty control buffer.
@@ -149,4 +258,18 @@ impl GsoPacketizer {
 
         msghdr as *const libc::msghdr
     }
+
+    /// Returns the `iov_len` of each prepared iovec (intent, header, payload) for a handle.
+    ///
+    /// Describes the three-segment `prepare_burst` layout; see `prepared_iovecs`
+    /// for bursts built with `prepare_segments`.
+    pub fn iovec_lens(&self, handle: usize) -> [usize; 3] {
+        let iovecs = &self.iovecs[handle];
+        [iovecs[0].iov_len, iovecs[1].iov_len, iovecs[2].iov_len]
+    }
+
+    /// Returns the iovecs of the last burst prepared for a handle.
+    pub fn prepared_iovecs(&self, handle: usize) -> &[libc::iovec] {
+        &self.iovecs[handle][..self.msghdrs[handle].msg_iovlen]
+    }
 }
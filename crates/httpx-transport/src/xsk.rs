@@ -0,0 +1,305 @@
+//! # httpx-transport: AF_XDP (XSK) Receive Path
+//!
+//! The `xdp_redirect` program in `bpf/xdp-filter` hands matching HTTP-X
+//! frames to an AF_XDP socket instead of `XDP_PASS`ing them up the kernel
+//! UDP stack. This module is the userspace end: a UMEM shared with the
+//! kernel plus the fill and RX rings, read without a syscall per frame.
+//!
+//! ## Map Layout
+//! `XSKS` is a `BPF_MAP_TYPE_XSKMAP` with `key: u32` = RX queue index and
+//! `value: u32` = XSK file descriptor (see `XskSocket::register`). A frame
+//! arriving on a queue with no registered socket falls back to `XDP_PASS`,
+//! so the normal socket path keeps working while the XSK is not bound.
+//!
+//! ## Mechanical Sympathy: Shared Rings
+//! Frames land directly in the UMEM; the RX ring only carries descriptors.
+//! `recv` hands the frame out by reference and recycles its chunk into the
+//! fill ring afterwards, so the data path never copies or allocates.
+
+use std::io;
+use std::os::fd::{AsRawFd, RawFd};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// Size of one UMEM chunk; each chunk holds exactly one frame.
+pub const XSK_FRAME_SIZE: usize = 2048;
+
+/// Number of UMEM chunks, and the depth of every ring (must be a power of two).
+pub const XSK_RING_SIZE: u32 = 64;
+
+/// `BPF_MAP_UPDATE_ELEM` command of the `bpf(2)` syscall.
+const BPF_MAP_UPDATE_ELEM: libc::c_long = 2;
+
+/// A single-producer/single-consumer ring shared with the kernel.
+struct XskRing<T> {
+    map: *mut libc::c_void,
+    map_len: usize,
+    producer: *const AtomicU32,
+    consumer: *const AtomicU32,
+    desc: *mut T,
+    mask: u32,
+}
+
+impl<T> XskRing<T> {
+    /// Maps the ring at `pgoff` using the kernel-reported offsets.
+    fn map(fd: RawFd, pgoff: u64, off: &libc::xdp_ring_offset) -> io::Result<Self> {
+        let map_len = off.desc as usize + XSK_RING_SIZE as usize * std::mem::size_of::<T>();
+        let map = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                map_len,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_SHARED | libc::MAP_POPULATE,
+                fd,
+                pgoff as libc::off_t,
+            )
+        };
+        if map == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+        let base = map as *mut u8;
+        // # Safety: the offsets come from XDP_MMAP_OFFSETS and lie inside `map_len`.
+        unsafe {
+            Ok(Self {
+                map,
+                map_len,
+                producer: base.add(off.producer as usize) as *const AtomicU32,
+                consumer: base.add(off.consumer as usize) as *const AtomicU32,
+                desc: base.add(off.desc as usize) as *mut T,
+                mask: XSK_RING_SIZE - 1,
+            })
+        }
+    }
+
+    fn producer(&self) -> &AtomicU32 {
+        unsafe { &*self.producer }
+    }
+
+    fn consumer(&self) -> &AtomicU32 {
+        unsafe { &*self.consumer }
+    }
+
+    fn slot(&self, idx: u32) -> *mut T {
+        unsafe { self.desc.add((idx & self.mask) as usize) }
+    }
+}
+
+impl<T> Drop for XskRing<T> {
+    fn drop(&mut self) {
+        unsafe {
+            libc::munmap(self.map, self.map_len);
+        }
+    }
+}
+
+/// An AF_XDP socket bound to one RX queue of an interface.
+pub struct XskSocket {
+    fd: RawFd,
+    umem: *mut libc::c_void,
+    umem_len: usize,
+    fill: XskRing<u64>,
+    rx: XskRing<libc::xdp_desc>,
+    // Held for its mapping: the kernel refuses to bind a UMEM without one.
+    _completion: XskRing<u64>,
+}
+
+// # Safety: the rings and UMEM are owned exclusively by this socket.
+unsafe impl Send for XskSocket {}
+
+impl XskSocket {
+    /// Creates an XSK on `ifname` queue `queue_id` in copy mode.
+    ///
+    /// Copy mode works on every driver (including veth and generic XDP);
+    /// the frame still bypasses the kernel UDP/IP stack.
+    pub fn bind(ifname: &str, queue_id: u32) -> io::Result<Self> {
+        let ifname = std::ffi::CString::new(ifname).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        let ifindex = unsafe { libc::if_nametoindex(ifname.as_ptr()) };
+        if ifindex == 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let fd = unsafe { libc::socket(libc::AF_XDP, libc::SOCK_RAW | libc::SOCK_CLOEXEC, 0) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // From here on `guard` closes the fd on any early return.
+        let guard = FdGuard(fd);
+
+        let umem_len = XSK_FRAME_SIZE * XSK_RING_SIZE as usize;
+        let umem = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                umem_len,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_PRIVATE | libc::MAP_ANONYMOUS | libc::MAP_POPULATE,
+                -1,
+                0,
+            )
+        };
+        if umem == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+        let umem_guard = MapGuard(umem, umem_len);
+
+        let reg = libc::xdp_umem_reg {
+            addr: umem as u64,
+            len: umem_len as u64,
+            chunk_size: XSK_FRAME_SIZE as u32,
+            headroom: 0,
+            flags: 0,
+            tx_metadata_len: 0,
+        };
+        setsockopt(fd, libc::XDP_UMEM_REG, &reg)?;
+        setsockopt(fd, libc::XDP_UMEM_FILL_RING, &XSK_RING_SIZE)?;
+        setsockopt(fd, libc::XDP_UMEM_COMPLETION_RING, &XSK_RING_SIZE)?;
+        setsockopt(fd, libc::XDP_RX_RING, &XSK_RING_SIZE)?;
+
+        let mut off: libc::xdp_mmap_offsets = unsafe { std::mem::zeroed() };
+        let mut optlen = std::mem::size_of::<libc::xdp_mmap_offsets>() as libc::socklen_t;
+        let rc = unsafe {
+            libc::getsockopt(fd, libc::SOL_XDP, libc::XDP_MMAP_OFFSETS, &mut off as *mut _ as *mut libc::c_void, &mut optlen)
+        };
+        if rc < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let fill = XskRing::<u64>::map(fd, libc::XDP_UMEM_PGOFF_FILL_RING, &off.fr)?;
+        let completion = XskRing::<u64>::map(fd, libc::XDP_UMEM_PGOFF_COMPLETION_RING, &off.cr)?;
+        let rx = XskRing::<libc::xdp_desc>::map(fd, libc::XDP_PGOFF_RX_RING as u64, &off.rx)?;
+
+        // Hand every chunk to the kernel before binding.
+        for i in 0..XSK_RING_SIZE {
+            unsafe { *fill.slot(i) = i as u64 * XSK_FRAME_SIZE as u64 };
+        }
+        fill.producer().store(XSK_RING_SIZE, Ordering::Release);
+
+        let addr = libc::sockaddr_xdp {
+            sxdp_family: libc::AF_XDP as u16,
+            sxdp_flags: libc::XDP_COPY,
+            sxdp_ifindex: ifindex,
+            sxdp_queue_id: queue_id,
+            sxdp_shared_umem_fd: 0,
+        };
+        let rc = unsafe {
+            libc::bind(
+                fd,
+                &addr as *const _ as *const libc::sockaddr,
+                std::mem::size_of::<libc::sockaddr_xdp>() as libc::socklen_t,
+            )
+        };
+        if rc < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        std::mem::forget(guard);
+        std::mem::forget(umem_guard);
+        Ok(Self { fd, umem, umem_len, fill, rx, _completion: completion })
+    }
+
+    /// Inserts this socket into an XSKMAP at `queue_id`, enabling the redirect.
+    pub fn register(&self, xskmap_fd: RawFd, queue_id: u32) -> io::Result<()> {
+        #[repr(C)]
+        struct MapUpdateAttr {
+            map_fd: u32,
+            _pad: u32,
+            key: u64,
+            value: u64,
+            flags: u64,
+        }
+        let value = self.fd as u32;
+        let attr = MapUpdateAttr {
+            map_fd: xskmap_fd as u32,
+            _pad: 0,
+            key: &queue_id as *const u32 as u64,
+            value: &value as *const u32 as u64,
+            flags: 0, // BPF_ANY
+        };
+        let rc = unsafe {
+            libc::syscall(
+                libc::SYS_bpf,
+                BPF_MAP_UPDATE_ELEM,
+                &attr as *const MapUpdateAttr,
+                std::mem::size_of::<MapUpdateAttr>(),
+            )
+        };
+        if rc < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    /// Runs `f` over the next redirected frame (Ethernet header included)
+    /// and returns its chunk to the kernel. `None` if the RX ring is empty.
+    pub fn recv<R>(&mut self, f: impl FnOnce(&[u8]) -> R) -> Option<R> {
+        let cons = self.rx.consumer().load(Ordering::Relaxed);
+        if self.rx.producer().load(Ordering::Acquire) == cons {
+            return None;
+        }
+        let desc = unsafe { *self.rx.slot(cons) };
+        let frame = unsafe {
+            std::slice::from_raw_parts((self.umem as *const u8).add(desc.addr as usize), desc.len as usize)
+        };
+        let out = f(frame);
+        self.rx.consumer().store(cons.wrapping_add(1), Ordering::Release);
+
+        // Recycle the chunk (aligned down, in case of kernel headroom).
+        let prod = self.fill.producer().load(Ordering::Relaxed);
+        unsafe { *self.fill.slot(prod) = desc.addr - desc.addr % XSK_FRAME_SIZE as u64 };
+        self.fill.producer().store(prod.wrapping_add(1), Ordering::Release);
+        Some(out)
+    }
+
+    /// Blocks up to `timeout_ms` for the RX ring to become readable.
+    pub fn wait_readable(&self, timeout_ms: i32) -> io::Result<bool> {
+        let mut pfd = libc::pollfd { fd: self.fd, events: libc::POLLIN, revents: 0 };
+        let rc = unsafe { libc::poll(&mut pfd, 1, timeout_ms) };
+        if rc < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(rc > 0)
+    }
+}
+
+impl AsRawFd for XskSocket {
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd
+    }
+}
+
+impl Drop for XskSocket {
+    fn drop(&mut self) {
+        unsafe {
+            libc::close(self.fd);
+            libc::munmap(self.umem, self.umem_len);
+        }
+    }
+}
+
+fn setsockopt<T>(fd: RawFd, opt: libc::c_int, val: &T) -> io::Result<()> {
+    let rc = unsafe {
+        libc::setsockopt(fd, libc::SOL_XDP, opt, val as *const T as *const libc::c_void, std::mem::size_of::<T>() as libc::socklen_t)
+    };
+    if rc < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+struct FdGuard(RawFd);
+
+impl Drop for FdGuard {
+    fn drop(&mut self) {
+        unsafe {
+            libc::close(self.0);
+        }
+    }
+}
+
+struct MapGuard(*mut libc::c_void, usize);
+
+impl Drop for MapGuard {
+    fn drop(&mut self) {
+        unsafe {
+            libc::munmap(self.0, self.1);
+        }
+    }
+}
@@ -31,9 +31,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Constraint: Statically resolved via u32 indices
     let payload_handle = 1;
     let payload = vec![0x41; 1024]; // 1KB of 'A'
-    unsafe {
-        std::ptr::copy_nonoverlapping(payload.as_ptr(), slab.get_slot(payload_handle as usize), 1024);
-    }
+    slab.write_slot(payload_handle as usize, 0, &payload)?;
     // High-frequency Freshness Chaos Certification (Phase 50/51)
     slab.set_version(payload_handle as usize, 100);
 
@@ -69,8 +67,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     
     let request = b"/api/v1/hello";
     let iterations = 1000;
-    let mut latencies_us = Vec::with_capacity(iterations);
-    let mut latencies_cycles = Vec::with_capacity(iterations);
+    let mut latencies = httpx_core::LatencyHistogram::new();
+    let mut last_cycles = 0;
 
     println!("Starting Final 15µs Synchronous Challenge...");
 
@@ -103,22 +101,22 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             }
         }
         
-        let end_cycles = rdtsc();
-        let cycles = end_cycles - start_cycles;
-        
-        let duration = start.elapsed();
-        latencies_us.push(duration.as_micros());
-        latencies_cycles.push(cycles);
+        last_cycles = rdtsc() - start_cycles;
+        latencies.record(start.elapsed());
     }
 
-    let avg_latency = if iterations > 0 { latencies_us.iter().sum::<u128>() / iterations as u128 } else { 0 };
-    println!("Final SAI Benchmark Result (Sync): {}µs avg over {} iterations", avg_latency, iterations);
-    println!("Cycles Per Packet (Last): {} cycles", latencies_cycles.last().unwrap_or(&0)); 
-    
-    if avg_latency < 15 && iterations > 0 {
-        println!("SAI SUCCESS: End-to-End Latency < 15µs Target Achieved.");
+    let p = latencies.percentiles();
+    println!(
+        "Final SAI Benchmark Result (Sync): p50 {:?}, p99 {:?}, p999 {:?}, max {:?} over {} iterations",
+        p.p50, p.p99, p.p999, p.max, p.count
+    );
+    println!("Cycles Per Packet (Last): {} cycles", last_cycles);
+
+    // The SLO is a tail target: an average hides exactly the outliers it is about.
+    if p.p99 < std::time::Duration::from_micros(15) && p.count > 0 {
+        println!("SAI SUCCESS: p99 End-to-End Latency < 15µs Target Achieved.");
     } else {
-        println!("SAI VIOLATION: Latency {}µs exceeds 15µs target.", avg_latency);
+        println!("SAI VIOLATION: p99 latency {:?} exceeds 15µs target.", p.p99);
     }
 
     Ok(())
@@ -55,3 +55,650 @@ fn test_reconciliation_buffer_stress() {
     let overhead = t.elapsed();
     println!("test_reconciliation_buffer_stress: Testing Overhead = {:?}", overhead);
 }
+
+/// Verifies that merge weights a context by how often it was recorded.
+#[test]
+fn test_reconciliation_merge_is_proportional() {
+    let t = Instant::now();
+
+    let mut buffer = ReconciliationBuffer::new();
+    for _ in 0..50 {
+        buffer.record_path(b"GET /hot", true);
+    }
+    for _ in 0..5 {
+        buffer.record_path(b"GET /cold", true);
+    }
+
+    let mut trie = LinearIntentTrie::new(64);
+    buffer.merge_into(&mut trie);
+
+    let hot = trie.get_node_at_path(b"GET /hot").unwrap().weights[1] as f32;
+    let cold = trie.get_node_at_path(b"GET /cold").unwrap().weights[1] as f32;
+    assert_eq!((hot, cold), (50.0, 5.0));
+    assert!((hot / cold - 10.0).abs() < 0.01, "weight ratio {} should be ~10:1", hot / cold);
+
+    let overhead = t.elapsed();
+    println!("test_reconciliation_merge_is_proportional: Testing Overhead = {:?}", overhead);
+}
+
+/// Verifies the canonical context hash is stable, distinct and shared.
+#[test]
+fn test_context_hash_is_canonical() {
+    use httpx_cluster::gossip::IntentDelta;
+    use httpx_dsa::context_hash;
+    let t = Instant::now();
+
+    // FNV-1a reference values: fixed across calls, processes and builds.
+    assert_eq!(context_hash(b""), 0xcbf29ce484222325);
+    assert_eq!(context_hash(b"a"), 0xaf63dc4c8601ec8c);
+    assert_eq!(context_hash(b"GET /hot"), context_hash(b"GET /hot"));
+
+    let paths: Vec<Vec<u8>> = (0..1000).map(|i| format!("GET /item/{}", i).into_bytes()).collect();
+    let hashes: std::collections::HashSet<u64> = paths.iter().map(|p| context_hash(p)).collect();
+    assert_eq!(hashes.len(), paths.len(), "Distinct paths must hash apart");
+
+    // Gossip and the reconciliation buffer name a path by the same hash.
+    let delta = IntentDelta::for_path(b"GET /hot", 1, 0, 1);
+    assert_eq!(delta.context_hash, context_hash(b"GET /hot"));
+    let mut buffer = ReconciliationBuffer::new();
+    buffer.record_path(b"GET /hot", true);
+    buffer.record(delta.context_hash, true);
+    assert_eq!(buffer.len(), 1, "A hash-only record must land on the path's entry");
+    let mut trie = LinearIntentTrie::new(64);
+    buffer.merge_into(&mut trie);
+    assert_eq!(trie.get_node_at_path(b"GET /hot").unwrap().weights[1], 2);
+
+    let overhead = t.elapsed();
+    println!("test_context_hash_is_canonical: Testing Overhead = {:?}", overhead);
+}
+
+/// Verifies that a sequence gap triggers a NACK for the skipped range.
+#[tokio::test]
+async fn test_gossip_gap_emits_nack() {
+    use httpx_cluster::gossip::{GossipMessage, IntentDelta};
+    use std::sync::Arc;
+
+    let t = Instant::now();
+
+    let (delta_tx, mut delta_rx) = tokio::sync::mpsc::channel(16);
+    let node = Arc::new(httpx_cluster::GossipProtocol::new("127.0.0.1:0", delta_tx));
+    let node_addr = node.local_addr().unwrap();
+
+    let listener = node.clone();
+    tokio::spawn(async move { listener.listen().await });
+
+    let peer = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    for seq in [1u64, 3] {
+        let delta = IntentDelta { context_hash: 0xABCD, delta_true: 1, delta_false: 0, sequence_number: seq };
+        let payload = serde_json::to_vec(&GossipMessage::Delta(delta)).unwrap();
+        peer.send_to(&payload, node_addr).await.unwrap();
+    }
+
+    // Both deltas are accepted; Seq 2 was "lost" on the wire.
+    assert_eq!(delta_rx.recv().await.unwrap().sequence_number, 1);
+    assert_eq!(delta_rx.recv().await.unwrap().sequence_number, 3);
+
+    let mut buf = [0u8; 1024];
+    let (len, _) = tokio::time::timeout(std::time::Duration::from_secs(1), peer.recv_from(&mut buf))
+        .await
+        .expect("NACK was not emitted")
+        .unwrap();
+    match serde_json::from_slice::<GossipMessage>(&buf[..len]).unwrap() {
+        GossipMessage::Nack { from, to } => assert_eq!((from, to), (2, 2)),
+        other => panic!("Expected NACK, got {:?}", other),
+    }
+
+    let overhead = t.elapsed();
+    println!("test_gossip_gap_emits_nack: Testing Overhead = {:?}", overhead);
+}
+
+/// Verifies that a NACKed delta is retransmitted from the sender's ring and fills the gap.
+#[tokio::test]
+async fn test_gossip_retransmit_fills_gap() {
+    use httpx_cluster::gossip::IntentDelta;
+    use std::sync::Arc;
+
+    let t = Instant::now();
+
+    let (sender_tx, _sender_rx) = tokio::sync::mpsc::channel(16);
+    let sender = Arc::new(httpx_cluster::GossipProtocol::new("127.0.0.1:0", sender_tx));
+    let (receiver_tx, mut receiver_rx) = tokio::sync::mpsc::channel(16);
+    let receiver = Arc::new(httpx_cluster::GossipProtocol::new("127.0.0.1:0", receiver_tx));
+    let peers = vec![receiver.local_addr().unwrap().to_string()];
+
+    let s = sender.clone();
+    tokio::spawn(async move { s.listen().await });
+    let r = receiver.clone();
+    tokio::spawn(async move { r.listen().await });
+
+    let delta = |seq| IntentDelta { context_hash: 0xABCD, delta_true: 1, delta_false: 0, sequence_number: seq };
+    sender.broadcast(&peers, delta(1));
+    sender.broadcast(&[], delta(2)); // Dropped: recorded in the ring, never sent.
+    sender.broadcast(&peers, delta(3));
+
+    let mut received = Vec::new();
+    for _ in 0..3 {
+        let d = tokio::time::timeout(std::time::Duration::from_secs(1), receiver_rx.recv())
+            .await
+            .expect("Gap was not filled by retransmission")
+            .unwrap();
+        received.push(d.sequence_number);
+    }
+    assert_eq!(received, vec![1, 3, 2]);
+
+    let overhead = t.elapsed();
+    println!("test_gossip_retransmit_fills_gap: Testing Overhead = {:?}", overhead);
+}
+
+/// Verifies that a byte-identical replay of a delta is dropped as a duplicate.
+#[tokio::test]
+async fn test_gossip_drops_replayed_delta() {
+    use httpx_cluster::gossip::{GossipMessage, IntentDelta};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    let t = Instant::now();
+
+    let (delta_tx, mut delta_rx) = tokio::sync::mpsc::channel(16);
+    let node = Arc::new(httpx_cluster::GossipProtocol::new("127.0.0.1:0", delta_tx));
+    let node_addr = node.local_addr().unwrap();
+
+    let listener = node.clone();
+    tokio::spawn(async move { listener.listen().await });
+
+    let peer = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let delta = IntentDelta { context_hash: 0xABCD, delta_true: 1, delta_false: 0, sequence_number: 1 };
+    let payload = serde_json::to_vec(&GossipMessage::Delta(delta)).unwrap();
+    peer.send_to(&payload, node_addr).await.unwrap();
+    assert_eq!(delta_rx.recv().await.unwrap().sequence_number, 1);
+
+    // The identical datagram again: rejected by digest before the sequence check.
+    peer.send_to(&payload, node_addr).await.unwrap();
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(1);
+    while node.replays_dropped() == 0 && tokio::time::Instant::now() < deadline {
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+    assert_eq!(node.replays_dropped(), 1, "Replay must be counted as a duplicate");
+    assert!(delta_rx.try_recv().is_err(), "Replay must not reach the learner");
+
+    let overhead = t.elapsed();
+    println!("test_gossip_drops_replayed_delta: Testing Overhead = {:?}", overhead);
+}
+
+/// Verifies that unfilled gaps older than the retransmit window are
+/// forgotten as the sequence advances, so the missing set stays bounded.
+#[tokio::test]
+async fn test_gossip_missing_set_is_bounded_by_retransmit_window() {
+    use httpx_cluster::gossip::{GossipMessage, IntentDelta, RETRANSMIT_WINDOW};
+    use std::sync::Arc;
+
+    let t = Instant::now();
+
+    let (delta_tx, mut delta_rx) = tokio::sync::mpsc::channel(16);
+    let node = Arc::new(httpx_cluster::GossipProtocol::new("127.0.0.1:0", delta_tx));
+    let node_addr = node.local_addr().unwrap();
+
+    let listener = node.clone();
+    tokio::spawn(async move { listener.listen().await });
+
+    // Every delta skips ahead, and no retransmission ever arrives.
+    let peer = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    for seq in [1u64, 100, 1_000, 10_000] {
+        let delta = IntentDelta { context_hash: 0xABCD, delta_true: 1, delta_false: 0, sequence_number: seq };
+        let payload = serde_json::to_vec(&GossipMessage::Delta(delta)).unwrap();
+        peer.send_to(&payload, node_addr).await.unwrap();
+        assert_eq!(delta_rx.recv().await.unwrap().sequence_number, seq);
+        assert!(node.missing_len() <= RETRANSMIT_WINDOW, "Missing set grew to {}", node.missing_len());
+    }
+    assert_eq!(node.missing_len(), RETRANSMIT_WINDOW, "Only the last window of Seq 10000's gap is kept");
+
+    let overhead = t.elapsed();
+    println!("test_gossip_missing_set_is_bounded_by_retransmit_window: Testing Overhead = {:?}", overhead);
+}
+
+/// Verifies that learnings buffered while Sovereign are merged, broadcast
+/// and cleared automatically on recovery to Integrated.
+#[tokio::test]
+async fn test_reconciliation_flush_on_recovery() {
+    use httpx_cluster::{ClusterMode, ClusterOrchestrator};
+    use httpx_core::{ControlSignal, SessionMode};
+
+    let t = Instant::now();
+
+    let (_learn_tx, learn_rx) = httpx_core::learn_channel(1024);
+    let (worker_tx, mut worker_rx) = tokio::sync::mpsc::channel(16);
+    let mut orchestrator = ClusterOrchestrator::new(0, learn_rx, vec![worker_tx]);
+    let mode = orchestrator.mode_watch();
+
+    // Partition: 3 missed heartbeats drop the node to Sovereign.
+    for _ in 0..3 {
+        orchestrator.record_heartbeat(false).await;
+    }
+    assert_eq!(*mode.borrow(), ClusterMode::Sovereign);
+    assert!(matches!(
+        worker_rx.try_recv(),
+        Ok(ControlSignal::SetSessionMode(SessionMode::SovereignAutonomous))
+    ));
+
+    // Offline learning is buffered, not applied to the shadow trie.
+    for _ in 0..5 {
+        orchestrator.ingest(b"/offline", true).await;
+    }
+    assert_eq!(orchestrator.pending_reconciliation(), 1);
+    assert_eq!(orchestrator.shadow_trie().get_probability(b"/offline", true), 0.0);
+
+    // Recovery: 10 stable heartbeats return to Integrated and trigger the flush.
+    for _ in 0..10 {
+        orchestrator.record_heartbeat(true).await;
+    }
+    assert_eq!(*mode.borrow(), ClusterMode::Integrated);
+    assert_eq!(orchestrator.pending_reconciliation(), 0, "Buffer should be cleared after flush");
+    assert!(matches!(
+        worker_rx.try_recv(),
+        Ok(ControlSignal::SetSessionMode(SessionMode::ClusterIntegrated))
+    ));
+
+    let node = orchestrator.shadow_trie().get_node_at_path(b"/offline").expect("Offline path not merged");
+    assert_eq!(node.weights, [0, 5], "All 5 offline successes should be merged");
+
+    match worker_rx.try_recv() {
+        Ok(ControlSignal::SwapTrie(trie)) => {
+            assert_eq!(trie.get_probability(b"/offline", true), 1.0, "Broadcast trie must carry merged weights");
+        }
+        other => panic!("Expected SwapTrie broadcast after recovery, got {:?}", other),
+    }
+
+    let overhead = t.elapsed();
+    println!("test_reconciliation_flush_on_recovery: Testing Overhead = {:?}", overhead);
+}
+
+/// Verifies that a learning-driven Shadow-Swap keeps build-time routes.
+#[tokio::test]
+async fn test_shadow_swap_carries_base_routes() {
+    use httpx_cluster::ClusterOrchestrator;
+    use httpx_core::{ControlSignal, PredictiveEngine, Session};
+
+    let t = Instant::now();
+
+    let mut base = LinearIntentTrie::new(64);
+    base.warm(b"/index");
+    base.associate_payload(b"/index", 5, 1).unwrap();
+
+    let (_learn_tx, learn_rx) = httpx_core::learn_channel(1024);
+    let (worker_tx, mut worker_rx) = tokio::sync::mpsc::channel(16);
+    let mut orchestrator = ClusterOrchestrator::new(0, learn_rx, vec![worker_tx]).with_base_routes(base.clone());
+
+    // 1000 learning events on an unrelated path trigger a swap.
+    for _ in 0..1000 {
+        orchestrator.ingest(b"/learned", true).await;
+    }
+    let Ok(ControlSignal::SwapTrie(swapped)) = worker_rx.try_recv() else {
+        panic!("Expected a SwapTrie broadcast");
+    };
+    assert_eq!(swapped.get_probability(b"/learned", true), 1.0);
+
+    let engine = PredictiveEngine::new(true);
+    engine.swap_weights((*swapped).clone());
+    let session = Session::new("127.0.0.1:9000".parse().unwrap());
+    assert_eq!(engine.predict_for_path(&session, b"/index"), Some((5, 1)), "Base route must survive the swap");
+
+    let overhead = t.elapsed();
+    println!("test_shadow_swap_carries_base_routes: Testing Overhead = {:?}", overhead);
+}
+
+/// Verifies that reloaded routes replace the base routes, keep learned
+/// weights, and survive the next learning-driven swap.
+#[tokio::test]
+async fn test_reloaded_routes_survive_later_swaps() {
+    use httpx_cluster::ClusterOrchestrator;
+    use httpx_core::ControlSignal;
+
+    let t = Instant::now();
+
+    let mut base = LinearIntentTrie::new(64);
+    base.warm(b"/old");
+    base.associate_payload(b"/old", 5, 1).unwrap();
+
+    let (_learn_tx, learn_rx) = httpx_core::learn_channel(1024);
+    let (worker_tx, mut worker_rx) = tokio::sync::mpsc::channel(16);
+    let mut orchestrator = ClusterOrchestrator::new(0, learn_rx, vec![worker_tx]).with_base_routes(base);
+    for _ in 0..10 {
+        orchestrator.ingest(b"/learned", true).await;
+    }
+
+    let mut reloaded = LinearIntentTrie::new(64);
+    reloaded.warm(b"/new");
+    reloaded.associate_payload(b"/new", 6, 2).unwrap();
+    orchestrator.reload_routes(reloaded).await;
+    let Ok(ControlSignal::SwapTrie(swapped)) = worker_rx.try_recv() else {
+        panic!("A reload must swap at once");
+    };
+    assert_eq!(swapped.get_node_at_path(b"/new").map(|n| (n.payload_handle, n.version_id)), Some((6, 2)));
+    assert!(swapped.routes().iter().all(|r| r.path != b"/old"), "Routes dropped from the file must unbind");
+    assert_eq!(swapped.get_probability(b"/learned", true), 1.0, "Learned weights must survive a reload");
+
+    // The next learning-driven swap rebuilds from the reloaded base.
+    for _ in 0..1000 {
+        orchestrator.ingest(b"/learned", true).await;
+    }
+    let Ok(ControlSignal::SwapTrie(swapped)) = worker_rx.try_recv() else {
+        panic!("Expected a SwapTrie broadcast");
+    };
+    assert_eq!(swapped.get_node_at_path(b"/new").map(|n| n.payload_handle), Some(6), "A swap must not revert the reload");
+    assert!(swapped.routes().iter().all(|r| r.path != b"/old"));
+
+    let overhead = t.elapsed();
+    println!("test_reloaded_routes_survive_later_swaps: Testing Overhead = {:?}", overhead);
+}
+
+/// Verifies that the shadow trie inherits a hashed base trie's configuration across swaps.
+#[tokio::test]
+async fn test_shadow_trie_follows_base_trie_config() {
+    use httpx_cluster::ClusterOrchestrator;
+    use httpx_core::ControlSignal;
+    use httpx_dsa::PathEncoding;
+
+    let t = Instant::now();
+
+    let mut base = LinearIntentTrie::new(64)
+        .with_path_encoding(PathEncoding::Hashed)
+        .with_max_depth_bits(96)
+        .with_node_limit(4096);
+    base.warm(b"/catalog");
+    base.associate_payload(b"/catalog", 9, 3).unwrap();
+
+    let (_learn_tx, learn_rx) = httpx_core::learn_channel(1024);
+    let (worker_tx, mut worker_rx) = tokio::sync::mpsc::channel(16);
+    let mut orchestrator = ClusterOrchestrator::new(0, learn_rx, vec![worker_tx]).with_base_routes(base);
+    assert_eq!(orchestrator.shadow_trie().path_encoding(), PathEncoding::Hashed);
+    assert_eq!(orchestrator.shadow_trie().max_depth_bits(), 96);
+    assert_eq!(orchestrator.shadow_trie().node_limit(), 4096);
+
+    for _ in 0..1000 {
+        orchestrator.ingest(b"/learned", true).await;
+    }
+    let Ok(ControlSignal::SwapTrie(swapped)) = worker_rx.try_recv() else {
+        panic!("Expected a SwapTrie broadcast");
+    };
+    assert_eq!(swapped.path_encoding(), PathEncoding::Hashed);
+    assert_eq!(swapped.get_node_at_path(b"/catalog").map(|n| (n.payload_handle, n.version_id)), Some((9, 3)));
+    assert_eq!(swapped.get_probability(b"/learned", true), 1.0);
+
+    // Reloading onto a different encoding rebuilds the shadow instead of mixing bit paths.
+    let mut reloaded = LinearIntentTrie::new(64);
+    reloaded.warm(b"/plain");
+    reloaded.associate_payload(b"/plain", 4, 1).unwrap();
+    orchestrator.reload_routes(reloaded).await;
+    let Ok(ControlSignal::SwapTrie(swapped)) = worker_rx.try_recv() else {
+        panic!("A reload must swap at once");
+    };
+    assert_eq!(swapped.path_encoding(), PathEncoding::Identity);
+    assert_eq!(swapped.get_node_at_path(b"/plain").map(|n| n.payload_handle), Some(4));
+
+    let overhead = t.elapsed();
+    println!("test_shadow_trie_follows_base_trie_config: Testing Overhead = {:?}", overhead);
+}
+
+/// Verifies that resetting learning zeroes every weight but keeps routes resolvable.
+#[tokio::test]
+async fn test_reset_learning_keeps_routes() {
+    use httpx_cluster::ClusterOrchestrator;
+    use httpx_core::ControlSignal;
+
+    let t = Instant::now();
+
+    let mut trie = LinearIntentTrie::new(64);
+    trie.warm(b"/index");
+    trie.associate_payload(b"/index", 5, 1).unwrap();
+    for path in [&b"/index"[..], b"/a", b"/b"] {
+        trie.observe(path, true);
+        trie.observe(path, false);
+    }
+    let routes = trie.routes();
+
+    trie.reset_learning();
+    let mut trained = 0;
+    trie.visit(|_, node| trained += (node.weights != [0, 0]) as usize);
+    assert_eq!(trained, 0, "Every weight must be forgotten");
+    assert_eq!(trie.routes(), routes, "Routes must survive the reset");
+    assert_eq!(trie.get_node_at_path(b"/index").unwrap().payload_handle, 5);
+
+    let (_learn_tx, learn_rx) = httpx_core::learn_channel(1024);
+    let (worker_tx, mut worker_rx) = tokio::sync::mpsc::channel(16);
+    let mut orchestrator = ClusterOrchestrator::new(0, learn_rx, vec![worker_tx]);
+    orchestrator.ingest(b"/a", true).await;
+    orchestrator.reset_learning().await;
+    assert_eq!(orchestrator.shadow_trie().get_node_at_path(b"/a").unwrap().weights, [0, 0]);
+    assert!(matches!(worker_rx.try_recv(), Ok(ControlSignal::ResetLearning)));
+
+    let overhead = t.elapsed();
+    println!("test_reset_learning_keeps_routes: Testing Overhead = {:?}", overhead);
+}
+
+/// Verifies that `run` returns once all learning senders drop, after a final swap.
+#[tokio::test]
+async fn test_orchestrator_exits_when_learning_senders_drop() {
+    use httpx_cluster::ClusterOrchestrator;
+    use httpx_core::ControlSignal;
+    use std::time::Duration;
+
+    let t = Instant::now();
+
+    let (mut learn_tx, learn_rx) = httpx_core::learn_channel(1024);
+    let (worker_tx, mut worker_rx) = tokio::sync::mpsc::channel(16);
+    // A one-hour cadence: only the shutdown path can swap these events.
+    let hour = Duration::from_secs(3600);
+    let orchestrator = ClusterOrchestrator::new(0, learn_rx, vec![worker_tx]).with_swap_cadence(hour, hour);
+    let run = tokio::spawn(orchestrator.run());
+
+    let mut second_tx = learn_tx.clone();
+    learn_tx.send(b"/pending", true);
+    second_tx.send(b"/pending", true);
+    drop(learn_tx);
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert!(!run.is_finished(), "One live sender must keep the orchestrator running");
+    drop(second_tx);
+
+    tokio::time::timeout(Duration::from_secs(1), run)
+        .await
+        .expect("run must return once every sender is dropped")
+        .unwrap();
+    match worker_rx.try_recv() {
+        Ok(ControlSignal::SwapTrie(trie)) => {
+            assert_eq!(trie.get_node_at_path(b"/pending").unwrap().weights, [0, 2], "Final swap must carry pending events");
+        }
+        other => panic!("Expected a final SwapTrie, got {:?}", other),
+    }
+
+    let overhead = t.elapsed();
+    println!("test_orchestrator_exits_when_learning_senders_drop: Testing Overhead = {:?}", overhead);
+}
+
+/// Verifies that a zero minimum swap interval is clamped to a 1ms tick
+/// instead of panicking inside `run`, and still swaps learned events.
+#[tokio::test]
+async fn test_zero_swap_cadence_is_clamped() {
+    use httpx_cluster::ClusterOrchestrator;
+    use httpx_core::ControlSignal;
+    use std::time::Duration;
+
+    let t = Instant::now();
+
+    let (mut learn_tx, learn_rx) = httpx_core::learn_channel(1024);
+    let (worker_tx, mut worker_rx) = tokio::sync::mpsc::channel(16);
+    let orchestrator = ClusterOrchestrator::new(0, learn_rx, vec![worker_tx]).with_swap_cadence(Duration::ZERO, Duration::ZERO);
+    let run = tokio::spawn(orchestrator.run());
+
+    learn_tx.send(b"/zero", true);
+    let swapped = tokio::time::timeout(Duration::from_secs(1), worker_rx.recv()).await.expect("No swap within 1s");
+    assert!(matches!(swapped, Some(ControlSignal::SwapTrie(_))), "Expected a SwapTrie, got {:?}", swapped);
+    assert!(!run.is_finished(), "run must keep ticking with a zero cadence");
+
+    drop(learn_tx);
+    tokio::time::timeout(Duration::from_secs(1), run).await.expect("run must exit").unwrap();
+
+    let overhead = t.elapsed();
+    println!("test_zero_swap_cadence_is_clamped: Testing Overhead = {:?}", overhead);
+}
+
+/// Verifies that the swap interval tightens under a learning burst and
+/// relaxes again once learning goes quiet.
+#[tokio::test]
+async fn test_swap_cadence_follows_learning_rate() {
+    use httpx_cluster::{ClusterOrchestrator, LearningRateMeter};
+    use std::time::Duration;
+
+    let t = Instant::now();
+
+    let (min, max) = (Duration::from_millis(10), Duration::from_millis(400));
+    let (_learn_tx, learn_rx) = httpx_core::learn_channel(1024);
+    let (worker_tx, _worker_rx) = tokio::sync::mpsc::channel(16);
+    let mut orchestrator = ClusterOrchestrator::new(0, learn_rx, vec![worker_tx]).with_swap_cadence(min, max);
+    assert_eq!(orchestrator.learning_rate(), 0.0);
+    assert_eq!(orchestrator.swap_interval(), max, "Idle orchestrator swaps at the slowest cadence");
+
+    // Burst: ~1500 events inside one window (~15k events/sec).
+    for i in 0..1500u32 {
+        orchestrator.ingest(format!("/burst/{}", i % 8).as_bytes(), true).await;
+    }
+    tokio::time::sleep(LearningRateMeter::WINDOW).await;
+    let burst_rate = orchestrator.learning_rate();
+    let burst_interval = orchestrator.swap_interval();
+    // The burst may straddle a window edge, so only bound it loosely.
+    assert!(burst_rate > 2_000.0, "burst rate {} too low", burst_rate);
+    assert!(burst_interval < max * 3 / 4, "burst interval {:?} should shorten", burst_interval);
+
+    // Quiet: five empty windows decay the rate by 2^-5.
+    tokio::time::sleep(LearningRateMeter::WINDOW * 5).await;
+    let quiet_rate = orchestrator.learning_rate();
+    let quiet_interval = orchestrator.swap_interval();
+    assert!(quiet_rate < burst_rate / 16.0, "quiet rate {} should decay", quiet_rate);
+    assert!(quiet_interval > burst_interval, "quiet interval {:?} should lengthen", quiet_interval);
+    assert!(quiet_interval > max * 9 / 10);
+
+    let overhead = t.elapsed();
+    println!("test_swap_cadence_follows_learning_rate: Testing Overhead = {:?}", overhead);
+}
+
+/// Verifies that a gossip node bound to an IPv6 address round-trips a delta
+/// to a v6 peer, and reaches a v4 peer through its dual-stack socket.
+#[tokio::test]
+async fn test_gossip_ipv6_roundtrip() {
+    use httpx_cluster::gossip::IntentDelta;
+    use std::sync::Arc;
+
+    let t = Instant::now();
+
+    let (sender_tx, _sender_rx) = tokio::sync::mpsc::channel(16);
+    let sender = Arc::new(httpx_cluster::GossipProtocol::new("[::]:0", sender_tx));
+    assert!(sender.local_addr().unwrap().is_ipv6());
+
+    let (v6_tx, mut v6_rx) = tokio::sync::mpsc::channel(16);
+    let v6_peer = Arc::new(httpx_cluster::GossipProtocol::new("[::1]:0", v6_tx));
+    let (v4_tx, mut v4_rx) = tokio::sync::mpsc::channel(16);
+    let v4_peer = Arc::new(httpx_cluster::GossipProtocol::new("127.0.0.1:0", v4_tx));
+
+    for node in [v6_peer.clone(), v4_peer.clone()] {
+        tokio::spawn(async move { node.listen().await });
+    }
+
+    let peers = vec![v6_peer.local_addr().unwrap().to_string(), v4_peer.local_addr().unwrap().to_string()];
+    sender.broadcast(&peers, IntentDelta { context_hash: 0x66, delta_true: 1, delta_false: 0, sequence_number: 1 });
+
+    for rx in [&mut v6_rx, &mut v4_rx] {
+        let d = tokio::time::timeout(std::time::Duration::from_secs(1), rx.recv())
+            .await
+            .expect("Delta not delivered over dual-stack gossip")
+            .unwrap();
+        assert_eq!(d.context_hash, 0x66);
+    }
+
+    let overhead = t.elapsed();
+    println!("test_gossip_ipv6_roundtrip: Testing Overhead = {:?}", overhead);
+}
+
+/// Verifies that a bind conflict surfaces as `GossipError::AddressInUse`
+/// instead of panicking, so the orchestrator can retry or degrade.
+#[tokio::test]
+async fn test_gossip_try_new_reports_address_in_use() {
+    use httpx_cluster::{GossipError, GossipProtocol};
+
+    let t = Instant::now();
+
+    let (first_tx, _first_rx) = tokio::sync::mpsc::channel(16);
+    let first = GossipProtocol::try_new("127.0.0.1:0", first_tx).expect("First bind must succeed");
+    let taken = first.local_addr().unwrap();
+
+    let (second_tx, _second_rx) = tokio::sync::mpsc::channel(16);
+    match GossipProtocol::try_new(&taken.to_string(), second_tx) {
+        Err(GossipError::AddressInUse(addr)) => assert_eq!(addr, taken),
+        Err(other) => panic!("Expected AddressInUse, got {}", other),
+        Ok(_) => panic!("Second bind to {} must fail", taken),
+    }
+
+    let (bad_tx, _bad_rx) = tokio::sync::mpsc::channel(16);
+    assert!(matches!(GossipProtocol::try_new("not-an-address", bad_tx), Err(GossipError::InvalidAddress(_))));
+
+    let overhead = t.elapsed();
+    println!("test_gossip_try_new_reports_address_in_use: Testing Overhead = {:?}", overhead);
+}
+
+/// Verifies that `run` drives the cluster mode from gossip: a silent peer
+/// drops the node to Sovereign, and its heartbeats bring it back.
+#[tokio::test]
+async fn test_orchestrator_mode_follows_gossip_heartbeats() {
+    use httpx_cluster::{ClusterMode, ClusterOrchestrator, GossipProtocol};
+    use httpx_core::{ControlSignal, SessionMode};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    let t = Instant::now();
+
+    let (node_tx, _node_rx) = tokio::sync::mpsc::channel(16);
+    let node = Arc::new(GossipProtocol::new("127.0.0.1:0", node_tx));
+    let (peer_tx, _peer_rx) = tokio::sync::mpsc::channel(16);
+    let peer = Arc::new(GossipProtocol::new("127.0.0.1:0", peer_tx));
+    let node_addr = vec![node.local_addr().unwrap().to_string()];
+
+    let listener = node.clone();
+    tokio::spawn(async move { listener.listen().await });
+
+    let (_learn_tx, learn_rx) = httpx_core::learn_channel(1024);
+    let (worker_tx, mut worker_rx) = tokio::sync::mpsc::channel(16);
+    let orchestrator = ClusterOrchestrator::new(0, learn_rx, vec![worker_tx])
+        .with_gossip(node.clone())
+        .with_peers(vec![peer.local_addr().unwrap().to_string()])
+        .with_heartbeat_period(Duration::from_millis(20));
+    let mut mode = orchestrator.mode_watch();
+    tokio::spawn(orchestrator.run());
+
+    // The peer stays silent: 3 empty heartbeat periods partition the node.
+    tokio::time::timeout(Duration::from_secs(2), mode.wait_for(|m| *m == ClusterMode::Sovereign))
+        .await
+        .expect("Silent peer must drop the node to Sovereign")
+        .unwrap();
+    assert!(matches!(
+        worker_rx.recv().await,
+        Some(ControlSignal::SetSessionMode(SessionMode::SovereignAutonomous))
+    ));
+
+    // The peer comes back and pulses faster than the node's period.
+    let pulser = peer.clone();
+    tokio::spawn(async move {
+        loop {
+            pulser.heartbeat(&node_addr);
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    });
+    tokio::time::timeout(Duration::from_secs(2), mode.wait_for(|m| *m == ClusterMode::Integrated))
+        .await
+        .expect("Peer heartbeats must restore Integrated mode")
+        .unwrap();
+    assert!(matches!(
+        worker_rx.recv().await,
+        Some(ControlSignal::SetSessionMode(SessionMode::ClusterIntegrated))
+    ));
+
+    let overhead = t.elapsed();
+    println!("test_orchestrator_mode_follows_gossip_heartbeats: Testing Overhead = {:?}", overhead);
+}
@@ -3,8 +3,8 @@
 //! Validates Procrustean Header Template creation and hot-patching
 //! across SecureSlab memory boundaries.
 
-use httpx_dsa::SecureSlab;
-use httpx_codec::HeaderTemplate;
+use httpx_dsa::{SecureSlab, SlabError};
+use httpx_codec::{DoubleBufferedTemplate, HeaderTemplate, HeapTemplate, ProbabilisticCodec};
 use std::time::Instant;
 
 /// Verifies that `HeaderTemplate::new` correctly stores base headers
@@ -83,3 +83,156 @@ fn test_header_template_patch_content_length() {
     let overhead = t.elapsed();
     println!("test_header_template_patch_content_length: Testing Overhead = {:?}", overhead);
 }
+
+/// Verifies that `DoubleBufferedTemplate` readers never observe a torn
+/// header while another thread keeps patching Date and Content-Length.
+#[test]
+fn test_double_buffered_template_has_no_torn_reads() {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    let t = Instant::now();
+
+    let slab = Arc::new(SecureSlab::new(8));
+    let base = b"HTTP/1.1 200 OK\r\nDate: AAAAAAAAAAAAAAAAAAAAAAAAAAAAA\r\nContent-Length: 0         \r\n\r\n";
+    let template = Arc::new(DoubleBufferedTemplate::new(&slab, [1, 2], base));
+    let date = 23..23 + 29;
+    let content_length = 80..90;
+    let done = Arc::new(AtomicBool::new(false));
+
+    let writer = {
+        let (slab, template, done) = (slab.clone(), template.clone(), done.clone());
+        std::thread::spawn(move || {
+            for i in 0..20_000u32 {
+                let fill = if i % 2 == 0 { b'B' } else { b'A' };
+                template.patch_date(&slab, &[fill; 29]).unwrap();
+                template.patch_content_length(&slab, if i % 2 == 0 { 2_222_222_222 } else { 1_111_111_111 }).unwrap();
+            }
+            done.store(true, Ordering::Release);
+        })
+    };
+
+    let readers: Vec<_> = (0..2)
+        .map(|_| {
+            let (slab, template, done) = (slab.clone(), template.clone(), done.clone());
+            let (date, content_length) = (date.clone(), content_length.clone());
+            std::thread::spawn(move || {
+                let mut reads = 0u64;
+                while !done.load(Ordering::Acquire) {
+                    let block = template.read(&slab);
+                    let d = &block[date.clone()];
+                    assert!(d.iter().all(|&b| b == d[0]), "Torn Date field: {:?}", std::str::from_utf8(d));
+                    let cl = &block[content_length.clone()];
+                    assert!(
+                        cl.iter().all(|&b| b == cl[0]) || cl.starts_with(b"0 "),
+                        "Torn Content-Length field: {:?}",
+                        std::str::from_utf8(cl)
+                    );
+                    reads += 1;
+                }
+                reads
+            })
+        })
+        .collect();
+
+    writer.join().unwrap();
+    let reads: u64 = readers.into_iter().map(|r| r.join().unwrap()).sum();
+    assert!(reads > 0);
+
+    // Both buffers rest at an even version, and the last patch is published.
+    assert!(slab.get_version(1).is_multiple_of(2) && slab.get_version(2).is_multiple_of(2));
+    let block = template.read(&slab);
+    assert_eq!(&block[date], &[b'A'; 29]);
+    assert_eq!(&block[content_length], b"1111111111");
+
+    let overhead = t.elapsed();
+    println!("test_double_buffered_template_has_no_torn_reads: {} consistent reads, Testing Overhead = {:?}", reads, overhead);
+}
+
+/// Verifies that `DoubleBufferedTemplate` refuses to overwrite the inactive
+/// buffer while a send still holds it, and publishes once it is released.
+#[test]
+fn test_double_buffered_template_refuses_in_flight_buffer() {
+    let t = Instant::now();
+
+    let slab = SecureSlab::new(8);
+    let base = b"HTTP/1.1 200 OK\r\nDate: AAAAAAAAAAAAAAAAAAAAAAAAAAAAA\r\nContent-Length: 0         \r\n\r\n";
+    let template = DoubleBufferedTemplate::new(&slab, [1, 2], base);
+    let date = 23..23 + 29;
+
+    // A send picks up the published block, then a patch moves it to slot 2.
+    let sent = template.active_handle();
+    slab.increment_rc(sent as usize);
+    template.patch_date(&slab, &[b'B'; 29]).unwrap();
+    assert_eq!(template.active_handle(), 2);
+
+    // The next patch would reuse slot 1 under the in-flight send.
+    let version = slab.get_version(sent as usize);
+    assert_eq!(template.patch_date(&slab, &[b'C'; 29]), Err(SlabError::InFlight(sent as usize)));
+    assert_eq!(template.active_handle(), 2);
+    assert_eq!(slab.get_version(sent as usize), version);
+    assert_eq!(&template.read(&slab)[date.clone()], &[b'B'; 29]);
+
+    slab.decrement_rc(sent as usize);
+    template.patch_date(&slab, &[b'C'; 29]).unwrap();
+    assert_eq!(template.active_handle(), sent);
+    assert_eq!(&template.read(&slab)[date], &[b'C'; 29]);
+    assert!(slab.get_version(sent as usize).is_multiple_of(2));
+
+    let overhead = t.elapsed();
+    println!("test_double_buffered_template_refuses_in_flight_buffer: Testing Overhead = {:?}", overhead);
+}
+
+/// Builds `httpx-codec` without the `slab` feature, i.e. the feature set used
+/// for `wasm32-unknown-unknown`, so a slab dependency creeping back into the
+/// portable path fails here even without a wasm toolchain installed.
+#[test]
+fn test_codec_builds_without_slab() {
+    let t = Instant::now();
+
+    let status = std::process::Command::new(env!("CARGO"))
+        .args(["check", "--quiet", "-p", "httpx-codec", "--no-default-features"])
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .env("CARGO_TARGET_DIR", std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("codec-portable"))
+        .status()
+        .expect("failed to invoke cargo");
+    assert!(status.success(), "httpx-codec must build with --no-default-features");
+
+    let overhead = t.elapsed();
+    println!("test_codec_builds_without_slab: Testing Overhead = {:?}", overhead);
+}
+
+/// Exercises the slab-free path: heap-backed template patching plus
+/// codec projection/reconstruction.
+#[test]
+fn test_heap_template_projection_roundtrip() {
+    let t = Instant::now();
+
+    let base = b"HTTP/1.1 200 OK\r\nDate: Thu, 01 Jan 1970 00:00:00 GMT\r\nContent-Length: 0         \r\n\r\n";
+    let mut template = HeapTemplate::new(base);
+    template.patch_date(b"Wed, 11 Feb 2026 22:00:00 GMT");
+    template.patch_content_length(4096);
+
+    let block = template.as_bytes();
+    assert_eq!(block.len(), 128);
+    let haystack = std::str::from_utf8(block).unwrap_or("");
+    assert!(haystack.contains("Wed, 11 Feb 2026"), "Date patch not found: {:?}", block);
+    assert!(haystack.contains("4096"), "Content-Length patch not found: {:?}", block);
+
+    // Heap and slab templates must lay out identical bytes.
+    let slab = SecureSlab::new(8);
+    let slab_template = HeaderTemplate::new(&slab, 0, base);
+    slab_template.patch_date(&slab, b"Wed, 11 Feb 2026 22:00:00 GMT");
+    slab_template.patch_content_length(&slab, 4096);
+    let stored = unsafe { std::slice::from_raw_parts(slab.get_slot(0), 128) };
+    assert_eq!(stored, block, "Heap template diverged from slab template");
+
+    let codec = ProbabilisticCodec::new();
+    let projection = codec.project_header(&block[..base.len()]);
+    assert!(!projection.is_empty());
+    let reconstructed = codec.reconstruct_header(&projection);
+    assert!(!reconstructed.is_empty());
+
+    let overhead = t.elapsed();
+    println!("test_heap_template_projection_roundtrip: Testing Overhead = {:?}", overhead);
+}
@@ -11,3 +11,740 @@ fn test_engine_initialization() {
     let context = [0u8; 4];
     let _ = engine.fire_push_if_likely(&session, &context);
 }
+
+#[test]
+fn test_sequence_probability_matches_chained_lookups() {
+    use httpx_dsa::LinearIntentTrie;
+
+    let mut trie = LinearIntentTrie::new(1024);
+    let context = b"GET /";
+
+    // "/a" lands three times in four; "/ab" every time it is requested.
+    trie.observe_weighted(b"GET /a", true, 3);
+    trie.observe(b"GET /a", false);
+    trie.observe_weighted(b"GET /ab", true, 2);
+    trie.warm(b"GET /ac");
+
+    let joint = trie.sequence_probability(context, b"ab");
+    let chained = trie.get_probability(b"GET /a", true) * trie.get_probability(b"GET /ab", true);
+    assert!((joint - chained).abs() < f32::EPSILON, "joint {} != chained {}", joint, chained);
+    assert!((joint - 0.75).abs() < f32::EPSILON);
+    assert!((trie.sequence_probability(context, b"a") - 0.75).abs() < f32::EPSILON);
+
+    // A warmed route nobody requested yet, or any missing node, scores zero.
+    assert_eq!(trie.sequence_probability(context, b"ac"), 0.0);
+    assert_eq!(trie.sequence_probability(context, b"b"), 0.0);
+    assert_eq!(trie.sequence_probability(b"POST /", b"a"), 0.0);
+}
+
+#[test]
+fn test_predictive_depth_bounds_lookahead() {
+    use httpx_dsa::LinearIntentTrie;
+
+    let mut trie = LinearIntentTrie::new(1024);
+    // Routes 1, 2, 3 and 4 bytes past the matched context "/a".
+    for (handle, path) in [&b"/a/"[..], b"/a/b", b"/a/bc", b"/a/bcd"].iter().enumerate() {
+        trie.observe(path, true);
+        trie.associate_payload(path, handle as u32 + 1, 1).unwrap();
+    }
+
+    let engine = PredictiveEngine::new(true).with_predictive_depth(2);
+    engine.swap_weights(trie);
+    assert_eq!(engine.predictive_depth(), 2);
+
+    let session = Session::new("127.0.0.1:8080".parse().unwrap());
+    let mut out = [(0u32, 0u32); 8];
+    let n = engine.predict_top_k(&session, b"/a", &mut out);
+    let mut handles: Vec<u32> = out[..n].iter().map(|&(h, _)| h).collect();
+    handles.sort_unstable();
+    assert_eq!(handles, vec![1, 2], "Depth 2 must never speculate past \"/a/b\"");
+
+    // Depth 0 turns speculation off: only the exact route survives, and the
+    // bit-level pushes never fire, even on a trained trie.
+    let mut trie = LinearIntentTrie::new(1024);
+    for (handle, path) in [&b"/a/"[..], b"/a/b"].iter().enumerate() {
+        trie.observe(path, true);
+        trie.associate_payload(path, handle as u32 + 1, 1).unwrap();
+    }
+    let idle = PredictiveEngine::new(true).with_predictive_depth(0);
+    idle.swap_weights(trie);
+    let session = Session::new("127.0.0.1:8080".parse().unwrap());
+    assert_eq!(idle.predict_top_k(&session, b"/a", &mut out), 0, "No exact route at \"/a\", nothing to speculate");
+    assert_eq!(idle.predict_top_k(&session, b"/a/", &mut out), 1);
+    assert_eq!(out[0].0, 1, "Only the exact route for \"/a/\", never \"/a/b\"");
+    assert_eq!(idle.fire_push_if_likely(&session, b"/a"), None);
+    assert_eq!(idle.fire_push_probabilistic(&session, b"/a"), None);
+}
+
+#[test]
+fn test_predict_top_k_skips_continuations_below_threshold() {
+    use httpx_dsa::LinearIntentTrie;
+
+    let mut trie = LinearIntentTrie::new(1024);
+    // "/a/b" is a hit every time, "/a/c" only once in four.
+    trie.observe_weighted(b"/a/b", true, 4);
+    trie.observe_weighted(b"/a/c", true, 1);
+    trie.observe_weighted(b"/a/c", false, 3);
+    trie.associate_payload(b"/a/b", 1, 1).unwrap();
+    trie.associate_payload(b"/a/c", 2, 1).unwrap();
+
+    let mut out = [(0u32, 0u32); 8];
+    assert_eq!(trie.top_continuations_within(b"/a", 4, 0.0, &mut out), 2);
+    assert_eq!(trie.top_continuations_within(b"/a", 4, 0.85, &mut out), 1);
+    assert_eq!(out[0], (1, 1));
+
+    let engine = PredictiveEngine::new(true);
+    engine.swap_weights(trie);
+    let session = Session::new("127.0.0.1:8080".parse().unwrap());
+    let n = engine.predict_top_k(&session, b"/a", &mut out);
+    assert_eq!(&out[..n], &[(1, 1)], "A 25% continuation must not be pushed");
+}
+
+#[test]
+fn test_structural_eq_ignores_insertion_order() {
+    use httpx_dsa::LinearIntentTrie;
+
+    let mut a = LinearIntentTrie::new(1024);
+    let mut b = LinearIntentTrie::new(1024);
+    a.observe(b"/alpha", true);
+    a.observe(b"/beta", false);
+    a.associate_payload(b"/alpha", 7, 1).unwrap();
+    b.observe(b"/beta", false);
+    b.observe(b"/alpha", true);
+    b.associate_payload(b"/alpha", 7, 1).unwrap();
+
+    assert!(a.structural_eq(&b), "Same paths in a different pool order must compare equal");
+    assert!(a.diff(&b).is_empty());
+}
+
+#[test]
+fn test_diff_reports_weight_changes() {
+    use httpx_dsa::LinearIntentTrie;
+
+    let mut a = LinearIntentTrie::new(1024);
+    a.observe(b"/x", true);
+    let mut b = a.clone();
+    b.observe(b"/x", true);
+
+    assert!(!a.structural_eq(&b));
+    let diffs = a.diff(&b);
+    assert_eq!(diffs.len(), 1, "Only the terminal weight moved: {:?}", diffs);
+    assert_eq!(diffs[0].path, b"/x");
+    assert_eq!(diffs[0].depth, 16);
+    assert_eq!(diffs[0].ours.unwrap().weights, [0, 1]);
+    assert_eq!(diffs[0].theirs.unwrap().weights, [0, 2]);
+}
+
+#[test]
+fn test_diff_reports_structural_changes() {
+    use httpx_dsa::LinearIntentTrie;
+
+    let mut a = LinearIntentTrie::new(1024);
+    a.warm(b"/a");
+    let mut b = a.clone();
+    // "/a" -> "/ab": eight new nodes below the shared prefix.
+    b.warm(b"/ab");
+    b.associate_payload(b"/ab", 3, 1).unwrap();
+
+    assert!(!a.structural_eq(&b));
+    let diffs = a.diff(&b);
+    assert_eq!(diffs.len(), 8);
+    assert!(diffs.iter().all(|d| d.ours.is_none() && d.theirs.is_some()));
+    let leaf = diffs.iter().find(|d| d.depth == 24).expect("terminal node listed");
+    assert_eq!(leaf.path, b"/ab");
+    assert_eq!(leaf.theirs.unwrap().payload_handle, 3);
+    assert!(!b.structural_eq(&a), "Equality must be symmetric");
+}
+
+#[test]
+fn test_visit_reaches_every_node() {
+    use httpx_dsa::LinearIntentTrie;
+
+    let mut trie = LinearIntentTrie::new(1024);
+    trie.warm(b"/a");
+    trie.warm(b"/ab");
+    trie.warm(b"/b");
+    trie.associate_payload(b"/ab", 3, 1).unwrap();
+
+    let mut count = 0;
+    let mut payloads = Vec::new();
+    trie.visit(|path, node| {
+        count += 1;
+        if node.payload_handle > 0 {
+            payloads.push((path.to_vec(), node.payload_handle));
+        }
+    });
+    assert_eq!(count, trie.node_count());
+    assert_eq!(payloads, vec![(b"/ab".to_vec(), 3)]);
+}
+
+#[test]
+fn test_probabilistic_push_rate_tracks_likelihood() {
+    use httpx_dsa::LinearIntentTrie;
+
+    let context = b"GET /maybe";
+    let mut trie = LinearIntentTrie::new(1024);
+    for bit in [true, true, true, false, false] {
+        trie.observe(context, bit);
+    }
+    assert!((trie.get_probability(context, true) - 0.6).abs() < f32::EPSILON);
+
+    let engine = PredictiveEngine::new(true);
+    engine.swap_weights(trie);
+    let session = Session::new("127.0.0.1:8080".parse().unwrap()).with_seed(0x5EED);
+
+    const TRIALS: usize = 20_000;
+    let mut pushes = 0;
+    for _ in 0..TRIALS {
+        session.replenish_credits();
+        if let Some(bit) = engine.fire_push_probabilistic(&session, context) {
+            assert!(bit, "Only the dominant prediction may be pushed");
+            pushes += 1;
+        }
+    }
+    let rate = pushes as f64 / TRIALS as f64;
+    assert!((rate - 0.6).abs() < 0.02, "push rate {} should converge near 0.6", rate);
+
+    // The hard threshold never pushes this context.
+    assert_eq!(engine.fire_push_if_likely(&session, context), None);
+}
+
+#[test]
+fn test_active_sequence_tracks_swapped_trie() {
+    use httpx_dsa::LinearIntentTrie;
+    use std::sync::Arc;
+
+    let engine = Arc::new(PredictiveEngine::new(true));
+    assert_eq!(engine.active_sequence(), 0);
+
+    let mut trie = LinearIntentTrie::new(1024);
+    trie.sequence_number = 42;
+    engine.swap_weights(trie);
+    assert_eq!(engine.active_sequence(), 42);
+
+    // Readers racing a swap only ever observe a fully installed generation.
+    let reader = {
+        let engine = engine.clone();
+        std::thread::spawn(move || {
+            for _ in 0..10_000 {
+                let seq = engine.active_sequence();
+                assert!(seq == 42 || seq == 43, "unexpected generation {}", seq);
+            }
+        })
+    };
+    let mut next = LinearIntentTrie::new(1024);
+    next.sequence_number = 43;
+    engine.swap_weights(next);
+    reader.join().unwrap();
+    assert_eq!(engine.active_sequence(), 43);
+}
+
+#[test]
+fn test_trie_reserve_prevents_realloc_during_warm() {
+    use httpx_dsa::LinearIntentTrie;
+
+    let routes: [&[u8]; 4] = [b"/api/v1/users", b"/api/v1/orders", b"/api/v2/users", b"/static/app.js"];
+
+    let mut trie = LinearIntentTrie::new(16);
+    assert!(trie.would_realloc(routes));
+
+    let needed = trie.nodes_needed(routes);
+    // Shared prefixes ("/api/v", "/api/v1/") are only counted once.
+    assert!(needed < routes.iter().map(|r| r.len() * 8).sum::<usize>());
+    trie.reserve(needed);
+    assert!(!trie.would_realloc(routes));
+
+    let capacity = trie.capacity();
+    let before = trie.node_count();
+    for route in routes {
+        trie.warm(route);
+        trie.observe(route, true);
+    }
+    assert_eq!(trie.node_count(), before + needed, "nodes_needed must be exact");
+    assert_eq!(trie.capacity(), capacity, "warm-up must not reallocate the node pool");
+    assert_eq!(trie.nodes_needed(routes), 0);
+}
+
+#[test]
+fn test_add_credits_grants_exact_pushes() {
+    let session = Session::new("127.0.0.1:8080".parse().unwrap()).with_max_credits(12);
+    while session.consume_credit() {}
+    assert!(!session.has_credit());
+
+    session.add_credits(5);
+    let pushes = std::iter::from_fn(|| session.consume_credit().then_some(())).count();
+    assert_eq!(pushes, 5);
+
+    session.add_credits(3);
+    session.add_credits(usize::MAX);
+    assert_eq!(session.credits(), 12, "add_credits saturates at the maximum");
+    session.set_credits(2);
+    assert_eq!(session.credits(), 2);
+    session.set_credits(100);
+    assert_eq!(session.credits(), 12);
+    session.replenish_credits();
+    assert_eq!(session.credits(), httpx_core::session::INITIAL_IIW_CREDITS);
+}
+
+#[test]
+fn test_prefix_cached_lookup_matches_cold_lookup() {
+    use httpx_dsa::LinearIntentTrie;
+
+    let build = |prefix: &str, routes: u32| {
+        let mut trie = LinearIntentTrie::new(1024);
+        trie.warm(b"/health");
+        trie.associate_payload(b"/health", 999, 1).unwrap();
+        for i in 0..routes {
+            let path = format!("{}{}", prefix, i);
+            trie.warm(path.as_bytes());
+            trie.associate_payload(path.as_bytes(), i + 1, 7).unwrap();
+        }
+        trie
+    };
+    // Interleaves shared-prefix hits, prefix changes, misses and root-level paths.
+    let probes: Vec<String> = (0..40)
+        .flat_map(|i| {
+            [
+                format!("/api/v1/catalog/item/{}", i % 25),
+                format!("/api/v1/catalog/{}", i),
+                "/health".to_string(),
+                format!("/api/v2/catalog/item/{}", i),
+                format!("/api/v1/catalog/item/{}/extra", i),
+            ]
+        })
+        .collect();
+
+    let cached = PredictiveEngine::new(true);
+    let cold = PredictiveEngine::new(true).with_prefix_cache(false);
+    for trie in [build("/api/v1/catalog/item/", 20), build("/api/v2/catalog/item/", 30)] {
+        // The second swap changes every node index: stale cache entries would show.
+        cached.swap_weights(trie.clone());
+        cold.swap_weights(trie);
+        for probe in &probes {
+            assert_eq!(cached.lookup(probe.as_bytes()), cold.lookup(probe.as_bytes()), "{}", probe);
+        }
+    }
+    assert_eq!(cached.lookup(b"/api/v2/catalog/item/29"), Some((30, 7)));
+    assert_eq!(cached.lookup(b"/api/v1/catalog/item/3"), None);
+}
+
+#[test]
+fn test_prefix_cache_keyed_on_swap_generation() {
+    use httpx_dsa::LinearIntentTrie;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    // Same routes, different node layouts: a cached prefix node from one
+    // trie points somewhere else in the other.
+    let build = |filler: u32| {
+        let mut trie = LinearIntentTrie::new(1024);
+        for i in 0..filler {
+            trie.warm(format!("/pad/{}", i).as_bytes());
+        }
+        trie.warm(b"/api/v1/item");
+        trie.associate_payload(b"/api/v1/item", 7, 1).unwrap();
+        trie
+    };
+    let engine = Arc::new(PredictiveEngine::new(true));
+    let first = engine.swap_generation();
+    engine.swap_weights(build(0));
+    assert_eq!(engine.swap_generation(), first + 1);
+
+    let done = Arc::new(AtomicBool::new(false));
+    let readers: Vec<_> = (0..2)
+        .map(|_| {
+            let (engine, done) = (engine.clone(), done.clone());
+            std::thread::spawn(move || {
+                while !done.load(Ordering::Relaxed) {
+                    assert_eq!(engine.lookup(b"/api/v1/item"), Some((7, 1)));
+                }
+            })
+        })
+        .collect();
+    // Dropped tries are reclaimed and their addresses reused by later ones.
+    for i in 0..500 {
+        engine.swap_weights(build(i % 3));
+    }
+    done.store(true, Ordering::Relaxed);
+    for reader in readers {
+        reader.join().unwrap();
+    }
+    assert_eq!(engine.swap_generation(), first + 501);
+}
+
+#[test]
+fn test_merge_weights_keeps_local_and_incoming_learning() {
+    use httpx_dsa::LinearIntentTrie;
+
+    let engine = PredictiveEngine::new(true);
+    let mut base = LinearIntentTrie::new(1024);
+    base.warm(b"/index");
+    base.associate_payload(b"/index", 1, 1).unwrap();
+    engine.swap_weights(base.clone());
+
+    // Local training on the active trie after the shadow was forked.
+    let addr: SocketAddr = "127.0.0.1:8080".parse().unwrap();
+    let session = Session::new(addr);
+    for _ in 0..4 {
+        engine.train(&session, b"/local", true);
+    }
+
+    // The shadow learns something disjoint and binds a new route.
+    let mut shadow = base;
+    shadow.sequence_number = 9;
+    for _ in 0..3 {
+        shadow.observe(b"/remote", false);
+    }
+    shadow.warm(b"/remote/asset");
+    shadow.associate_payload(b"/remote/asset", 2, 1).unwrap();
+
+    engine.merge_weights(&shadow);
+
+    let weights = |path: &[u8]| engine.inspect_trie(|t| t.get_node_at_path(path).map(|n| n.weights)).flatten();
+    assert_eq!(weights(b"/local"), Some([0, 4]));
+    assert_eq!(weights(b"/remote"), Some([3, 0]));
+    assert_eq!(engine.lookup(b"/index"), Some((1, 1)));
+    assert_eq!(engine.lookup(b"/remote/asset"), Some((2, 1)));
+    assert_eq!(engine.active_sequence(), 9);
+}
+
+#[test]
+fn test_decision_trace_records_pushes_in_order() {
+    use httpx_core::engine::DecisionRecord;
+    use httpx_dsa::{context_hash, LinearIntentTrie};
+
+    let engine = PredictiveEngine::new(true).with_decision_trace(3);
+    let mut trie = LinearIntentTrie::new(1024);
+    for _ in 0..9 {
+        trie.observe(b"/hot", true);
+    }
+    trie.observe(b"/split", true);
+    trie.observe(b"/split", false);
+    engine.swap_weights(trie);
+
+    let session = Session::new("127.0.0.1:8080".parse().unwrap());
+    assert_eq!(engine.fire_push_if_likely(&session, b"/warmup"), None);
+    assert_eq!(engine.fire_push_if_likely(&session, b"/hot"), Some(true));
+    assert_eq!(engine.fire_push_if_likely(&session, b"/split"), None);
+    session.set_credits(0);
+    assert_eq!(engine.fire_push_if_likely(&session, b"/hot"), None);
+
+    // Capacity 3: the "/warmup" miss was evicted.
+    let record = |path: &[u8], p_true, p_false, decision, credit_available| DecisionRecord {
+        context_hash: context_hash(path),
+        p_true,
+        p_false,
+        decision,
+        credit_available,
+    };
+    assert_eq!(
+        engine.decision_trace(),
+        vec![
+            record(b"/hot", 1.0, 0.0, Some(true), true),
+            record(b"/split", 0.5, 0.5, None, true),
+            record(b"/hot", 1.0, 0.0, None, false),
+        ]
+    );
+    assert!(PredictiveEngine::new(true).decision_trace().is_empty());
+}
+
+#[test]
+fn test_sovereign_session_doubles_local_training_weight() {
+    use httpx_core::SessionMode;
+
+    let engine = PredictiveEngine::new(true);
+    let session = Session::new("127.0.0.1:8080".parse().unwrap());
+    assert_eq!(session.mode(), SessionMode::ClusterIntegrated);
+    engine.train(&session, b"/integrated", true);
+
+    session.set_mode(SessionMode::SovereignAutonomous);
+    assert_eq!(session.mode(), SessionMode::SovereignAutonomous);
+    engine.train(&session, b"/sovereign", true);
+
+    let weight = |path: &[u8]| engine.inspect_trie(|t| t.get_node_at_path(path).unwrap().weights[1]).unwrap();
+    assert_eq!(weight(b"/integrated"), 1);
+    assert_eq!(weight(b"/sovereign"), 2, "Sovereign sessions trust local learning 2x");
+}
+
+#[test]
+fn test_hashed_path_encoder_bounds_depth() {
+    use httpx_dsa::trie::HASHED_DEPTH_BITS;
+    use httpx_dsa::{LinearIntentTrie, PathEncoding};
+
+    let long = vec![b'a'; 4096];
+    for path in [&b""[..], b"/", b"/api/v1/catalog", &long] {
+        assert_eq!(PathEncoding::Hashed.encode(path).count(), HASHED_DEPTH_BITS);
+        assert_eq!(PathEncoding::Identity.encode(path).count(), path.len() * 8);
+    }
+    assert!(PathEncoding::Hashed.encode(b"/a").ne(PathEncoding::Hashed.encode(b"/b")));
+
+    // A crafted 4 KiB path costs exactly 64 nodes instead of 32768.
+    let mut trie = LinearIntentTrie::new(1024).with_path_encoding(PathEncoding::Hashed);
+    assert!(trie.check_depth(&long).is_ok());
+    trie.observe(&long, true);
+    assert_eq!(trie.node_count(), 1 + HASHED_DEPTH_BITS);
+    assert_eq!(trie.get_probability(&long, true), 1.0);
+    assert_eq!(trie.get_probability(&long[..4095], true), 0.0);
+
+    // Route binding and engine lookups go through the same encoding.
+    assert_eq!(trie.warm_many(&[(b"/index", 3, 1), (b"/index.html", 4, 1)]), Ok(2));
+    let engine = PredictiveEngine::new(true);
+    engine.swap_weights(trie);
+    assert_eq!(engine.lookup(b"/index.html"), Some((4, 1)));
+    assert_eq!(engine.lookup(b"/index"), Some((3, 1)));
+    assert_eq!(engine.lookup(b"/index.htm"), None);
+}
+
+#[test]
+fn test_node_budget_evicts_cold_paths_but_keeps_routes() {
+    use httpx_dsa::LinearIntentTrie;
+
+    let budget = 512;
+    let mut trie = LinearIntentTrie::new(budget).with_node_budget(budget);
+    trie.warm_many(&[(b"/api/v1/catalog", 7, 3), (b"/index", 8, 1)]).unwrap();
+    let pinned = trie.node_count();
+
+    for i in 0..400 {
+        let path = format!("/cold/{}", i);
+        trie.observe(path.as_bytes(), i % 2 == 0);
+        assert!(trie.node_count() <= budget, "{} nodes after {} observations", trie.node_count(), i + 1);
+    }
+    assert!(trie.evictions() > 0);
+    assert!(trie.node_count() >= pinned);
+
+    // Registered routes survive every pass with their bindings intact.
+    let catalog = trie.get_node_at_path(b"/api/v1/catalog").unwrap();
+    assert_eq!((catalog.payload_handle, catalog.version_id), (7, 3));
+    let index = trie.get_node_at_path(b"/index").unwrap();
+    assert_eq!((index.payload_handle, index.version_id), (8, 1));
+
+    // The most recent observation is hot; the earliest ones were evicted.
+    assert_eq!(trie.get_probability(b"/cold/399", false), 1.0);
+    assert!(trie.get_node_at_path(b"/cold/0").is_none());
+
+    // Observing a known path again refreshes it instead of growing the pool.
+    let before = trie.node_count();
+    trie.observe(b"/cold/399", true);
+    assert_eq!(trie.node_count(), before);
+    assert_eq!(trie.get_probability(b"/cold/399", false), 0.5);
+}
+
+#[test]
+fn test_node_flags_are_independent_bits() {
+    use httpx_dsa::{FlagBit, LinearIntentTrie, TrieError};
+
+    let mut trie = LinearIntentTrie::new(1024);
+    trie.warm(b"/api/v1");
+    trie.warm(b"/api/v2");
+    trie.associate_payload(b"/api/v2", 5, 1).unwrap();
+
+    // Registration marks the route terminal and pinned, nothing else.
+    assert!(trie.has_flag(b"/api/v2", FlagBit::TERMINAL));
+    assert!(trie.has_flag(b"/api/v2", FlagBit::PINNED));
+    assert!(!trie.has_flag(b"/api/v2", FlagBit::DEPRECATED));
+    assert!(!trie.has_flag(b"/api/v1", FlagBit::TERMINAL));
+
+    let custom = FlagBit::user(3);
+    for flag in [FlagBit::WILDCARD, FlagBit::DEPRECATED, FlagBit::user(0), custom] {
+        trie.set_flag(b"/api/v1", flag).unwrap();
+    }
+    trie.set_flag(b"/api/v2", FlagBit::DEPRECATED).unwrap();
+    trie.clear_flag(b"/api/v1", FlagBit::WILDCARD).unwrap();
+
+    let v1 = trie.get_node_at_path(b"/api/v1").unwrap();
+    assert_eq!(v1.flags, FlagBit::DEPRECATED.mask() | FlagBit::user(0).mask() | custom.mask());
+    assert!(v1.has_flag(custom) && !v1.has_flag(FlagBit::user(1)) && !v1.has_flag(FlagBit::WILDCARD));
+    // Flags on one node never leak onto its sibling or its parent.
+    let v2 = trie.get_node_at_path(b"/api/v2").unwrap();
+    assert_eq!(v2.flags, FlagBit::PINNED.mask() | FlagBit::TERMINAL.mask() | FlagBit::DEPRECATED.mask());
+    assert_eq!(trie.get_node_at_path(b"/api/v").unwrap().flags, 0);
+
+    // Flags survive a serialization round-trip; unknown paths are refused.
+    let restored = LinearIntentTrie::from_bytes(&trie.to_bytes()).unwrap();
+    assert!(restored.has_flag(b"/api/v1", custom) && restored.has_flag(b"/api/v2", FlagBit::DEPRECATED));
+    assert!(matches!(trie.set_flag(b"/api/v3", FlagBit::WILDCARD), Err(TrieError::UnknownPath { .. })));
+    assert!(!trie.has_flag(b"/api/v3", FlagBit::WILDCARD));
+}
+
+#[test]
+fn test_node_flags_follow_routes_and_newer_merges() {
+    use httpx_dsa::{FlagBit, LinearIntentTrie};
+
+    let mut base = LinearIntentTrie::new(1024);
+    base.warm(b"/legacy");
+    base.associate_payload(b"/legacy", 3, 1).unwrap();
+    base.set_flag(b"/legacy", FlagBit::DEPRECATED).unwrap();
+    let route = base.routes().into_iter().find(|r| r.path == b"/legacy").unwrap();
+    assert_eq!(route.flags, FlagBit::PINNED.mask() | FlagBit::TERMINAL.mask() | FlagBit::DEPRECATED.mask());
+
+    // A shadow trie carrying the routes keeps their flags, base winning.
+    let mut shadow = base.empty_like();
+    shadow.warm(b"/legacy");
+    shadow.set_flag(b"/legacy", FlagBit::WILDCARD).unwrap();
+    shadow.carry_routes_from(&base);
+    assert_eq!(shadow.get_node_at_path(b"/legacy").unwrap().flags, route.flags);
+
+    // A newer trie that cleared a flag does not get it back from an older one.
+    let mut newer = LinearIntentTrie::from_bytes(&base.to_bytes()).unwrap();
+    newer.clear_flag(b"/legacy", FlagBit::DEPRECATED).unwrap();
+    newer.sequence_number = 2;
+    let mut merged = LinearIntentTrie::from_bytes(&newer.to_bytes()).unwrap();
+    merged.merge_structural(&base);
+    assert!(!merged.has_flag(b"/legacy", FlagBit::DEPRECATED));
+    base.merge_structural(&newer);
+    assert!(!base.has_flag(b"/legacy", FlagBit::DEPRECATED));
+    assert!(base.has_flag(b"/legacy", FlagBit::PINNED));
+
+    // A higher route version carries its flags even from an older trie.
+    let mut bumped = LinearIntentTrie::from_bytes(&base.to_bytes()).unwrap();
+    bumped.sequence_number = 0;
+    bumped.associate_payload(b"/legacy", 4, 2).unwrap();
+    bumped.set_flag(b"/legacy", FlagBit::user(1)).unwrap();
+    base.merge_structural(&bumped);
+    assert_eq!(base.get_node_at_path(b"/legacy").unwrap().payload_handle, 4);
+    assert!(base.has_flag(b"/legacy", FlagBit::user(1)));
+}
+
+#[test]
+fn test_byte_trie_answers_like_linear_trie() {
+    use httpx_dsa::{ByteIntentTrie, IntentTrie, LinearIntentTrie, TrieError};
+
+    fn train<T: IntentTrie>(trie: &mut T) {
+        let paths: [&[u8]; 5] = [b"/api/v1/users", b"/api/v1/users/42", b"/api/v2", b"/", b""];
+        for (i, path) in paths.iter().cycle().take(23).enumerate() {
+            trie.observe(path, i % 3 == 0);
+        }
+        trie.warm(b"/static/app.js");
+        trie.associate_payload(b"/static/app.js", 9, 4).unwrap();
+    }
+
+    let mut linear = LinearIntentTrie::new(1024);
+    let mut bytes = ByteIntentTrie::new(64);
+    train(&mut linear);
+    train(&mut bytes);
+
+    let probes: [&[u8]; 8] =
+        [b"/api/v1/users", b"/api/v1/users/42", b"/api/v2", b"/", b"", b"/api", b"/static/app.js", b"/missing"];
+    for path in probes {
+        for bit in [false, true] {
+            assert_eq!(
+                IntentTrie::get_probability(&linear, path, bit),
+                IntentTrie::get_probability(&bytes, path, bit),
+                "{:?} diverges",
+                String::from_utf8_lossy(path)
+            );
+        }
+        let (a, b) = (IntentTrie::get_node_at_path(&linear, path), IntentTrie::get_node_at_path(&bytes, path));
+        assert_eq!(a.is_some(), b.is_some());
+        if let (Some(a), Some(b)) = (a, b) {
+            assert_eq!((a.weights, a.payload_handle, a.version_id, a.flags), (b.weights, b.payload_handle, b.version_id, b.flags));
+        }
+    }
+
+    // One node per byte instead of eight. Unknown routes are refused by both;
+    // the byte trie can only place the divergence at a byte boundary.
+    assert!(bytes.node_count() * 7 < linear.node_count());
+    assert_eq!(bytes.associate_payload(b"/apx", 1, 1), Err(TrieError::UnknownPath { depth: 24 }));
+    assert_eq!(linear.associate_payload(b"/apx", 1, 1), Err(TrieError::UnknownPath { depth: 27 }));
+}
+
+#[test]
+fn test_trie_bytes_roundtrip_preserves_learning() {
+    use httpx_dsa::{FlagBit, LinearIntentTrie, PathEncoding, TrieError};
+
+    let mut trie = LinearIntentTrie::new(1024).with_max_depth_bits(512);
+    for (i, path) in [&b"/api/v1/users"[..], b"/api/v1/orders", b"/index", b"/api/v1/users"].iter().enumerate() {
+        trie.observe(path, i % 2 == 0);
+    }
+    trie.warm(b"/static/app.js");
+    trie.associate_payload(b"/static/app.js", 9, 4).unwrap();
+    trie.set_flag(b"/index", FlagBit::DEPRECATED).unwrap();
+    trie.sequence_number = 77;
+
+    let restored = LinearIntentTrie::from_bytes(&trie.to_bytes()).unwrap();
+    assert!(restored.structural_eq(&trie));
+    assert_eq!(restored.digest(), trie.digest());
+    assert_eq!(restored.sequence_number, 77);
+    assert_eq!(restored.max_depth_bits(), 512);
+    assert_eq!(restored.path_encoding(), PathEncoding::Identity);
+    assert_eq!(restored.get_probability(b"/api/v1/users", true), trie.get_probability(b"/api/v1/users", true));
+    assert!(restored.has_flag(b"/index", FlagBit::DEPRECATED));
+    let node = restored.get_node_at_path(b"/static/app.js").unwrap();
+    assert_eq!((node.payload_handle, node.version_id), (9, 4));
+
+    // A node linked from two parents is refused: it would alias two paths.
+    let mut shared = LinearIntentTrie::new(4);
+    shared.warm(&[0x80]);
+    let mut bytes = shared.to_bytes();
+    // `warm` of one byte allocates 8 nodes under the root; point both root links at the same child.
+    let root_left = bytes.len() - 9 * 23;
+    bytes.copy_within(root_left + 4..root_left + 8, root_left);
+    assert_eq!(LinearIntentTrie::from_bytes(&bytes).err(), Some(TrieError::Malformed));
+}
+
+#[test]
+fn test_node_limit_refuses_inserts_instead_of_aliasing_null() {
+    use httpx_dsa::trie::MAX_TRIE_NODES;
+    use httpx_dsa::{LinearIntentTrie, TrieError};
+
+    // The default ceiling stops one short of the `u32::MAX` null link.
+    assert_eq!(LinearIntentTrie::new(16).node_limit(), u32::MAX as usize);
+    assert_eq!(LinearIntentTrie::new(16).with_node_limit(usize::MAX).node_limit(), MAX_TRIE_NODES);
+
+    // Stand in for a pool at the boundary: one byte fills 9 of 10 nodes.
+    let mut trie = LinearIntentTrie::new(16).with_node_limit(10);
+    trie.observe(&[0x80], true);
+    assert_eq!(trie.node_count(), 9);
+
+    // The next path gets one node before the pool is full.
+    assert_eq!(trie.try_warm(&[0x40]), Err(TrieError::CapacityExhausted { nodes: 10 }));
+    assert_eq!(trie.try_observe(&[0x20], true), Err(TrieError::CapacityExhausted { nodes: 10 }));
+    assert_eq!(trie.node_count(), 10);
+
+    // The infallible calls drop the update instead of corrupting links.
+    trie.observe(&[0x20], true);
+    trie.warm(b"/api");
+    assert_eq!(trie.node_count(), 10);
+    assert_eq!(trie.get_probability(&[0x20], true), 0.0);
+    assert!(trie.get_node_at_path(b"/api").is_none());
+
+    // Paths already in the pool keep learning.
+    trie.try_observe(&[0x80], false).unwrap();
+    assert_eq!(trie.get_probability(&[0x80], true), 0.5);
+
+    // Batched routes report exhaustion instead of dropping routes silently;
+    // routes bound before the failure stay.
+    assert_eq!(trie.warm_many(&[(&[0x80], 1, 1)]), Ok(1));
+    assert_eq!(trie.warm_many(&[(&[0xc0], 3, 1), (&[0x80], 2, 1)]), Err(TrieError::CapacityExhausted { nodes: 10 }));
+    assert_eq!(trie.get_node_at_path(&[0x80]).map(|n| n.payload_handle), Some(2));
+    assert!(trie.get_node_at_path(&[0xc0]).is_none());
+}
+
+#[test]
+fn test_byte_trie_node_limit_through_intent_trie() {
+    use httpx_dsa::byte_trie::DEFAULT_NODE_LIMIT;
+    use httpx_dsa::trie::MAX_TRIE_NODES;
+    use httpx_dsa::{ByteIntentTrie, IntentTrie, TrieError};
+
+    fn fill<T: IntentTrie>(trie: &mut T) {
+        // Root plus two byte levels fill a limit of 3.
+        trie.observe(b"ab", true);
+        assert_eq!(trie.try_warm(b"c"), Err(TrieError::CapacityExhausted { nodes: 3 }));
+        assert_eq!(trie.try_observe(b"ad", true), Err(TrieError::CapacityExhausted { nodes: 3 }));
+
+        trie.observe(b"ad", true);
+        trie.warm(b"/api");
+        assert_eq!(trie.get_probability(b"ad", true), 0.0);
+        assert!(trie.get_node_at_path(b"/api").is_none());
+
+        trie.try_observe(b"ab", false).unwrap();
+        assert_eq!(trie.get_probability(b"ab", true), 0.5);
+    }
+
+    // 1 KiB of child links per node keeps the default pool in the thousands.
+    assert_eq!(IntentTrie::node_limit(&ByteIntentTrie::new(16)), DEFAULT_NODE_LIMIT);
+    assert_eq!(ByteIntentTrie::new(16).with_node_limit(usize::MAX).node_limit(), MAX_TRIE_NODES);
+    let mut trie = ByteIntentTrie::new(16).with_node_limit(3);
+    fill(&mut trie);
+    assert_eq!(trie.node_count(), 3);
+}
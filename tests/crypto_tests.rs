@@ -1,9 +1,13 @@
 //! # Crypto Layer Tests: AEAD In-Place Transformation
 //!
-//! Validates ChaCha20-Poly1305 encrypt/decrypt roundtrip
-//! using the crate's `SecureInPlaceAEAD` trait and `AEADStack`.
+//! Validates ChaCha20-Poly1305 and AES-256-GCM encrypt/decrypt roundtrips
+//! using the crate's `SecureInPlaceAEAD` trait, `AEADStack` and `AesGcmStack`,
+//! and XChaCha20-Poly1305 through `SecureInPlaceXAEAD` and `XAEADStack`.
 
-use httpx_crypto::{SecureInPlaceAEAD, AEADStack};
+use httpx_crypto::{
+    SecureInPlaceAEAD, SecureInPlaceXAEAD, AEADStack, AesGcmStack, XAEADStack, CipherSuite, CryptoError, DeterministicNonce,
+    FrameNonce, NonceSequencer,
+};
 use zeroize::Zeroizing;
 use std::time::Instant;
 
@@ -60,3 +64,404 @@ fn test_aead_decrypt_tampered() {
     let overhead = t.elapsed();
     println!("test_aead_decrypt_tampered: Testing Overhead = {:?}", overhead);
 }
+
+/// Verifies successful in-place encrypt → decrypt roundtrip under AES-256-GCM,
+/// and that the same suite picked through `CipherSuite` interoperates.
+#[test]
+fn test_aes_gcm_decrypt_valid() {
+    let t = Instant::now();
+
+    let key = Zeroizing::new(*b"an example very very secret key.");
+    let nonce = b"unique nonce";
+    let aad = b"associated-data";
+
+    let plaintext = b"Hello, HTTP-X Sovereign World!!";
+    let mut buffer = plaintext.to_vec();
+
+    let stack = AesGcmStack;
+
+    // Encrypt
+    let tag = stack.seal_in_place(&key, nonce, aad, &mut buffer)
+        .expect("Encryption failed");
+    assert_ne!(&buffer, plaintext);
+
+    // Decrypt through the runtime-selected suite
+    let result = CipherSuite::Aes256Gcm.open_in_place(&key, nonce, aad, &mut buffer, &tag);
+    assert!(result.is_ok(), "Decryption should succeed with valid data");
+    assert_eq!(&buffer, plaintext, "Decrypted data should match original plaintext");
+
+    // The suites are not interchangeable on the wire.
+    let mut sealed = plaintext.to_vec();
+    let tag = AEADStack.seal_in_place(&key, nonce, aad, &mut sealed).unwrap();
+    assert!(stack.open_in_place(&key, nonce, aad, &mut sealed, &tag).is_err());
+    let detected = CipherSuite::detect();
+    assert!(matches!(detected, CipherSuite::Aes256Gcm | CipherSuite::ChaCha20Poly1305));
+
+    let overhead = t.elapsed();
+    println!("test_aes_gcm_decrypt_valid: Testing Overhead = {:?} (detected {})", overhead, detected.name());
+}
+
+/// Verifies that tampered AES-256-GCM ciphertext, tag or AAD returns an error.
+#[test]
+fn test_aes_gcm_decrypt_tampered() {
+    let t = Instant::now();
+
+    let key = Zeroizing::new(*b"an example very very secret key.");
+    let nonce = b"unique nonce";
+    let aad = b"associated-data";
+
+    let plaintext = b"Hello, HTTP-X Sovereign World!!";
+    let mut buffer = plaintext.to_vec();
+
+    let stack = AesGcmStack;
+
+    let tag = stack.seal_in_place(&key, nonce, aad, &mut buffer)
+        .expect("Encryption failed");
+    let sealed = buffer.clone();
+
+    // Tamper with the ciphertext
+    buffer[0] ^= 0xFF;
+    assert!(stack.open_in_place(&key, nonce, aad, &mut buffer, &tag).is_err(), "Decryption should fail with tampered data");
+
+    // Tamper with the tag, then with the associated data
+    let mut bad_tag = tag;
+    bad_tag[15] ^= 0x01;
+    buffer.copy_from_slice(&sealed);
+    assert!(stack.open_in_place(&key, nonce, aad, &mut buffer, &bad_tag).is_err());
+    buffer.copy_from_slice(&sealed);
+    assert!(stack.open_in_place(&key, nonce, b"associated-datA", &mut buffer, &tag).is_err());
+
+    let overhead = t.elapsed();
+    println!("test_aes_gcm_decrypt_tampered: Testing Overhead = {:?}", overhead);
+}
+
+/// Verifies `ct_eq` correctness and that its timing does not depend on
+/// where the first mismatching byte is.
+#[test]
+fn test_ct_eq_timing_independence() {
+    use httpx_crypto::ct_eq;
+
+    let t = Instant::now();
+
+    let a = vec![0x5Au8; 4096];
+    let equal = a.clone();
+    let mut first_byte_diff = a.clone();
+    first_byte_diff[0] ^= 0x01;
+
+    assert!(ct_eq(&a, &equal));
+    assert!(!ct_eq(&a, &first_byte_diff));
+    assert!(!ct_eq(&a, &a[..4095]), "Length mismatch must compare unequal");
+
+    // Median of many batched samples, interleaved to share noise.
+    let sample = |other: &[u8]| {
+        let start = Instant::now();
+        for _ in 0..200 {
+            std::hint::black_box(ct_eq(std::hint::black_box(&a), std::hint::black_box(other)));
+        }
+        start.elapsed().as_nanos()
+    };
+    let mut matching = Vec::new();
+    let mut mismatching = Vec::new();
+    for _ in 0..101 {
+        matching.push(sample(&equal));
+        mismatching.push(sample(&first_byte_diff));
+    }
+    matching.sort_unstable();
+    mismatching.sort_unstable();
+    let (m_eq, m_ne) = (matching[50] as f64, mismatching[50] as f64);
+    let ratio = m_ne / m_eq;
+
+    println!("ct_eq timing: match median {}ns, first-byte mismatch median {}ns (ratio {:.3})", m_eq, m_ne, ratio);
+    // An early-exit comparison would make the mismatch case orders of magnitude faster.
+    assert!((0.67..1.5).contains(&ratio), "ct_eq timing leaks mismatch position (ratio {:.3})", ratio);
+
+    let overhead = t.elapsed();
+    println!("test_ct_eq_timing_independence: Testing Overhead = {:?}", overhead);
+}
+
+/// Pins the `DeterministicNonce` stream to committed constants: a change to
+/// the PRNG or the byte layout would silently invalidate every fixture.
+#[test]
+fn test_deterministic_nonce_known_answers() {
+    let t = Instant::now();
+
+    let mut nonces = DeterministicNonce::from_seed(42);
+    assert_eq!(nonces.next_nonce(), [0x95, 0x6e, 0xeb, 0x2f, 0x26, 0x32, 0xd7, 0xbd, 0x03, 0xf1, 0x66, 0xb2]);
+    assert_eq!(nonces.next_nonce(), [0x52, 0x9f, 0x0f, 0x13, 0x57, 0x67, 0x52, 0x47, 0x94, 0xe3, 0x4a, 0x0e]);
+
+    // Same seed, same stream; the resulting ciphertext is reproducible.
+    let key = Zeroizing::new([7u8; 32]);
+    let seal = |nonce: [u8; 12]| {
+        let mut buffer = b"fixture".to_vec();
+        let tag = AEADStack.seal_in_place(&key, &nonce, b"", &mut buffer).unwrap();
+        (buffer, tag)
+    };
+    let a = seal(DeterministicNonce::from_seed(42).next_nonce());
+    let b = seal(DeterministicNonce::from_seed(42).next_nonce());
+    assert_eq!(a, b);
+
+    let overhead = t.elapsed();
+    println!("test_deterministic_nonce_known_answers: Testing Overhead = {:?}", overhead);
+}
+
+/// Verifies that `seal_next` hands out distinct prefixed nonces that open
+/// the frame, and that a counter driven to the top of its range reports
+/// `NonceExhausted` instead of wrapping.
+#[test]
+fn test_nonce_sequencer_exhausts_instead_of_wrapping() {
+    let t = Instant::now();
+
+    let key = Zeroizing::new(*b"an example very very secret key.");
+    let aad = b"associated-data";
+    let plaintext = b"Hello, HTTP-X Sovereign World!!";
+
+    let mut sequencer = NonceSequencer::new(*b"HXc1");
+    let mut buffer = plaintext.to_vec();
+    let (nonce, tag) = AEADStack.seal_next(&mut sequencer, &key, aad, &mut buffer).unwrap();
+    assert_eq!(&nonce, b"HXc1\0\0\0\0\0\0\0\0");
+    AEADStack.open_in_place(&key, &nonce, aad, &mut buffer, &tag).unwrap();
+    assert_eq!(&buffer, plaintext);
+    assert_eq!(sequencer.next_nonce().unwrap()[4..], 1u64.to_be_bytes());
+
+    // Three nonces left: u64::MAX - 2, u64::MAX - 1 and u64::MAX.
+    let mut sequencer = NonceSequencer::starting_at(*b"HXc1", u64::MAX - 2);
+    assert_eq!(sequencer.remaining(), 3);
+    let mut issued = Vec::new();
+    for _ in 0..3 {
+        let mut buffer = plaintext.to_vec();
+        let (nonce, _) = AesGcmStack.seal_next(&mut sequencer, &key, aad, &mut buffer).unwrap();
+        issued.push(nonce);
+    }
+    assert_eq!(issued[2][4..], u64::MAX.to_be_bytes());
+    assert!(issued[0] != issued[1] && issued[1] != issued[2]);
+    assert_eq!(sequencer.remaining(), 0);
+
+    let mut buffer = plaintext.to_vec();
+    let result = CipherSuite::ChaCha20Poly1305.seal_next(&mut sequencer, &key, aad, &mut buffer);
+    assert!(matches!(result, Err(CryptoError::NonceExhausted)), "Counter must not wrap: {:?}", result);
+    assert_eq!(&buffer, plaintext, "An exhausted seal must leave the buffer untouched");
+    assert!(matches!(sequencer.next_nonce(), Err(CryptoError::NonceExhausted)));
+
+    let overhead = t.elapsed();
+    println!("test_nonce_sequencer_exhausts_instead_of_wrapping: Testing Overhead = {:?}", overhead);
+}
+
+/// Verifies that a tampered tag and a truncated buffer fail with distinct
+/// `CryptoError` variants, each carrying the frame's nonce, on both stacks.
+#[test]
+fn test_decrypt_failures_name_their_cause() {
+    let t = Instant::now();
+
+    let key = Zeroizing::new(*b"an example very very secret key.");
+    let nonce = b"unique nonce";
+    let aad = b"associated-data";
+
+    for suite in [CipherSuite::ChaCha20Poly1305, CipherSuite::Aes256Gcm] {
+        let mut buffer = b"Hello, HTTP-X Sovereign World!!".to_vec();
+        let tag = suite.seal_in_place(&key, nonce, aad, &mut buffer).expect("Encryption failed");
+
+        let mut bad_tag = tag;
+        bad_tag[0] ^= 0x01;
+        let tampered = suite.open_in_place(&key, nonce, aad, &mut buffer.clone(), &bad_tag);
+        assert_eq!(tampered, Err(CryptoError::IntegrityCheckFailed { nonce: FrameNonce::Standard(*nonce) }), "{}", suite.name());
+
+        let truncated = suite.open_in_place(&key, nonce, aad, &mut buffer[..0], &tag);
+        assert_eq!(truncated, Err(CryptoError::EmptyBuffer { nonce: FrameNonce::Standard(*nonce) }), "{}", suite.name());
+
+        let message = truncated.unwrap_err().to_string();
+        assert!(message.contains("756e69717565206e6f6e6365"), "Nonce missing from {:?}", message);
+    }
+
+    let overhead = t.elapsed();
+    println!("test_decrypt_failures_name_their_cause: Testing Overhead = {:?}", overhead);
+}
+
+/// Verifies that an empty plaintext is a valid AAD-only frame on every
+/// stack: it seals, opens, and still fails if the AAD is altered.
+#[test]
+fn test_empty_plaintext_seals_and_opens() {
+    use httpx_crypto::AeadSession;
+
+    let t = Instant::now();
+
+    let key = Zeroizing::new(*b"an example very very secret key.");
+    let nonce = b"unique nonce";
+    let aad = b"associated-data";
+
+    for suite in [CipherSuite::ChaCha20Poly1305, CipherSuite::Aes256Gcm] {
+        let tag = suite.seal_in_place(&key, nonce, aad, &mut []).expect("Empty seal failed");
+        assert_eq!(suite.open_in_place(&key, nonce, aad, &mut [], &tag), Ok(()), "{}", suite.name());
+        let forged = suite.open_in_place(&key, nonce, b"other-data", &mut [], &tag);
+        assert_eq!(forged, Err(CryptoError::EmptyBuffer { nonce: FrameNonce::Standard(*nonce) }), "{}", suite.name());
+
+        let session = AeadSession::new(suite, &key);
+        let tag = session.seal(nonce, aad, &mut []).expect("Empty session seal failed");
+        assert_eq!(session.open(nonce, aad, &mut [], &tag), Ok(()), "{}", suite.name());
+    }
+
+    let xnonce = [7u8; 24];
+    let tag = XAEADStack.seal_in_place(&key, &xnonce, aad, &mut []).expect("Empty XChaCha seal failed");
+    assert_eq!(XAEADStack.open_in_place(&key, &xnonce, aad, &mut [], &tag), Ok(()));
+
+    let overhead = t.elapsed();
+    println!("test_empty_plaintext_seals_and_opens: Testing Overhead = {:?}", overhead);
+}
+
+/// Verifies an XChaCha20-Poly1305 roundtrip under a random 24-byte nonce,
+/// and that a wrong nonce fails with the extended nonce in the error.
+#[test]
+fn test_xchacha_roundtrip_with_random_nonce() {
+    use std::io::Read;
+
+    let t = Instant::now();
+
+    let key = Zeroizing::new(*b"an example very very secret key.");
+    let mut nonce = [0u8; 24];
+    std::fs::File::open("/dev/urandom").unwrap().read_exact(&mut nonce).unwrap();
+    let aad = b"associated-data";
+
+    let plaintext = b"Hello, HTTP-X Sovereign World!!";
+    let mut buffer = plaintext.to_vec();
+
+    let stack = XAEADStack;
+
+    let tag = stack.seal_in_place(&key, &nonce, aad, &mut buffer).expect("Encryption failed");
+    assert_ne!(&buffer[..], &plaintext[..], "Ciphertext must differ from plaintext");
+    let sealed = buffer.clone();
+
+    stack.open_in_place(&key, &nonce, aad, &mut buffer, &tag).expect("Decryption failed");
+    assert_eq!(&buffer[..], &plaintext[..]);
+
+    // Only the last nonce byte differs: still rejected, and reported in full.
+    let mut other = nonce;
+    other[23] ^= 0x01;
+    buffer.copy_from_slice(&sealed);
+    assert_eq!(
+        stack.open_in_place(&key, &other, aad, &mut buffer, &tag),
+        Err(CryptoError::IntegrityCheckFailed { nonce: FrameNonce::Extended(other) })
+    );
+
+    let overhead = t.elapsed();
+    println!("test_xchacha_roundtrip_with_random_nonce: Testing Overhead = {:?}", overhead);
+}
+
+/// Verifies that `seal_slot` encrypts a partial slab payload in place with
+/// the tag appended to the payload, and `open_slot` recovers it.
+#[test]
+fn test_seal_slot_in_place_roundtrip() {
+    use httpx_crypto::{open_slot, seal_slot, SLOT_TAG_LEN};
+    use httpx_dsa::{SecureSlab, SlabError};
+
+    let t = Instant::now();
+
+    let key = Zeroizing::new(*b"an example very very secret key.");
+    let nonce = b"unique nonce";
+    let aad = b"slot-7";
+
+    let slab = SecureSlab::new(8);
+    let plaintext: Vec<u8> = (0..1500u32).map(|i| (i % 251) as u8).collect();
+    slab.write_slot(7, 0, &plaintext).unwrap();
+    let version = slab.get_version(7);
+
+    seal_slot(&AEADStack, &slab, 7, plaintext.len(), &key, nonce, aad).expect("Slot seal failed");
+    assert_eq!(slab.payload_len(7), plaintext.len() + SLOT_TAG_LEN, "A push must ship the tag");
+    assert!(slab.get_version(7) > version, "Pushes committed against the plaintext must go stale");
+
+    // What a push ships decrypts with the one-shot stack.
+    let mut sealed = vec![0u8; slab.payload_len(7)];
+    slab.read_slot(7, 0, &mut sealed).unwrap();
+    let (body, tag) = sealed.split_at(plaintext.len());
+    assert_ne!(body, plaintext, "Slot must hold ciphertext after sealing");
+    let mut body = body.to_vec();
+    let tag = chacha20poly1305::Tag::from_slice(tag);
+    AEADStack.open_in_place(&key, nonce, aad, &mut body, tag).expect("Shipped bytes must open");
+    assert_eq!(body, plaintext);
+
+    open_slot(&AEADStack, &slab, 7, plaintext.len(), &key, nonce, aad).expect("Slot open failed");
+    assert_eq!(slab.payload_len(7), plaintext.len());
+    let mut raw = vec![0u8; plaintext.len()];
+    slab.read_slot(7, 0, &mut raw).unwrap();
+    assert_eq!(raw, plaintext);
+
+    // The payload may not run into the tag region, and an in-flight slot is off limits.
+    let full = slab.slot_size() - SLOT_TAG_LEN + 1;
+    assert!(matches!(seal_slot(&AEADStack, &slab, 7, full, &key, nonce, aad), Err(CryptoError::Slab(SlabError::OutOfBounds { .. }))));
+    slab.increment_rc(7);
+    assert_eq!(seal_slot(&AEADStack, &slab, 7, plaintext.len(), &key, nonce, aad), Err(CryptoError::SlotInFlight(7)));
+    slab.decrement_rc(7);
+
+    let overhead = t.elapsed();
+    println!("test_seal_slot_in_place_roundtrip: Testing Overhead = {:?}", overhead);
+}
+
+/// Verifies that an `AeadSession` interoperates with the one-shot stacks in
+/// both directions and reports failures the same way.
+#[test]
+fn test_aead_session_matches_one_shot_stacks() {
+    use httpx_crypto::AeadSession;
+
+    let t = Instant::now();
+
+    let key = Zeroizing::new(*b"an example very very secret key.");
+    let nonce = b"unique nonce";
+    let aad = b"associated-data";
+    let plaintext = b"Hello, HTTP-X Sovereign World!!";
+
+    for suite in [CipherSuite::ChaCha20Poly1305, CipherSuite::Aes256Gcm] {
+        let session = AeadSession::new(suite, &key);
+        assert_eq!(session.suite(), suite);
+
+        let mut ours = plaintext.to_vec();
+        let mut theirs = plaintext.to_vec();
+        let tag = session.seal(nonce, aad, &mut ours).expect("Session seal failed");
+        let one_shot_tag = suite.seal_in_place(&key, nonce, aad, &mut theirs).expect("One-shot seal failed");
+        assert_eq!((&ours, tag), (&theirs, one_shot_tag), "{}", suite.name());
+
+        suite.open_in_place(&key, nonce, aad, &mut ours, &tag).expect("One-shot open failed");
+        assert_eq!(&ours[..], &plaintext[..]);
+        session.open(nonce, aad, &mut theirs, &one_shot_tag).expect("Session open failed");
+        assert_eq!(&theirs[..], &plaintext[..]);
+
+        let mut bad_tag = tag;
+        bad_tag[0] ^= 0x01;
+        session.seal(nonce, aad, &mut ours).unwrap();
+        assert_eq!(
+            session.open(nonce, aad, &mut ours, &bad_tag),
+            Err(CryptoError::IntegrityCheckFailed { nonce: FrameNonce::Standard(*nonce) })
+        );
+    }
+
+    let overhead = t.elapsed();
+    println!("test_aead_session_matches_one_shot_stacks: Testing Overhead = {:?}", overhead);
+}
+
+/// Verifies that `derive_session_key` is deterministic per context, diverges
+/// across contexts and masters, and yields keys that seal and open.
+#[test]
+fn test_derive_session_key_is_deterministic_and_isolated() {
+    use httpx_crypto::derive_session_key;
+
+    let t = Instant::now();
+
+    let master = Zeroizing::new(*b"an example very very secret key.");
+    let peer_a = "10.0.0.1:4433".parse::<std::net::SocketAddr>().unwrap().to_string();
+    let peer_b = "10.0.0.2:4433".parse::<std::net::SocketAddr>().unwrap().to_string();
+
+    let key_a = derive_session_key(&master, peer_a.as_bytes());
+    assert_eq!(*key_a, *derive_session_key(&master, peer_a.as_bytes()), "Same context must give the same key");
+    assert_ne!(*key_a, *derive_session_key(&master, peer_b.as_bytes()), "Contexts must diverge");
+    assert_ne!(*key_a, *derive_session_key(&master, b""));
+    assert_ne!(*key_a, *master, "The master must never be used directly");
+
+    let other_master = Zeroizing::new([0x42u8; 32]);
+    assert_ne!(*key_a, *derive_session_key(&other_master, peer_a.as_bytes()), "Masters must diverge");
+
+    let mut buffer = b"Hello, HTTP-X Sovereign World!!".to_vec();
+    let tag = AEADStack.seal_in_place(&key_a, b"unique nonce", b"", &mut buffer).unwrap();
+    let key_b = derive_session_key(&master, peer_b.as_bytes());
+    assert!(AEADStack.open_in_place(&key_b, b"unique nonce", b"", &mut buffer.clone(), &tag).is_err());
+    AEADStack.open_in_place(&key_a, b"unique nonce", b"", &mut buffer, &tag).unwrap();
+
+    let overhead = t.elapsed();
+    println!("test_derive_session_key_is_deterministic_and_isolated: Testing Overhead = {:?}", overhead);
+}
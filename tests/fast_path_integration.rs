@@ -13,7 +13,7 @@ async fn test_fast_path_full_lifecycle() {
     let version = 1;
     
     trie.observe(context, true);
-    trie.associate_payload(context, handle, version);
+    trie.associate_payload(context, handle, version).unwrap();
 
     // 2. Setup the Hardware Layer (Slab & io_uring)
     let slab = Arc::new(SecureSlab::new(64));
@@ -28,7 +28,7 @@ async fn test_fast_path_full_lifecycle() {
     let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
     let addr = socket.local_addr().unwrap();
     let (_tx, rx) = tokio::sync::mpsc::channel(10);
-    let (learn_tx, _learn_rx) = tokio::sync::mpsc::unbounded_channel();
+    let (learn_tx, _learn_rx) = httpx_core::learn_channel(1024);
     let mut dispatcher = CoreDispatcher::new_with_socket(0, socket, rx, ServerConfig::default(), trie.clone(), learn_tx).await.unwrap();
 
     // 3. Execution: Submit Linked Burst
@@ -53,7 +53,7 @@ async fn test_invalid_handle_safety() {
     let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
     let addr = socket.local_addr().unwrap();
     let (_tx, rx) = tokio::sync::mpsc::channel(10);
-    let (learn_tx, _learn_rx) = tokio::sync::mpsc::unbounded_channel();
+    let (learn_tx, _learn_rx) = httpx_core::learn_channel(1024);
     let mut dispatcher = CoreDispatcher::new_with_socket(0, socket, rx, ServerConfig::default(), LinearIntentTrie::new(1024), learn_tx).await.unwrap();
 
     // Attempting to submit a handle that is out-of-bounds for the slab
@@ -89,3 +89,1232 @@ async fn test_gso_batch_integrity() {
     assert!(res.is_ok());
     assert_eq!(res.unwrap(), 4, "Should have batched 4 fragments");
 }
+
+#[tokio::test]
+async fn test_stream_batch_rejects_oversize_batch() {
+    use httpx_transport::stream::PayloadStreamer;
+    use httpx_transport::TransportError;
+
+    let receiver = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+    receiver.set_nonblocking(true).unwrap();
+    let target = receiver.local_addr().unwrap();
+    let streamer = PayloadStreamer::new(UdpSocket::bind("127.0.0.1:0").await.unwrap(), 1500).unwrap();
+
+    let slab = SecureSlab::new(32);
+    for i in 0..20 {
+        slab.set_version(i, 1);
+    }
+
+    // 20 x 4KB = 80KB: previously sent as a silent 15-fragment partial batch.
+    let handles: Vec<(u32, u32)> = (0..20).map(|i| (i, 1)).collect();
+    let err = streamer.stream_batch(&slab, &handles, target).await.unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    match err {
+        TransportError::PayloadTooLarge { len, limit } => {
+            // 80KB of slots cut into 56 fragments of 1487 bytes, each framed.
+            assert_eq!(len, 20 * 4096 + 56 * httpx_transport::frame::FRAGMENTED_HEADER_LEN);
+            assert_eq!(limit, httpx_transport::stream::MAX_BATCH_BYTES);
+        }
+        other => panic!("Expected PayloadTooLarge, got {:?}", other),
+    }
+
+    let mut buf = [0u8; 2048];
+    assert!(receiver.recv_from(&mut buf).is_err(), "Nothing may be sent for a rejected batch");
+}
+
+/// The batch limit counts fragment headers: slots that fit on their own
+/// but overflow once framed are refused, and nothing goes out.
+#[tokio::test]
+async fn test_stream_batch_limit_counts_fragment_headers() {
+    use httpx_transport::frame::FRAGMENTED_HEADER_LEN;
+    use httpx_transport::stream::{PayloadStreamer, MAX_BATCH_BYTES};
+    use httpx_transport::TransportError;
+
+    let receiver = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+    receiver.set_nonblocking(true).unwrap();
+    let target = receiver.local_addr().unwrap();
+    // 100-byte segments carry 87 slot bytes each, so 61440 slot bytes (which
+    // fit alone) grow to 70631 once framed.
+    let streamer = PayloadStreamer::new(UdpSocket::bind("127.0.0.1:0").await.unwrap(), 100).unwrap();
+
+    let slab = SecureSlab::new(16);
+    for i in 0..15 {
+        slab.set_version(i, 1);
+    }
+    let handles: Vec<(u32, u32)> = (0..15).map(|i| (i, 1)).collect();
+
+    let err = streamer.stream_batch(&slab, &handles, target).await.unwrap_err();
+    let body = 100 - FRAGMENTED_HEADER_LEN;
+    let framed = 15 * 4096 + (15 * 4096usize).div_ceil(body) * FRAGMENTED_HEADER_LEN;
+    assert!(matches!(err, TransportError::PayloadTooLarge { len, limit: MAX_BATCH_BYTES } if len == framed));
+    assert!(receiver.recv_from(&mut [0u8; 2048]).is_err(), "Nothing may be sent for a rejected batch");
+    println!("Framing Audit: {} slot bytes framed to {} bytes, over the {} limit.", 15 * 4096, framed, MAX_BATCH_BYTES);
+}
+
+#[tokio::test]
+async fn test_pacing_spaces_burst_submissions() {
+    let slab = Arc::new(SecureSlab::new(64));
+    let handle = 1;
+    slab.set_payload_len(handle as usize, 4096);
+    slab.set_version(handle as usize, 1);
+
+    // Each burst is Intent (17B) + Header (128B) + Payload (4096B) = 4241B.
+    // At 424_100 B/s that is exactly 10ms of egress per burst.
+    let config = ServerConfig {
+        pacing_rate: Some(424_100),
+        ..Default::default()
+    };
+
+    let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let addr = socket.local_addr().unwrap();
+    let (_tx, rx) = tokio::sync::mpsc::channel(10);
+    let (learn_tx, _learn_rx) = httpx_core::learn_channel(1024);
+    let mut dispatcher = CoreDispatcher::new_with_socket(0, socket, rx, config, LinearIntentTrie::new(1024), learn_tx).await.unwrap();
+
+    let started = std::time::Instant::now();
+    for _ in 0..6 {
+        dispatcher.submit_linked_burst(addr, handle, 0, 1, &slab).await.unwrap();
+    }
+    assert!(started.elapsed() < std::time::Duration::from_millis(9), "Paced submits must not block the caller");
+    assert!(dispatcher.paced_len() > 0, "Bursts past the first slot must be queued");
+
+    let mut stamps = Vec::new();
+    while stamps.len() < 6 {
+        dispatcher.submit_due_bursts(&slab);
+        while stamps.len() < dispatcher.stats().pushes_submitted as usize {
+            stamps.push(std::time::Instant::now());
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+    }
+
+    // Burst `i` is due `i * 10ms` after the first; it may be seen late, never early.
+    for (i, stamp) in stamps.iter().enumerate() {
+        let offset = *stamp - started;
+        assert!(offset >= std::time::Duration::from_millis(9 * i as u64), "Burst {} not paced: sent after {:?}", i, offset);
+    }
+
+    dispatcher.reap_completions(&slab);
+    println!("Pacing Audit: {} bursts released by the due-time queue.", stamps.len());
+}
+
+#[tokio::test]
+async fn test_drain_waits_for_in_flight_slot() {
+    use httpx_core::ControlSignal;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::time::Duration;
+
+    let slab = Arc::new(SecureSlab::new(16));
+    let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let (control_tx, rx) = tokio::sync::mpsc::channel(10);
+    let (learn_tx, _learn_rx) = httpx_core::learn_channel(1024);
+    let mut dispatcher = CoreDispatcher::new_with_socket(0, socket, rx, ServerConfig::default(), LinearIntentTrie::new(1024), learn_tx).await.unwrap();
+
+    // A burst the kernel still holds: no CQE will arrive to release it.
+    let in_flight = 5;
+    slab.increment_rc(in_flight);
+
+    let done = AtomicBool::new(false);
+    let run = async {
+        dispatcher.run_loop(&slab).await;
+        done.store(true, Ordering::SeqCst);
+    };
+    let drive = async {
+        control_tx.send(ControlSignal::Drain).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!done.load(Ordering::SeqCst), "run_loop exited while slot {} was in-flight", in_flight);
+
+        // Kernel completes the send.
+        slab.decrement_rc(in_flight);
+        for _ in 0..100 {
+            if done.load(Ordering::SeqCst) {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("run_loop did not exit after the slot was released");
+    };
+    tokio::join!(run, drive);
+
+    assert!(!slab.is_in_flight(in_flight));
+    println!("Drain Audit: run_loop exited only after RC reached zero.");
+}
+
+// Paused clock: sleeps advance virtual time, so the heartbeat ages below
+// are exact rather than at the mercy of a loaded CI host.
+#[tokio::test(start_paused = true)]
+async fn test_watchdog_flags_stalled_run_loop() {
+    use httpx_core::{ControlSignal, DispatcherStats, Watchdog};
+    use httpx_transport::dispatcher::HEARTBEAT_INTERVAL;
+
+    let slab = SecureSlab::new(4);
+    let stats = Arc::new(DispatcherStats::default());
+    let idle_worker = Arc::new(DispatcherStats::default());
+    let watchdog = Watchdog::new(vec![stats.clone(), idle_worker], HEARTBEAT_INTERVAL * 2);
+    assert_eq!(stats.since_heartbeat(), None);
+
+    let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let (control_tx, rx) = tokio::sync::mpsc::channel(10);
+    let (learn_tx, _learn_rx) = httpx_core::learn_channel(1024);
+    let mut dispatcher = CoreDispatcher::new_with_socket(0, socket, rx, ServerConfig::default(), LinearIntentTrie::new(1024), learn_tx)
+        .await
+        .unwrap()
+        .with_stats(stats.clone());
+
+    let run = dispatcher.run_loop(&slab);
+    let drive = async {
+        // No traffic at all: the loop must still wake up and beat.
+        tokio::time::sleep(HEARTBEAT_INTERVAL * 3).await;
+        let age = stats.since_heartbeat().expect("run_loop never beat");
+        assert!(age <= HEARTBEAT_INTERVAL, "Idle heartbeat is {:?} old", age);
+        assert!(watchdog.check().is_empty(), "A live loop (or one never started) must not be flagged");
+
+        // Wedge the worker: its loop stops, so the heartbeat stops advancing.
+        control_tx.send(ControlSignal::KillAll).await.unwrap();
+    };
+    tokio::join!(run, drive);
+
+    tokio::time::sleep(HEARTBEAT_INTERVAL * 3).await;
+    assert_eq!(watchdog.check(), vec![0]);
+    assert!(watchdog.is_stalled(0));
+    assert_eq!(watchdog.stalled_workers(), vec![0]);
+
+    // A fresh beat clears the verdict.
+    stats.beat();
+    assert!(watchdog.check().is_empty());
+    println!("Watchdog Audit: idle loop kept beating; stopped loop flagged as stalled.");
+}
+
+#[tokio::test]
+async fn test_write_slot_invalidates_old_version() {
+    let slab = Arc::new(SecureSlab::new(64));
+    let handle = 3u32;
+    slab.set_version(handle as usize, 7);
+
+    // Rewriting the payload must advance the version without an explicit bump.
+    slab.write_slot(handle as usize, 0, b"updated payload").unwrap();
+    assert_eq!(slab.get_version(handle as usize), 8);
+
+    let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let addr = socket.local_addr().unwrap();
+    let (_tx, rx) = tokio::sync::mpsc::channel(10);
+    let (learn_tx, _learn_rx) = httpx_core::learn_channel(1024);
+    let mut dispatcher = CoreDispatcher::new_with_socket(0, socket, rx, ServerConfig::default(), LinearIntentTrie::new(1024), learn_tx).await.unwrap();
+
+    // A push still committed against the pre-write version is rejected.
+    let err = dispatcher.submit_linked_burst(addr, handle, 0, 7, &slab).await.unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert!(!slab.is_in_flight(handle as usize));
+
+    let res = dispatcher.submit_linked_burst(addr, handle, 0, 8, &slab).await;
+    assert!(res.is_ok(), "Fresh version must be accepted");
+    dispatcher.reap_completions(&slab);
+
+    // Opting out leaves version management to the caller.
+    let manual = SecureSlab::new(4).with_version_on_write(false);
+    manual.write_slot(0, 0, b"staged").unwrap();
+    assert_eq!(manual.get_version(0), 0);
+
+    println!("Freshness Certified: write_slot advances the version and stale pushes are dropped.");
+}
+
+#[tokio::test]
+async fn test_per_core_stats_are_independent() {
+    use httpx_core::{DispatcherStats, StatsSnapshot};
+
+    // Each worker's counters must occupy their own cache line.
+    assert_eq!(std::mem::align_of::<DispatcherStats>(), 64);
+
+    let slab = Arc::new(SecureSlab::new(64));
+    slab.set_version(0, 1);
+
+    let mut dispatchers = Vec::new();
+    let mut handles = Vec::new();
+    for core_id in 0..2 {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let (_tx, rx) = tokio::sync::mpsc::channel(10);
+        let (learn_tx, _learn_rx) = httpx_core::learn_channel(1024);
+        let stats = Arc::new(DispatcherStats::default());
+        handles.push(stats.clone());
+        let dispatcher = CoreDispatcher::new_with_socket(core_id, socket, rx, ServerConfig::default(), LinearIntentTrie::new(1024), learn_tx)
+            .await
+            .unwrap()
+            .with_stats(stats);
+        dispatchers.push(dispatcher);
+    }
+
+    let target = "127.0.0.1:9".parse().unwrap();
+    for _ in 0..3 {
+        dispatchers[0].submit_linked_burst(target, 0, 0, 1, &slab).await.unwrap();
+    }
+    dispatchers[1].submit_linked_burst(target, 0, 0, 1, &slab).await.unwrap();
+
+    // Increments stay local to the owning worker.
+    assert_eq!(dispatchers[0].stats().pushes_submitted, 3);
+    assert_eq!(dispatchers[1].stats().pushes_submitted, 1);
+    assert_eq!(handles[0].snapshot(), dispatchers[0].stats(), "Shared handle must observe the owner's counters");
+
+    let aggregate: StatsSnapshot = handles.iter().map(|s| s.snapshot()).sum();
+    assert_eq!(aggregate.pushes_submitted, 4);
+    assert_eq!(aggregate.packets_received, 0);
+
+    for d in dispatchers.iter_mut() {
+        d.reap_completions(&slab);
+    }
+    println!("Per-Core Stats Certified: independent cache lines, aggregate = {:?}", aggregate);
+}
+
+#[tokio::test]
+async fn test_burst_iovec_uses_payload_length() {
+    let slab = Arc::new(SecureSlab::new(64));
+    let handle = 2u32;
+
+    // Fill the page with stale bytes, then commit a short 100-byte response.
+    unsafe { std::ptr::write_bytes(slab.get_slot(handle as usize), 0xEE, 4096); }
+    slab.write_slot(handle as usize, 0, &[0x41; 100]).unwrap();
+    slab.set_payload_len(handle as usize, 100);
+    assert_eq!(slab.payload_len(handle as usize), 100);
+    let version = slab.get_version(handle as usize);
+
+    let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let addr = socket.local_addr().unwrap();
+    let (_tx, rx) = tokio::sync::mpsc::channel(10);
+    let (learn_tx, _learn_rx) = httpx_core::learn_channel(1024);
+    let mut dispatcher = CoreDispatcher::new_with_socket(0, socket, rx, ServerConfig::default(), LinearIntentTrie::new(1024), learn_tx).await.unwrap();
+
+    dispatcher.submit_linked_burst(addr, handle, 0, version, &slab).await.unwrap();
+
+    let [_, header_len, payload_len] = dispatcher.packetizer().iovec_lens(handle as usize);
+    assert_eq!(header_len, 128);
+    assert_eq!(payload_len, 100, "Payload iovec must not pad to the 4KB page");
+
+    dispatcher.reap_completions(&slab);
+    println!("Zero-Copy Certified: payload iovec trimmed to {} live bytes.", payload_len);
+}
+
+#[tokio::test]
+async fn test_learning_channel_flood_stays_bounded() {
+    let slab = Arc::new(SecureSlab::new(64));
+    let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let peer = socket.local_addr().unwrap();
+    let (_tx, rx) = tokio::sync::mpsc::channel(10);
+
+    // The orchestrator is deliberately never polled during the flood.
+    let (learn_tx, mut learn_rx) = httpx_core::learn_channel(64);
+    let probe = learn_tx.clone();
+    let mut dispatcher = CoreDispatcher::new_with_socket(0, socket, rx, ServerConfig::default(), LinearIntentTrie::new(1024), learn_tx).await.unwrap();
+
+    const FLOOD: usize = 10_000;
+    let flood = async {
+        for i in 0..FLOOD {
+            let path = format!("/flood/{}", i);
+            dispatcher.on_packet(path.as_bytes(), peer, &slab).await;
+        }
+    };
+    tokio::time::timeout(std::time::Duration::from_secs(5), flood)
+        .await
+        .expect("Dispatcher blocked on a full learning channel");
+
+    // Memory is capped at capacity; the excess was dropped oldest-first.
+    assert_eq!(probe.len(), probe.capacity());
+    assert_eq!(probe.dropped(), (FLOOD - 64) as u64);
+
+    let oldest = learn_rx.try_recv().unwrap();
+    assert_eq!(oldest.path(), format!("/flood/{}", FLOOD - 64).as_bytes());
+    assert!(oldest.success);
+
+    println!("Backpressure Certified: {} events flooded, {} retained, {} dropped.", FLOOD, probe.capacity(), probe.dropped());
+}
+
+#[tokio::test]
+async fn test_sovereign_sessions_weight_learning_through_the_channel() {
+    use httpx_cluster::ClusterOrchestrator;
+    use httpx_core::{ControlSignal, SessionMode};
+
+    let slab = Arc::new(SecureSlab::new(64));
+    let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let peer = socket.local_addr().unwrap();
+    let (_tx, rx) = tokio::sync::mpsc::channel(10);
+    let (learn_tx, learn_rx) = httpx_core::learn_channel(64);
+    let mut dispatcher = CoreDispatcher::new_with_socket(0, socket, rx, ServerConfig::default(), LinearIntentTrie::new(1024), learn_tx).await.unwrap();
+
+    dispatcher.on_packet(b"/integrated", peer, &slab).await;
+    dispatcher.set_session_mode(SessionMode::SovereignAutonomous);
+    dispatcher.on_packet(b"/sovereign", peer, &slab).await;
+    drop(dispatcher);
+
+    // The orchestrator drains the channel, then swaps once the sender is gone.
+    let (worker_tx, mut worker_rx) = tokio::sync::mpsc::channel(4);
+    ClusterOrchestrator::new(0, learn_rx, vec![worker_tx]).run().await;
+    let Ok(ControlSignal::SwapTrie(trie)) = worker_rx.try_recv() else {
+        panic!("Expected a final SwapTrie broadcast");
+    };
+    assert_eq!(trie.get_node_at_path(b"/integrated").unwrap().weights[1], 1);
+    assert_eq!(trie.get_node_at_path(b"/sovereign").unwrap().weights[1], 2, "Sovereign sessions trust local learning 2x");
+
+    println!("Sovereign Weighting Audit: learn events from Sovereign sessions counted twice.");
+}
+
+#[tokio::test]
+async fn test_learning_channel_truncates_long_paths_and_drops_oldest() {
+    use httpx_core::learn::MAX_LEARN_PATH;
+
+    let (mut core_a, mut learn_rx) = httpx_core::learn_channel(4);
+    let mut core_b = core_a.clone();
+
+    // Like the trie past its depth cap, a long context is learned on its prefix.
+    let long: Vec<u8> = (0..MAX_LEARN_PATH + 40).map(|i| b'a' + (i % 26) as u8).collect();
+    core_a.send(&long, true);
+    core_a.send(&[b'a'; MAX_LEARN_PATH], true);
+    assert_eq!(learn_rx.truncated(), 1);
+    assert_eq!(learn_rx.try_recv().unwrap().path(), &long[..MAX_LEARN_PATH]);
+    assert_eq!(learn_rx.try_recv().unwrap().path().len(), MAX_LEARN_PATH);
+
+    // Each worker fills its own ring; one flooding core cannot crowd out
+    // another, and a full ring keeps its freshest events.
+    for i in 0..8 {
+        core_a.send(format!("/a/{}", i).as_bytes(), true);
+    }
+    core_b.send(b"/b", true);
+    assert_eq!(learn_rx.dropped(), 4);
+    let paths: Vec<Vec<u8>> = std::iter::from_fn(|| learn_rx.try_recv()).map(|e| e.path().to_vec()).collect();
+    assert_eq!(paths.len(), 5);
+    assert!(paths.contains(&b"/b".to_vec()));
+    for i in 4..8 {
+        assert!(paths.contains(&format!("/a/{}", i).into_bytes()), "Event {} is among the newest", i);
+    }
+
+    // A dropped sender's pending events are still delivered.
+    core_b.send(b"/late", true);
+    drop(core_b);
+    assert_eq!(learn_rx.try_recv().unwrap().path(), b"/late");
+    assert!(learn_rx.try_recv().is_none());
+
+    println!("Learning Audit: {} long path truncated, {} oldest events dropped per-core.", learn_rx.truncated(), learn_rx.dropped());
+}
+
+#[test]
+fn test_learning_ring_eviction_races_receiver_safely() {
+    const EVENTS: usize = 200_000;
+    let (mut learn_tx, mut learn_rx) = httpx_core::learn_channel(8);
+    let probe = learn_tx.clone();
+
+    // The producer evicts from the same ring the receiver drains.
+    let producer = std::thread::spawn(move || {
+        for i in 0..EVENTS {
+            learn_tx.send(&(i as u64).to_be_bytes(), true);
+        }
+    });
+    let mut received = Vec::new();
+    loop {
+        match learn_rx.try_recv() {
+            Some(event) => received.push(u64::from_be_bytes(event.path().try_into().unwrap())),
+            None if producer.is_finished() && probe.is_empty() => break,
+            None => std::hint::spin_loop(),
+        }
+    }
+    producer.join().unwrap();
+
+    // Nothing duplicated or reordered, and every event is either delivered or counted.
+    assert!(received.windows(2).all(|w| w[0] < w[1]));
+    assert_eq!(received.len() as u64 + learn_rx.dropped(), EVENTS as u64);
+    assert_eq!(*received.last().unwrap(), EVENTS as u64 - 1, "The newest event always survives");
+
+    println!("Eviction Race Audit: {} delivered, {} evicted.", received.len(), learn_rx.dropped());
+}
+
+#[tokio::test]
+async fn test_credit_level_sets_push_fanout() {
+    use httpx_transport::CreditPolicy;
+
+    let slab = Arc::new(SecureSlab::new(64));
+    let mut trie = LinearIntentTrie::new(1024);
+    for (i, path) in [&b"/a"[..], b"/a/b", b"/a/c", b"/a/d"].iter().enumerate() {
+        let handle = i as u32 + 1;
+        trie.observe(path, true);
+        trie.associate_payload(path, handle, 1).unwrap();
+        slab.set_version(handle as usize, 1);
+    }
+
+    let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let client = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let peer = client.local_addr().unwrap();
+    let (_tx, rx) = tokio::sync::mpsc::channel(10);
+    let (learn_tx, _learn_rx) = httpx_core::learn_channel(1024);
+    let mut dispatcher = CoreDispatcher::new_with_socket(0, socket, rx, ServerConfig::default(), trie, learn_tx)
+        .await
+        .unwrap()
+        .with_credit_policy(CreditPolicy::new(3));
+
+    let expectations = [
+        (0u8, 0u64, "Level 0 must suppress all pushes"),
+        (1, 1, "Level 1 pushes only the strongest prediction"),
+        (2, 3, "Level 2 pushes the top-K predictions"),
+    ];
+    for (level, expected, msg) in expectations {
+        dispatcher.set_credit_level(level);
+        let before = dispatcher.stats().pushes_submitted;
+        dispatcher.on_packet(b"/a", peer, &slab).await;
+        dispatcher.reap_completions(&slab);
+        assert_eq!(dispatcher.stats().pushes_submitted - before, expected, "{}", msg);
+    }
+
+    println!("Multi-Level Credit Certified: fanout 0 / 1 / K by level.");
+}
+
+#[tokio::test]
+async fn test_credit_level_follows_controller_rtt_samples() {
+    use httpx_transport::{CreditPolicy, DefaultCongestionController};
+
+    let slab = Arc::new(SecureSlab::new(64));
+    let mut trie = LinearIntentTrie::new(1024);
+    for (i, path) in [&b"/a"[..], b"/a/b", b"/a/c", b"/a/d"].iter().enumerate() {
+        let handle = i as u32 + 1;
+        trie.observe(path, true);
+        trie.associate_payload(path, handle, 1).unwrap();
+        slab.set_version(handle as usize, 1);
+    }
+
+    let client = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let peer = client.local_addr().unwrap();
+    let mut fanout = Vec::new();
+    // A 10s baseline never backs off; a 1ns baseline backs off on the first sample.
+    for base_rtt in [10_000_000_000u64, 1] {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let (_tx, rx) = tokio::sync::mpsc::channel(10);
+        let (learn_tx, _learn_rx) = httpx_core::learn_channel(1024);
+        let mut dispatcher = CoreDispatcher::new_with_socket(0, socket, rx, ServerConfig::default(), trie.clone(), learn_tx)
+            .await
+            .unwrap()
+            .with_credit_policy(CreditPolicy::new(3))
+            .with_congestion_controller(Arc::new(DefaultCongestionController::new(base_rtt)));
+
+        let mut pushes = Vec::new();
+        for _ in 0..2 {
+            let before = dispatcher.stats().pushes_submitted;
+            dispatcher.on_packet(b"/a", peer, &slab).await;
+            for _ in 0..100 {
+                let stats = dispatcher.stats();
+                if stats.pushes_submitted == stats.completions_reaped {
+                    break;
+                }
+                dispatcher.reap_completions(&slab);
+                tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+            }
+            pushes.push(dispatcher.stats().pushes_submitted - before);
+        }
+        fanout.push(pushes);
+    }
+
+    assert_eq!(fanout[0], vec![1, 3], "Level 1 until the first sample, then the controller's Level 2");
+    assert_eq!(fanout[1], vec![1, 0], "An RTT past 1.2x the baseline drops to Level 0");
+
+    println!("Credit Feedback Certified: fanout {:?} under a calm path, {:?} under a congested one.", fanout[0], fanout[1]);
+}
+
+#[tokio::test]
+async fn test_submit_recovers_from_full_sq() {
+    let slab = Arc::new(SecureSlab::new(64));
+    slab.set_version(1, 1);
+
+    let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let addr = socket.local_addr().unwrap();
+    let (_tx, rx) = tokio::sync::mpsc::channel(10);
+    let (learn_tx, _learn_rx) = httpx_core::learn_channel(1024);
+    let mut dispatcher = CoreDispatcher::new_with_socket(0, socket, rx, ServerConfig::default(), LinearIntentTrie::new(1024), learn_tx).await.unwrap();
+
+    // Fill the SQ with unsubmitted NOPs so the burst's first push fails.
+    {
+        let ring = dispatcher.ring_mut();
+        let nop = io_uring::opcode::Nop::new().build().user_data(0);
+        let mut sq = ring.submission();
+        while unsafe { sq.push(&nop) }.is_ok() {}
+        assert!(sq.is_full());
+    }
+
+    let res = dispatcher.submit_linked_burst(addr, 1, 0, 1, &slab).await;
+    assert!(res.is_ok(), "Burst must succeed after the internal drain: {:?}", res);
+    assert_eq!(slab.ref_count(1), 1, "Retry must not double-increment the RC");
+
+    for _ in 0..100 {
+        dispatcher.reap_completions(&slab);
+        if !slab.is_in_flight(1) {
+            break;
+        }
+        dispatcher.ring_mut().submit_and_wait(1).unwrap();
+    }
+    assert!(!slab.is_in_flight(1));
+
+    println!("SQ Backpressure Certified: full SQ drained and burst retried once.");
+}
+
+#[tokio::test]
+async fn test_pivot_cancels_in_flight_burst() {
+    let slab = Arc::new(SecureSlab::new(64));
+    slab.set_version(1, 1);
+
+    let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let addr = socket.local_addr().unwrap();
+    let (_tx, rx) = tokio::sync::mpsc::channel(10);
+    let (learn_tx, _learn_rx) = httpx_core::learn_channel(1024);
+    let mut dispatcher = CoreDispatcher::new_with_socket(0, socket, rx, ServerConfig::default(), LinearIntentTrie::new(1024), learn_tx).await.unwrap();
+
+    // Hold the burst in the ring: a drain barrier behind a pending timeout
+    // keeps the sendmsg queued until it is cancelled.
+    {
+        let ring = dispatcher.ring_mut();
+        let ts = io_uring::types::Timespec::new().nsec(200_000_000);
+        let timeout = io_uring::opcode::Timeout::new(&ts).build().user_data(0);
+        let barrier = io_uring::opcode::Nop::new().build().flags(io_uring::squeue::Flags::IO_DRAIN).user_data(0);
+        let mut sq = ring.submission();
+        unsafe {
+            sq.push(&timeout).unwrap();
+            sq.push(&barrier).unwrap();
+        }
+    }
+
+    dispatcher.submit_linked_burst(addr, 1, 0, 1, &slab).await.unwrap();
+    assert!(slab.is_in_flight(1));
+    assert_eq!(dispatcher.cancel_for(addr), 1, "One in-flight burst targets the pivoting address");
+
+    for _ in 0..100 {
+        dispatcher.reap_completions(&slab);
+        if !slab.is_in_flight(1) {
+            break;
+        }
+        dispatcher.ring_mut().submit_and_wait(1).unwrap();
+    }
+    assert!(!slab.is_in_flight(1));
+    assert_eq!(dispatcher.stats().pushes_canceled, 1, "The burst CQE must report -ECANCELED");
+    assert_eq!(dispatcher.cancel_for(addr), 0, "Nothing left in flight after the reap");
+
+    println!("Pivot Cancellation Certified: in-flight burst cancelled by address.");
+}
+
+#[tokio::test]
+async fn test_pivot_spares_same_payload_burst_to_other_peer() {
+    let slab = Arc::new(SecureSlab::new(64));
+    slab.set_version(1, 1);
+
+    let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let peer_a = UdpSocket::bind("127.0.0.1:0").await.unwrap().local_addr().unwrap();
+    let peer_b = UdpSocket::bind("127.0.0.1:0").await.unwrap().local_addr().unwrap();
+    let (_tx, rx) = tokio::sync::mpsc::channel(10);
+    let (learn_tx, _learn_rx) = httpx_core::learn_channel(1024);
+    let mut dispatcher = CoreDispatcher::new_with_socket(0, socket, rx, ServerConfig::default(), LinearIntentTrie::new(1024), learn_tx).await.unwrap();
+
+    // Hold both bursts behind a drain barrier so the cancel finds them queued.
+    {
+        let ring = dispatcher.ring_mut();
+        let ts = io_uring::types::Timespec::new().nsec(200_000_000);
+        let timeout = io_uring::opcode::Timeout::new(&ts).build().user_data(0);
+        let barrier = io_uring::opcode::Nop::new().build().flags(io_uring::squeue::Flags::IO_DRAIN).user_data(0);
+        let mut sq = ring.submission();
+        unsafe {
+            sq.push(&timeout).unwrap();
+            sq.push(&barrier).unwrap();
+        }
+    }
+
+    // The same payload and template to two peers.
+    dispatcher.submit_linked_burst(peer_a, 1, 0, 1, &slab).await.unwrap();
+    dispatcher.submit_linked_burst(peer_b, 1, 0, 1, &slab).await.unwrap();
+    assert_eq!(slab.ref_count(1), 2);
+    assert_eq!(dispatcher.cancel_for(peer_a), 1, "Only peer A's burst may be targeted");
+
+    for _ in 0..100 {
+        dispatcher.reap_completions(&slab);
+        if !slab.is_in_flight(1) {
+            break;
+        }
+        dispatcher.ring_mut().submit_and_wait(1).unwrap();
+    }
+    assert!(!slab.is_in_flight(1), "Both bursts must release their RC exactly once");
+    assert_eq!(dispatcher.stats().pushes_canceled, 1, "Peer B's burst must not be cancelled");
+    assert_eq!(dispatcher.cancel_for(peer_b), 0, "Peer B's burst completed and left the in-flight table");
+
+    println!("Burst Token Audit: pivot cancelled one of two identical bursts.");
+}
+
+#[tokio::test]
+async fn test_push_latency_histogram_reports_percentiles() {
+    let slab = Arc::new(SecureSlab::new(16));
+    let handle = 1u32;
+    slab.write_slot(handle as usize, 0, &[0x42; 512]).unwrap();
+    let version = slab.get_version(handle as usize);
+
+    let receiver = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+    let target = receiver.local_addr().unwrap();
+    let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    socket.connect(target).await.unwrap();
+    let (_tx, rx) = tokio::sync::mpsc::channel(10);
+    let (learn_tx, _learn_rx) = httpx_core::learn_channel(1024);
+    let mut dispatcher = CoreDispatcher::new_with_socket(0, socket, rx, ServerConfig::default(), LinearIntentTrie::new(1024), learn_tx).await.unwrap();
+    assert!(dispatcher.latency().is_empty());
+
+    const BURSTS: u64 = 8;
+    for _ in 0..BURSTS {
+        dispatcher.submit_linked_burst(target, handle, 0, version, &slab).await.unwrap();
+    }
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(2);
+    while dispatcher.stats().completions_reaped < BURSTS && std::time::Instant::now() < deadline {
+        dispatcher.reap_completions(&slab);
+        tokio::task::yield_now().await;
+    }
+
+    let latency = dispatcher.latency().percentiles();
+    assert_eq!(latency.count, BURSTS, "Every successful completion records one sample");
+    assert!(latency.p50 > std::time::Duration::ZERO);
+    assert!(latency.p50 < std::time::Duration::from_secs(1), "Implausible loopback p50 {:?}", latency.p50);
+    assert!(latency.p50 <= latency.p99 && latency.p99 <= latency.p999 && latency.p999 <= latency.max);
+    println!("Latency Audit: p50 {:?}, p99 {:?}, p999 {:?} over {} pushes.", latency.p50, latency.p99, latency.p999, latency.count);
+}
+
+#[test]
+fn test_latency_buckets_record_lock_free_within_one_percent() {
+    use httpx_core::DispatcherStats;
+    use std::time::Duration;
+
+    let stats = Arc::new(DispatcherStats::default());
+    let samples = [Duration::from_nanos(7), Duration::from_micros(42), Duration::from_millis(3), Duration::from_secs(60)];
+    let recorders: Vec<_> = (0..4)
+        .map(|_| {
+            let stats = stats.clone();
+            std::thread::spawn(move || {
+                for _ in 0..1000 {
+                    for &sample in &samples {
+                        stats.record_latency(sample);
+                    }
+                }
+            })
+        })
+        .collect();
+    for recorder in recorders {
+        recorder.join().unwrap();
+    }
+
+    let latency = stats.latency();
+    assert_eq!(latency.len(), 16_000, "Concurrent records must not be lost");
+    // Sub-256ns latencies are exact; larger ones land within 1%; overlong ones clamp to 10s.
+    let expected = [Duration::from_nanos(7), Duration::from_micros(42), Duration::from_millis(3), Duration::from_secs(10)];
+    for (i, want) in expected.iter().enumerate() {
+        let got = latency.quantile(0.25 * i as f64 + 0.1);
+        let error = got.as_nanos().abs_diff(want.as_nanos()) as f64 / want.as_nanos() as f64;
+        assert!(error < 0.01, "Bucket {} reported {:?} for {:?}", i, got, want);
+    }
+    println!("Latency Audit: {} lock-free samples, p50 {:?}.", latency.len(), latency.quantile(0.5));
+}
+
+#[tokio::test]
+async fn test_frame_version_negotiation() {
+    use httpx_transport::{HttpxFrame, ProtocolError};
+
+    let slab = Arc::new(SecureSlab::new(16));
+    let mut trie = LinearIntentTrie::new(1024);
+    trie.observe(b"/v", true);
+    trie.associate_payload(b"/v", 1, 1).unwrap();
+    slab.set_version(1, 1);
+
+    let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let client = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let peer = client.local_addr().unwrap();
+    let (_tx, rx) = tokio::sync::mpsc::channel(10);
+    let (learn_tx, _learn_rx) = httpx_core::learn_channel(1024);
+    let config = ServerConfig { accepted_versions: vec![1, 2], ..Default::default() };
+    let mut dispatcher = CoreDispatcher::new_with_socket(0, socket, rx, config, trie, learn_tx).await.unwrap();
+
+    // An accepted version resolves like the bare path.
+    let whole = httpx_transport::FragmentHeader { payload_handle: 0, index: 0, total: 1 };
+    let v2 = HttpxFrame::fragment(b"/v", whole).encode();
+    assert_eq!(HttpxFrame::decode_accepting(&v2, &[1, 2]), Ok(HttpxFrame::fragment(b"/v", whole)));
+    dispatcher.on_packet(&v2, peer, &slab).await;
+    dispatcher.reap_completions(&slab);
+    assert_eq!(dispatcher.stats().pushes_submitted, 1, "Accepted version must be served");
+
+    // An unsupported version is rejected before prediction.
+    let v9 = HttpxFrame { version: 9, ..HttpxFrame::new(b"/v") }.encode();
+    assert_eq!(HttpxFrame::decode_accepting(&v9, &[1, 2]), Err(ProtocolError::UnsupportedVersion(9)));
+    dispatcher.on_packet(&v9, peer, &slab).await;
+    dispatcher.reap_completions(&slab);
+    assert_eq!(dispatcher.stats().pushes_submitted, 1, "Unsupported version must not be served");
+
+    assert_eq!(HttpxFrame::decode(b"HTPX"), Err(ProtocolError::Truncated));
+    assert_eq!(HttpxFrame::decode(b"/v"), Err(ProtocolError::BadMagic));
+    println!("Protocol Negotiation Certified: v2 accepted, v9 rejected.");
+}
+
+#[tokio::test]
+async fn test_stream_batch_scales_with_credit_level() {
+    use httpx_transport::stream::{PayloadStreamer, MAX_BATCH_BYTES};
+    use httpx_transport::{CongestionController, TransportError};
+    use std::sync::atomic::{AtomicU8, Ordering};
+
+    struct FixedLevel(AtomicU8);
+    impl CongestionController for FixedLevel {
+        fn evaluate_intent_credit(&self, _rtt_nanos: u64) -> u8 {
+            self.credit_level()
+        }
+        fn notify_loss(&self) {}
+        fn credit_level(&self) -> u8 {
+            self.0.load(Ordering::Relaxed)
+        }
+    }
+
+    let receiver = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+    receiver.set_nonblocking(true).unwrap();
+    let target = receiver.local_addr().unwrap();
+    let cc = Arc::new(FixedLevel(AtomicU8::new(1)));
+    let streamer = PayloadStreamer::new(UdpSocket::bind("127.0.0.1:0").await.unwrap(), 1500)
+        .unwrap()
+        .with_congestion_controller(cc.clone());
+
+    let slab = SecureSlab::new(32);
+    for i in 0..16 {
+        slab.set_version(i, 1);
+    }
+    let handles: Vec<(u32, u32)> = (0..16).map(|i| (i, 1)).collect();
+
+    // Level 1: a quarter of the 64KB super-packet.
+    assert_eq!(streamer.batch_limit(), MAX_BATCH_BYTES / 4);
+    let err = streamer.stream_batch(&slab, &handles, target).await.unwrap_err();
+    assert!(matches!(err, TransportError::PayloadTooLarge { len: 66121, limit } if limit == MAX_BATCH_BYTES / 4));
+    let fits = streamer.batch_limit() / 4096;
+    assert_eq!(streamer.stream_batch(&slab, &handles[..fits], target).await.unwrap(), 3);
+
+    // Level 0 sends nothing at all; Level 2 restores the full batch.
+    cc.0.store(0, Ordering::Relaxed);
+    while receiver.recv_from(&mut [0u8; 2048]).is_ok() {}
+    assert_eq!(streamer.stream_batch(&slab, &handles[..1], target).await.unwrap(), 0);
+    assert!(receiver.recv_from(&mut [0u8; 2048]).is_err(), "Level 0 must not send");
+    cc.0.store(2, Ordering::Relaxed);
+    assert_eq!(streamer.stream_batch(&slab, &handles[..15], target).await.unwrap(), 15);
+    println!("Adaptive GSO Certified: Level 1 batch capped at {} bytes.", MAX_BATCH_BYTES / 4);
+}
+
+#[tokio::test]
+async fn test_zero_copy_ingress_lands_in_pool_slot() {
+    use httpx_transport::TransportError;
+    use httpx_dsa::SlabError;
+
+    let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let server = socket.local_addr().unwrap();
+    let (_tx, rx) = tokio::sync::mpsc::channel(10);
+    let (learn_tx, _learn_rx) = httpx_core::learn_channel(16);
+    let mut dispatcher = CoreDispatcher::new_with_socket(0, socket, rx, ServerConfig::default(), LinearIntentTrie::new(1024), learn_tx)
+        .await
+        .unwrap()
+        .with_ingress_pool(SecureSlab::new(2)); // one usable slot: 0 is reserved
+    let slab = SecureSlab::new(8);
+    dispatcher.register_slab(&slab).unwrap();
+
+    let slot = dispatcher.post_ingress().unwrap();
+    let pool = dispatcher.ingress_buffers().unwrap();
+    assert!(pool.is_in_flight(slot), "A posted receive holds its slot");
+    assert_eq!(dispatcher.ingress_posted(), 1);
+    assert!(matches!(dispatcher.post_ingress(), Err(TransportError::Slab(SlabError::Exhausted))), "An exhausted pool must fail the post");
+    assert_eq!((0..8).filter(|&i| slab.is_in_flight(i)).count(), 0, "Ingress must not borrow payload slots");
+
+    let client = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+    client.send_to(b"GET /zero-copy", server).unwrap();
+
+    let t = std::time::Instant::now();
+    let dgram = loop {
+        dispatcher.reap_completions(&slab);
+        if let Some(dgram) = dispatcher.next_ingress() {
+            break dgram;
+        }
+        assert!(t.elapsed() < std::time::Duration::from_secs(2), "Datagram never completed");
+        tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+    };
+
+    assert_eq!(dgram.slot, slot);
+    assert_eq!(dgram.src, client.local_addr().unwrap());
+    let pool = dispatcher.ingress_buffers().unwrap();
+    let landed = unsafe { std::slice::from_raw_parts(pool.get_slot(slot), dgram.len) };
+    assert_eq!(landed, b"GET /zero-copy", "Bytes must be in pool memory");
+    assert!(!pool.is_in_flight(slot));
+    assert_eq!(dispatcher.ingress_posted(), 0);
+
+    dispatcher.release_ingress(slot);
+    assert_eq!(dispatcher.post_ingress().unwrap(), slot, "Released ingress slot returns to the free-list");
+    println!("Zero-Copy Ingress Certified: {} bytes landed in slot {}.", dgram.len, slot);
+}
+
+#[tokio::test]
+async fn test_dispatcher_drives_mock_slab() {
+    use httpx_dsa::{SlabError, SlabLike};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Plain heap pages with reference counts; no mmap, no guard pages.
+    struct MockSlab {
+        pages: Vec<std::cell::UnsafeCell<[u8; 4096]>>,
+        rcs: Vec<AtomicUsize>,
+        increments: AtomicUsize,
+    }
+    unsafe impl Sync for MockSlab {}
+
+    // # Safety: the pages are heap-allocated once and never moved or freed
+    // while the mock lives, and in-flight state follows the RC counters.
+    unsafe impl SlabLike for MockSlab {
+        fn get_slot(&self, idx: usize) -> *mut u8 {
+            self.pages[idx].get() as *mut u8
+        }
+        fn slots(&self) -> usize {
+            self.pages.len()
+        }
+        fn get_version(&self, _idx: usize) -> u32 {
+            7
+        }
+        fn payload_len(&self, _idx: usize) -> usize {
+            64
+        }
+        fn increment_rc(&self, idx: usize) {
+            self.increments.fetch_add(1, Ordering::Relaxed);
+            self.rcs[idx].fetch_add(1, Ordering::AcqRel);
+        }
+        fn decrement_rc(&self, idx: usize) {
+            self.rcs[idx].fetch_sub(1, Ordering::AcqRel);
+        }
+        fn is_in_flight(&self, idx: usize) -> bool {
+            self.rcs[idx].load(Ordering::Acquire) > 0
+        }
+        fn is_poisoned(&self, _idx: usize) -> bool {
+            false
+        }
+        fn allocate_slot(&self) -> Result<usize, SlabError> {
+            Err(SlabError::Exhausted)
+        }
+        fn free_slot(&self, _idx: usize) {}
+    }
+
+    let slab = MockSlab {
+        pages: (0..4).map(|_| std::cell::UnsafeCell::new([0x5A; 4096])).collect(),
+        rcs: (0..4).map(|_| AtomicUsize::new(0)).collect(),
+        increments: AtomicUsize::new(0),
+    };
+
+    let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let addr = socket.local_addr().unwrap();
+    let (_tx, rx) = tokio::sync::mpsc::channel(10);
+    let (learn_tx, _learn_rx) = httpx_core::learn_channel(1024);
+    let mut dispatcher = CoreDispatcher::new_with_socket(0, socket, rx, ServerConfig::default(), LinearIntentTrie::new(1024), learn_tx)
+        .await
+        .unwrap()
+        .for_slab::<MockSlab>();
+
+    // A stale version is refused before any slot is pinned.
+    assert!(dispatcher.submit_linked_burst(addr, 2, 1, 6, &slab).await.is_err());
+    assert_eq!(slab.increments.load(Ordering::Relaxed), 0);
+
+    dispatcher.submit_linked_burst(addr, 2, 1, 7, &slab).await.unwrap();
+    assert_eq!(slab.increments.load(Ordering::Relaxed), 2, "Payload and template pinned once each");
+    let [_, _, payload_len] = dispatcher.packetizer().iovec_lens(2);
+    assert_eq!(payload_len, 64, "Burst length comes from the mock");
+
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(2);
+    while (slab.is_in_flight(1) || slab.is_in_flight(2)) && std::time::Instant::now() < deadline {
+        dispatcher.reap_completions(&slab);
+        tokio::task::yield_now().await;
+    }
+    assert!(!slab.is_in_flight(1) && !slab.is_in_flight(2), "Reaping must release the mock's RCs");
+    assert_eq!(dispatcher.stats().completions_reaped, 1);
+    println!("SlabLike Certified: burst submitted and reaped over a heap-backed mock slab.");
+}
+
+#[tokio::test]
+async fn test_push_path_refuses_poisoned_slots() {
+    use httpx_dsa::SlabError;
+    use httpx_transport::stream::PayloadStreamer;
+    use httpx_transport::TransportError;
+
+    let slab = SecureSlab::new(8);
+    slab.write_slot(2, 0, &[0x42; 256]).unwrap();
+    let version = slab.get_version(2);
+
+    let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let addr = socket.local_addr().unwrap();
+    let (_tx, rx) = tokio::sync::mpsc::channel(10);
+    let (learn_tx, _learn_rx) = httpx_core::learn_channel(16);
+    let mut dispatcher = CoreDispatcher::new_with_socket(0, socket, rx, ServerConfig::default(), LinearIntentTrie::new(1024), learn_tx).await.unwrap();
+    dispatcher.register_slab(&slab).unwrap();
+
+    // Neither the payload nor the template may ship while quarantined.
+    for poisoned in [2, 1] {
+        slab.poison(poisoned);
+        let refused = dispatcher.submit_linked_burst(addr, 2, 1, version, &slab).await;
+        assert!(matches!(refused, Err(TransportError::Slab(SlabError::Poisoned(h))) if h == poisoned));
+        assert!(!slab.is_in_flight(1) && !slab.is_in_flight(2), "A refused burst pins nothing");
+        slab.clear_poison(poisoned);
+    }
+
+    // A paced burst poisoned while queued is dropped at its due time.
+    dispatcher.set_pacing_rate(Some(100_000));
+    for _ in 0..4 {
+        dispatcher.submit_linked_burst(addr, 2, 1, version, &slab).await.unwrap();
+    }
+    assert!(dispatcher.paced_len() > 0);
+    slab.poison(2);
+    std::thread::sleep(std::time::Duration::from_millis(50));
+    assert_eq!(dispatcher.submit_due_bursts(&slab), 0, "A poisoned queued burst must not be submitted");
+    assert_eq!(dispatcher.paced_len(), 0);
+    slab.clear_poison(2);
+
+    let streamer = PayloadStreamer::new(UdpSocket::bind("127.0.0.1:0").await.unwrap(), 1500).unwrap();
+    slab.poison(2);
+    let refused = streamer.stream_batch(&slab, &[(2, version)], addr).await;
+    assert!(matches!(refused, Err(TransportError::Slab(SlabError::Poisoned(2)))));
+    slab.clear_poison(2);
+    assert_eq!(streamer.stream_batch(&slab, &[(2, version)], addr).await.unwrap(), 1);
+
+    println!("Quarantine Certified: poisoned slots refused by bursts, paced bursts and batches.");
+}
+
+#[tokio::test]
+async fn test_client_detects_dropped_batch_fragment() {
+    use httpx_transport::stream::PayloadStreamer;
+    use httpx_transport::{HttpxFrame, Reassembler};
+    use std::time::{Duration, Instant};
+
+    let receiver = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let target = receiver.local_addr().unwrap();
+    let streamer = PayloadStreamer::new(UdpSocket::bind("127.0.0.1:0").await.unwrap(), 1500).unwrap();
+
+    let slab = SecureSlab::new(8);
+    for i in 1..3 {
+        slab.set_version(i, 1);
+        unsafe { std::ptr::write_bytes(slab.get_slot(i), i as u8, 4096); }
+    }
+    assert_eq!(streamer.stream_batch(&slab, &[(1, 1), (2, 1)], target).await.unwrap(), 2);
+
+    // 8192 bytes in 1491-byte bodies: 6 segments, each its own datagram.
+    let mut datagrams = Vec::new();
+    let mut buf = [0u8; 2048];
+    while datagrams.len() < 6 {
+        let (len, _) = tokio::time::timeout(std::time::Duration::from_secs(2), receiver.recv_from(&mut buf))
+            .await
+            .expect("segment never arrived")
+            .unwrap();
+        datagrams.push(buf[..len].to_vec());
+    }
+
+    let frames: Vec<HttpxFrame> = datagrams.iter().map(|d| HttpxFrame::decode(d).unwrap()).collect();
+    assert!(frames.iter().enumerate().all(|(i, f)| {
+        f.fragment.is_some_and(|h| h.payload_handle == 1 && h.index == i as u16 && h.total == 6)
+    }));
+    let body: Vec<u8> = frames.iter().flat_map(|f| f.path.iter().copied()).collect();
+    assert_eq!(body.len(), 8192);
+    assert!(body[..4096].iter().all(|&b| b == 1) && body[4096..].iter().all(|&b| b == 2));
+
+    // Drop fragment 2 in flight: the reassembler holds the rest and names the gap.
+    let start = Instant::now();
+    let mut reassembler = Reassembler::new(Duration::from_millis(100));
+    for frame in frames.iter().filter(|f| f.fragment.unwrap().index != 2) {
+        assert_eq!(reassembler.ingest_frame_at(frame, start), None);
+    }
+    assert_eq!(reassembler.missing(1), vec![2]);
+
+    // A retransmitted fragment completes the batch under its first handle.
+    assert_eq!(reassembler.ingest_frame_at(&frames[2], start), Some((1, body.clone())));
+    assert_eq!(reassembler.pending(), 0);
+
+    // Without the retransmission the partial batch times out.
+    for frame in &frames[..2] {
+        reassembler.ingest_frame_at(frame, start);
+    }
+    assert_eq!(reassembler.expire(start + Duration::from_millis(150)), 1);
+    assert!(reassembler.missing(1).is_empty());
+    println!("Fragment Loss Certified: gap at fragment 2 of {} detected.", frames.len());
+}
+
+#[tokio::test]
+async fn test_8k_slot_slab_uses_full_slot_everywhere() {
+    use httpx_transport::stream::PayloadStreamer;
+    use httpx_transport::HttpxFrame;
+
+    let slab = SecureSlab::with_slot_size(8, 8192);
+    assert_eq!(slab.slot_size(), 8192);
+
+    let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let server = socket.local_addr().unwrap();
+    let (_tx, rx) = tokio::sync::mpsc::channel(10);
+    let (learn_tx, _learn_rx) = httpx_core::learn_channel(16);
+    let mut dispatcher = CoreDispatcher::new_with_socket(0, socket, rx, ServerConfig::default(), LinearIntentTrie::new(1024), learn_tx).await.unwrap();
+    dispatcher.register_slab(&slab).unwrap();
+
+    // Ingress: a 6000-byte datagram lands whole instead of being cut at 4KB.
+    let slot = dispatcher.post_ingress().unwrap();
+    let client = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+    client.send_to(&[0x6B; 6000], server).unwrap();
+    let t = std::time::Instant::now();
+    let dgram = loop {
+        dispatcher.reap_completions(&slab);
+        if let Some(dgram) = dispatcher.next_ingress() {
+            break dgram;
+        }
+        assert!(t.elapsed() < std::time::Duration::from_secs(2), "Datagram never completed");
+        tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+    };
+    assert_eq!(dgram.len, 6000);
+    dispatcher.release_ingress(slot);
+
+    // Egress: a full 8KB payload is accepted and sent at its real length.
+    let handle = 2usize;
+    slab.write_slot(handle, 0, &[0x42; 8192]).unwrap();
+    let version = slab.get_version(handle);
+    dispatcher.submit_linked_burst(client.local_addr().unwrap(), handle as u32, 0, version, &slab).await.unwrap();
+    let [_, _, payload_len] = dispatcher.packetizer().iovec_lens(handle);
+    assert_eq!(payload_len, 8192);
+    dispatcher.reap_completions(&slab);
+
+    // Streaming: each handle contributes the whole 8KB slot.
+    let receiver = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let target = receiver.local_addr().unwrap();
+    let streamer = PayloadStreamer::new(UdpSocket::bind("127.0.0.1:0").await.unwrap(), 1500).unwrap();
+    assert_eq!(streamer.stream_batch(&slab, &[(handle as u32, version)], target).await.unwrap(), 1);
+
+    let mut body = Vec::new();
+    let mut buf = [0u8; 2048];
+    loop {
+        let (len, _) = tokio::time::timeout(std::time::Duration::from_secs(2), receiver.recv_from(&mut buf))
+            .await
+            .expect("segment never arrived")
+            .unwrap();
+        let frame = HttpxFrame::decode(&buf[..len]).unwrap();
+        body.extend_from_slice(frame.path);
+        let fragment = frame.fragment.unwrap();
+        if fragment.index + 1 == fragment.total {
+            break;
+        }
+    }
+    assert_eq!(body.len(), 8192);
+    assert!(body.iter().all(|&b| b == 0x42));
+    println!("Slot Size Audit: ingress, burst and batch all carried 8192-byte slots.");
+}
+
+#[tokio::test]
+async fn test_reaper_drains_overflowed_cq_within_iteration_cap() {
+    const BURSTS: usize = 64;
+
+    // A 4-entry ring has an 8-entry CQ, so most completions overflow into
+    // the kernel's backlog and only come back once the ring is re-entered.
+    async fn overflowed(cap: usize, slab: &SecureSlab) -> CoreDispatcher {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let target = UdpSocket::bind("127.0.0.1:0").await.unwrap().local_addr().unwrap();
+        let (_tx, rx) = tokio::sync::mpsc::channel(10);
+        let (learn_tx, _learn_rx) = httpx_core::learn_channel(16);
+        let config = ServerConfig { reap_drain_iterations: cap, ..Default::default() };
+        let ring = io_uring::IoUring::builder().build(4).unwrap();
+        let mut dispatcher =
+            CoreDispatcher::new_from_ring(0, socket, rx, config, LinearIntentTrie::new(16), ring, learn_tx).await.unwrap();
+        let version = slab.get_version(1);
+        for _ in 0..BURSTS {
+            dispatcher.submit_linked_burst(target, 1, 0, version, slab).await.unwrap();
+        }
+        dispatcher
+    }
+
+    let slab = SecureSlab::new(4);
+    slab.write_slot(1, 0, &[0x5A; 64]).unwrap();
+
+    // One pass only sees what fits in the CQ.
+    let mut capped = overflowed(1, &slab).await;
+    let first = capped.reap_completions(&slab);
+    assert!(first < BURSTS, "A single pass reaped {} of {}", first, BURSTS);
+    assert!(slab.is_in_flight(1));
+    let mut total = first;
+    while total < BURSTS {
+        total += capped.reap_completions(&slab);
+    }
+    assert!(!slab.is_in_flight(1) && !slab.is_in_flight(0));
+
+    // With room for enough passes, one call empties CQ and backlog alike.
+    let mut drained = overflowed(16, &slab).await;
+    assert_eq!(drained.reap_completions(&slab), BURSTS);
+    assert!(!slab.is_in_flight(1) && !slab.is_in_flight(0));
+    assert_eq!(drained.reap_completions(&slab), 0);
+    println!("Reaper Drain Audit: {} overflowed completions reaped in one call (capped pass: {}).", BURSTS, first);
+}
+
+#[tokio::test]
+async fn test_run_loop_receives_datagrams_past_4k_intact() {
+    use httpx_core::ControlSignal;
+    use httpx_transport::replay::read_log;
+    use httpx_transport::{DispatchRecorder, ReplayEvent};
+
+    let dir = tempfile::tempdir().unwrap();
+    let log = dir.path().join("ingress.hxrp");
+    let slab = SecureSlab::new(4);
+
+    let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let server = socket.local_addr().unwrap();
+    let client = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let (tx, rx) = tokio::sync::mpsc::channel(10);
+    let (learn_tx, _learn_rx) = httpx_core::learn_channel(16);
+    let config = ServerConfig { max_datagram: 6000, ..Default::default() };
+    assert_eq!(ServerConfig::default().max_datagram, 65535);
+    let mut dispatcher = CoreDispatcher::new_with_socket(0, socket, rx, config, LinearIntentTrie::new(16), learn_tx)
+        .await
+        .unwrap()
+        .with_recorder(DispatchRecorder::create(&log).unwrap());
+
+    let large: Vec<u8> = (0..5000u32).map(|i| (i % 251) as u8).collect();
+    let drive = async {
+        client.send_to(&large, server).await.unwrap();
+        // Over the configured cap: logged and dropped, never half-processed.
+        client.send_to(&[0x7E; 7000], server).await.unwrap();
+        client.send_to(b"/after", server).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        tx.send(ControlSignal::KillAll).await.unwrap();
+    };
+    tokio::join!(dispatcher.run_loop(&slab), drive);
+    drop(dispatcher);
+
+    let received: Vec<Vec<u8>> = read_log(&log)
+        .unwrap()
+        .into_iter()
+        .filter_map(|e| match e {
+            ReplayEvent::Packet(p) => Some(p.data),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(received.len(), 2, "Only the oversized datagram may be dropped");
+    assert_eq!(received[0], large, "5000-byte datagram must arrive untruncated");
+    assert_eq!(received[1], b"/after");
+    println!("Datagram Size Audit: 5000-byte request received whole; 7000-byte request over the cap dropped.");
+}
+
+#[tokio::test]
+async fn test_refused_submit_leaves_no_phantom_rc() {
+    let slab = SecureSlab::new(4);
+    slab.write_slot(1, 0, b"stranded").unwrap();
+    let version = slab.get_version(1);
+
+    // A disabled ring refuses every io_uring_enter (EBADFD), so the burst's
+    // SQE is published to the SQ but never taken by the kernel.
+    let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let target = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let (_tx, rx) = tokio::sync::mpsc::channel(10);
+    let (learn_tx, _learn_rx) = httpx_core::learn_channel(16);
+    let ring = io_uring::IoUring::builder().setup_r_disabled().build(8).unwrap();
+    let mut dispatcher =
+        CoreDispatcher::new_from_ring(0, socket, rx, ServerConfig::default(), LinearIntentTrie::new(16), ring, learn_tx)
+            .await
+            .unwrap();
+
+    dispatcher.submit_linked_burst(target.local_addr().unwrap(), 1, 0, version, &slab).await.unwrap();
+    assert_eq!(dispatcher.ring_mut().submission().len(), 1, "Refused SQE must stay queued");
+    assert!(slab.is_in_flight(1), "A queued SQE keeps its RC until it completes");
+    assert_eq!(dispatcher.reap_completions(&slab), 0);
+    assert_eq!(dispatcher.flush_submissions(), 1);
+
+    // Once the kernel accepts submissions again, the next reap retries the
+    // remainder and the completion releases every RC it took.
+    dispatcher.ring_mut().submitter().register_enable_rings().unwrap();
+    let t = std::time::Instant::now();
+    while slab.is_in_flight(1) || slab.is_in_flight(0) {
+        dispatcher.reap_completions(&slab);
+        assert!(t.elapsed() < std::time::Duration::from_secs(2), "Stranded SQE was never retried");
+        tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+    }
+    assert_eq!(dispatcher.ring_mut().submission().len(), 0);
+    println!("Partial Submit Audit: refused SQE retried on the next reap; no phantom RC left behind.");
+}
@@ -37,6 +37,7 @@ fn test_zero_blocking_bridge_saturation() {
         if let Err(e) = bridge.try_push(999) {
              match e {
                  DropReason::Congested => { /* Expected: Zero Blocking */ }
+                 DropReason::CreditExhausted => unreachable!("try_push is not credit-gated"),
              }
         }
     }
@@ -60,3 +61,70 @@ fn test_zero_blocking_bridge_saturation() {
     // 11th push must fail (DropReason: IIW Depletion)
     assert!(!session.consume_credit());
 }
+
+#[test]
+fn test_drop_reason_distinguishes_credits_from_congestion() {
+    let addr: SocketAddr = "127.0.0.1:8080".parse().unwrap();
+
+    // A drained session is refused before the bridge is touched.
+    let bridge = SqBridge::<u32>::new(4);
+    let session = Session::new(addr);
+    session.set_credits(0);
+    assert_eq!(bridge.try_push_for(&session, 1), Err(DropReason::CreditExhausted));
+    assert!(bridge.pop().is_none());
+
+    // A funded session against a full bridge is congested, and keeps its credit.
+    for i in 0..4 {
+        bridge.try_push(i).unwrap();
+    }
+    session.set_credits(1);
+    assert_eq!(bridge.try_push_for(&session, 9), Err(DropReason::Congested));
+    assert_eq!(session.credits(), 1, "A congested push must not spend a credit");
+
+    bridge.pop();
+    assert_eq!(bridge.try_push_for(&session, 9), Ok(()));
+    assert_eq!(session.credits(), 0);
+}
+
+#[test]
+fn test_publish_batch_is_visible_after_notification() {
+    use std::sync::atomic::{fence, AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    const ROUNDS: usize = 500;
+    const BATCH: [u32; 6] = [1, 2, 3, 4, 5, 6];
+
+    let bridge = SqBridge::<u32>::new(8);
+    // The notification is a Relaxed store: the only ordering comes from the
+    // bridge's trailing fence and the consumer's Acquire fence.
+    let announced = Arc::new(AtomicUsize::new(0));
+    let drained = Arc::new(AtomicUsize::new(0));
+
+    let producer = {
+        let (bridge, announced, drained) = (bridge.clone(), announced.clone(), drained.clone());
+        std::thread::spawn(move || {
+            for round in 1..=ROUNDS {
+                assert_eq!(bridge.publish_batch(&BATCH), BATCH.len());
+                announced.store(round, Ordering::Relaxed);
+                while drained.load(Ordering::Acquire) != round {
+                    std::thread::yield_now();
+                }
+            }
+        })
+    };
+
+    for round in 1..=ROUNDS {
+        while announced.load(Ordering::Relaxed) != round {
+            std::thread::yield_now();
+        }
+        fence(Ordering::Acquire);
+        let seen: Vec<u32> = std::iter::from_fn(|| bridge.pop()).collect();
+        assert_eq!(seen, BATCH, "Round {} saw a partial batch", round);
+        drained.store(round, Ordering::Release);
+    }
+    producer.join().unwrap();
+
+    // A batch larger than the free space is truncated, not blocked.
+    assert_eq!(bridge.publish_batch(&[0; 12]), 8);
+    assert_eq!(bridge.try_push(0), Err(DropReason::Congested));
+}
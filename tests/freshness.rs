@@ -14,7 +14,7 @@ async fn test_stale_push_freshness_gate() {
 
     // 1. Setup Trie with Versioned Payload
     trie.observe(context, true);
-    trie.associate_payload(context, handle, initial_version);
+    trie.associate_payload(context, handle, initial_version).unwrap();
 
     let slab = Arc::new(SecureSlab::new(64));
     slab.set_version(handle as usize, initial_version);
@@ -22,7 +22,7 @@ async fn test_stale_push_freshness_gate() {
     let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
     let addr = socket.local_addr().unwrap();
     let (_tx, rx) = tokio::sync::mpsc::channel(10);
-    let (learn_tx, _learn_rx) = tokio::sync::mpsc::unbounded_channel();
+    let (learn_tx, _learn_rx) = httpx_core::learn_channel(1024);
     let mut dispatcher = CoreDispatcher::new_with_socket(0, socket, rx, ServerConfig::default(), trie.clone(), learn_tx).await.unwrap();
 
     // 2. Scenario A: VERSION MATCH (Success)
@@ -62,7 +62,7 @@ async fn test_high_frequency_freshness_chaos() {
     let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
     let addr = socket.local_addr().unwrap();
     let (_tx, rx) = tokio::sync::mpsc::channel(10);
-    let (learn_tx, _learn_rx) = tokio::sync::mpsc::unbounded_channel();
+    let (learn_tx, _learn_rx) = httpx_core::learn_channel(1024);
     let mut dispatcher = CoreDispatcher::new_with_socket(0, socket, rx, ServerConfig::default(), httpx_dsa::LinearIntentTrie::new(1024), learn_tx).await.unwrap();
 
     for v in 0..100 {
@@ -35,3 +35,37 @@ fn test_fec_0rtt_reconstruction() {
     // Reconstruction is purely XOR operations in L1 registers.
     assert_eq!(reconstructed, packets[lost_packet_idx]);
 }
+
+#[test]
+fn test_trie_from_bytes_rejects_truncated_and_corrupt_buffers() {
+    use httpx_dsa::{LinearIntentTrie, TrieError};
+
+    let mut trie = LinearIntentTrie::new(256);
+    for path in [&b"/api/v1"[..], b"/api/v2", b"/x"] {
+        trie.observe(path, true);
+    }
+    let bytes = trie.to_bytes();
+
+    // Every strict prefix (and any trailing garbage) is malformed, never a panic.
+    for len in 0..bytes.len() {
+        assert_eq!(LinearIntentTrie::from_bytes(&bytes[..len]).err(), Some(TrieError::Malformed), "prefix {}", len);
+    }
+    let mut padded = bytes.clone();
+    padded.push(0);
+    assert_eq!(LinearIntentTrie::from_bytes(&padded).err(), Some(TrieError::Malformed));
+
+    // Single bit flips anywhere either fail to load or load a trie that every
+    // traversal can walk without leaving the pool.
+    let probes: [&[u8]; 4] = [b"/api/v1", b"/api/v2", b"/x", &[0xFF; 16]];
+    for i in 0..bytes.len() * 8 {
+        let mut corrupt = bytes.clone();
+        corrupt[i / 8] ^= 1 << (i % 8);
+        let Ok(mut loaded) = LinearIntentTrie::from_bytes(&corrupt) else { continue };
+        for path in probes {
+            let _ = loaded.get_probability(path, true);
+            let _ = loaded.get_node_at_path(path);
+        }
+        let _ = loaded.routes();
+        loaded.observe(b"/api/v3", false);
+    }
+}
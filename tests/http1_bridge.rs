@@ -0,0 +1,194 @@
+//! # HTTP/1.1 Bridge Tests
+//!
+//! Validates that `HttpxServer::with_http1_bridge` serves HTTP-X routes to
+//! plain HTTP/1.1 clients over TCP, from the same trie and slab the UDP
+//! workers use.
+
+use httpx_core::{PayloadRegistry, ServerBuilder, ServerConfig};
+use httpx_dsa::SecureSlab;
+use httpx_transport::HttpxServer;
+use std::io::{Read, Write};
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Reads one response (head + `Content-Length` body) off a keep-alive stream.
+fn read_response(stream: &mut std::net::TcpStream) -> (String, Vec<u8>) {
+    let mut raw = Vec::new();
+    let mut byte = [0u8; 1];
+    while !raw.ends_with(b"\r\n\r\n") {
+        assert_eq!(stream.read(&mut byte).unwrap(), 1, "Connection closed mid-head");
+        raw.push(byte[0]);
+    }
+    let head = String::from_utf8(raw).unwrap();
+    let length: usize = head
+        .lines()
+        .find_map(|l| l.strip_prefix("Content-Length:"))
+        .expect("Response has no Content-Length")
+        .trim()
+        .parse()
+        .unwrap();
+    let mut body = vec![0u8; length];
+    stream.read_exact(&mut body).unwrap();
+    (head, body)
+}
+
+/// Verifies that a raw `GET` over TCP returns the registered payload with a
+/// matching Content-Length, and that unknown routes get a 404 on the same
+/// keep-alive connection.
+#[tokio::test(flavor = "multi_thread")]
+async fn test_http1_get_serves_registered_payload() {
+    let t = Instant::now();
+
+    let slab = Arc::new(SecureSlab::new(16));
+    let mut payloads = PayloadRegistry::new(slab.clone());
+    let hello = payloads.register("hello", b"Hello from HTTP-X over HTTP/1.1");
+    let version = payloads.version_of("hello").unwrap();
+
+    let config = ServerConfig { threads: 1, slab_capacity: 16, ..Default::default() };
+    let builder = ServerBuilder::new().with_config(config).route("/api/v1/hello", hello, version);
+    let handle = HttpxServer::from_builder(builder, "127.0.0.1:0")
+        .with_slab(slab)
+        .with_http1_bridge("127.0.0.1:0")
+        .spawn()
+        .await
+        .expect("Server failed to spawn");
+    let addr = handle.http1_addr().expect("Bridge must report its address");
+
+    let (ok, missing) = tokio::task::spawn_blocking(move || {
+        let mut stream = std::net::TcpStream::connect(addr).unwrap();
+        stream.set_read_timeout(Some(std::time::Duration::from_secs(5))).unwrap();
+        stream.write_all(b"GET /api/v1/hello HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+        let ok = read_response(&mut stream);
+        stream.write_all(b"GET /api/v1/missing?x=1 HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+        let missing = read_response(&mut stream);
+        (ok, missing)
+    })
+    .await
+    .unwrap();
+
+    let (head, body) = ok;
+    assert!(head.starts_with("HTTP/1.1 200 OK\r\n"), "Unexpected head: {:?}", head);
+    assert!(head.contains("\r\nContent-Length: 31"), "Unexpected head: {:?}", head);
+    assert!(head.lines().any(|l| l.starts_with("Date: ") && l.ends_with(" GMT") && l.len() == 35));
+    assert_eq!(body, b"Hello from HTTP-X over HTTP/1.1");
+
+    let (head, body) = missing;
+    assert!(head.starts_with("HTTP/1.1 404 Not Found\r\n"), "Unexpected head: {:?}", head);
+    assert!(body.is_empty());
+
+    handle.shutdown(std::time::Duration::from_secs(1)).await;
+
+    let overhead = t.elapsed();
+    println!("test_http1_get_serves_registered_payload: Testing Overhead = {:?}", overhead);
+}
+
+/// Verifies that a route added by a routes-file hot reload is served over
+/// HTTP/1.1 without restarting the bridge.
+#[tokio::test(flavor = "multi_thread")]
+async fn test_http1_bridge_follows_route_reload() {
+    let t = Instant::now();
+
+    let slab = Arc::new(SecureSlab::new(16));
+    let mut payloads = PayloadRegistry::new(slab.clone());
+    let old = payloads.register("old", b"old");
+    let new = payloads.register("new", b"reloaded");
+    let (v1, v2) = (payloads.version_of("old").unwrap(), payloads.version_of("new").unwrap());
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("routes.toml");
+    let write = |contents: String| {
+        let tmp = dir.path().join("routes.toml.tmp");
+        std::fs::write(&tmp, contents).unwrap();
+        std::fs::rename(&tmp, &path).unwrap();
+    };
+    let hello = format!("[[route]]\npath = \"/hello\"\nhandle = {}\nversion = {}\n", old, v1);
+    write(hello.clone());
+
+    let config = ServerConfig { threads: 1, slab_capacity: 16, ..Default::default() };
+    let handle = HttpxServer::listen("127.0.0.1:0")
+        .with_config(config)
+        .with_slab(slab)
+        .with_routes_file(&path)
+        .with_http1_bridge("127.0.0.1:0")
+        .spawn()
+        .await
+        .expect("Server failed to spawn");
+    let addr = handle.http1_addr().unwrap();
+    let get = move |target: &'static str| {
+        tokio::task::spawn_blocking(move || {
+            let mut stream = std::net::TcpStream::connect(addr).unwrap();
+            stream.set_read_timeout(Some(std::time::Duration::from_secs(5))).unwrap();
+            write!(stream, "GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", target).unwrap();
+            read_response(&mut stream)
+        })
+    };
+    assert!(get("/new").await.unwrap().0.starts_with("HTTP/1.1 404"));
+
+    write(format!("{}\n[[route]]\npath = \"/new\"\nhandle = {}\nversion = {}\n", hello, new, v2));
+    let deadline = Instant::now() + std::time::Duration::from_secs(5);
+    let (head, body) = loop {
+        let (head, body) = get("/new").await.unwrap();
+        if head.starts_with("HTTP/1.1 200") {
+            break (head, body);
+        }
+        assert!(Instant::now() < deadline, "Reloaded route never reached the bridge: {:?}", head);
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    };
+    assert!(head.contains("\r\nContent-Length: 8"), "Unexpected head: {:?}", head);
+    assert_eq!(body, b"reloaded");
+
+    handle.shutdown(std::time::Duration::from_secs(1)).await;
+
+    let overhead = t.elapsed();
+    println!("test_http1_bridge_follows_route_reload: Testing Overhead = {:?}", overhead);
+}
+
+/// Verifies that shutting the server down closes idle keep-alive
+/// connections instead of leaving their tasks serving a stopped server.
+#[tokio::test(flavor = "multi_thread")]
+async fn test_http1_shutdown_closes_keep_alive_connections() {
+    let t = Instant::now();
+
+    let slab = Arc::new(SecureSlab::new(16));
+    let mut payloads = PayloadRegistry::new(slab.clone());
+    let hello = payloads.register("hello", b"still here");
+    let version = payloads.version_of("hello").unwrap();
+
+    let config = ServerConfig { threads: 1, slab_capacity: 16, ..Default::default() };
+    let builder = ServerBuilder::new().with_config(config).route("/hello", hello, version);
+    let handle = HttpxServer::from_builder(builder, "127.0.0.1:0")
+        .with_slab(slab)
+        .with_http1_bridge("127.0.0.1:0")
+        .spawn()
+        .await
+        .expect("Server failed to spawn");
+    let addr = handle.http1_addr().unwrap();
+
+    let mut stream = tokio::task::spawn_blocking(move || {
+        let mut stream = std::net::TcpStream::connect(addr).unwrap();
+        stream.set_read_timeout(Some(std::time::Duration::from_secs(5))).unwrap();
+        stream.write_all(b"GET /hello HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+        assert_eq!(read_response(&mut stream).1, b"still here");
+        stream
+    })
+    .await
+    .unwrap();
+
+    // The connection is idle in keep-alive when the server goes down.
+    handle.shutdown(std::time::Duration::from_secs(1)).await;
+
+    let closed = tokio::task::spawn_blocking(move || {
+        let mut byte = [0u8; 1];
+        match stream.read(&mut byte) {
+            Ok(0) => true,
+            Ok(_) => false,
+            Err(e) => e.kind() == std::io::ErrorKind::ConnectionReset,
+        }
+    })
+    .await
+    .unwrap();
+    assert!(closed, "A keep-alive connection must be closed by shutdown");
+
+    let overhead = t.elapsed();
+    println!("test_http1_shutdown_closes_keep_alive_connections: Testing Overhead = {:?}", overhead);
+}
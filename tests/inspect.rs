@@ -0,0 +1,99 @@
+//! # Admin Inspection Tests
+//!
+//! Validates that `ServerHandle::inspect` reports live trie, slab and worker state,
+//! including routes hot-reloaded from a routes file.
+
+use httpx_cluster::ClusterMode;
+use httpx_core::{ServerBuilder, ServerConfig};
+use httpx_transport::HttpxServer;
+use std::time::Instant;
+
+#[tokio::test]
+async fn test_inspect_reports_registered_route() {
+    let t = Instant::now();
+
+    let config = ServerConfig {
+        threads: 1,
+        slab_capacity: 16,
+        ..Default::default()
+    };
+    let builder = ServerBuilder::new()
+        .with_config(config)
+        .route("/api/v1/hello", 3, 100);
+
+    let handle = HttpxServer::from_builder(builder, "127.0.0.1:0")
+        .spawn()
+        .await
+        .expect("Server failed to spawn");
+
+    let report = handle.inspect().await;
+
+    assert_eq!(report.workers.len(), 1, "One worker should report");
+    assert_eq!(report.slots.len(), 16, "Every slab slot should be reported");
+    assert!(report.slots.iter().all(|s| !s.in_flight), "No slot should be in-flight at idle");
+    assert_eq!(report.cluster_mode, ClusterMode::Integrated);
+
+    let route = report.routes.iter()
+        .find(|r| r.path == b"/api/v1/hello")
+        .expect("Registered route missing from inspect report");
+    assert_eq!(route.payload_handle, 3);
+    assert_eq!(route.version_id, 100);
+    assert_eq!(report.trie_digest, report.workers[0].trie_digest);
+
+    let overhead = t.elapsed();
+    println!("test_inspect_reports_registered_route: Testing Overhead = {:?}", overhead);
+}
+
+/// Writes a routes file, hot-reloads an extended revision into the running
+/// workers, then verifies a broken revision is rejected without disturbing them.
+#[tokio::test]
+async fn test_routes_file_hot_reload() {
+    let t = Instant::now();
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("routes.toml");
+    // Atomic replace, as editors and config managers do.
+    let write = |contents: &str| {
+        let tmp = dir.path().join("routes.toml.tmp");
+        std::fs::write(&tmp, contents).unwrap();
+        std::fs::rename(&tmp, &path).unwrap();
+    };
+    write("[[route]]\npath = \"/hello\"\nhandle = 1\nversion = 1\n");
+
+    let config = ServerConfig { threads: 1, slab_capacity: 16, ..Default::default() };
+    let handle = HttpxServer::listen("127.0.0.1:0")
+        .with_config(config)
+        .with_routes_file(&path)
+        .spawn()
+        .await
+        .expect("Server failed to spawn");
+    let has_route = |report: &httpx_transport::InspectReport, p: &[u8]| report.routes.iter().any(|r| r.path == p);
+    assert!(has_route(&handle.inspect().await, b"/hello"));
+
+    write("[[route]]\npath = \"/hello\"\nhandle = 1\nversion = 1\n\n[[route]]\npath = \"/new\"\nhandle = 2\nversion = 7\n");
+    let deadline = Instant::now() + std::time::Duration::from_secs(5);
+    let report = loop {
+        let report = handle.inspect().await;
+        if has_route(&report, b"/new") {
+            break report;
+        }
+        assert!(Instant::now() < deadline, "Reloaded route never reached the workers");
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    };
+    let route = report.routes.iter().find(|r| r.path == b"/new").unwrap();
+    assert_eq!((route.payload_handle, route.version_id), (2, 7));
+
+    let watcher = handle.route_watcher().expect("Watcher must be running");
+    let rejected = watcher.rejected();
+    write("[[route]]\npath = \"/broken\"\nhandle = \"not a number\"\n");
+    while watcher.rejected() == rejected {
+        assert!(Instant::now() < deadline, "Broken routes file was never evaluated");
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+    let report = handle.inspect().await;
+    assert!(has_route(&report, b"/new"), "A rejected file must leave the running routes intact");
+    assert!(!has_route(&report, b"/broken"));
+
+    let overhead = t.elapsed();
+    println!("test_routes_file_hot_reload: Testing Overhead = {:?}", overhead);
+}
@@ -0,0 +1,59 @@
+//! # Multi-Address Binding Test
+//!
+//! Verifies that a single `HttpxServer` serves the same routes on every bind address.
+
+use httpx_core::{ServerBuilder, ServerConfig};
+use httpx_dsa::SecureSlab;
+use httpx_transport::HttpxServer;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn free_port() -> u16 {
+    std::net::UdpSocket::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port()
+}
+
+#[tokio::test]
+async fn test_listen_many_resolves_same_route() {
+    let slab = Arc::new(SecureSlab::new(16));
+    slab.write_slot(1, 0, &[0x41; 1024]).unwrap();
+    slab.set_version(1, 100);
+
+    let config = ServerConfig {
+        threads: 1,
+        slab_capacity: 16,
+        ..Default::default()
+    };
+    let builder = ServerBuilder::new()
+        .with_config(config)
+        .route("/api/v1/hello", 1, 100);
+
+    let addr_a = format!("127.0.0.1:{}", free_port());
+    let addr_b = format!("127.0.0.1:{}", free_port());
+
+    let handle = HttpxServer::listen_many(&[&addr_a, &addr_b])
+        .with_config(builder.config.clone())
+        .with_trie(builder.registry.take_trie())
+        .with_slab(slab.clone())
+        .spawn()
+        .await
+        .expect("Server failed to spawn");
+
+    // Workers bind asynchronously on their own threads.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(handle.inspect().await.workers.len(), 2, "One worker per address expected");
+
+    for addr in [&addr_a, &addr_b] {
+        let client = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        client.send_to(b"/api/v1/hello", addr.as_str()).await.unwrap();
+
+        let mut buf = [0u8; 8192];
+        let (len, _) = tokio::time::timeout(Duration::from_secs(2), client.recv_from(&mut buf))
+            .await
+            .unwrap_or_else(|_| panic!("No push received from {}", addr))
+            .unwrap();
+
+        // Intent (17B) + Header (128B) + Payload (1024B)
+        assert_eq!(len, 17 + 128 + 1024, "Unexpected push size from {}", addr);
+        assert_eq!(&buf[..17], b"INTENT_SYNC_FRAME");
+    }
+}
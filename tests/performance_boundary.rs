@@ -11,7 +11,7 @@ async fn test_gso_batch_saturation_stress() {
     let target = socket.local_addr().unwrap();
     
     // Learning and Control Plane Bridge
-    let (learn_tx, _learn_rx) = tokio::sync::mpsc::unbounded_channel();
+    let (learn_tx, _learn_rx) = httpx_core::learn_channel(1024);
     let (_control_tx, control_rx) = tokio::sync::mpsc::channel(100);
     
     let mut config = ServerConfig::default();
@@ -48,9 +48,16 @@ async fn test_gso_batch_saturation_stress() {
     let streamer_socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
     let streamer = PayloadStreamer::new(streamer_socket, 1500).unwrap();
     
+    // 16 * 4096 = 65536 slot bytes plus 45 fragment headers overflow a
+    // 65535-byte super-packet.
     let res = streamer.stream_batch(&slab, &handles, target).await;
-    assert!(res.is_ok(), "64KB GSO Batch failed");
-    assert_eq!(res.unwrap(), 15, "Should batch 15 fragments (15*4096 = 61440, next would exceed 65535)");
+    assert!(
+        matches!(res, Err(httpx_transport::TransportError::PayloadTooLarge { len: 66121, limit: 65535 })),
+        "Oversize batch must be rejected, not truncated: {:?}", res
+    );
+
+    let res = streamer.stream_batch(&slab, &handles[..15], target).await;
+    assert_eq!(res.unwrap(), 15, "15 slots (15*4096 = 61440, 61986 framed) fit one super-packet");
 }
 
 #[tokio::test]
@@ -0,0 +1,96 @@
+//! # Fragment Reassembly Tests
+//!
+//! Validates framed streaming of payloads larger than a GSO super-packet
+//! and client-side reassembly, including out-of-order and stalled fragments.
+
+use httpx_transport::fragment::{fragment_payload, Reassembler};
+use httpx_transport::stream::PayloadStreamer;
+use std::time::{Duration, Instant};
+use tokio::net::UdpSocket;
+
+/// Streams a 200KB payload over loopback and reassembles it intact.
+#[tokio::test]
+async fn test_stream_fragmented_200kb_roundtrip() {
+    let t = Instant::now();
+
+    let client = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let target = client.local_addr().unwrap();
+    let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let streamer = PayloadStreamer::new(server, 1400).unwrap();
+
+    let payload: Vec<u8> = (0..200 * 1024).map(|i| (i * 31 % 251) as u8).collect();
+
+    let recv = async {
+        let mut reassembler = Reassembler::new(Duration::from_secs(2));
+        let mut buf = [0u8; 2048];
+        loop {
+            let (len, _) = client.recv_from(&mut buf).await.unwrap();
+            if let Some(done) = reassembler.ingest(&buf[..len]) {
+                return done;
+            }
+        }
+    };
+    let send = streamer.stream_fragmented(7, &payload, target);
+
+    let (sent, (handle, reassembled)) = tokio::time::timeout(Duration::from_secs(5), async {
+        tokio::join!(async { send.await.unwrap() }, recv)
+    })
+    .await
+    .expect("Reassembly did not complete");
+
+    assert!(sent > 1, "200KB must span multiple fragments");
+    assert_eq!(handle, 7);
+    assert_eq!(reassembled.len(), payload.len());
+    assert!(reassembled == payload, "Reassembled payload corrupted");
+
+    let overhead = t.elapsed();
+    println!("test_stream_fragmented_200kb_roundtrip: Testing Overhead = {:?} ({} fragments)", overhead, sent);
+}
+
+/// Fragments arriving in reverse order still reassemble correctly.
+#[test]
+fn test_reassembly_out_of_order() {
+    let t = Instant::now();
+
+    let payload: Vec<u8> = (0..10_000u32).map(|i| i as u8).collect();
+    let fragments = fragment_payload(3, &payload, 1400);
+    assert!(fragments.len() > 2);
+
+    let mut reassembler = Reassembler::new(Duration::from_secs(1));
+    let mut result = None;
+    for fragment in fragments.iter().rev() {
+        assert!(result.is_none(), "Completed before all fragments arrived");
+        result = reassembler.ingest(fragment);
+    }
+    assert_eq!(result, Some((3, payload)));
+    assert_eq!(reassembler.pending(), 0);
+
+    let overhead = t.elapsed();
+    println!("test_reassembly_out_of_order: Testing Overhead = {:?}", overhead);
+}
+
+/// A payload missing a fragment is discarded once the timeout elapses.
+#[test]
+fn test_reassembly_discards_stalled_payload() {
+    let t = Instant::now();
+
+    let fragments = fragment_payload(9, &[0xAB; 5000], 1400);
+    let mut reassembler = Reassembler::new(Duration::from_millis(100));
+    let start = Instant::now();
+
+    // Drop the last fragment in flight.
+    for fragment in &fragments[..fragments.len() - 1] {
+        assert!(reassembler.ingest_at(fragment, start).is_none());
+    }
+    assert_eq!(reassembler.pending(), 1);
+
+    assert_eq!(reassembler.expire(start + Duration::from_millis(150)), 1);
+    assert_eq!(reassembler.pending(), 0);
+
+    // The late fragment alone cannot resurrect the discarded assembly.
+    let late = fragments.last().unwrap();
+    assert!(reassembler.ingest_at(late, start + Duration::from_millis(160)).is_none());
+
+    let overhead = t.elapsed();
+    println!("test_reassembly_discards_stalled_payload: Testing Overhead = {:?}", overhead);
+}
@@ -29,6 +29,39 @@ fn test_resource_registry_route_roundtrip() {
     println!("test_resource_registry_route_roundtrip: Testing Overhead = {:?}", overhead);
 }
 
+/// Verifies that `route_many` registers a 100-route table identically to
+/// 100 individual `route` calls, with every path resolving to its handle.
+#[test]
+fn test_resource_registry_route_many() {
+    let t = Instant::now();
+
+    let paths: Vec<String> = (0..100).map(|i| format!("/api/v{}/items/{}", i % 3, i)).collect();
+    // Shuffled relative to sorted order, so prefix sharing must not depend on input order.
+    let routes: Vec<(&str, u32, u32)> =
+        paths.iter().enumerate().rev().map(|(i, p)| (p.as_str(), i as u32 + 1, 7)).collect();
+
+    let mut bulk = httpx_core::ResourceRegistry::new();
+    bulk.route_many(&routes);
+    let bulk = bulk.take_trie();
+
+    let mut single = httpx_core::ResourceRegistry::new();
+    for &(path, handle, version) in &routes {
+        single.route(path, handle, version);
+    }
+    let single = single.take_trie();
+
+    for (i, path) in paths.iter().enumerate() {
+        let node = bulk.get_node_at_path(path.as_bytes()).expect("Bulk-registered path not found");
+        assert_eq!(node.payload_handle, i as u32 + 1, "Handle mismatch for {}", path);
+        assert_eq!(node.version_id, 7);
+    }
+    assert_eq!(bulk.node_count(), single.node_count(), "Bulk registration must not duplicate nodes");
+    assert_eq!(bulk.routes().len(), 100);
+
+    let overhead = t.elapsed();
+    println!("test_resource_registry_route_many: Testing Overhead = {:?}", overhead);
+}
+
 /// Verifies that `ServerConfig::default()` returns sane values.
 #[test]
 fn test_server_config_defaults() {
@@ -65,3 +98,104 @@ fn test_server_builder_production_mode() {
     let overhead = t.elapsed();
     println!("test_server_builder_production_mode: Testing Overhead = {:?}", overhead);
 }
+
+/// Verifies that named payloads get distinct slab handles, resolve by name,
+/// and carry their bytes into the slots the handles point at.
+#[test]
+fn test_payload_registry_resolves_names() {
+    use httpx_core::{PayloadRegistry, ResourceRegistry};
+    use httpx_dsa::SecureSlab;
+    use std::sync::Arc;
+
+    let t = Instant::now();
+
+    let slab = Arc::new(SecureSlab::new(8));
+    let mut payloads = PayloadRegistry::new(slab.clone());
+    let hello = payloads.register("hello_payload", b"Hello, HTTP-X");
+    let bye = payloads.register("bye_payload", b"Goodbye");
+
+    assert_ne!(hello, 0, "Handle 0 means 'no payload' and must never be allocated");
+    assert_ne!(hello, bye, "Named payloads must get distinct handles");
+    assert_eq!(payloads.handle_of("hello_payload"), Some(hello));
+    assert_eq!(payloads.handle_of("bye_payload"), Some(bye));
+    assert_eq!(payloads.handle_of("missing"), None);
+
+    let mut out = [0u8; 7];
+    slab.read_slot(bye as usize, 0, &mut out).unwrap();
+    assert_eq!(&out, b"Goodbye");
+
+    // Re-registering a name rewrites its slot rather than allocating a new one.
+    assert_eq!(payloads.register("bye_payload", b"See you"), bye);
+    assert_eq!(payloads.len(), 2);
+
+    // Routes can be declared by name.
+    let mut routes = ResourceRegistry::new();
+    routes.route("/hello", payloads.handle_of("hello_payload").unwrap(), payloads.version_of("hello_payload").unwrap());
+    let trie = routes.take_trie();
+    assert_eq!(trie.get_node_at_path(b"/hello").unwrap().payload_handle, hello);
+
+    let overhead = t.elapsed();
+    println!("test_payload_registry_resolves_names: Testing Overhead = {:?}", overhead);
+}
+
+/// Verifies the depth cap bounds trie growth and rejects over-long routes.
+#[test]
+fn test_trie_depth_cap_bounds_adversarial_paths() {
+    use httpx_core::ResourceRegistry;
+    use httpx_dsa::{LinearIntentTrie, TrieError};
+    let t = Instant::now();
+
+    // 16-byte cap: at most 128 nodes below the root on any one path.
+    let mut trie = LinearIntentTrie::new(1024).with_max_depth_bits(16 * 8);
+    let attack = vec![b'A'; 4096];
+    trie.observe(&attack, true);
+    assert_eq!(trie.node_count(), 1 + 16 * 8, "Growth must stop at the cap");
+
+    // Anything sharing the first 16 bytes collides at the cap node.
+    let mut other = attack.clone();
+    other[100] = b'B';
+    trie.observe(&other, true);
+    trie.warm(&other);
+    assert_eq!(trie.node_count(), 1 + 16 * 8);
+    assert_eq!(trie.get_node_at_path(&attack).unwrap().weights[1], 2);
+    assert_eq!(trie.get_probability(&other, true), 1.0);
+
+    // Over-long routes are rejected at registration, not silently truncated.
+    let mut registry = ResourceRegistry::new().with_max_depth_bits(16 * 8);
+    let long = "/".repeat(17);
+    assert_eq!(
+        registry.try_route(&long, 1, 1),
+        Err(TrieError::PathTooDeep { bits: 17 * 8, max_bits: 16 * 8 })
+    );
+    assert!(registry.try_route_many(&[("/ok", 1, 1), (long.as_str(), 2, 1)]).is_err());
+    registry.try_route(&"/".repeat(16), 3, 1).unwrap();
+    let trie = registry.take_trie();
+    assert!(trie.get_node_at_path(b"/ok").is_none(), "A rejected batch registers nothing");
+    assert_eq!(trie.get_node_at_path("/".repeat(16).as_bytes()).unwrap().payload_handle, 3);
+
+    let err = ServerBuilder::new().with_max_trie_depth_bits(8).try_route("/ab", 1, 1).err().unwrap();
+    assert_eq!(err, TrieError::PathTooDeep { bits: 24, max_bits: 8 });
+
+    let overhead = t.elapsed();
+    println!("test_trie_depth_cap_bounds_adversarial_paths: Testing Overhead = {:?}", overhead);
+}
+
+/// Verifies that binding a payload to a path that was never warmed is reported.
+#[test]
+fn test_associate_payload_rejects_unwarmed_path() {
+    use httpx_dsa::{LinearIntentTrie, TrieError};
+    let t = Instant::now();
+
+    let mut trie = LinearIntentTrie::new(64);
+    trie.warm(b"/a");
+    // "/a" = 0x2F 0x61 and "/b" = 0x2F 0x62 first differ at bit 8 + 6.
+    assert_eq!(trie.associate_payload(b"/b", 7, 1), Err(TrieError::UnknownPath { depth: 14 }));
+    assert!(trie.get_node_at_path(b"/b").is_none());
+    assert_eq!(trie.get_node_at_path(b"/a").unwrap().payload_handle, 0, "A failed bind must not touch the prefix");
+
+    trie.associate_payload(b"/a", 7, 1).unwrap();
+    assert_eq!(trie.get_node_at_path(b"/a").unwrap().payload_handle, 7);
+
+    let overhead = t.elapsed();
+    println!("test_associate_payload_rejects_unwarmed_path: Testing Overhead = {:?}", overhead);
+}
@@ -0,0 +1,104 @@
+//! # Runtime Flavor Test
+//!
+//! Verifies that the worker swarm serves requests when all workers share one
+//! multi-threaded runtime instead of owning a current-thread runtime each.
+
+use httpx_core::{RuntimeFlavor, ServerBuilder, ServerConfig};
+use httpx_dsa::SecureSlab;
+use httpx_transport::HttpxServer;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+fn free_port() -> u16 {
+    std::net::UdpSocket::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port()
+}
+
+/// Serializes the tests here, so each sees only its own shared pool.
+static SHARED_POOL: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+/// Live threads of the shared runtime's pool in this process.
+fn shared_pool_threads() -> usize {
+    std::fs::read_dir("/proc/self/task")
+        .unwrap()
+        .filter_map(|task| std::fs::read_to_string(task.ok()?.path().join("comm")).ok())
+        .filter(|comm| comm.trim_end() == "httpx-shared-rt")
+        .count()
+}
+
+/// A request to a `SharedMultiThread` swarm resolves to a predictive push.
+#[tokio::test]
+async fn test_shared_multi_thread_runtime_resolves_route() {
+    let _pool = SHARED_POOL.lock().await;
+    let t = Instant::now();
+
+    let slab = Arc::new(SecureSlab::new(16));
+    slab.write_slot(1, 0, &[0x41; 1024]).unwrap();
+    slab.set_version(1, 100);
+
+    let config = ServerConfig {
+        threads: 2,
+        slab_capacity: 16,
+        runtime_flavor: RuntimeFlavor::SharedMultiThread,
+        ..Default::default()
+    };
+    let builder = ServerBuilder::new()
+        .with_config(config)
+        .route("/api/v1/hello", 1, 100);
+
+    let addr = format!("127.0.0.1:{}", free_port());
+    let handle = HttpxServer::from_builder(builder, &addr)
+        .with_slab(slab.clone())
+        .spawn()
+        .await
+        .expect("Server failed to spawn");
+
+    // Workers bind asynchronously on their own threads.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(handle.inspect().await.workers.len(), 2, "Both workers must answer on the shared runtime");
+
+    let client = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    client.send_to(b"/api/v1/hello", addr.as_str()).await.unwrap();
+
+    let mut buf = [0u8; 8192];
+    let (len, _) = tokio::time::timeout(Duration::from_secs(2), client.recv_from(&mut buf))
+        .await
+        .expect("No push received from the shared-runtime swarm")
+        .unwrap();
+    assert_eq!(len, 17 + 128 + 1024);
+    assert_eq!(&buf[..17], b"INTENT_SYNC_FRAME");
+    handle.shutdown(Duration::from_secs(2)).await;
+
+    let overhead = t.elapsed();
+    println!("test_shared_multi_thread_runtime_resolves_route: Testing Overhead = {:?}", overhead);
+}
+
+/// The shared pool gets `threads` threads per address and is torn down by
+/// `shutdown`, not leaked.
+#[tokio::test]
+async fn test_shared_runtime_sized_per_address_and_stopped_on_shutdown() {
+    let _pool = SHARED_POOL.lock().await;
+    let t = Instant::now();
+
+    let config = ServerConfig {
+        threads: 2,
+        slab_capacity: 16,
+        runtime_flavor: RuntimeFlavor::SharedMultiThread,
+        ..Default::default()
+    };
+    let (a, b) = (format!("127.0.0.1:{}", free_port()), format!("127.0.0.1:{}", free_port()));
+    let handle = HttpxServer::listen_many(&[&a, &b])
+        .with_config(config)
+        .spawn()
+        .await
+        .expect("Server failed to spawn");
+
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(handle.inspect().await.workers.len(), 4);
+    assert_eq!(shared_pool_threads(), 4, "2 threads x 2 addresses");
+
+    handle.shutdown(Duration::from_secs(2)).await;
+    assert_eq!(shared_pool_threads(), 0, "Shutdown must stop the shared runtime");
+
+    let overhead = t.elapsed();
+    println!("test_shared_runtime_sized_per_address_and_stopped_on_shutdown: Testing Overhead = {:?}", overhead);
+}
@@ -0,0 +1,93 @@
+//! # Sealed End-to-End Test
+//!
+//! Certifies the encrypted response path across crates: a payload sealed in
+//! the slab with a session-derived key is resolved and pushed by a running
+//! server, and only the client holding that session key can open it.
+//!
+//! Requests still travel in plaintext: the dispatcher has no decrypt path,
+//! so the sealed direction covered here is server to client.
+
+use httpx_core::{ServerBuilder, ServerConfig};
+use httpx_crypto::{derive_session_key, seal_slot, AeadSession, CipherSuite, CryptoError, NonceSequencer, SLOT_TAG_LEN};
+use httpx_dsa::SecureSlab;
+use httpx_transport::HttpxServer;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use zeroize::Zeroizing;
+
+/// Intent frame (17B) + template header (128B) ahead of every pushed payload.
+const BURST_PREFIX_LEN: usize = 17 + 128;
+
+fn free_port() -> u16 {
+    std::net::UdpSocket::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port()
+}
+
+/// Seals a response under the client's session key, requests its route over
+/// UDP, and opens the pushed ciphertext on the client; a client holding
+/// another session's key must fail authentication.
+#[tokio::test]
+async fn test_sealed_response_opens_only_with_session_key() {
+    let t = Instant::now();
+
+    let path = b"/api/v1/secret";
+    let plaintext = [0x5au8; 1024];
+    let master = Zeroizing::new([7u8; 32]);
+    let suite = CipherSuite::detect();
+
+    let client = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let context = client.local_addr().unwrap().to_string();
+    let key = derive_session_key(&master, context.as_bytes());
+    let nonce = NonceSequencer::new(*b"e2e0").next_nonce().unwrap();
+
+    // The response is sealed in place; the route path is bound in as AAD.
+    let slab = Arc::new(SecureSlab::new(16));
+    slab.write_slot(1, 0, &plaintext).unwrap();
+    seal_slot(&suite, &slab, 1, plaintext.len(), &key, &nonce, path).unwrap();
+    slab.set_version(1, 100);
+
+    let config = ServerConfig {
+        threads: 1,
+        slab_capacity: 16,
+        ..Default::default()
+    };
+    let builder = ServerBuilder::new()
+        .with_config(config)
+        .route("/api/v1/secret", 1, 100);
+    let addr = format!("127.0.0.1:{}", free_port());
+    let handle = HttpxServer::listen_many(&[&addr])
+        .with_config(builder.config.clone())
+        .with_trie(builder.registry.take_trie())
+        .with_slab(slab.clone())
+        .spawn()
+        .await
+        .expect("Server failed to spawn");
+
+    // Workers bind asynchronously on their own threads.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    client.send_to(path, addr.as_str()).await.unwrap();
+
+    let mut buf = [0u8; 8192];
+    let (len, _) = tokio::time::timeout(Duration::from_secs(2), client.recv_from(&mut buf))
+        .await
+        .expect("No sealed push received")
+        .unwrap();
+    assert_eq!(len, BURST_PREFIX_LEN + plaintext.len() + SLOT_TAG_LEN, "Push must carry ciphertext and tag");
+
+    let (sealed, tag) = buf[BURST_PREFIX_LEN..len].split_at(plaintext.len());
+    let tag = chacha20poly1305::Tag::from_slice(tag);
+    assert_ne!(sealed, &plaintext[..], "Payload must leave the server sealed");
+
+    // Another session's key fails authentication and reveals nothing.
+    let stranger = AeadSession::new(suite, &derive_session_key(&master, b"127.0.0.1:1"));
+    let mut wrong = sealed.to_vec();
+    assert!(matches!(stranger.open(&nonce, path, &mut wrong, tag), Err(CryptoError::IntegrityCheckFailed { .. })));
+
+    let mut opened = sealed.to_vec();
+    AeadSession::new(suite, &key).open(&nonce, path, &mut opened, tag).expect("Session key must open the push");
+    assert_eq!(opened, plaintext);
+
+    handle.shutdown(Duration::from_secs(1)).await;
+
+    let overhead = t.elapsed();
+    println!("test_sealed_response_opens_only_with_session_key: Testing Overhead = {:?}", overhead);
+}
@@ -0,0 +1,48 @@
+//! # Startup Self-Test Tests
+//!
+//! Validates that `HttpxServer::self_test` drives one synthetic request
+//! through the slab, engine, dispatcher and reaper, and names the stage that
+//! breaks when the server is misconfigured.
+
+use httpx_core::{HttpXError, PayloadRegistry, ServerBuilder, ServerConfig};
+use httpx_dsa::SecureSlab;
+use httpx_transport::HttpxServer;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Verifies that a correctly registered server passes, leaving no slot in
+/// flight, and that a route bound to a handle no payload was registered
+/// under fails at the `route` stage.
+#[tokio::test]
+async fn test_self_test_passes_on_valid_server_and_names_broken_route() {
+    let t = Instant::now();
+
+    let slab = Arc::new(SecureSlab::new(16));
+    let mut payloads = PayloadRegistry::new(slab.clone());
+    let hello = payloads.register("hello", b"self-test payload");
+    let version = payloads.version_of("hello").unwrap();
+    let config = ServerConfig { threads: 1, slab_capacity: 16, ..Default::default() };
+
+    let builder = ServerBuilder::new().with_config(config.clone()).route("/api/v1/hello", hello, version);
+    let server = HttpxServer::from_builder(builder, "127.0.0.1:0").with_slab(slab.clone());
+    server.self_test().await.expect("A correctly built server must pass its self-test");
+    assert!((0..slab.slots()).all(|i| !slab.is_in_flight(i)), "Self-test must release every RC");
+
+    // Handle 9 was never claimed by the registry, so nothing holds the version.
+    let builder = ServerBuilder::new()
+        .with_config(config)
+        .route("/api/v1/hello", hello, version)
+        .route("/api/v1/ghost", 9, 1);
+    let server = HttpxServer::from_builder(builder, "127.0.0.1:0").with_slab(slab.clone());
+    let err = server.self_test().await.expect_err("An unregistered handle must fail the self-test");
+    assert!(matches!(err, HttpXError::SelfTest { stage: "route", .. }), "Unexpected error: {:?}", err);
+    let message = err.to_string();
+    assert!(message.contains("/api/v1/ghost") && message.contains("handle 9"), "Unclear error: {}", message);
+
+    // No routes at all is a misconfiguration too.
+    let err = HttpxServer::listen("127.0.0.1:0").with_slab(slab).self_test().await.unwrap_err();
+    assert!(matches!(err, HttpXError::SelfTest { stage: "route", .. }));
+
+    let overhead = t.elapsed();
+    println!("test_self_test_passes_on_valid_server_and_names_broken_route: Testing Overhead = {:?}", overhead);
+}
@@ -0,0 +1,56 @@
+//! # Signal Shutdown Tests
+//!
+//! Validates that `HttpxServer::with_signal_shutdown` turns SIGTERM into a
+//! drain + join, so `start` returns instead of being SIGKILLed.
+//!
+//! The signal is raised inside a re-executed copy of this test binary, so
+//! the harness process itself never receives it.
+
+use httpx_transport::HttpxServer;
+use std::process::Command;
+use std::time::Instant;
+
+const CHILD_ENV: &str = "HTTPX_SIGNAL_SHUTDOWN_CHILD";
+const CLEAN_EXIT: &str = "signal-shutdown: start returned";
+
+/// Verifies that SIGTERM makes `start` drain the workers and return cleanly.
+#[test]
+fn test_sigterm_drains_workers_and_returns() {
+    if std::env::var_os(CHILD_ENV).is_some() {
+        run_child();
+        return;
+    }
+
+    let t = Instant::now();
+
+    let output = Command::new(std::env::current_exe().unwrap())
+        .args(["--exact", "test_sigterm_drains_workers_and_returns", "--nocapture", "--test-threads=1"])
+        .env(CHILD_ENV, "1")
+        .output()
+        .expect("failed to re-execute the test binary");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success(), "Child must exit cleanly, got {:?}\n{}", output.status, stdout);
+    assert!(stdout.contains(CLEAN_EXIT), "start() must return after SIGTERM:\n{}", stdout);
+
+    let overhead = t.elapsed();
+    println!("test_sigterm_drains_workers_and_returns: Testing Overhead = {:?}", overhead);
+}
+
+/// Child side: serve with signal shutdown enabled and SIGTERM ourselves.
+fn run_child() {
+    let rt = tokio::runtime::Builder::new_multi_thread().enable_all().build().unwrap();
+    rt.block_on(async {
+        tokio::spawn(async {
+            tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+            nix::sys::signal::raise(nix::sys::signal::Signal::SIGTERM).unwrap();
+        });
+
+        HttpxServer::listen("127.0.0.1:0")
+            .with_signal_shutdown()
+            .start()
+            .await
+            .unwrap();
+        println!("{}", CLEAN_EXIT);
+    });
+}
@@ -1,4 +1,4 @@
-use httpx_dsa::SecureSlab;
+use httpx_dsa::{GuardPages, HugePages, SecureSlab, SlabError};
 use std::sync::Arc;
 use std::thread;
 
@@ -44,3 +44,323 @@ fn test_slab_safety_rc_stressor() {
     slab.explicit_release(slot_idx);
     println!("Slab Safety Audit: Atomic RC stressed and verified (0 leaks).");
 }
+
+#[test]
+fn test_slab_checked_write_read_roundtrip() {
+    let slab = SecureSlab::new(4);
+
+    slab.write_slot(2, 100, b"sovereign").expect("In-bounds write must succeed");
+
+    let mut out = [0u8; 9];
+    slab.read_slot(2, 100, &mut out).expect("In-bounds read must succeed");
+    assert_eq!(&out, b"sovereign");
+
+    // A write ending exactly at the slot boundary is still valid.
+    slab.write_slot(2, 4096 - 4, b"edge").unwrap();
+    println!("Slab Safety Audit: Checked write/read round-trip verified.");
+}
+
+#[test]
+fn test_slab_partial_write_never_shrinks_payload() {
+    let slab = SecureSlab::new(4);
+    let idx = slab.allocate_slot().unwrap();
+    // Slots start empty, whether claimed or addressed directly.
+    assert_eq!(slab.payload_len(idx), 0);
+    assert_eq!(slab.payload_len(3), 0);
+
+    slab.write_slot(idx, 0, &[0x42; 512]).unwrap();
+    assert_eq!(slab.payload_len(idx), 512);
+
+    // Patching a header in place must keep the body live.
+    slab.write_slot(idx, 0, b"HDR").unwrap();
+    assert_eq!(slab.payload_len(idx), 512, "A write inside the payload must not truncate it");
+    slab.write_slot(idx, 600, b"tail").unwrap();
+    assert_eq!(slab.payload_len(idx), 604);
+
+    // Only an explicit commit shrinks the slot.
+    slab.set_payload_len(idx, 3);
+    assert_eq!(slab.payload_len(idx), 3);
+    println!("Slab Safety Audit: Partial writes grow the payload length, never truncate it.");
+}
+
+#[test]
+fn test_slab_checked_write_rejects_overflow() {
+    let slab = SecureSlab::new(4);
+
+    // One byte past the 4KB slot would land in the guard page.
+    let res = slab.write_slot(0, 4090, &[0xAA; 7]);
+    assert_eq!(res, Err(SlabError::OutOfBounds { offset: 4090, len: 7 }));
+
+    let mut out = [0u8; 8];
+    assert_eq!(slab.read_slot(0, usize::MAX, &mut out), Err(SlabError::OutOfBounds { offset: usize::MAX, len: 8 }));
+    assert_eq!(slab.write_slot(4, 0, b"x"), Err(SlabError::InvalidSlot(4)));
+    println!("Slab Safety Audit: Over-length slot write rejected before touching the guard page.");
+}
+
+#[test]
+fn test_slab_try_new_reports_allocation_failure() {
+    // 2^40 guarded slots need ~9 PB of address space: no mmap can satisfy it.
+    match SecureSlab::try_new(1 << 40) {
+        Err(SlabError::AllocationFailed(errno)) => assert!(errno > 0, "errno must be captured"),
+        Err(other) => panic!("Unexpected error: {}", other),
+        Ok(_) => panic!("try_new must not succeed for an impossible slot count"),
+    }
+
+    // A layout size that overflows usize is rejected before reaching mmap.
+    assert!(matches!(SecureSlab::try_new(usize::MAX / 2), Err(SlabError::AllocationFailed(_))));
+
+    // Degrading to a sane size still works.
+    let slab = SecureSlab::try_new(8).expect("Small slab must allocate");
+    assert_eq!(slab.slots(), 8);
+    println!("Slab Safety Audit: mmap failure surfaced as SlabError instead of aborting.");
+}
+
+#[test]
+fn test_slab_allocator_exhausts_and_recycles() {
+    let slab = SecureSlab::new(4);
+
+    // Slot 0 is the reserved "no payload" handle: only 1..4 are allocatable.
+    let mut claimed: Vec<usize> = (0..3).map(|_| slab.allocate_slot().unwrap()).collect();
+    claimed.sort_unstable();
+    assert_eq!(claimed, vec![1, 2, 3]);
+    assert_eq!(slab.allocate_slot(), Err(SlabError::Exhausted));
+
+    slab.free_slot(2);
+    assert_eq!(slab.allocate_slot(), Ok(2), "A freed slot must be reusable");
+    println!("Slab Safety Audit: Free-slot allocator exhausts cleanly and recycles released slots.");
+}
+
+#[test]
+#[should_panic(expected = "not allocated")]
+fn test_slab_rejects_double_free() {
+    let slab = SecureSlab::new(4);
+
+    let idx = slab.allocate_slot().unwrap();
+    slab.free_slot(idx);
+    // A second free would hand the slot to two future owners.
+    slab.free_slot(idx);
+}
+
+#[test]
+fn test_slab_poisoned_slot_is_quarantined() {
+    let slab = SecureSlab::new(4);
+
+    let suspect = slab.allocate_slot().unwrap();
+    slab.poison(suspect);
+    slab.free_slot(suspect);
+    assert!(slab.is_poisoned(suspect));
+    assert_eq!(slab.write_slot(suspect, 0, b"x"), Err(SlabError::Poisoned(suspect)));
+
+    // Every other slot is handed out, but never the poisoned one.
+    let mut claimed = Vec::new();
+    while let Ok(idx) = slab.allocate_slot() {
+        claimed.push(idx);
+    }
+    assert_eq!(claimed.len(), 2);
+    assert!(!claimed.contains(&suspect), "A poisoned slot must not be allocated");
+
+    slab.clear_poison(suspect);
+    assert_eq!(slab.allocate_slot(), Ok(suspect), "A cleared slot must be allocatable again");
+    slab.write_slot(suspect, 0, b"ok").unwrap();
+    println!("Slab Safety Audit: Poisoned slot quarantined until explicitly cleared.");
+}
+
+#[test]
+fn test_slab_checksum_detects_corruption() {
+    let slab = SecureSlab::new(4).with_checksums(true);
+    let idx = slab.allocate_slot().unwrap();
+    slab.write_slot(idx, 0, b"cached payload").unwrap();
+    assert!(slab.verify_slot(idx));
+
+    // Simulate a bit flip behind the slab's back.
+    unsafe { *slab.get_slot(idx).add(3) ^= 0x01 };
+    assert!(!slab.verify_slot(idx), "A flipped byte must fail verification");
+
+    // A fresh write re-seals the slot; raw writes re-seal on commit.
+    slab.write_slot(idx, 0, b"cached payload").unwrap();
+    assert!(slab.verify_slot(idx));
+    unsafe { *slab.get_slot(idx) = b'C' };
+    slab.set_payload_len(idx, 14);
+    assert!(slab.verify_slot(idx));
+
+    // Bytes past the live payload are never shipped, so they are not covered.
+    unsafe { *slab.get_slot(idx).add(100) = 0xFF };
+    assert!(slab.verify_slot(idx));
+    assert!(SecureSlab::new(2).verify_slot(1), "Disabled checksums always verify");
+    println!("Slab Safety Audit: Slot checksum caught a single-byte corruption.");
+}
+
+#[test]
+fn test_lazy_slab_activates_only_touched_slots() {
+    let slab = Arc::new(SecureSlab::new_lazy(1000));
+    if !slab.is_guarded() {
+        // HugeTLB slabs have no guard pages and are always fully active.
+        assert_eq!(slab.activated_slots(), 1000);
+        println!("Slab Safety Audit: HugeTLB slab, lazy activation not applicable.");
+        return;
+    }
+    assert_eq!(slab.activated_slots(), 0, "A lazy slab must not mprotect at startup");
+
+    slab.write_slot(7, 0, b"lazy").unwrap();
+    let mut out = [0u8; 4];
+    slab.read_slot(7, 0, &mut out).unwrap();
+    assert_eq!(&out, b"lazy");
+    unsafe { *slab.get_slot(500) = 0xAB };
+
+    // Concurrent first access to one slot activates it exactly once.
+    let handles: Vec<_> = (0..8)
+        .map(|i| {
+            let slab = slab.clone();
+            thread::spawn(move || slab.write_slot(999, i, &[i as u8]).unwrap())
+        })
+        .collect();
+    for h in handles {
+        h.join().unwrap();
+    }
+
+    assert_eq!(slab.activated_slots(), 3);
+    assert!(slab.is_activated(7) && slab.is_activated(500) && slab.is_activated(999));
+    assert!(!slab.is_activated(1) && !slab.is_activated(998));
+    println!("Slab Safety Audit: Lazy slab activated exactly the 3 touched slots of 1000.");
+}
+
+#[test]
+fn test_slab_layout_decouples_huge_pages_from_guards() {
+    const HUGE: usize = 2 * 1024 * 1024;
+    let stride = |slab: &SecureSlab| slab.get_slot(1) as usize - slab.get_slot(0) as usize;
+
+    // Isolation without huge pages: each 4K slot sits behind its own guard.
+    let guarded = SecureSlab::with_layout(4, HugePages(false), GuardPages(true));
+    assert!(guarded.is_guarded() && !guarded.is_huge_pages());
+    assert_eq!(stride(&guarded), 2 * 4096);
+
+    // Neither: contiguous 4K slots, no guards to mprotect.
+    let flat = SecureSlab::with_layout(4, HugePages(false), GuardPages(false));
+    assert!(!flat.is_guarded() && !flat.is_huge_pages());
+    assert_eq!(stride(&flat), 4096);
+    flat.write_slot(3, 0, b"flat").unwrap();
+
+    // Both: 2MB slots separated by 2MB PROT_NONE guards.
+    match SecureSlab::try_with_layout(2, HugePages(true), GuardPages(true)) {
+        Ok(slab) => {
+            assert!(slab.is_guarded() && slab.is_huge_pages());
+            assert_eq!(stride(&slab), 2 * HUGE, "Each slot must be followed by a 2MB guard");
+            assert_eq!(slab.get_slot(0) as usize % HUGE, 0, "Slots must be huge-page aligned");
+            slab.write_slot(1, 0, b"huge").unwrap();
+            let mut out = [0u8; 4];
+            slab.read_slot(1, 0, &mut out).unwrap();
+            assert_eq!(&out, b"huge");
+            println!("Slab Safety Audit: Guarded huge-page slab maps 2MB slots between 2MB guards.");
+        }
+        Err(SlabError::AllocationFailed(errno)) => {
+            println!("Slab Safety Audit: No HugeTLB pages reserved (errno {}), guarded huge layout skipped.", errno);
+        }
+        Err(other) => panic!("Unexpected error: {}", other),
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_slab_wide_guard_band_isolates_slots() {
+    use nix::sys::signal::Signal;
+    use nix::sys::wait::{waitpid, WaitStatus};
+    use nix::unistd::{fork, ForkResult};
+    const PAGE: usize = 4096;
+    const GUARD: usize = 16; // 64KB band
+
+    let slab = SecureSlab::with_guard_pages(3, HugePages(false), GUARD);
+    assert!(slab.is_guarded());
+    assert_eq!(slab.guard_pages(), GUARD);
+    let base = slab.get_slot(0) as usize;
+    for idx in 0..3 {
+        assert_eq!(slab.get_slot(idx) as usize - base, idx * (GUARD + 1) * PAGE);
+    }
+    slab.write_slot(1, PAGE - 4, b"edge").unwrap();
+    slab.write_slot(2, 0, b"next").unwrap();
+
+    // A stride past one guard page would have landed in slot 1 with the
+    // default layout; here it must still hit the band and fault.
+    // # Safety: the child only performs the faulting write and then aborts.
+    match unsafe { fork() }.expect("fork failed") {
+        ForkResult::Child => {
+            unsafe { std::ptr::write_volatile(slab.get_slot(0).add(2 * PAGE), 0xFF) };
+            std::process::abort();
+        }
+        ForkResult::Parent { child } => {
+            let status = waitpid(child, None).expect("waitpid failed");
+            assert!(
+                matches!(status, WaitStatus::Signaled(_, Signal::SIGSEGV, _)),
+                "Child must die from SIGSEGV inside the guard band, got {:?}",
+                status
+            );
+        }
+    }
+
+    let mut out = [0u8; 4];
+    slab.read_slot(2, 0, &mut out).unwrap();
+    assert_eq!(&out, b"next");
+    println!("Slab Safety Audit: 64KB guard band keeps strided overruns out of the next slot.");
+}
+
+#[test]
+fn test_slab_guard_band_errors_instead_of_panicking() {
+    assert!(matches!(SecureSlab::try_with_guard_pages(3, HugePages(false), 0), Err(SlabError::EmptyGuardBand)));
+    // A band so wide its page count overflows is refused, not wrapped.
+    assert!(matches!(
+        SecureSlab::try_with_guard_pages(3, HugePages(false), usize::MAX),
+        Err(SlabError::AllocationFailed(_))
+    ));
+    println!("Slab Safety Audit: invalid guard bands surface as SlabError.");
+}
+
+#[test]
+fn test_slab_slot_size_errors_instead_of_panicking() {
+    for size in [0, 6000, 1 << 32] {
+        assert!(
+            matches!(SecureSlab::try_with_slot_size(4, size), Err(SlabError::InvalidSlotSize(s)) if s == size),
+            "Slot size {} must be refused",
+            size
+        );
+    }
+    assert_eq!(SecureSlab::try_with_slot_size(4, 8192).unwrap().slot_size(), 8192);
+    println!("Slab Safety Audit: invalid slot sizes surface as SlabError.");
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_guarded_trie_arena_faults_on_overrun() {
+    use httpx_dsa::{GuardedNodes, LinearIntentTrie};
+    use nix::sys::signal::Signal;
+    use nix::sys::wait::{waitpid, WaitStatus};
+    use nix::unistd::{fork, ForkResult};
+
+    let mut trie = LinearIntentTrie::new_in(16, Arc::new(GuardedNodes::default()));
+    trie.warm(b"/api/v1/catalog");
+    trie.associate_payload(b"/api/v1/catalog", 7, 3).unwrap();
+    assert!(trie.capacity() > 16, "Warm-up must have grown the guarded pool");
+
+    // One byte past the last node lands on the trailing guard page, not on
+    // heap memory another structure might own.
+    let end = unsafe { (trie.get_node(0).unwrap() as *const _ as *mut u8).add(trie.capacity() * 64) };
+    // # Safety: the child only performs the faulting write and then aborts.
+    match unsafe { fork() }.expect("fork failed") {
+        ForkResult::Child => {
+            unsafe { std::ptr::write_volatile(end, 0xFF) };
+            std::process::abort();
+        }
+        ForkResult::Parent { child } => {
+            let status = waitpid(child, None).expect("waitpid failed");
+            assert!(
+                matches!(status, WaitStatus::Signaled(_, Signal::SIGSEGV, _)),
+                "Child must die from SIGSEGV on the arena guard, got {:?}",
+                status
+            );
+        }
+    }
+
+    let node = trie.get_node_at_path(b"/api/v1/catalog").unwrap();
+    assert_eq!((node.payload_handle, node.version_id), (7, 3));
+    let copy = trie.clone();
+    assert!(copy.structural_eq(&trie));
+    println!("Slab Safety Audit: Overrun past the guarded trie arena faulted; routes intact.");
+}
@@ -64,6 +64,43 @@ fn test_congestion_controller_loss_notification() {
     println!("test_congestion_controller_loss_notification: Testing Overhead = {:?}", overhead);
 }
 
+/// Verifies that recalibrating the base RTT moves the backoff threshold,
+/// both directly and via `ControlSignal::SetBaseRtt` on a dispatcher.
+#[tokio::test]
+async fn test_congestion_controller_base_rtt_recalibration() {
+    use httpx_core::{ControlSignal, ServerConfig};
+    use httpx_dsa::{LinearIntentTrie, SecureSlab};
+    use httpx_transport::dispatcher::CoreDispatcher;
+    use std::sync::Arc;
+
+    let t = Instant::now();
+
+    // 10µs baseline: 13µs is past the 1.2x threshold.
+    let cc = DefaultCongestionController::new(10_000);
+    cc.set_base_rtt(30_000);
+    assert_eq!(cc.base_rtt(), 30_000);
+    assert_eq!(cc.evaluate_intent_credit(13_000), 2, "13µs is within 1.2x of a 30µs baseline");
+    assert_eq!(cc.evaluate_intent_credit(36_001), 0, "Threshold must track the new baseline");
+
+    let cc = Arc::new(DefaultCongestionController::new(10_000));
+    let socket = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let (control_tx, control_rx) = tokio::sync::mpsc::channel(4);
+    let (learn_tx, _learn_rx) = httpx_core::learn_channel(16);
+    let mut dispatcher = CoreDispatcher::new_with_socket(0, socket, control_rx, ServerConfig::default(), LinearIntentTrie::new(64), learn_tx)
+        .await
+        .unwrap()
+        .with_congestion_controller(cc.clone());
+
+    control_tx.send(ControlSignal::SetBaseRtt(30_000)).await.unwrap();
+    control_tx.send(ControlSignal::Drain).await.unwrap();
+    dispatcher.run_loop(&SecureSlab::new(4)).await;
+    assert_eq!(cc.base_rtt(), 30_000, "SetBaseRtt must reach the attached controller");
+    assert_eq!(cc.evaluate_intent_credit(13_000), 2);
+
+    let overhead = t.elapsed();
+    println!("test_congestion_controller_base_rtt_recalibration: Testing Overhead = {:?}", overhead);
+}
+
 /// Verifies that `GsoPacketizer::prepare_burst` correctly sets up
 /// the iovec array with Intent, Header, and Payload pointers.
 #[test]
@@ -99,3 +136,64 @@ fn test_gso_packetizer_prepare_burst() {
     let overhead = t.elapsed();
     println!("test_gso_packetizer_prepare_burst: Testing Overhead = {:?}", overhead);
 }
+
+/// Verifies that `prepare_segments` gathers a variable number of iovecs
+/// and reports the count actually used in `msg_iovlen`.
+#[test]
+fn test_gso_packetizer_prepare_segments() {
+    let t = Instant::now();
+
+    let mut packetizer = GsoPacketizer::new(16);
+
+    let intent = b"INTENT_SYNC_FRAME";
+    let header = [0xBBu8; 128];
+    let payload = [0xAAu8; 4096];
+    let trailer = b"X-Checksum: 42";
+    let segments = [
+        (intent.as_ptr(), intent.len()),
+        (header.as_ptr(), header.len()),
+        (payload.as_ptr(), payload.len()),
+        (trailer.as_ptr(), trailer.len()),
+    ];
+
+    let msghdr = unsafe { &*packetizer.prepare_segments(3, &segments, 0) };
+    assert_eq!(msghdr.msg_iovlen, 4, "Should gather Intent, Header, Payload and Trailer");
+    let iovecs = unsafe { std::slice::from_raw_parts(msghdr.msg_iov, 4) };
+    for (iov, &(ptr, len)) in iovecs.iter().zip(&segments) {
+        assert_eq!(iov.iov_base as *const u8, ptr);
+        assert_eq!(iov.iov_len, len);
+    }
+    assert_eq!(packetizer.prepared_iovecs(3).len(), 4);
+    let storage = msghdr.msg_iov;
+
+    // Re-preparing the slot with fewer segments shrinks the count in place.
+    let msghdr = unsafe { &*packetizer.prepare_segments(3, &segments[..2], 0) };
+    assert_eq!(msghdr.msg_iovlen, 2, "Header-only response uses two iovecs");
+    assert_eq!(msghdr.msg_iov, storage, "iovec storage must not move");
+
+    let overhead = t.elapsed();
+    println!("test_gso_packetizer_prepare_segments: Testing Overhead = {:?}", overhead);
+}
+
+/// Verifies that the pacer admits the first send immediately and delays
+/// subsequent sends by the configured byte cost.
+#[test]
+fn test_pacer_token_bucket_delay() {
+    let t = Instant::now();
+
+    let mut pacer = httpx_transport::Pacer::new(1_000_000); // 1 MB/s => 1µs per byte
+
+    assert!(pacer.reserve(1000).is_zero(), "First send should not be delayed");
+    let delay = pacer.reserve(1000);
+    assert!(delay > std::time::Duration::from_micros(900), "Second send should wait ~1ms, got {:?}", delay);
+
+    // A burst allowance lets the sender run 4000 bytes ahead of schedule.
+    let mut bursty = httpx_transport::Pacer::new(1_000_000).with_burst(4000);
+    for _ in 0..5 {
+        assert!(bursty.reserve(1000).is_zero(), "Sends within the burst should not be delayed");
+    }
+    assert!(!bursty.reserve(1000).is_zero(), "Send beyond the burst should be delayed");
+
+    let overhead = t.elapsed();
+    println!("test_pacer_token_bucket_delay: Testing Overhead = {:?}", overhead);
+}
@@ -0,0 +1,413 @@
+//! # AF_XDP Redirect Certification
+//!
+//! Loads an XDP redirect program on one end of a veth pair, binds an
+//! `XskSocket` to its RX queue and verifies that a matching HTTP-X frame is
+//! read straight from the XSK ring, bypassing the kernel UDP stack, while
+//! frames with a corrupted UDP checksum are dropped and counted in `STATS`.
+//!
+//! The program is the bytecode equivalent of `xdp_redirect` in
+//! `bpf/xdp-filter` with `VERIFY_UDP_CSUM` set, assembled here so the test
+//! runs without a BPF toolchain. Requires root (CAP_NET_ADMIN + CAP_BPF);
+//! skips otherwise. The checksum and length decision itself is the
+//! program's own `csum` module, which runs here without any privileges.
+
+#[path = "../bpf/xdp-filter/src/csum.rs"]
+mod csum;
+
+use httpx_transport::XskSocket;
+use nix::libc;
+use std::os::fd::{AsRawFd, RawFd};
+use std::process::Command;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{Duration, Instant};
+
+const BPF_MAP_CREATE: libc::c_long = 0;
+const BPF_MAP_LOOKUP_ELEM: libc::c_long = 1;
+const BPF_PROG_LOAD: libc::c_long = 5;
+const BPF_LINK_CREATE: libc::c_long = 28;
+const BPF_MAP_TYPE_PERCPU_ARRAY: u32 = 6;
+const BPF_MAP_TYPE_XSKMAP: u32 = 17;
+const BPF_PROG_TYPE_XDP: u32 = 6;
+const BPF_XDP: u32 = 37;
+const XDP_FLAGS_SKB_MODE: u32 = 1 << 1;
+
+/// Tears the veth pair down even if an assertion fails.
+struct VethPair(String, String);
+
+impl VethPair {
+    fn create() -> Option<Self> {
+        static NEXT: AtomicU32 = AtomicU32::new(0);
+        let id = format!("{}{}", std::process::id() % 100_000, NEXT.fetch_add(1, Ordering::Relaxed));
+        let pair = Self(format!("hxa{}", id), format!("hxb{}", id));
+        let ip = |args: &[&str]| Command::new("ip").args(args).status().map(|s| s.success()).unwrap_or(false);
+        if !ip(&["link", "add", &pair.0, "type", "veth", "peer", "name", &pair.1]) {
+            return None;
+        }
+        if !ip(&["link", "set", &pair.0, "up"]) || !ip(&["link", "set", &pair.1, "up"]) {
+            return None;
+        }
+        Some(pair)
+    }
+}
+
+impl Drop for VethPair {
+    fn drop(&mut self) {
+        let _ = Command::new("ip").args(["link", "del", &self.0]).status();
+    }
+}
+
+struct Fd(RawFd);
+
+impl Drop for Fd {
+    fn drop(&mut self) {
+        unsafe { libc::close(self.0) };
+    }
+}
+
+impl AsRawFd for Fd {
+    fn as_raw_fd(&self) -> RawFd {
+        self.0
+    }
+}
+
+fn bpf<T>(cmd: libc::c_long, attr: &T) -> std::io::Result<Fd> {
+    let rc = unsafe { libc::syscall(libc::SYS_bpf, cmd, attr as *const T, std::mem::size_of::<T>()) };
+    if rc < 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(Fd(rc as RawFd))
+}
+
+fn insn(code: u8, dst: u8, src: u8, off: i16, imm: i32) -> u64 {
+    (code as u64) | ((dst as u64 | (src as u64) << 4) << 8) | ((off as u16 as u64) << 16) | ((imm as u32 as u64) << 32)
+}
+
+/// `xdp_redirect`: IPv4/UDP + "HTPX" passing `csum::udp_checksum_ok`
+/// -> XSKS[rx_queue_index]; bad checksum -> STATS[0] += 1 and DROP;
+/// non-magic UDP -> DROP; everything else -> PASS.
+fn redirect_program(xsks_fd: RawFd, stats_fd: RawFd) -> Vec<u64> {
+    vec![
+        insn(0xbf, 6, 1, 0, 0),           // 0:  r6 = ctx
+        insn(0x61, 2, 6, 0, 0),           // 1:  r2 = ctx->data
+        insn(0x61, 3, 6, 4, 0),           // 2:  r3 = ctx->data_end
+        insn(0xbf, 4, 2, 0, 0),           // 3:  r4 = r2
+        insn(0x07, 4, 0, 0, 46),          // 4:  r4 += eth + ipv4 + udp + magic
+        insn(0x2d, 4, 3, 69, 0),          // 5:  if r4 > r3 goto pass
+        insn(0x69, 5, 2, 12, 0),          // 6:  r5 = ether_type
+        insn(0x55, 5, 0, 67, 0x0008),     // 7:  if != IPv4 (0x0800 BE) goto pass
+        insn(0x71, 5, 2, 23, 0),          // 8:  r5 = ip proto
+        insn(0x55, 5, 0, 65, 17),         // 9:  if != UDP goto pass
+        insn(0x61, 5, 2, 42, 0),          // 10: r5 = magic
+        insn(0x55, 5, 0, 65, 0x58505448), // 11: if != "HTPX" goto drop
+        // UDP checksum, summed as little-endian words (byte-order independent).
+        insn(0x69, 5, 2, 40, 0),          // 12: r5 = udp check
+        insn(0x15, 5, 0, 55, 0),          // 13: if == 0 (optional on v4) goto redirect
+        insn(0xb7, 7, 0, 0, 0x1100),      // 14: r7 = pseudo-header proto (UDP)
+        insn(0x69, 5, 2, 26, 0),          // 15: src ip hi
+        insn(0x0f, 7, 5, 0, 0),           // 16
+        insn(0x69, 5, 2, 28, 0),          // 17: src ip lo
+        insn(0x0f, 7, 5, 0, 0),           // 18
+        insn(0x69, 5, 2, 30, 0),          // 19: dst ip hi
+        insn(0x0f, 7, 5, 0, 0),           // 20
+        insn(0x69, 5, 2, 32, 0),          // 21: dst ip lo
+        insn(0x0f, 7, 5, 0, 0),           // 22
+        insn(0x69, 9, 2, 38, 0),          // 23: r9 = udp len (wire order)
+        insn(0x0f, 7, 9, 0, 0),           // 24: pseudo-header length
+        insn(0xdc, 9, 0, 0, 16),          // 25: r9 = be16(r9)
+        insn(0x25, 9, 0, 42, csum::MAX_UDP_LEN as i32), // 26: if len > MAX_UDP_LEN goto redirect
+        insn(0xa5, 9, 0, 30, 8),          // 27: if len < udp header goto bad
+        insn(0xbf, 8, 2, 0, 0),           // 28: r8 = data
+        insn(0x07, 8, 0, 0, 34),          // 29: r8 += eth + ipv4
+        insn(0xa5, 9, 0, 8, 2),           // 30: loop: if r9 < 2 goto tail
+        insn(0xbf, 4, 8, 0, 0),           // 31: r4 = r8
+        insn(0x07, 4, 0, 0, 2),           // 32: r4 += 2
+        insn(0x2d, 4, 3, 24, 0),          // 33: if r4 > r3 goto bad (truncated)
+        insn(0x69, 5, 8, 0, 0),           // 34: r5 = *(u16 *)r8
+        insn(0x0f, 7, 5, 0, 0),           // 35: r7 += r5
+        insn(0x07, 8, 0, 0, 2),           // 36: r8 += 2
+        insn(0x17, 9, 0, 0, 2),           // 37: r9 -= 2
+        insn(0x05, 0, 0, -9, 0),          // 38: goto loop
+        insn(0x15, 9, 0, 5, 0),           // 39: tail: if r9 == 0 goto fold
+        insn(0xbf, 4, 8, 0, 0),           // 40: r4 = r8
+        insn(0x07, 4, 0, 0, 1),           // 41: r4 += 1
+        insn(0x2d, 4, 3, 15, 0),          // 42: if r4 > r3 goto bad
+        insn(0x71, 5, 8, 0, 0),           // 43: r5 = odd trailing byte
+        insn(0x0f, 7, 5, 0, 0),           // 44: r7 += r5
+        insn(0xbf, 5, 7, 0, 0),           // 45: fold: r5 = r7
+        insn(0x77, 5, 0, 0, 16),          // 46: r5 >>= 16
+        insn(0x57, 7, 0, 0, 0xffff),      // 47: r7 &= 0xffff
+        insn(0x0f, 7, 5, 0, 0),           // 48: r7 += r5
+        insn(0xbf, 5, 7, 0, 0),           // 49
+        insn(0x77, 5, 0, 0, 16),          // 50
+        insn(0x57, 7, 0, 0, 0xffff),      // 51
+        insn(0x0f, 7, 5, 0, 0),           // 52
+        insn(0xbf, 5, 7, 0, 0),           // 53
+        insn(0x77, 5, 0, 0, 16),          // 54
+        insn(0x57, 7, 0, 0, 0xffff),      // 55
+        insn(0x0f, 7, 5, 0, 0),           // 56
+        insn(0x15, 7, 0, 11, 0xffff),     // 57: if sum == 0xffff goto redirect
+        insn(0x62, 10, 0, -4, 0),         // 58: bad: *(u32 *)(fp - 4) = STAT_CSUM_DROP
+        insn(0xbf, 2, 10, 0, 0),          // 59: r2 = fp
+        insn(0x07, 2, 0, 0, -4),          // 60: r2 = &key
+        insn(0x18, 1, 1, 0, stats_fd),    // 61: r1 = STATS (pseudo map fd)
+        0,                                // 62
+        insn(0x85, 0, 0, 0, 1),           // 63: call bpf_map_lookup_elem
+        insn(0x15, 0, 0, 12, 0),          // 64: if r0 == NULL goto drop
+        insn(0x79, 1, 0, 0, 0),           // 65: r1 = *counter
+        insn(0x07, 1, 0, 0, 1),           // 66: r1 += 1
+        insn(0x7b, 0, 1, 0, 0),           // 67: *counter = r1
+        insn(0x05, 0, 0, 8, 0),           // 68: goto drop
+        insn(0x61, 2, 6, 16, 0),          // 69: redirect: r2 = ctx->rx_queue_index
+        insn(0x18, 1, 1, 0, xsks_fd),     // 70: r1 = XSKS (pseudo map fd)
+        0,                                // 71
+        insn(0xb7, 3, 0, 0, 2),           // 72: r3 = XDP_PASS (fallback)
+        insn(0x85, 0, 0, 0, 51),          // 73: call bpf_redirect_map
+        insn(0x95, 0, 0, 0, 0),           // 74: exit
+        insn(0xb7, 0, 0, 0, 2),           // 75: pass: r0 = XDP_PASS
+        insn(0x95, 0, 0, 0, 0),           // 76: exit
+        insn(0xb7, 0, 0, 0, 1),           // 77: drop: r0 = XDP_DROP
+        insn(0x95, 0, 0, 0, 0),           // 78: exit
+    ]
+}
+
+fn udp_frame(payload: &[u8]) -> Vec<u8> {
+    let mut frame = vec![0xff; 6]; // broadcast dst
+    frame.extend_from_slice(&[0x02, 0, 0, 0, 0, 1]); // src
+    frame.extend_from_slice(&[0x08, 0x00]); // IPv4
+    let total_len = (20 + 8 + payload.len()) as u16;
+    frame.extend_from_slice(&[0x45, 0]);
+    frame.extend_from_slice(&total_len.to_be_bytes());
+    frame.extend_from_slice(&[0, 0, 0, 0, 64, 17, 0, 0]); // id, frag, ttl, UDP, csum
+    frame.extend_from_slice(&[10, 0, 0, 1, 10, 0, 0, 2]);
+    frame.extend_from_slice(&4433u16.to_be_bytes());
+    frame.extend_from_slice(&4433u16.to_be_bytes());
+    frame.extend_from_slice(&((8 + payload.len()) as u16).to_be_bytes());
+    frame.extend_from_slice(&[0, 0]);
+    frame.extend_from_slice(payload);
+    frame
+}
+
+/// Fills in the UDP checksum of a frame built by `udp_frame`.
+fn with_checksum(mut frame: Vec<u8>) -> Vec<u8> {
+    let udp = &frame[34..];
+    let mut sum: u32 = 17 + udp.len() as u32;
+    for chunk in frame[26..34].chunks(2).chain(udp.chunks(2)) {
+        sum += u32::from(chunk[0]) << 8 | u32::from(*chunk.get(1).unwrap_or(&0));
+    }
+    while sum > 0xffff {
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+    let check = match !(sum as u16) { 0 => 0xffff, c => c };
+    frame[40..42].copy_from_slice(&check.to_be_bytes());
+    frame
+}
+
+/// `csum::udp_checksum_ok` over a frame built by `udp_frame`, loading words
+/// natively as the program does.
+fn checksum_ok(frame: &[u8]) -> bool {
+    let u32_at = |at: usize| u32::from_ne_bytes(frame[at..at + 4].try_into().unwrap());
+    let u16_at = |at: usize| u16::from_ne_bytes(frame[at..at + 2].try_into().unwrap());
+    let udp = &frame[34..];
+    csum::udp_checksum_ok(
+        u32_at(26),
+        u32_at(30),
+        u16_at(38),
+        u16_at(40),
+        |i| udp.get(i..i + 2).map(|w| u16::from_ne_bytes([w[0], w[1]])),
+        |i| udp.get(i).copied(),
+    )
+}
+
+fn send_raw(ifname: &str, frame: &[u8]) {
+    let fd = unsafe { libc::socket(libc::AF_PACKET, libc::SOCK_RAW, 0) };
+    assert!(fd >= 0, "AF_PACKET socket: {}", std::io::Error::last_os_error());
+    let fd = Fd(fd);
+    let name = std::ffi::CString::new(ifname).unwrap();
+    let mut addr: libc::sockaddr_ll = unsafe { std::mem::zeroed() };
+    addr.sll_family = libc::AF_PACKET as u16;
+    addr.sll_ifindex = unsafe { libc::if_nametoindex(name.as_ptr()) } as i32;
+    addr.sll_halen = 6;
+    addr.sll_addr[..6].copy_from_slice(&frame[..6]);
+    let rc = unsafe {
+        libc::sendto(
+            fd.0,
+            frame.as_ptr() as *const libc::c_void,
+            frame.len(),
+            0,
+            &addr as *const _ as *const libc::sockaddr,
+            std::mem::size_of::<libc::sockaddr_ll>() as libc::socklen_t,
+        )
+    };
+    assert_eq!(rc, frame.len() as isize, "sendto: {}", std::io::Error::last_os_error());
+}
+
+/// A veth pair with the redirect program attached to its receiving end
+/// and an XSK bound to queue 0.
+struct Harness {
+    xsk: XskSocket,
+    stats: Fd,
+    // Field order is drop order: XSK, maps, link (detaches), then the veth.
+    _xsks: Fd,
+    _link: Fd,
+    _prog: Fd,
+    veth: VethPair,
+}
+
+impl Harness {
+    fn setup() -> Option<Self> {
+        let Some(veth) = VethPair::create() else {
+            println!("AF_XDP Redirect: veth unavailable (needs CAP_NET_ADMIN), skipping.");
+            return None;
+        };
+
+        #[repr(C)]
+        struct MapCreateAttr { map_type: u32, key_size: u32, value_size: u32, max_entries: u32, map_flags: u32 }
+        let xsks = bpf(BPF_MAP_CREATE, &MapCreateAttr {
+            map_type: BPF_MAP_TYPE_XSKMAP, key_size: 4, value_size: 4, max_entries: 4, map_flags: 0,
+        });
+        let Ok(xsks) = xsks else {
+            println!("AF_XDP Redirect: XSKMAP unavailable ({:?}), skipping.", xsks.err());
+            return None;
+        };
+        let stats = bpf(BPF_MAP_CREATE, &MapCreateAttr {
+            map_type: BPF_MAP_TYPE_PERCPU_ARRAY, key_size: 4, value_size: 8, max_entries: 1, map_flags: 0,
+        }).expect("STATS map");
+
+        let insns = redirect_program(xsks.0, stats.0);
+        let license = b"GPL\0";
+        #[repr(C)]
+        struct ProgLoadAttr {
+            prog_type: u32, insn_cnt: u32, insns: u64, license: u64,
+            log_level: u32, log_size: u32, log_buf: u64, kern_version: u32, prog_flags: u32,
+            prog_name: [u8; 16], prog_ifindex: u32, expected_attach_type: u32,
+        }
+        let prog = bpf(BPF_PROG_LOAD, &ProgLoadAttr {
+            prog_type: BPF_PROG_TYPE_XDP, insn_cnt: insns.len() as u32, insns: insns.as_ptr() as u64,
+            license: license.as_ptr() as u64, log_level: 0, log_size: 0, log_buf: 0, kern_version: 0,
+            prog_flags: 0, prog_name: *b"xdp_redirect\0\0\0\0", prog_ifindex: 0, expected_attach_type: BPF_XDP,
+        }).expect("BPF_PROG_LOAD");
+
+        let ifname = std::ffi::CString::new(veth.1.as_str()).unwrap();
+        let ifindex = unsafe { libc::if_nametoindex(ifname.as_ptr()) };
+        #[repr(C)]
+        struct LinkCreateAttr { prog_fd: u32, target_ifindex: u32, attach_type: u32, flags: u32 }
+        let link = bpf(BPF_LINK_CREATE, &LinkCreateAttr {
+            prog_fd: prog.0 as u32, target_ifindex: ifindex, attach_type: BPF_XDP, flags: XDP_FLAGS_SKB_MODE,
+        }).expect("BPF_LINK_CREATE (XDP, SKB mode)");
+
+        let xsk = XskSocket::bind(&veth.1, 0).expect("XSK bind");
+        xsk.register(xsks.as_raw_fd(), 0).expect("XSKMAP insert");
+
+        Some(Self { xsk, stats, _xsks: xsks, _link: link, _prog: prog, veth })
+    }
+
+    fn send(&self, frame: &[u8]) {
+        send_raw(&self.veth.0, frame);
+    }
+
+    /// Next redirected frame, waiting up to two seconds.
+    fn recv(&mut self) -> Option<Vec<u8>> {
+        let deadline = Instant::now() + Duration::from_secs(2);
+        loop {
+            if let Some(bytes) = self.xsk.recv(|f| f.to_vec()) {
+                return Some(bytes);
+            }
+            if Instant::now() >= deadline {
+                return None;
+            }
+            self.xsk.wait_readable(100).unwrap();
+        }
+    }
+
+    /// `STATS[0]` summed across CPUs.
+    fn checksum_drops(&self) -> u64 {
+        #[repr(C)]
+        struct MapLookupAttr { map_fd: u32, _pad: u32, key: u64, value: u64, flags: u64 }
+        let key = 0u32;
+        // One slot per possible CPU; unused slots stay zero.
+        let mut values = vec![0u64; 4096];
+        bpf(BPF_MAP_LOOKUP_ELEM, &MapLookupAttr {
+            map_fd: self.stats.0 as u32, _pad: 0, key: &key as *const u32 as u64,
+            value: values.as_mut_ptr() as u64, flags: 0,
+        }).map(std::mem::forget).expect("STATS lookup");
+        values.iter().sum()
+    }
+}
+
+#[test]
+fn test_xdp_redirects_httpx_frame_into_xsk_ring() {
+    let t = Instant::now();
+    let Some(mut h) = Harness::setup() else { return };
+
+    // Malformed traffic is dropped by the program; only the HTTP-X frame is redirected.
+    h.send(&udp_frame(b"JUNK-not-httpx"));
+    let frame = udp_frame(b"HTPX-intent-sync");
+    h.send(&frame);
+
+    let received = h.recv().expect("No frame reached the XSK RX ring");
+    assert_eq!(received, frame, "The redirected frame must arrive byte-for-byte");
+    assert_eq!(&received[42..46], b"HTPX");
+    assert!(h.xsk.recv(|f| f.len()).is_none(), "Non-HTTP-X traffic must not be redirected");
+
+    let overhead = t.elapsed();
+    println!("test_xdp_redirects_httpx_frame_into_xsk_ring: Testing Overhead = {:?}", overhead);
+}
+
+/// A frame whose payload was corrupted after checksumming must be dropped
+/// and counted; intact and checksum-less (v4 optional) frames pass through.
+#[test]
+fn test_xdp_drops_corrupted_udp_checksum() {
+    let t = Instant::now();
+    let Some(mut h) = Harness::setup() else { return };
+
+    let valid = with_checksum(udp_frame(b"HTPX-checksummed-odd"));
+    let mut corrupted = with_checksum(udp_frame(b"HTPX-checksummed-odd"));
+    *corrupted.last_mut().unwrap() ^= 0x40; // bit flip in flight, magic intact
+    let unchecked = udp_frame(b"HTPX-no-checksum");
+
+    h.send(&corrupted);
+    h.send(&valid);
+    h.send(&unchecked);
+
+    assert_eq!(h.recv().as_deref(), Some(&valid[..]), "Corrupted frame must not reach the XSK ring");
+    assert_eq!(h.recv().as_deref(), Some(&unchecked[..]), "A zero checksum skips validation");
+    assert!(h.xsk.recv(|f| f.len()).is_none());
+    assert_eq!(h.checksum_drops(), 1, "The drop must be counted in STATS");
+
+    let overhead = t.elapsed();
+    println!("test_xdp_drops_corrupted_udp_checksum: Testing Overhead = {:?}", overhead);
+}
+
+/// The program's checksum and length decision, run on the host: intact,
+/// checksum-less and over-MTU datagrams pass; corrupted, undersized and
+/// truncated ones fail.
+#[test]
+fn test_udp_checksum_decision() {
+    let t = Instant::now();
+
+    let even = with_checksum(udp_frame(b"HTPX-checksummed"));
+    let odd = with_checksum(udp_frame(b"HTPX-checksummed-odd"));
+    assert!(checksum_ok(&even) && checksum_ok(&odd));
+    assert!(checksum_ok(&udp_frame(b"HTPX-no-checksum")), "A zero checksum skips validation");
+
+    for at in [42, 50, odd.len() - 1] {
+        let mut corrupted = odd.clone();
+        corrupted[at] ^= 0x40;
+        assert!(!checksum_ok(&corrupted), "A bit flip at byte {} must fail", at);
+    }
+
+    // Longer than the loop can walk: left to the kernel stack.
+    let mut jumbo = with_checksum(udp_frame(&[b'x'; csum::MAX_UDP_LEN]));
+    *jumbo.last_mut().unwrap() ^= 0x40;
+    assert!(checksum_ok(&jumbo));
+
+    // A UDP length below the header, or past the end of the packet, fails.
+    let mut undersized = even.clone();
+    undersized[38..40].copy_from_slice(&4u16.to_be_bytes());
+    assert!(!checksum_ok(&undersized));
+    let truncated = &even[..even.len() - 3];
+    assert!(!checksum_ok(truncated));
+
+    let overhead = t.elapsed();
+    println!("test_udp_checksum_decision: Testing Overhead = {:?}", overhead);
+}